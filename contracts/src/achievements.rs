@@ -0,0 +1,167 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::events;
+use crate::storage_types::{Badge, BadgeAward, DataKey, UserStreak};
+use crate::streaks;
+
+/// Cumulative deposits required for the `TenKSaved` badge
+pub const TEN_K_SAVED_THRESHOLD: i128 = 10_000;
+
+/// Streak length, in seconds, required for the `TwelveWeekStreak` badge
+const TWELVE_WEEKS_SECONDS: u64 = 12 * 7 * 24 * 60 * 60;
+
+/// Returns every badge awarded to a user, in award order
+pub fn get_user_badges(env: &Env, user: &Address) -> Vec<BadgeAward> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserBadges(user.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Returns the total amount a user has ever deposited across all plan types
+pub fn get_total_deposited(env: &Env, user: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserTotalDeposited(user.clone()))
+        .unwrap_or(0)
+}
+
+/// Awards a badge once; later calls for the same badge are no-ops
+pub fn award(env: &Env, user: &Address, badge: Badge) {
+    let mut badges = get_user_badges(env, user);
+    if badges.iter().any(|award| award.badge == badge) {
+        return;
+    }
+
+    badges.push_back(BadgeAward {
+        badge,
+        awarded_at: env.ledger().timestamp(),
+    });
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserBadges(user.clone()), &badges);
+
+    events::publish(
+        env,
+        events::BadgeAwarded {
+            user: user.clone(),
+            badge,
+        },
+    );
+}
+
+/// Awards the deposit-driven badges after a deposit of `amount`
+pub fn on_deposit(env: &Env, user: &Address, amount: i128, streak: &UserStreak) {
+    award(env, user, Badge::FirstDeposit);
+
+    let total = get_total_deposited(env, user).saturating_add(amount);
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserTotalDeposited(user.clone()), &total);
+    if total >= TEN_K_SAVED_THRESHOLD {
+        award(env, user, Badge::TenKSaved);
+    }
+
+    let period = streaks::period_seconds(&streaks::get_streak_period(env));
+    if (streak.current_streak as u64).saturating_mul(period) >= TWELVE_WEEKS_SECONDS {
+        award(env, user, Badge::TwelveWeekStreak);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::storage_types::Badge;
+    use crate::{GroupMetadata, NesteraContract, NesteraContractClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, Env, String, Symbol,
+    };
+
+    const WEEK: u64 = 7 * 24 * 60 * 60;
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        (env, client, user)
+    }
+
+    fn badge_ids(client: &NesteraContractClient, user: &Address) -> std::vec::Vec<Badge> {
+        client
+            .get_user_badges(user)
+            .iter()
+            .map(|a| a.badge)
+            .collect()
+    }
+
+    #[test]
+    fn test_first_deposit_and_ten_k_badges() {
+        let (env, client, user) = setup();
+
+        env.ledger().with_mut(|li| li.timestamp = 42);
+        client.deposit_flexi(&user, &4_000);
+        let badges = client.get_user_badges(&user);
+        assert_eq!(badges.len(), 1);
+        assert_eq!(badges.get(0).unwrap().badge, Badge::FirstDeposit);
+        assert_eq!(badges.get(0).unwrap().awarded_at, 42);
+
+        client.create_goal_save(&user, &Symbol::new(&env, "car"), &50_000, &6_000);
+        assert_eq!(
+            badge_ids(&client, &user),
+            std::vec![Badge::FirstDeposit, Badge::TenKSaved]
+        );
+    }
+
+    #[test]
+    fn test_goal_completed_and_group_founder_badges() {
+        let (env, client, user) = setup();
+
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "tv"), &500, &100);
+        client.deposit_to_goal_save(&user, &goal_id, &400);
+        client.create_group_save(
+            &user,
+            &GroupMetadata {
+                title: String::from_str(&env, "Circle"),
+                description: String::from_str(&env, "Description"),
+                category: String::from_str(&env, "savings"),
+            },
+            &5000,
+            &0,
+            &100,
+            &true,
+            &1,
+            &500,
+            &0,
+        );
+
+        assert_eq!(
+            badge_ids(&client, &user),
+            std::vec![
+                Badge::FirstDeposit,
+                Badge::GoalCompleted,
+                Badge::GroupFounder
+            ]
+        );
+    }
+
+    #[test]
+    fn test_twelve_week_streak_badge() {
+        let (env, client, user) = setup();
+
+        for _ in 0..11 {
+            client.deposit_flexi(&user, &1);
+            env.ledger().with_mut(|li| li.timestamp += WEEK);
+        }
+        assert!(!badge_ids(&client, &user).contains(&Badge::TwelveWeekStreak));
+
+        client.deposit_flexi(&user, &1);
+        assert!(badge_ids(&client, &user).contains(&Badge::TwelveWeekStreak));
+    }
+}
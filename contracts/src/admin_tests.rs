@@ -1,6 +1,6 @@
 use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Error, InvokeError, Symbol};
 
-use crate::{NesteraContract, NesteraContractClient, SavingsError};
+use crate::{GroupMetadata, NesteraContract, NesteraContractClient, SavingsError};
 
 fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let env = Env::default();
@@ -10,7 +10,13 @@ fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
 
     env.mock_all_auths();
-    client.initialize(&admin, &admin_pk);
+    client.initialize(
+        &admin,
+        &admin_pk,
+        &Address::generate(&env),
+        &0,
+        &Address::generate(&env),
+    );
 
     (env, client, admin)
 }
@@ -112,15 +118,18 @@ fn paused_blocks_write_paths() {
         client
             .try_create_group_save(
                 &user,
-                &soroban_sdk::String::from_str(&env, "title"),
-                &soroban_sdk::String::from_str(&env, "desc"),
-                &soroban_sdk::String::from_str(&env, "cat"),
+                &GroupMetadata {
+                    title: soroban_sdk::String::from_str(&env, "title"),
+                    description: soroban_sdk::String::from_str(&env, "desc"),
+                    category: soroban_sdk::String::from_str(&env, "cat"),
+                },
                 &1000,
                 &0,
                 &10,
                 &true,
                 &1,
                 &2,
+                &0,
             )
             .unwrap_err(),
         SavingsError::ContractPaused,
@@ -152,28 +161,95 @@ fn unpause_restores_write_paths() {
 
 #[test]
 fn admin_can_set_early_break_fee_and_recipient() {
-    let (env, client, _admin) = setup();
+    let (env, client, admin) = setup();
     let treasury = Address::generate(&env);
 
     env.mock_all_auths();
 
-    assert!(client.try_set_fee_recipient(&treasury).is_ok());
+    assert!(client.try_set_fee_recipient(&admin, &treasury).is_ok());
     assert_eq!(client.get_fee_recipient().unwrap(), treasury);
 
-    assert!(client.try_set_early_break_fee_bps(&500).is_ok());
+    assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok());
     assert_eq!(client.get_early_break_fee_bps(), 500);
 
-    let invalid = client.try_set_early_break_fee_bps(&10_001);
+    let invalid = client.try_set_early_break_fee_bps(&admin, &10_001);
     assert_savings_error(invalid.unwrap_err(), SavingsError::InvalidAmount);
 }
 
 #[test]
 fn non_admin_cannot_set_fee_config() {
-    let (env, client, _admin) = setup();
+    let (env, client, admin) = setup();
     let treasury = Address::generate(&env);
 
     env.mock_auths(&[]);
 
-    assert!(client.try_set_fee_recipient(&treasury).is_err());
-    assert!(client.try_set_early_break_fee_bps(&500).is_err());
+    assert!(client.try_set_fee_recipient(&admin, &treasury).is_err());
+    assert!(client.try_set_early_break_fee_bps(&admin, &500).is_err());
+}
+
+#[test]
+fn initialize_configures_treasury_fee_and_token() {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let token = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &admin_pk, &treasury, &250, &token);
+
+    assert!(client.is_initialized());
+    assert_eq!(client.get_fee_recipient(), Some(treasury.clone()));
+    assert_eq!(client.get_early_break_fee_bps(), 250);
+    assert_eq!(client.get_protocol_params().token, Some(token.clone()));
+
+    assert!(client
+        .try_initialize(&admin, &admin_pk, &treasury, &250, &token)
+        .is_err());
+    assert!(client.try_initialize_admin(&admin, &admin_pk).is_err());
+}
+
+#[test]
+fn initialize_with_invalid_fee_leaves_contract_uninitialized() {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+    assert_contract_error(
+        client
+            .try_initialize(
+                &admin,
+                &admin_pk,
+                &Address::generate(&env),
+                &10_001,
+                &Address::generate(&env),
+            )
+            .unwrap_err(),
+        SavingsError::InvalidAmount,
+    );
+    assert!(!client.is_initialized());
+    assert_savings_error(
+        client.try_set_admin(&admin, &admin).unwrap_err(),
+        SavingsError::Unauthorized,
+    );
+}
+
+#[test]
+fn legacy_initialize_admin_sets_admin_only() {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize_admin(&admin, &BytesN::from_array(&env, &[1u8; 32]));
+
+    assert!(client.is_initialized());
+    assert!(client.try_pause(&admin).is_ok());
+    assert_eq!(client.get_fee_recipient(), None);
 }
@@ -0,0 +1,236 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::flexi;
+use crate::guard::{self, Op};
+use crate::storage_types::{DataKey, DepositAllowance, UserKey};
+
+fn key(user: &Address, spender: &Address) -> DataKey {
+    DataKey::UserState(UserKey::DepositAllowance(user.clone(), spender.clone()))
+}
+
+fn spenders_key(user: &Address) -> DataKey {
+    DataKey::UserState(UserKey::AllowanceSpenders(user.clone()))
+}
+
+/// Spenders the user has an allowance open for, in the order first granted
+pub(crate) fn get_spenders(env: &Env, user: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&spenders_key(user))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn set_spenders(env: &Env, user: &Address, spenders: &Vec<Address>) {
+    if spenders.is_empty() {
+        env.storage().persistent().remove(&spenders_key(user));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&spenders_key(user), spenders);
+    }
+}
+
+fn set_allowance(env: &Env, user: &Address, spender: &Address, allowance: &DepositAllowance) {
+    env.storage()
+        .persistent()
+        .set(&key(user, spender), allowance);
+    let mut spenders = get_spenders(env, user);
+    if !spenders.contains(spender) {
+        spenders.push_back(spender.clone());
+        set_spenders(env, user, &spenders);
+    }
+}
+
+fn remove_allowance(env: &Env, user: &Address, spender: &Address) {
+    env.storage().persistent().remove(&key(user, spender));
+    let mut spenders = get_spenders(env, user);
+    if let Some(index) = spenders.first_index_of(spender) {
+        spenders.remove(index);
+        set_spenders(env, user, &spenders);
+    }
+}
+
+/// Drops every allowance the user granted. Used when the account is closed.
+pub(crate) fn remove_all(env: &Env, user: &Address) {
+    for spender in get_spenders(env, user).iter() {
+        env.storage().persistent().remove(&key(user, &spender));
+    }
+    env.storage().persistent().remove(&spenders_key(user));
+}
+
+/// Returns what the spender may still deposit for the user, if anything
+pub fn get_allowance(env: &Env, user: &Address, spender: &Address) -> Option<DepositAllowance> {
+    env.storage().persistent().get(&key(user, spender))
+}
+
+/// Lets `spender` deposit up to `max_amount` into the user's Flexi Save
+/// until `expiry`, replacing any earlier allowance.
+///
+/// # Errors
+/// * `InvalidAmount` - If `max_amount` is not positive
+/// * `InvalidTimestamp` - If `expiry` has already passed
+pub fn grant_allowance(
+    env: &Env,
+    user: Address,
+    spender: Address,
+    max_amount: i128,
+    expiry: u64,
+) -> Result<(), SavingsError> {
+    user.require_auth();
+    if max_amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if expiry <= env.ledger().timestamp() {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    let allowance = DepositAllowance {
+        remaining: max_amount,
+        expiry,
+    };
+    set_allowance(env, &user, &spender, &allowance);
+    events::publish(
+        env,
+        events::AllowanceGranted {
+            user,
+            spender,
+            max_amount,
+            expiry,
+        },
+    );
+    Ok(())
+}
+
+pub fn revoke_allowance(env: &Env, user: Address, spender: Address) -> Result<(), SavingsError> {
+    user.require_auth();
+    remove_allowance(env, &user, &spender);
+    events::publish(env, events::AllowanceRevoked { user, spender });
+    Ok(())
+}
+
+/// Deposits into the user's Flexi Save on the spender's authorization,
+/// drawing down the allowance the user granted them.
+///
+/// # Errors
+/// * `Unauthorized` - If the user granted the spender no allowance
+/// * `TooLate` - If the allowance has expired
+/// * `AmountExceedsLimit` - If `amount` is more than the allowance has left
+pub fn deposit_flexi_for(
+    env: &Env,
+    spender: Address,
+    user: Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Deposit, amount)?;
+    spender.require_auth();
+
+    let mut allowance = get_allowance(env, &user, &spender).ok_or(SavingsError::Unauthorized)?;
+    if env.ledger().timestamp() > allowance.expiry {
+        return Err(SavingsError::TooLate);
+    }
+    if amount > allowance.remaining {
+        return Err(SavingsError::AmountExceedsLimit);
+    }
+
+    flexi::apply_flexi_deposit(env, &user, amount)?;
+    allowance.remaining -= amount;
+    if allowance.remaining == 0 {
+        remove_allowance(env, &user, &spender);
+    } else {
+        set_allowance(env, &user, &spender, &allowance);
+    }
+
+    events::publish(
+        env,
+        events::DepositedFor {
+            spender,
+            user,
+            amount,
+            remaining: allowance.remaining,
+        },
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+        client.initialize(
+            &Address::generate(&env),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        let spender = Address::generate(&env);
+        (env, client, user, spender)
+    }
+
+    #[test]
+    fn test_spender_deposits_up_to_allowance() {
+        let (_env, client, user, spender) = setup();
+        client.grant_allowance(&user, &spender, &1_000, &100);
+
+        client.deposit_flexi_for(&spender, &user, &600);
+        assert_eq!(client.get_flexi_balance(&user), 600);
+        assert_eq!(
+            client.get_allowance(&user, &spender).unwrap().remaining,
+            400
+        );
+        assert_eq!(
+            client.try_deposit_flexi_for(&spender, &user, &401),
+            Err(Ok(SavingsError::AmountExceedsLimit))
+        );
+
+        client.deposit_flexi_for(&spender, &user, &400);
+        assert_eq!(client.get_allowance(&user, &spender), None);
+        assert_eq!(
+            client.try_deposit_flexi_for(&spender, &user, &1),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+        assert_eq!(client.get_flexi_balance(&user), 1_000);
+    }
+
+    #[test]
+    fn test_expired_or_revoked_allowance_cannot_be_used() {
+        let (env, client, user, spender) = setup();
+        assert_eq!(
+            client.try_grant_allowance(&user, &spender, &0, &100),
+            Err(Ok(SavingsError::InvalidAmount))
+        );
+        assert_eq!(
+            client.try_grant_allowance(&user, &spender, &500, &0),
+            Err(Ok(SavingsError::InvalidTimestamp))
+        );
+
+        client.grant_allowance(&user, &spender, &500, &100);
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        assert_eq!(
+            client.try_deposit_flexi_for(&spender, &user, &100),
+            Err(Ok(SavingsError::TooLate))
+        );
+
+        client.grant_allowance(&user, &spender, &500, &200);
+        client.revoke_allowance(&user, &spender);
+        assert_eq!(
+            client.try_deposit_flexi_for(&spender, &user, &100),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+        assert_eq!(client.get_flexi_balance(&user), 0);
+    }
+}
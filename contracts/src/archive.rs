@@ -0,0 +1,178 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::goal;
+use crate::guard::{self, Op};
+use crate::lock;
+use crate::pagination;
+use crate::storage_types::{ArchivedPlanSummary, ChunkedList, ChunkedListId, Cursor, PlanKind};
+
+/// Deletes a withdrawn Lock or Goal Save, keeping only a compact summary in
+/// the owner's archived history.
+///
+/// # Errors
+/// * `PlanNotFound` - If the plan doesn't exist or was already archived
+/// * `Unauthorized` - If `user` doesn't own the plan
+/// * `PlanLocked` - If the plan hasn't been withdrawn yet
+/// * `InvalidPlanConfig` - If `plan_kind` isn't Lock or Goal
+pub fn archive_plan(
+    env: &Env,
+    user: Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+) -> Result<ArchivedPlanSummary, SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let now = env.ledger().timestamp();
+    let summary = match plan_kind {
+        PlanKind::Lock => {
+            let lock_save = lock::get_lock_save(env, plan_id).ok_or(SavingsError::PlanNotFound)?;
+            if lock_save.owner != user {
+                return Err(SavingsError::Unauthorized);
+            }
+            if !lock_save.is_withdrawn {
+                return Err(SavingsError::PlanLocked);
+            }
+            lock::remove_lock_save(env, &lock_save);
+            ArchivedPlanSummary {
+                plan_kind,
+                plan_id,
+                amount: lock_save.amount,
+                start_time: lock_save.start_time,
+                end_time: lock_save.maturity_time,
+                archived_at: now,
+            }
+        }
+        PlanKind::Goal => {
+            let goal_save = goal::get_goal_save(env, plan_id).ok_or(SavingsError::PlanNotFound)?;
+            if goal_save.owner != user {
+                return Err(SavingsError::Unauthorized);
+            }
+            if !goal_save.is_withdrawn {
+                return Err(SavingsError::PlanLocked);
+            }
+            goal::remove_goal_save(env, &goal_save);
+            ArchivedPlanSummary {
+                plan_kind,
+                plan_id,
+                amount: goal_save.current_amount,
+                start_time: goal_save.start_time,
+                end_time: goal_save.deadline,
+                archived_at: now,
+            }
+        }
+        PlanKind::Flexi | PlanKind::Group => return Err(SavingsError::InvalidPlanConfig),
+    };
+
+    ChunkedList::new(ChunkedListId::ArchivedPlans(user.clone())).push(env, summary.clone());
+    events::publish(
+        env,
+        events::PlanArchived {
+            user,
+            plan_kind,
+            plan_id,
+        },
+    );
+
+    Ok(summary)
+}
+
+/// Returns a page of a user's archived plan summaries, oldest first
+pub fn get_archived_plans(
+    env: &Env,
+    user: Address,
+    cursor: Cursor,
+) -> (Vec<ArchivedPlanSummary>, Option<Cursor>) {
+    let list = ChunkedList::new(ChunkedListId::ArchivedPlans(user));
+    pagination::paginate_chunked(env, &list, &cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cursor, NesteraContract, NesteraContractClient, PlanKind, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, Symbol,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+
+        (env, client, user)
+    }
+
+    #[test]
+    fn test_archive_withdrawn_plans_keeps_summaries() {
+        let (env, client, user) = setup();
+
+        let lock_id = client.create_lock_save(&user, &1_000, &100);
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "car"), &500, &500);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.withdraw_lock_save(&user, &lock_id);
+        client.withdraw_completed_goal_save(&user, &goal_id);
+
+        let summary = client.archive_plan(&user, &PlanKind::Lock, &lock_id);
+        assert_eq!(summary.amount, 1_000);
+        assert_eq!((summary.start_time, summary.end_time), (0, 100));
+        client.archive_plan(&user, &PlanKind::Goal, &goal_id);
+
+        assert!(client.try_get_lock_save_detail(&lock_id).is_err());
+        assert!(client.try_get_goal_save_detail(&goal_id).is_err());
+        assert_eq!(client.get_user_lock_saves(&user).len(), 0);
+        assert_eq!(client.get_user_goal_saves(&user).len(), 0);
+
+        let (archived, next) = client.get_archived_plans(
+            &user,
+            &Cursor {
+                offset: 0,
+                limit: 0,
+            },
+        );
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived.get(1).unwrap().plan_kind, PlanKind::Goal);
+        assert_eq!(archived.get(1).unwrap().amount, 500);
+        assert_eq!(next, None);
+
+        assert_eq!(
+            client.try_archive_plan(&user, &PlanKind::Lock, &lock_id),
+            Err(Ok(SavingsError::PlanNotFound))
+        );
+    }
+
+    #[test]
+    fn test_archive_rejects_open_or_foreign_plans() {
+        let (env, client, user) = setup();
+        let lock_id = client.create_lock_save(&user, &1_000, &100);
+
+        assert_eq!(
+            client.try_archive_plan(&user, &PlanKind::Lock, &lock_id),
+            Err(Ok(SavingsError::PlanLocked))
+        );
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.withdraw_lock_save(&user, &lock_id);
+        assert_eq!(
+            client.try_archive_plan(&Address::generate(&env), &PlanKind::Lock, &lock_id),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_archive_plan(&user, &PlanKind::Flexi, &lock_id),
+            Err(Ok(SavingsError::InvalidPlanConfig))
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use soroban_sdk::{token, Address, Env};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::storage_types::{AssetConfig, ConfigKey, DataKey, ParamKey};
+use crate::strategy;
+
+/// Decimals amount thresholds (minimum deposit, dust, withdraw limit) are
+/// configured in, those of a standard Stellar asset
+pub const REFERENCE_DECIMALS: u32 = 7;
+
+/// Most decimals a registered asset may use
+pub const MAX_ASSET_DECIMALS: u32 = 18;
+
+pub fn get_asset_config(env: &Env, token: &Address) -> Option<AssetConfig> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::Param(ParamKey::AssetConfig(
+            token.clone(),
+        ))))
+}
+
+/// Records the decimals the token reports, replacing any earlier record.
+///
+/// # Errors
+/// * `UnsupportedAsset` - If the token uses more than `MAX_ASSET_DECIMALS`
+pub fn register_asset(env: &Env, token: Address) -> Result<AssetConfig, SavingsError> {
+    let decimals = token::Client::new(env, &token).decimals();
+    if decimals > MAX_ASSET_DECIMALS {
+        return Err(SavingsError::UnsupportedAsset);
+    }
+
+    let config = AssetConfig {
+        token: token.clone(),
+        decimals,
+    };
+    env.storage().instance().set(
+        &DataKey::Config(ConfigKey::Param(ParamKey::AssetConfig(token.clone()))),
+        &config,
+    );
+    events::publish(env, events::AssetRegistered { token, decimals });
+    Ok(config)
+}
+
+/// Decimals of the deposit token, or `REFERENCE_DECIMALS` while no token is
+/// configured or it has not been registered
+pub fn deposit_decimals(env: &Env) -> u32 {
+    strategy::get_token(env)
+        .ok()
+        .and_then(|token| get_asset_config(env, &token))
+        .map_or(REFERENCE_DECIMALS, |config| config.decimals)
+}
+
+/// Converts an amount in reference units into base units of the deposit
+/// token, rounding down and saturating at `i128::MAX`
+pub fn to_token_units(env: &Env, amount: i128) -> i128 {
+    let decimals = deposit_decimals(env);
+    if decimals >= REFERENCE_DECIMALS {
+        amount.saturating_mul(10i128.pow(decimals - REFERENCE_DECIMALS))
+    } else {
+        amount / 10i128.pow(REFERENCE_DECIMALS - decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AssetConfig, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, BytesN, Env};
+
+    /// Token exposing only the decimals it reports
+    #[contract]
+    pub struct MockToken;
+
+    #[contractimpl]
+    impl MockToken {
+        pub fn decimals(_env: Env) -> u32 {
+            6
+        }
+    }
+
+    /// Token claiming more decimals than amounts can safely be scaled by
+    #[contract]
+    pub struct WideToken;
+
+    #[contractimpl]
+    impl WideToken {
+        pub fn decimals(_env: Env) -> u32 {
+            24
+        }
+    }
+
+    #[test]
+    fn test_thresholds_follow_registered_decimals() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+        // 1 token in 7-decimal reference units
+        client.set_minimum_deposit(&admin, &10_000_000);
+
+        let token = env.register(MockToken, ());
+        client.set_token(&admin, &token);
+        // Unregistered tokens are taken to use the reference decimals
+        assert_eq!(client.get_asset_config(&token), None);
+        assert_eq!(
+            client.try_deposit_flexi(&user, &1_000_000),
+            Err(Ok(SavingsError::AmountBelowMinimum))
+        );
+
+        let config = client.register_asset(&admin, &token);
+        assert_eq!(
+            config,
+            AssetConfig {
+                token: token.clone(),
+                decimals: 6
+            }
+        );
+        assert_eq!(client.get_asset_config(&token), Some(config));
+        client.deposit_flexi(&user, &1_000_000);
+        assert_eq!(
+            client.try_deposit_flexi(&user, &999_999),
+            Err(Ok(SavingsError::AmountBelowMinimum))
+        );
+
+        let wide = env.register(WideToken, ());
+        assert_eq!(
+            client.try_register_asset(&admin, &wide),
+            Err(Ok(SavingsError::UnsupportedAsset))
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use soroban_sdk::{Address, BytesN, Env};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::goal;
+use crate::group;
+use crate::guard::{self, Op};
+use crate::storage_types::{DataKey, PlanKind};
+
+/// Attaches a content hash (e.g. an IPFS CID digest) to a goal or group.
+///
+/// Only the goal owner or group creator may set it; setting it again replaces
+/// the previous hash.
+pub fn set_attachment_hash(
+    env: &Env,
+    user: Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    hash: BytesN<32>,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let owner = match plan_kind {
+        PlanKind::Goal => {
+            goal::get_goal_save(env, plan_id)
+                .ok_or(SavingsError::PlanNotFound)?
+                .owner
+        }
+        PlanKind::Group => {
+            group::get_group_save(env, plan_id)
+                .ok_or(SavingsError::PlanNotFound)?
+                .creator
+        }
+        PlanKind::Flexi | PlanKind::Lock => return Err(SavingsError::InvalidPlanConfig),
+    };
+    if owner != user {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::AttachmentHash(plan_kind, plan_id), &hash);
+    events::publish(
+        env,
+        events::AttachmentSet {
+            plan_kind,
+            plan_id,
+            hash,
+        },
+    );
+
+    Ok(())
+}
+
+pub fn get_attachment_hash(env: &Env, plan_kind: PlanKind, plan_id: u64) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::AttachmentHash(plan_kind, plan_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::PlanKind;
+    use crate::{GroupMetadata, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String, Symbol};
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        (env, client, user)
+    }
+
+    #[test]
+    fn test_set_and_get_goal_attachment() {
+        let (env, client, user) = setup();
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "house"), &5_000, &100);
+        let hash = BytesN::from_array(&env, &[7u8; 32]);
+
+        assert_eq!(client.get_attachment_hash(&PlanKind::Goal, &goal_id), None);
+        client.set_attachment_hash(&user, &PlanKind::Goal, &goal_id, &hash);
+        assert_eq!(
+            client.get_attachment_hash(&PlanKind::Goal, &goal_id),
+            Some(hash)
+        );
+    }
+
+    #[test]
+    fn test_group_attachment_requires_creator() {
+        let (env, client, user) = setup();
+        let group_id = client.create_group_save(
+            &user,
+            &GroupMetadata {
+                title: String::from_str(&env, "Circle"),
+                description: String::from_str(&env, "Description"),
+                category: String::from_str(&env, "savings"),
+            },
+            &5000,
+            &0,
+            &100,
+            &true,
+            &1,
+            &500,
+            &0,
+        );
+        let hash = BytesN::from_array(&env, &[9u8; 32]);
+        let stranger = Address::generate(&env);
+
+        assert_eq!(
+            client
+                .try_set_attachment_hash(&stranger, &PlanKind::Group, &group_id, &hash)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+
+        client.set_attachment_hash(&user, &PlanKind::Group, &group_id, &hash);
+        assert_eq!(
+            client.get_attachment_hash(&PlanKind::Group, &group_id),
+            Some(hash)
+        );
+        // Attachments are keyed by plan kind, so the same ID on a goal is unaffected
+        assert_eq!(client.get_attachment_hash(&PlanKind::Goal, &group_id), None);
+    }
+
+    #[test]
+    fn test_attachment_rejects_unsupported_or_missing_plans() {
+        let (env, client, user) = setup();
+        let hash = BytesN::from_array(&env, &[1u8; 32]);
+
+        assert_eq!(
+            client
+                .try_set_attachment_hash(&user, &PlanKind::Lock, &1, &hash)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidPlanConfig)
+        );
+        assert_eq!(
+            client
+                .try_set_attachment_hash(&user, &PlanKind::Goal, &99, &hash)
+                .unwrap_err(),
+            Ok(SavingsError::PlanNotFound)
+        );
+    }
+}
@@ -1,8 +1,21 @@
 use crate::errors::SavingsError;
+use crate::events;
+use crate::fees;
 use crate::flexi;
-use crate::storage_types::{AutoSave, DataKey};
+use crate::goal;
+use crate::group;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::limits;
+use crate::lock;
+use crate::pagination;
+use crate::signing;
+use crate::storage_types::{
+    AutoSave, AutoSaveCatchUp, AutoSaveConsent, AutoSaveLeg, AutoSaveLegResult, AutoSaveSource,
+    AutoSaveTarget, ConfigKey, Cursor, DataKey, PlanKind, TxKind, UserConsent,
+};
 use crate::users;
-use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{xdr::ToXdr, Address, BytesN, Env, Vec};
 
 /// Creates a new AutoSave schedule for recurring Flexi deposits
 ///
@@ -23,8 +36,186 @@ pub fn create_autosave(
     interval_seconds: u64,
     start_time: u64,
 ) -> Result<u64, SavingsError> {
+    guard::check(env, &user, Op::CreatePlan, amount)?;
     user.require_auth();
 
+    insert_autosave(
+        env,
+        &user,
+        amount,
+        interval_seconds,
+        start_time,
+        AutoSaveSource::ExternalToken,
+        Vec::new(env),
+    )
+}
+
+/// Most legs a split AutoSave schedule may have
+pub const MAX_AUTOSAVE_LEGS: u32 = 5;
+
+/// Creates an AutoSave schedule whose executions are split across several
+/// plans, e.g. 50% to Flexi, 30% to a goal and 20% to a group.
+///
+/// With `AutoSaveSource::FlexiBalance` each execution moves funds out of the
+/// user's Flexi balance instead of depositing new ones.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If there are no legs, more than `MAX_AUTOSAVE_LEGS`,
+///   a leg with a zero percent, the percents do not sum to 100, or a
+///   Flexi-funded schedule has a Flexi leg
+pub fn create_split_autosave(
+    env: &Env,
+    user: Address,
+    amount: i128,
+    interval_seconds: u64,
+    start_time: u64,
+    source: AutoSaveSource,
+    split: Vec<AutoSaveLeg>,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &user, Op::CreatePlan, amount)?;
+    user.require_auth();
+
+    validate_split(&source, &split)?;
+    insert_autosave(
+        env,
+        &user,
+        amount,
+        interval_seconds,
+        start_time,
+        source,
+        split,
+    )
+}
+
+fn validate_split(source: &AutoSaveSource, split: &Vec<AutoSaveLeg>) -> Result<(), SavingsError> {
+    if split.is_empty() || split.len() > MAX_AUTOSAVE_LEGS {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    let mut total: u32 = 0;
+    for leg in split.iter() {
+        if leg.percent == 0 {
+            return Err(SavingsError::InvalidPlanConfig);
+        }
+        if *source == AutoSaveSource::FlexiBalance && leg.target == AutoSaveTarget::Flexi {
+            return Err(SavingsError::InvalidPlanConfig);
+        }
+        total = total
+            .checked_add(leg.percent)
+            .ok_or(SavingsError::InvalidPlanConfig)?;
+    }
+
+    if total != 100 {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    Ok(())
+}
+
+/// Registers the Ed25519 key a user signs autosave consents with
+pub fn set_consent_key(
+    env: &Env,
+    user: Address,
+    public_key: BytesN<32>,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let next_nonce = get_consent(env, &user).map(|c| c.next_nonce).unwrap_or(0);
+    env.storage().persistent().set(
+        &DataKey::UserConsent(user),
+        &UserConsent {
+            public_key,
+            next_nonce,
+        },
+    );
+    Ok(())
+}
+
+/// Gets a user's registered consent key and next nonce
+pub fn get_consent(env: &Env, user: &Address) -> Option<UserConsent> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserConsent(user.clone()))
+}
+
+/// Creates an AutoSave schedule on a user's behalf from a signed consent
+///
+/// The partner submitting the call authorizes it; the user's authorization is
+/// the Ed25519 signature over the consent's `SignedEnvelope` (see `signing`),
+/// checked against the key they registered with `set_consent_key`.
+///
+/// # Errors
+/// * `InvalidSignature` - If the user has no consent key or the nonce is not the next one
+/// * `TooLate` - If the consent has expired
+pub fn create_autosave_with_consent(
+    env: &Env,
+    partner: Address,
+    consent: AutoSaveConsent,
+    signature: BytesN<64>,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &consent.user, Op::CreatePlan, consent.amount)?;
+    partner.require_auth();
+
+    let mut user_consent = get_consent(env, &consent.user).ok_or(SavingsError::InvalidSignature)?;
+
+    if env.ledger().timestamp() > consent.expiry {
+        return Err(SavingsError::TooLate);
+    }
+    if consent.nonce != user_consent.next_nonce {
+        return Err(SavingsError::InvalidSignature);
+    }
+
+    // Panics (and rolls back the call) if the signature does not match
+    signing::verify(
+        env,
+        &user_consent.public_key,
+        signing::AUTOSAVE_CONSENT,
+        consent.nonce,
+        consent.clone().to_xdr(env),
+        &signature,
+    );
+
+    user_consent.next_nonce += 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserConsent(consent.user.clone()), &user_consent);
+
+    let schedule_id = insert_autosave(
+        env,
+        &consent.user,
+        consent.amount,
+        consent.interval_seconds,
+        consent.start_time,
+        AutoSaveSource::ExternalToken,
+        Vec::new(env),
+    )?;
+
+    events::publish(
+        env,
+        events::AutoSaveDelegated {
+            partner,
+            user: consent.user,
+            schedule_id,
+        },
+    );
+
+    Ok(schedule_id)
+}
+
+/// Validates and stores a new schedule for `user`
+fn insert_autosave(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+    interval_seconds: u64,
+    start_time: u64,
+    source: AutoSaveSource,
+    split: Vec<AutoSaveLeg>,
+) -> Result<u64, SavingsError> {
     // Validate amount
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
@@ -36,9 +227,13 @@ pub fn create_autosave(
     }
 
     // Ensure user exists
-    if !users::user_exists(env, &user) {
+    if !users::user_exists(env, user) {
         return Err(SavingsError::UserNotFound);
     }
+    limits::ensure_plan_capacity(
+        limits::get_plan_limits(env).max_autosaves,
+        count_active_autosaves(env, user),
+    )?;
 
     // Generate unique schedule ID
     let schedule_id = get_next_schedule_id(env);
@@ -51,6 +246,11 @@ pub fn create_autosave(
         interval_seconds,
         next_execution_time: start_time,
         is_active: true,
+        is_paused: false,
+        source,
+        catch_up: AutoSaveCatchUp::SkipMissed,
+        split,
+        last_results: Vec::new(env),
     };
 
     // Store the schedule
@@ -59,7 +259,8 @@ pub fn create_autosave(
         .set(&DataKey::AutoSave(schedule_id), &schedule);
 
     // Link schedule to user
-    add_schedule_to_user(env, &user, schedule_id);
+    add_schedule_to_user(env, user, schedule_id);
+    index_add(env, schedule_id, start_time);
 
     // Increment the next schedule ID
     increment_next_schedule_id(env);
@@ -77,15 +278,64 @@ pub fn create_autosave(
 /// * `Ok(())` - If execution succeeds
 /// * `Err(SavingsError)` - If the schedule is not found, inactive, or not yet due
 pub fn execute_autosave(env: &Env, schedule_id: u64) -> Result<(), SavingsError> {
+    let mut schedule = get_due_autosave(env, schedule_id)?;
+    run_autosave(env, &mut schedule)?;
+    Ok(())
+}
+
+/// Keeper batch: executes every due schedule in `schedule_ids`.
+///
+/// Returns one flag per ID. Schedules that are unknown, inactive, not yet due
+/// or whose Flexi source cannot cover the amount are left untouched and
+/// reported as `false`, so one underfunded user does not block the batch.
+/// `keeper` is tipped for the schedules executed, other than its own.
+pub fn execute_autosave_batch(
+    env: &Env,
+    keeper: Address,
+    schedule_ids: Vec<u64>,
+) -> Result<Vec<bool>, SavingsError> {
+    keeper.require_auth();
+
+    let mut executed = Vec::new(env);
+    let mut tipped_executions: u32 = 0;
+    for schedule_id in schedule_ids.iter() {
+        let ran = match get_due_autosave(env, schedule_id) {
+            Ok(mut schedule) if has_source_funds(env, &schedule) => {
+                let amount = run_autosave(env, &mut schedule)?;
+                events::publish(
+                    env,
+                    events::AutoSaveExecuted {
+                        keeper: keeper.clone(),
+                        schedule_id,
+                        amount,
+                    },
+                );
+                if schedule.user != keeper {
+                    tipped_executions += 1;
+                }
+                true
+            }
+            _ => false,
+        };
+        executed.push_back(ran);
+    }
+
+    fees::pay_keeper_tip(env, &keeper, tipped_executions)?;
+
+    Ok(executed)
+}
+
+/// Loads a schedule, checking it is active and due
+fn get_due_autosave(env: &Env, schedule_id: u64) -> Result<AutoSave, SavingsError> {
     // Fetch the schedule
-    let mut schedule: AutoSave = env
+    let schedule: AutoSave = env
         .storage()
         .persistent()
         .get(&DataKey::AutoSave(schedule_id))
         .ok_or(SavingsError::PlanNotFound)?;
 
-    // Ensure schedule is active
-    if !schedule.is_active {
+    // Ensure schedule is active and not paused
+    if !schedule.is_active || schedule.is_paused {
         return Err(SavingsError::InvalidPlanConfig);
     }
 
@@ -95,18 +345,132 @@ pub fn execute_autosave(env: &Env, schedule_id: u64) -> Result<(), SavingsError>
         return Err(SavingsError::InvalidTimestamp);
     }
 
-    // Perform Flexi deposit
-    flexi::flexi_deposit(env.clone(), schedule.user.clone(), schedule.amount)?;
+    Ok(schedule)
+}
+
+/// Number of intervals a due schedule has fallen due for, including the
+/// current one
+fn intervals_due(env: &Env, schedule: &AutoSave) -> u64 {
+    (env.ledger().timestamp() - schedule.next_execution_time) / schedule.interval_seconds + 1
+}
 
-    // Update next execution time
-    schedule.next_execution_time += schedule.interval_seconds;
+/// Amount the next run of a due schedule deposits under its catch-up policy
+fn run_amount(env: &Env, schedule: &AutoSave) -> Result<i128, SavingsError> {
+    let executions = match schedule.catch_up {
+        AutoSaveCatchUp::SkipMissed => 1,
+        AutoSaveCatchUp::ExecuteAll(max) => intervals_due(env, schedule).min(max as u64),
+    };
+    schedule
+        .amount
+        .checked_mul(executions as i128)
+        .ok_or(SavingsError::Overflow)
+}
+
+/// Whether the schedule's source can fund its next run
+fn has_source_funds(env: &Env, schedule: &AutoSave) -> bool {
+    match schedule.source {
+        AutoSaveSource::ExternalToken => true,
+        AutoSaveSource::FlexiBalance => run_amount(env, schedule).is_ok_and(|amount| {
+            flexi::get_flexi_balance(env, schedule.user.clone()).unwrap_or(0) >= amount
+        }),
+    }
+}
+
+/// Performs one run of a due schedule and advances it, returning the amount
+/// deposited
+///
+/// Missed executions the catch-up policy keeps are deposited together in this
+/// run, and the next execution moves to the first grid point after now.
+fn run_autosave(env: &Env, schedule: &mut AutoSave) -> Result<i128, SavingsError> {
+    let amount = run_amount(env, schedule)?;
+
+    // Perform the deposits; any failing leg reverts the whole execution
+    schedule.last_results = if schedule.split.is_empty() {
+        flexi::flexi_deposit(env.clone(), schedule.user.clone(), amount)?;
+        let mut results = Vec::new(env);
+        results.push_back(AutoSaveLegResult {
+            target: AutoSaveTarget::Flexi,
+            amount,
+        });
+        results
+    } else {
+        execute_split(env, schedule, amount)?
+    };
+
+    // Realign the next execution time to the schedule grid
+    index_remove(env, schedule.id, schedule.next_execution_time);
+    schedule.next_execution_time += intervals_due(env, schedule) * schedule.interval_seconds;
+    index_add(env, schedule.id, schedule.next_execution_time);
 
     // Save updated schedule
     env.storage()
         .persistent()
-        .set(&DataKey::AutoSave(schedule_id), &schedule);
+        .set(&DataKey::AutoSave(schedule.id), schedule);
 
-    Ok(())
+    Ok(amount)
+}
+
+/// Deposits each leg's share of `total` into its target.
+///
+/// Shares are rounded down and the rounding remainder goes to the last leg,
+/// so the legs always add up to `total`.
+fn execute_split(
+    env: &Env,
+    schedule: &AutoSave,
+    total: i128,
+) -> Result<Vec<AutoSaveLegResult>, SavingsError> {
+    schedule.user.require_auth();
+
+    if schedule.source == AutoSaveSource::FlexiBalance {
+        flexi::debit_flexi(env, &schedule.user, total)?;
+        history::record(
+            env,
+            &schedule.user,
+            TxKind::Convert,
+            PlanKind::Flexi,
+            0,
+            total,
+            0,
+        );
+    }
+
+    let mut results = Vec::new(env);
+    let mut remaining = total;
+    let last = schedule.split.len() - 1;
+
+    for (i, leg) in schedule.split.iter().enumerate() {
+        let amount = if i as u32 == last {
+            remaining
+        } else {
+            total
+                .checked_mul(leg.percent as i128)
+                .ok_or(SavingsError::Overflow)?
+                / 100
+        };
+        remaining -= amount;
+
+        if amount > 0 {
+            let user = &schedule.user;
+            guard::check(env, user, Op::Deposit, amount)?;
+            apply_target_deposit(env, user, &leg.target, amount)?;
+        }
+
+        results.push_back(AutoSaveLegResult {
+            target: leg.target,
+            amount,
+        });
+    }
+
+    events::publish(
+        env,
+        events::AutoSaveSplit {
+            user: schedule.user.clone(),
+            schedule_id: schedule.id,
+            results: results.clone(),
+        },
+    );
+
+    Ok(results)
 }
 
 /// Cancels an AutoSave schedule
@@ -120,22 +484,14 @@ pub fn execute_autosave(env: &Env, schedule_id: u64) -> Result<(), SavingsError>
 /// * `Ok(())` - If cancellation succeeds
 /// * `Err(SavingsError)` - If the schedule is not found or user is not the owner
 pub fn cancel_autosave(env: &Env, user: Address, schedule_id: u64) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
     user.require_auth();
 
-    // Fetch the schedule
-    let mut schedule: AutoSave = env
-        .storage()
-        .persistent()
-        .get(&DataKey::AutoSave(schedule_id))
-        .ok_or(SavingsError::PlanNotFound)?;
-
-    // Ensure caller owns the schedule
-    if schedule.user != user {
-        return Err(SavingsError::Unauthorized);
-    }
+    let mut schedule = get_owned_autosave(env, &user, schedule_id)?;
 
     // Deactivate the schedule
     schedule.is_active = false;
+    index_remove(env, schedule_id, schedule.next_execution_time);
 
     // Save updated schedule
     env.storage()
@@ -145,6 +501,183 @@ pub fn cancel_autosave(env: &Env, user: Address, schedule_id: u64) -> Result<(),
     Ok(())
 }
 
+/// Pauses an AutoSave schedule until its owner resumes it
+///
+/// # Errors
+/// * `PlanNotFound` - If the schedule does not exist
+/// * `Unauthorized` - If `user` does not own the schedule
+/// * `InvalidPlanConfig` - If the schedule is cancelled or already paused
+pub fn pause_autosave(env: &Env, user: Address, schedule_id: u64) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let mut schedule = get_owned_autosave(env, &user, schedule_id)?;
+    if !schedule.is_active || schedule.is_paused {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    schedule.is_paused = true;
+    index_remove(env, schedule_id, schedule.next_execution_time);
+    env.storage()
+        .persistent()
+        .set(&DataKey::AutoSave(schedule_id), &schedule);
+
+    events::publish(env, events::AutoSavePaused { user, schedule_id });
+
+    Ok(())
+}
+
+/// Resumes a paused AutoSave schedule
+///
+/// Executions that fell due while paused are skipped: the next execution
+/// moves forward along the schedule's interval to the first one not yet past.
+///
+/// # Errors
+/// * `PlanNotFound` - If the schedule does not exist
+/// * `Unauthorized` - If `user` does not own the schedule
+/// * `InvalidPlanConfig` - If the schedule is cancelled or not paused
+pub fn resume_autosave(env: &Env, user: Address, schedule_id: u64) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let mut schedule = get_owned_autosave(env, &user, schedule_id)?;
+    if !schedule.is_active || !schedule.is_paused {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    let now = env.ledger().timestamp();
+    if schedule.next_execution_time < now {
+        let missed = (now - schedule.next_execution_time).div_ceil(schedule.interval_seconds);
+        schedule.next_execution_time += missed * schedule.interval_seconds;
+    }
+    schedule.is_paused = false;
+    index_add(env, schedule_id, schedule.next_execution_time);
+    env.storage()
+        .persistent()
+        .set(&DataKey::AutoSave(schedule_id), &schedule);
+
+    events::publish(
+        env,
+        events::AutoSaveResumed {
+            user,
+            schedule_id,
+            next_execution_time: schedule.next_execution_time,
+        },
+    );
+
+    Ok(())
+}
+
+/// Changes the amount and interval of an AutoSave schedule
+///
+/// The next execution time, split and execution history are kept; the new
+/// interval applies from the next execution on.
+///
+/// # Errors
+/// * `PlanNotFound` - If the schedule does not exist
+/// * `Unauthorized` - If `user` does not own the schedule
+/// * `InvalidPlanConfig` - If the schedule is cancelled
+/// * `InvalidAmount` - If `new_amount` is not positive
+/// * `InvalidTimestamp` - If `new_interval` is zero
+pub fn update_autosave(
+    env: &Env,
+    user: Address,
+    schedule_id: u64,
+    new_amount: i128,
+    new_interval: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::CreatePlan, new_amount)?;
+    user.require_auth();
+
+    let mut schedule = get_owned_autosave(env, &user, schedule_id)?;
+    if !schedule.is_active {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if new_amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if new_interval == 0 {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    schedule.amount = new_amount;
+    schedule.interval_seconds = new_interval;
+    env.storage()
+        .persistent()
+        .set(&DataKey::AutoSave(schedule_id), &schedule);
+
+    events::publish(
+        env,
+        events::AutoSaveUpdated {
+            user,
+            schedule_id,
+            amount: new_amount,
+            interval: new_interval,
+        },
+    );
+
+    Ok(())
+}
+
+/// Most missed executions an `ExecuteAll` catch-up policy may deposit in one run
+pub const MAX_AUTOSAVE_CATCH_UP: u32 = 12;
+
+/// Sets how an AutoSave schedule handles executions missed by a late run
+///
+/// # Errors
+/// * `PlanNotFound` - If the schedule does not exist
+/// * `Unauthorized` - If `user` does not own the schedule
+/// * `InvalidPlanConfig` - If the schedule is cancelled, or an `ExecuteAll`
+///   limit is zero or above `MAX_AUTOSAVE_CATCH_UP`
+pub fn set_autosave_catch_up(
+    env: &Env,
+    user: Address,
+    schedule_id: u64,
+    catch_up: AutoSaveCatchUp,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let mut schedule = get_owned_autosave(env, &user, schedule_id)?;
+    if !schedule.is_active {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if let AutoSaveCatchUp::ExecuteAll(max) = catch_up {
+        if max == 0 || max > MAX_AUTOSAVE_CATCH_UP {
+            return Err(SavingsError::InvalidPlanConfig);
+        }
+    }
+
+    schedule.catch_up = catch_up.clone();
+    env.storage()
+        .persistent()
+        .set(&DataKey::AutoSave(schedule_id), &schedule);
+
+    events::publish(
+        env,
+        events::AutoSaveCatchUpSet {
+            user,
+            schedule_id,
+            catch_up,
+        },
+    );
+
+    Ok(())
+}
+
+/// Loads a schedule, checking `user` owns it
+fn get_owned_autosave(
+    env: &Env,
+    user: &Address,
+    schedule_id: u64,
+) -> Result<AutoSave, SavingsError> {
+    let schedule = get_autosave(env, schedule_id).ok_or(SavingsError::PlanNotFound)?;
+    if schedule.user != *user {
+        return Err(SavingsError::Unauthorized);
+    }
+    Ok(schedule)
+}
+
 /// Gets an AutoSave schedule by ID
 pub fn get_autosave(env: &Env, schedule_id: u64) -> Option<AutoSave> {
     env.storage()
@@ -160,9 +693,156 @@ pub fn get_user_autosaves(env: &Env, user: &Address) -> Vec<u64> {
         .unwrap_or(Vec::new(env))
 }
 
+/// Deletes every schedule of the user, taking live ones off the due index
+pub(crate) fn remove_user_autosaves(env: &Env, user: &Address) {
+    for schedule_id in get_user_autosaves(env, user).iter() {
+        if let Some(schedule) = get_autosave(env, schedule_id) {
+            index_remove(env, schedule_id, schedule.next_execution_time);
+            env.storage()
+                .persistent()
+                .remove(&DataKey::AutoSave(schedule_id));
+        }
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::UserAutoSaves(user.clone()));
+}
+
+/// Number of the user's schedules that have not been cancelled
+pub fn count_active_autosaves(env: &Env, user: &Address) -> u32 {
+    get_user_autosaves(env, user)
+        .iter()
+        .filter(|id| get_autosave(env, *id).is_some_and(|s| s.is_active))
+        .count() as u32
+}
+
+/// Width, in seconds, of the time buckets the due-schedule index is kept in
+pub const AUTOSAVE_BUCKET_SECONDS: u64 = 3_600;
+
+/// Returns one page of the IDs of runnable schedules whose next execution is
+/// before `before_timestamp`, earliest bucket first.
+///
+/// Lets keepers find due schedules without tracking IDs off-chain. Cancelled
+/// and paused schedules are not listed. The cursor pages over the index
+/// entries of every bucket that starts before `before_timestamp`, and only
+/// the schedules on the requested page are loaded, so a page can hold fewer
+/// IDs than its limit when the last bucket has schedules that are not yet due.
+pub fn get_due_autosaves(
+    env: &Env,
+    before_timestamp: u64,
+    cursor: Cursor,
+) -> (Vec<u64>, Option<Cursor>) {
+    let limit = pagination::effective_limit(&cursor);
+    let start = cursor.offset;
+    let end = start.saturating_add(limit);
+
+    let mut due = Vec::new(env);
+    let mut position: u32 = 0;
+    for bucket in get_index_buckets(env).iter() {
+        if bucket * AUTOSAVE_BUCKET_SECONDS >= before_timestamp {
+            break;
+        }
+        // Empty buckets are dropped from the index, so another due bucket
+        // means another page
+        if position >= end {
+            return (due, Some(Cursor { offset: end, limit }));
+        }
+        let ids = get_index_bucket(env, bucket);
+        let bucket_end = position.saturating_add(ids.len());
+        for index in start.max(position)..end.min(bucket_end) {
+            let schedule_id = ids.get_unchecked(index - position);
+            if get_autosave(env, schedule_id)
+                .is_some_and(|s| s.next_execution_time < before_timestamp)
+            {
+                due.push_back(schedule_id);
+            }
+        }
+        if bucket_end > end {
+            return (due, Some(Cursor { offset: end, limit }));
+        }
+        position = bucket_end;
+    }
+    (due, None)
+}
+
+fn get_index_buckets(env: &Env) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::AutoSaveDueBuckets))
+        .unwrap_or(Vec::new(env))
+}
+
+fn get_index_bucket(env: &Env, bucket: u64) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::AutoSaveDueBucket(bucket)))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Lists a schedule in the bucket of its next execution time
+fn index_add(env: &Env, schedule_id: u64, execution_time: u64) {
+    let bucket = execution_time / AUTOSAVE_BUCKET_SECONDS;
+    let mut ids = get_index_bucket(env, bucket);
+    if ids.is_empty() {
+        // Keep the bucket list sorted so due buckets come first
+        let mut buckets = get_index_buckets(env);
+        let position = buckets
+            .iter()
+            .position(|b| b > bucket)
+            .unwrap_or(buckets.len() as usize);
+        buckets.insert(position as u32, bucket);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Config(ConfigKey::AutoSaveDueBuckets), &buckets);
+    }
+    ids.push_back(schedule_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::AutoSaveDueBucket(bucket)), &ids);
+}
+
+/// Lists a live schedule in the due index if it is missing from it, for
+/// schedules created before the index existed
+pub(crate) fn reindex_autosave(env: &Env, schedule_id: u64) {
+    let Some(schedule) = get_autosave(env, schedule_id) else {
+        return;
+    };
+    if !schedule.is_active || schedule.is_paused {
+        return;
+    }
+    let bucket = schedule.next_execution_time / AUTOSAVE_BUCKET_SECONDS;
+    if !get_index_bucket(env, bucket).contains(schedule_id) {
+        index_add(env, schedule_id, schedule.next_execution_time);
+    }
+}
+
+/// Drops a schedule from the bucket of `execution_time`, if listed there
+fn index_remove(env: &Env, schedule_id: u64, execution_time: u64) {
+    let bucket = execution_time / AUTOSAVE_BUCKET_SECONDS;
+    let key = DataKey::Config(ConfigKey::AutoSaveDueBucket(bucket));
+    let mut ids = get_index_bucket(env, bucket);
+    let Some(index) = ids.first_index_of(schedule_id) else {
+        return;
+    };
+    ids.remove(index);
+
+    if ids.is_empty() {
+        env.storage().persistent().remove(&key);
+        let mut buckets = get_index_buckets(env);
+        if let Some(index) = buckets.first_index_of(bucket) {
+            buckets.remove(index);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Config(ConfigKey::AutoSaveDueBuckets), &buckets);
+    } else {
+        env.storage().persistent().set(&key, &ids);
+    }
+}
+
 // ========== Helper Functions ==========
 
-fn get_next_schedule_id(env: &Env) -> u64 {
+pub(crate) fn get_next_schedule_id(env: &Env) -> u64 {
     env.storage()
         .persistent()
         .get(&DataKey::NextAutoSaveId)
@@ -187,3 +867,22 @@ fn add_schedule_to_user(env: &Env, user: &Address, schedule_id: u64) {
     schedules.push_back(schedule_id);
     env.storage().persistent().set(&key, &schedules);
 }
+
+/// Deposits `amount` into one target plan of the user.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn apply_target_deposit(
+    env: &Env,
+    user: &Address,
+    target: &AutoSaveTarget,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    match *target {
+        AutoSaveTarget::Flexi => flexi::apply_flexi_deposit(env, user, amount),
+        AutoSaveTarget::Goal(goal_id) => goal::apply_goal_deposit(env, user, goal_id, amount),
+        AutoSaveTarget::Lock(lock_id) => lock::apply_lock_deposit(env, user, lock_id, amount),
+        AutoSaveTarget::Group(group_id) => {
+            group::apply_group_contribution(env, user.clone(), group_id, amount)
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::autosave;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::guard::{self, Op};
+use crate::storage_types::DepositOp;
+use crate::users;
+
+/// Most deposits a single `batch_deposit` call may carry
+pub const MAX_BATCH_DEPOSIT_OPS: u32 = 10;
+
+/// Makes every deposit in `ops` under a single authorization, so a user
+/// funding several plans on payday signs and pays for one transaction.
+///
+/// The batch is atomic: if any deposit fails, none of them is applied.
+/// Each deposit still goes through the guard and counts towards streaks,
+/// badges and history on its own. Returns the total deposited.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `ops` is empty
+/// * `AmountExceedsLimit` - If `ops` holds more than `MAX_BATCH_DEPOSIT_OPS`
+/// * `UserNotFound` - If the user is not initialized
+/// * Any error of the individual deposits
+pub fn batch_deposit(env: &Env, user: Address, ops: Vec<DepositOp>) -> Result<i128, SavingsError> {
+    if ops.is_empty() {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if ops.len() > MAX_BATCH_DEPOSIT_OPS {
+        return Err(SavingsError::AmountExceedsLimit);
+    }
+    user.require_auth();
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let mut total: i128 = 0;
+    for op in ops.iter() {
+        guard::check(env, &user, Op::Deposit, op.amount)?;
+        autosave::apply_target_deposit(env, &user, &op.target, op.amount)?;
+        total = total.checked_add(op.amount).ok_or(SavingsError::Overflow)?;
+    }
+
+    events::publish(
+        env,
+        events::BatchDeposited {
+            user,
+            count: ops.len(),
+            total,
+        },
+    );
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::{AutoSaveTarget, DepositOp};
+    use crate::{GroupMetadata, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{testutils::Address as _, vec, Address, Env, String, Symbol};
+
+    #[test]
+    fn test_batch_deposit_funds_every_plan() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "car"), &5_000, &0);
+        let group_id = client.create_group_save(
+            &user,
+            &GroupMetadata {
+                title: String::from_str(&env, "Club"),
+                description: String::from_str(&env, "Saving club"),
+                category: String::from_str(&env, "savings"),
+            },
+            &10_000,
+            &1,
+            &100,
+            &true,
+            &1,
+            &1_000,
+            &0,
+        );
+
+        let total = client.batch_deposit(
+            &user,
+            &vec![
+                &env,
+                DepositOp {
+                    target: AutoSaveTarget::Flexi,
+                    amount: 300,
+                },
+                DepositOp {
+                    target: AutoSaveTarget::Goal(goal_id),
+                    amount: 200,
+                },
+                DepositOp {
+                    target: AutoSaveTarget::Group(group_id),
+                    amount: 100,
+                },
+            ],
+        );
+        // One signature covers the whole batch
+        assert_eq!(env.auths().len(), 1);
+        assert_eq!(total, 600);
+        assert_eq!(client.get_flexi_balance(&user), 300);
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 200);
+        let contribution = env.as_contract(&contract_id, || {
+            crate::group::get_member_contribution(&env, group_id, &user)
+        });
+        assert_eq!(contribution, 100);
+    }
+
+    #[test]
+    fn test_failed_op_reverts_whole_batch() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        assert_eq!(
+            client.try_batch_deposit(
+                &user,
+                &vec![
+                    &env,
+                    DepositOp {
+                        target: AutoSaveTarget::Flexi,
+                        amount: 300,
+                    },
+                    DepositOp {
+                        target: AutoSaveTarget::Goal(42),
+                        amount: 200,
+                    },
+                ],
+            ),
+            Err(Ok(SavingsError::PlanNotFound))
+        );
+        assert_eq!(client.get_flexi_balance(&user), 0);
+        assert_eq!(
+            client.try_batch_deposit(&user, &vec![&env]),
+            Err(Ok(SavingsError::InvalidPlanConfig))
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::flexi;
+use crate::guard::{self, Op};
+use crate::storage_types::{DataKey, FlexiBucket, UserKey};
+
+/// Most buckets a user may split their Flexi balance into
+pub const MAX_FLEXI_BUCKETS: u32 = 10;
+
+fn key(user: &Address) -> DataKey {
+    DataKey::UserState(UserKey::FlexiBuckets(user.clone()))
+}
+
+/// The user's buckets, in creation order
+pub fn get_buckets(env: &Env, user: &Address) -> Vec<FlexiBucket> {
+    env.storage()
+        .persistent()
+        .get(&key(user))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn set_buckets(env: &Env, user: &Address, buckets: &Vec<FlexiBucket>) {
+    if buckets.is_empty() {
+        env.storage().persistent().remove(&key(user));
+    } else {
+        env.storage().persistent().set(&key(user), buckets);
+    }
+}
+
+pub fn get_bucketed_total(env: &Env, user: &Address) -> i128 {
+    get_buckets(env, user)
+        .iter()
+        .fold(0i128, |sum, bucket| sum.saturating_add(bucket.balance))
+}
+
+/// Part of the user's Flexi balance not assigned to any bucket
+pub fn get_unallocated(env: &Env, user: &Address) -> Result<i128, SavingsError> {
+    Ok(flexi::get_flexi_balance(env, user.clone())? - get_bucketed_total(env, user))
+}
+
+fn find(buckets: &Vec<FlexiBucket>, name: &Symbol) -> Option<u32> {
+    buckets
+        .iter()
+        .position(|b| b.name == *name)
+        .map(|i| i as u32)
+}
+
+/// Adds an empty bucket named `name`.
+///
+/// # Errors
+/// * `UserNotFound` - If the user is not initialized
+/// * `InvalidPlanConfig` - If the user already has a bucket of that name
+/// * `MaxPlansExceeded` - If the user already has `MAX_FLEXI_BUCKETS`
+pub fn create_bucket(env: &Env, user: Address, name: Symbol) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+    flexi::get_flexi_balance(env, user.clone())?;
+
+    let mut buckets = get_buckets(env, &user);
+    if find(&buckets, &name).is_some() {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if buckets.len() >= MAX_FLEXI_BUCKETS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+    buckets.push_back(FlexiBucket {
+        name: name.clone(),
+        balance: 0,
+    });
+    set_buckets(env, &user, &buckets);
+
+    events::publish(env, events::BucketCreated { user, name });
+    Ok(())
+}
+
+/// Removes a bucket; its balance becomes unallocated again
+///
+/// # Errors
+/// * `PlanNotFound` - If the user has no bucket of that name
+pub fn delete_bucket(env: &Env, user: Address, name: Symbol) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let mut buckets = get_buckets(env, &user);
+    let index = find(&buckets, &name).ok_or(SavingsError::PlanNotFound)?;
+    let bucket = buckets.get_unchecked(index);
+    buckets.remove(index);
+    set_buckets(env, &user, &buckets);
+
+    events::publish(env, events::BucketDeleted { user, bucket });
+    Ok(())
+}
+
+/// Moves `amount` between two buckets, where `None` stands for the
+/// unallocated part of the Flexi balance. No funds move.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive or `from` equals `to`
+/// * `PlanNotFound` - If either bucket does not exist
+/// * `InsufficientBalance` - If the source holds less than `amount`
+pub fn move_between_buckets(
+    env: &Env,
+    user: Address,
+    from: Option<Symbol>,
+    to: Option<Symbol>,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+    if amount <= 0 || from == to {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let mut buckets = get_buckets(env, &user);
+    match &from {
+        Some(name) => {
+            let index = find(&buckets, name).ok_or(SavingsError::PlanNotFound)?;
+            let mut bucket = buckets.get_unchecked(index);
+            if bucket.balance < amount {
+                return Err(SavingsError::InsufficientBalance);
+            }
+            bucket.balance -= amount;
+            buckets.set(index, bucket);
+        }
+        None => {
+            if get_unallocated(env, &user)? < amount {
+                return Err(SavingsError::InsufficientBalance);
+            }
+        }
+    }
+    if let Some(name) = &to {
+        let index = find(&buckets, name).ok_or(SavingsError::PlanNotFound)?;
+        let mut bucket = buckets.get_unchecked(index);
+        bucket.balance = bucket
+            .balance
+            .checked_add(amount)
+            .ok_or(SavingsError::Overflow)?;
+        buckets.set(index, bucket);
+    }
+    set_buckets(env, &user, &buckets);
+
+    events::publish(
+        env,
+        events::BucketTransfer {
+            user,
+            from,
+            to,
+            amount,
+        },
+    );
+    Ok(())
+}
+
+/// Shrinks the user's buckets, newest first, until they fit within their
+/// Flexi balance again. Withdrawals draw on the whole balance, so they take
+/// from the unallocated part before any bucket.
+pub(crate) fn fit_to_balance(env: &Env, user: &Address, balance: i128) {
+    let mut buckets = get_buckets(env, user);
+    let mut excess = get_bucketed_total(env, user) - balance;
+    if excess <= 0 {
+        return;
+    }
+    let mut index = buckets.len();
+    while excess > 0 && index > 0 {
+        index -= 1;
+        let mut bucket = buckets.get_unchecked(index);
+        let taken = bucket.balance.min(excess);
+        bucket.balance -= taken;
+        excess -= taken;
+        buckets.set(index, bucket);
+    }
+    set_buckets(env, user, &buckets);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env};
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        (env, client, user)
+    }
+
+    #[test]
+    fn test_buckets_partition_flexi_balance() {
+        let (_env, client, user) = setup();
+        let rent = symbol_short!("rent");
+        let rainy = symbol_short!("rainy");
+        client.deposit_flexi(&user, &1_000);
+        client.create_bucket(&user, &rent);
+        client.create_bucket(&user, &rainy);
+        assert_eq!(
+            client.try_create_bucket(&user, &rent),
+            Err(Ok(SavingsError::InvalidPlanConfig))
+        );
+
+        client.move_between_buckets(&user, &None, &Some(rent.clone()), &600);
+        client.move_between_buckets(&user, &Some(rent.clone()), &Some(rainy.clone()), &200);
+        assert_eq!(
+            client.try_move_between_buckets(&user, &None, &Some(rent.clone()), &401),
+            Err(Ok(SavingsError::InsufficientBalance))
+        );
+        let buckets = client.get_flexi_buckets(&user);
+        assert_eq!(buckets.get(0).unwrap().balance, 400);
+        assert_eq!(buckets.get(1).unwrap().balance, 200);
+        assert_eq!(client.get_unallocated_flexi(&user), 400);
+
+        client.delete_bucket(&user, &rainy);
+        assert_eq!(client.get_unallocated_flexi(&user), 600);
+        assert_eq!(client.get_flexi_balance(&user), 1_000);
+    }
+
+    #[test]
+    fn test_withdrawals_trim_buckets_after_unallocated() {
+        let (_env, client, user) = setup();
+        let rent = symbol_short!("rent");
+        let trip = symbol_short!("trip");
+        client.deposit_flexi(&user, &1_000);
+        client.create_bucket(&user, &rent);
+        client.create_bucket(&user, &trip);
+        client.move_between_buckets(&user, &None, &Some(rent.clone()), &500);
+        client.move_between_buckets(&user, &None, &Some(trip.clone()), &300);
+
+        // 200 unallocated goes first, then the newest bucket
+        client.withdraw_flexi(&user, &350);
+        let buckets = client.get_flexi_buckets(&user);
+        assert_eq!(buckets.get(0).unwrap().balance, 500);
+        assert_eq!(buckets.get(1).unwrap().balance, 150);
+        assert_eq!(client.get_unallocated_flexi(&user), 0);
+        assert!(client.check_invariants(&user).ok);
+    }
+}
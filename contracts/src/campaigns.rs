@@ -0,0 +1,679 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::fees;
+use crate::flexi;
+use crate::goal;
+use crate::history;
+use crate::lock;
+use crate::pagination;
+use crate::storage_types::{
+    Campaign, CampaignAccrual, CampaignHolder, ConfigKey, Cursor, DataKey, ParamKey, PlanKind,
+    TxKind, UserKey,
+};
+use crate::users;
+
+/// Most campaigns that may be scheduled or running at once, keeping the
+/// work done on every deposit bounded
+pub const MAX_LIVE_CAMPAIGNS: u32 = 5;
+
+/// Starts a campaign paying `bonus_bps` of every deposit into a `plan_kind`
+/// plan made between `start_time` and `end_time`, funded with `budget` taken
+/// out of the treasury's fees.
+///
+/// Bonuses are paid into the depositor's Flexi Save when the plan is paid
+/// out: a Lock Save at maturity, a Goal Save once completed, and Flexi
+/// deposits on the first withdrawal after the campaign ends. Withdrawing
+/// Flexi funds while the campaign runs, or breaking a goal, forfeits the
+/// bonus back to the campaign. Callers are responsible for authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `budget` is not positive or `bonus_bps` is 0 or
+///   above 10_000
+/// * `InvalidTimestamp` - If the window is empty or has already ended
+/// * `InvalidPlanConfig` - If `plan_kind` is `Group`, whose funds are never
+///   paid out to a single depositor
+/// * `MaxPlansExceeded` - If `MAX_LIVE_CAMPAIGNS` campaigns have not ended
+/// * `InsufficientBalance` - If no treasury is set or it holds less than `budget`
+pub fn create_campaign(
+    env: &Env,
+    plan_kind: PlanKind,
+    start_time: u64,
+    end_time: u64,
+    bonus_bps: u32,
+    budget: i128,
+) -> Result<u64, SavingsError> {
+    if budget <= 0 || bonus_bps == 0 || bonus_bps > 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let now = env.ledger().timestamp();
+    if end_time <= start_time || end_time <= now {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    if plan_kind == PlanKind::Group {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    let mut live = Vec::new(env);
+    for campaign_id in get_live_campaigns(env).iter() {
+        if get_campaign(env, campaign_id).is_some_and(|c| c.end_time > now) {
+            live.push_back(campaign_id);
+        }
+    }
+    if live.len() >= MAX_LIVE_CAMPAIGNS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+
+    let recipient: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::FeeRecipient)
+        .ok_or(SavingsError::InsufficientBalance)?;
+    fees::debit_treasury(env, &recipient, budget)?;
+
+    let id_key = DataKey::Config(ConfigKey::NextCampaignId);
+    let id: u64 = env.storage().persistent().get(&id_key).unwrap_or(1);
+    env.storage().persistent().set(&id_key, &(id + 1));
+
+    let campaign = Campaign {
+        id,
+        plan_kind,
+        start_time,
+        end_time,
+        bonus_bps,
+        budget,
+        accrued: 0,
+        paid: 0,
+        qualifying_deposits: 0,
+        closed: false,
+    };
+    set_campaign(env, &campaign);
+
+    live.push_back(id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::LiveCampaigns), &live);
+    let mut all = get_all_campaigns(env);
+    all.push_back(id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::Campaigns), &all);
+
+    events::publish(
+        env,
+        events::CampaignCreated {
+            campaign_id: id,
+            campaign,
+        },
+    );
+    Ok(id)
+}
+
+/// Returns the part of an ended campaign's budget that was never earned to
+/// the treasury. Bonuses still held on plans that ended without settling
+/// them, or whose owner closed their account, are forfeited first. Bonuses
+/// forfeited later go straight to the treasury too. Returns the amount
+/// returned.
+///
+/// # Errors
+/// * `PlanNotFound` - If the campaign does not exist
+/// * `TooEarly` - If it has not ended yet
+/// * `PlanCompleted` - If it was already closed
+pub fn close_campaign(env: &Env, campaign_id: u64) -> Result<i128, SavingsError> {
+    let campaign = get_campaign(env, campaign_id).ok_or(SavingsError::PlanNotFound)?;
+    if env.ledger().timestamp() < campaign.end_time {
+        return Err(SavingsError::TooEarly);
+    }
+    if campaign.closed {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    for holder in get_campaign_holders(env, campaign_id).iter() {
+        if !holds_open_plan(env, &holder) {
+            settle(env, &holder.user, holder.plan_kind, holder.plan_id, |_| {
+                false
+            })?;
+        }
+    }
+    let mut campaign = get_campaign(env, campaign_id).ok_or(SavingsError::PlanNotFound)?;
+
+    let unused = remaining_budget(&campaign);
+    if unused > 0 && fees::credit_treasury(env, unused)?.is_none() {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    campaign.budget -= unused;
+    campaign.closed = true;
+    set_campaign(env, &campaign);
+
+    let mut live = get_live_campaigns(env);
+    if let Some(index) = live.first_index_of(campaign_id) {
+        live.remove(index);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Config(ConfigKey::LiveCampaigns), &live);
+    }
+
+    events::publish(
+        env,
+        events::CampaignClosed {
+            campaign_id,
+            unused,
+        },
+    );
+    Ok(unused)
+}
+
+/// Earns the user a bonus from every running campaign for `plan_kind`, as
+/// far as each campaign's budget still covers it. Called for every deposit.
+pub(crate) fn on_deposit(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    amount: i128,
+) {
+    let now = env.ledger().timestamp();
+    let mut accruals = get_campaign_bonuses(env, user, plan_kind, plan_id);
+    let mut changed = false;
+    for campaign_id in get_live_campaigns(env).iter() {
+        let Some(mut campaign) = get_campaign(env, campaign_id) else {
+            continue;
+        };
+        if campaign.plan_kind != plan_kind || now < campaign.start_time || now >= campaign.end_time
+        {
+            continue;
+        }
+        let remaining = remaining_budget(&campaign);
+        let bonus = (amount.saturating_mul(campaign.bonus_bps as i128) / 10_000).min(remaining);
+        if bonus <= 0 {
+            continue;
+        }
+
+        campaign.accrued += bonus;
+        campaign.qualifying_deposits = campaign.qualifying_deposits.saturating_add(amount);
+        set_campaign(env, &campaign);
+        if bonus == remaining {
+            events::publish(
+                env,
+                events::CampaignExhausted {
+                    campaign_id,
+                    budget: campaign.budget,
+                },
+            );
+        }
+
+        match accruals.iter().position(|a| a.campaign_id == campaign_id) {
+            Some(index) => {
+                let mut accrual = accruals.get_unchecked(index as u32);
+                accrual.amount += bonus;
+                accruals.set(index as u32, accrual);
+            }
+            None => {
+                accruals.push_back(CampaignAccrual {
+                    campaign_id,
+                    amount: bonus,
+                });
+                let mut holders = get_campaign_holders(env, campaign_id);
+                holders.push_back(CampaignHolder {
+                    user: user.clone(),
+                    plan_kind,
+                    plan_id,
+                });
+                set_campaign_holders(env, campaign_id, &holders);
+            }
+        }
+        changed = true;
+    }
+    if changed {
+        set_campaign_bonuses(env, user, plan_kind, plan_id, &accruals);
+    }
+}
+
+/// Settles every bonus the user earned on a plan being paid out (`paid`) or
+/// broken. Returns the amount credited to their Flexi Save.
+pub(crate) fn settle_plan(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    paid: bool,
+) -> Result<i128, SavingsError> {
+    settle(env, user, plan_kind, plan_id, |_| paid)
+}
+
+/// Settles the user's Flexi bonuses on a withdrawal: those of ended campaigns
+/// are paid, those of running campaigns forfeited. Returns the amount
+/// credited to their Flexi Save.
+pub(crate) fn settle_flexi(env: &Env, user: &Address) -> Result<i128, SavingsError> {
+    let now = env.ledger().timestamp();
+    settle(env, user, PlanKind::Flexi, 0, |campaign| {
+        now >= campaign.end_time
+    })
+}
+
+fn settle(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    pays: impl Fn(&Campaign) -> bool,
+) -> Result<i128, SavingsError> {
+    let accruals = get_campaign_bonuses(env, user, plan_kind, plan_id);
+    if accruals.is_empty() {
+        return Ok(0);
+    }
+
+    let mut credited = 0i128;
+    let holder = CampaignHolder {
+        user: user.clone(),
+        plan_kind,
+        plan_id,
+    };
+    for accrual in accruals.iter() {
+        let mut holders = get_campaign_holders(env, accrual.campaign_id);
+        if let Some(index) = holders.first_index_of(&holder) {
+            holders.remove(index);
+            set_campaign_holders(env, accrual.campaign_id, &holders);
+        }
+        let Some(mut campaign) = get_campaign(env, accrual.campaign_id) else {
+            continue;
+        };
+        campaign.accrued -= accrual.amount;
+        if pays(&campaign) {
+            campaign.paid += accrual.amount;
+            credited = credited
+                .checked_add(accrual.amount)
+                .ok_or(SavingsError::Overflow)?;
+            events::publish(
+                env,
+                events::CampaignBonusPaid {
+                    user: user.clone(),
+                    campaign_id: campaign.id,
+                    amount: accrual.amount,
+                },
+            );
+        } else {
+            // Once closed, the campaign keeps no spare budget
+            if campaign.closed {
+                campaign.budget -= accrual.amount;
+                fees::credit_treasury(env, accrual.amount)?;
+            }
+            events::publish(
+                env,
+                events::CampaignBonusForfeited {
+                    user: user.clone(),
+                    campaign_id: campaign.id,
+                    amount: accrual.amount,
+                },
+            );
+        }
+        set_campaign(env, &campaign);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::UserState(UserKey::CampaignBonus(
+            user.clone(),
+            plan_kind,
+            plan_id,
+        )));
+
+    if credited > 0 {
+        flexi::credit_flexi(env, user, credited)?;
+        history::record(env, user, TxKind::Credit, plan_kind, plan_id, credited, 0);
+    }
+    Ok(credited)
+}
+
+/// Forfeits every bonus the user still holds on a plan. Used when the
+/// account is closed.
+pub(crate) fn forfeit_plan(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+) -> Result<(), SavingsError> {
+    settle(env, user, plan_kind, plan_id, |_| false)?;
+    Ok(())
+}
+
+/// Hands the bonuses earned on a plan to its new owner when the plan changes
+/// hands, so they are settled with it.
+pub(crate) fn transfer_plan(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+) {
+    let accruals = get_campaign_bonuses(env, from, plan_kind, plan_id);
+    if accruals.is_empty() {
+        return;
+    }
+
+    let old_holder = CampaignHolder {
+        user: from.clone(),
+        plan_kind,
+        plan_id,
+    };
+    let mut merged = get_campaign_bonuses(env, to, plan_kind, plan_id);
+    for accrual in accruals.iter() {
+        let mut holders = get_campaign_holders(env, accrual.campaign_id);
+        if let Some(index) = holders.first_index_of(&old_holder) {
+            holders.remove(index);
+        }
+        match merged
+            .iter()
+            .position(|a| a.campaign_id == accrual.campaign_id)
+        {
+            Some(index) => {
+                let mut existing = merged.get_unchecked(index as u32);
+                existing.amount += accrual.amount;
+                merged.set(index as u32, existing);
+            }
+            None => {
+                merged.push_back(accrual.clone());
+                holders.push_back(CampaignHolder {
+                    user: to.clone(),
+                    plan_kind,
+                    plan_id,
+                });
+            }
+        }
+        set_campaign_holders(env, accrual.campaign_id, &holders);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::UserState(UserKey::CampaignBonus(
+            from.clone(),
+            plan_kind,
+            plan_id,
+        )));
+    set_campaign_bonuses(env, to, plan_kind, plan_id, &merged);
+}
+
+/// Whether the holder's bonus can still be settled by the plan paying out
+fn holds_open_plan(env: &Env, holder: &CampaignHolder) -> bool {
+    match holder.plan_kind {
+        PlanKind::Lock => lock::get_lock_save(env, holder.plan_id)
+            .is_some_and(|l| !l.is_withdrawn && l.owner == holder.user),
+        PlanKind::Goal => goal::get_goal_save(env, holder.plan_id).is_some_and(|g| !g.is_withdrawn),
+        _ => users::user_exists(env, &holder.user),
+    }
+}
+
+/// Plans holding an unsettled bonus from the campaign
+pub fn get_campaign_holders(env: &Env, campaign_id: u64) -> Vec<CampaignHolder> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::Param(
+            ParamKey::CampaignHolders(campaign_id),
+        )))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn set_campaign_holders(env: &Env, campaign_id: u64, holders: &Vec<CampaignHolder>) {
+    let key = DataKey::Config(ConfigKey::Param(ParamKey::CampaignHolders(campaign_id)));
+    if holders.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, holders);
+    }
+}
+
+/// Budget not yet earned by any deposit
+pub fn remaining_budget(campaign: &Campaign) -> i128 {
+    campaign.budget - campaign.accrued - campaign.paid
+}
+
+pub fn get_campaign(env: &Env, campaign_id: u64) -> Option<Campaign> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::Campaign(campaign_id)))
+}
+
+/// A page of every campaign, in creation order
+pub fn get_campaigns(env: &Env, cursor: Cursor) -> (Vec<Campaign>, Option<Cursor>) {
+    let (ids, next) = pagination::paginate(env, &get_all_campaigns(env), &cursor);
+    let mut page = Vec::new(env);
+    for campaign_id in ids.iter() {
+        if let Some(campaign) = get_campaign(env, campaign_id) {
+            page.push_back(campaign);
+        }
+    }
+    (page, next)
+}
+
+/// Bonuses the user has earned on a plan and not been paid yet
+pub fn get_campaign_bonuses(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+) -> Vec<CampaignAccrual> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::CampaignBonus(
+            user.clone(),
+            plan_kind,
+            plan_id,
+        )))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn set_campaign_bonuses(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    accruals: &Vec<CampaignAccrual>,
+) {
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::CampaignBonus(user.clone(), plan_kind, plan_id)),
+        accruals,
+    );
+}
+
+fn set_campaign(env: &Env, campaign: &Campaign) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::Campaign(campaign.id)), campaign);
+}
+
+fn get_live_campaigns(env: &Env) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::LiveCampaigns))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn get_all_campaigns(env: &Env) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::Campaigns))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::{Cursor, PlanKind};
+    use crate::{NesteraContract, NesteraContractClient, SavingsError, WithdrawalFeeConfig};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env,
+    };
+
+    #[test]
+    fn test_campaign_bonuses_paid_forfeited_and_budget_capped() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &treasury,
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&alice);
+        client.initialize_user(&bob);
+
+        // Fill the treasury with 1_000 of fees
+        client.set_withdrawal_fee(
+            &admin,
+            &WithdrawalFeeConfig {
+                bps: 1_000,
+                flat: 0,
+            },
+        );
+        client.deposit_flexi(&bob, &10_000);
+        client.withdraw_flexi(&bob, &10_000);
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 0, flat: 0 });
+
+        assert_eq!(
+            client
+                .try_create_campaign(&admin, &PlanKind::Group, &0, &1_000, &500, &100)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidPlanConfig)
+        );
+        assert_eq!(
+            client
+                .try_create_campaign(&admin, &PlanKind::Lock, &0, &1_000, &500, &1_001)
+                .unwrap_err(),
+            Ok(SavingsError::InsufficientBalance)
+        );
+        assert_eq!(
+            client
+                .try_create_campaign(&alice, &PlanKind::Lock, &0, &1_000, &500, &100)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+        let lock_campaign = client.create_campaign(&admin, &PlanKind::Lock, &0, &1_000, &500, &300);
+        let flexi_campaign =
+            client.create_campaign(&admin, &PlanKind::Flexi, &0, &1_000, &1_000, &200);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 500);
+
+        // 5% of each lock, until the 300 budget runs out
+        let alice_lock = client.create_lock_save(&alice, &4_000, &2_000);
+        let bob_lock = client.create_lock_save(&bob, &4_000, &2_000);
+        client.create_lock_save(&bob, &4_000, &2_000);
+        assert_eq!(
+            client
+                .get_campaign_bonuses(&bob, &PlanKind::Lock, &bob_lock)
+                .get(0)
+                .unwrap()
+                .amount,
+            100
+        );
+        let campaign = client.get_campaign(&lock_campaign).unwrap();
+        assert_eq!(
+            (campaign.accrued, campaign.qualifying_deposits),
+            (300, 8_000)
+        );
+
+        // Withdrawing Flexi funds mid-campaign forfeits the bonus earned so far
+        client.deposit_flexi(&alice, &1_000);
+        client.withdraw_flexi(&alice, &100);
+        client.deposit_flexi(&alice, &500);
+        assert_eq!(client.get_flexi_balance(&alice), 1_400);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.withdraw_flexi(&alice, &100);
+        assert_eq!(client.get_flexi_balance(&alice), 1_350);
+
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        client.withdraw_lock_save(&alice, &alice_lock);
+        assert_eq!(client.get_flexi_balance(&alice), 1_550);
+        assert!(client
+            .get_campaign_bonuses(&alice, &PlanKind::Lock, &alice_lock)
+            .is_empty());
+
+        // Unearned budget goes back to the treasury
+        assert_eq!(client.close_campaign(&admin, &lock_campaign), 0);
+        assert_eq!(client.close_campaign(&admin, &flexi_campaign), 150);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 650);
+        assert_eq!(
+            client
+                .try_close_campaign(&admin, &flexi_campaign)
+                .unwrap_err(),
+            Ok(SavingsError::PlanCompleted)
+        );
+
+        let (campaigns, next) = client.get_campaigns(&Cursor {
+            offset: 0,
+            limit: 0,
+        });
+        assert!(next.is_none());
+        let flexi = campaigns.get(1).unwrap();
+        assert_eq!((flexi.budget, flexi.accrued, flexi.paid), (50, 0, 50));
+        assert_eq!(campaigns.get(0).unwrap().paid, 200);
+    }
+
+    #[test]
+    fn test_bonuses_follow_swept_rolled_and_transferred_locks() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &treasury,
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&bob);
+        client.initialize_user(&carol);
+        client.set_withdrawal_fee(
+            &admin,
+            &WithdrawalFeeConfig {
+                bps: 1_000,
+                flat: 0,
+            },
+        );
+        client.deposit_flexi(&bob, &10_000);
+        client.withdraw_flexi(&bob, &10_000);
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 0, flat: 0 });
+
+        let campaign_id = client.create_campaign(&admin, &PlanKind::Lock, &0, &1_000, &1_000, &600);
+        let transferred = client.create_lock_save(&bob, &1_000, &2_000);
+        let swept = client.create_lock_save(&bob, &1_000, &2_000);
+        let rolled = client.create_lock_save(&bob, &1_000, &2_000);
+        let orphaned = client.create_lock_save(&bob, &1_000, &2_000);
+        client.set_lock_rollover(&bob, &rolled, &true);
+
+        // The bonus moves with the lock
+        client.transfer_lock_save(&bob, &carol, &transferred);
+        assert!(client
+            .get_campaign_bonuses(&bob, &PlanKind::Lock, &transferred)
+            .is_empty());
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        client.withdraw_lock_save(&carol, &transferred);
+        assert_eq!(client.get_flexi_balance(&carol), 100);
+
+        // Sweeps and rollovers pay it into Flexi
+        client.sweep_matured_locks(&Address::generate(&env), &soroban_sdk::vec![&env, swept]);
+        assert_eq!(client.get_flexi_balance(&bob), 1_100);
+        client.process_matured_locks(&soroban_sdk::vec![&env, rolled]);
+        assert_eq!(client.get_flexi_balance(&bob), 1_200);
+
+        // Closing releases bonuses stranded on plans that are gone
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .persistent()
+                .remove(&crate::DataKey::LockSave(orphaned));
+        });
+        assert_eq!(client.close_campaign(&admin, &campaign_id), 300);
+        let campaign = client.get_campaign(&campaign_id).unwrap();
+        assert_eq!((campaign.accrued, campaign.paid), (0, 300));
+    }
+}
@@ -0,0 +1,541 @@
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::achievements;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::flexi;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::pagination;
+use crate::roles;
+use crate::storage_types::{
+    Badge, ChallengeEnrollment, ChallengeStanding, ChallengeTemplate, ConfigKey, Cursor, DataKey,
+    PlanKind, TxKind, UserKey,
+};
+use crate::users;
+use crate::yield_pool;
+
+/// Most periods a challenge can span, enough for a weekly 52-week challenge
+pub const MAX_CHALLENGE_PERIODS: u32 = 52;
+
+/// Most unclaimed challenges a user can be enrolled in at once
+pub const MAX_ACTIVE_CHALLENGES: u32 = 5;
+
+/// Most users that can enroll in one challenge, keeping its leaderboard
+/// within budget
+pub const MAX_CHALLENGE_PARTICIPANTS: u32 = 100;
+
+/// Publishes a challenge: a schedule of amounts to deposit, one per period
+/// of `period_seconds`. Any user may publish one; only the admin may attach
+/// a completion `bonus`, since it is paid from the yield pool.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If the schedule is empty, longer than
+///   `MAX_CHALLENGE_PERIODS`, or has a zero period length
+/// * `InvalidAmount` - If an amount is not positive or the bonus is negative
+/// * `Unauthorized` - If a non-admin attaches a bonus
+pub fn create_challenge(
+    env: &Env,
+    creator: Address,
+    name: Symbol,
+    period_seconds: u64,
+    amounts: Vec<i128>,
+    bonus: i128,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    if amounts.is_empty() || amounts.len() > MAX_CHALLENGE_PERIODS || period_seconds == 0 {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if amounts.iter().any(|amount| amount <= 0) || bonus < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if bonus > 0 && !roles::is_admin(env, &creator) {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    let id: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::NextChallengeId))
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::NextChallengeId), &(id + 1));
+
+    let template = ChallengeTemplate {
+        id,
+        creator: creator.clone(),
+        name,
+        period_seconds,
+        amounts,
+        bonus,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::Challenge(id)), &template);
+
+    events::publish(
+        env,
+        events::ChallengeCreated {
+            creator,
+            challenge_id: id,
+        },
+    );
+    Ok(id)
+}
+
+/// Enrolls the user in a challenge starting now. Every deposit they make
+/// from then on counts towards the period it falls in.
+///
+/// # Errors
+/// * `UserNotFound` - If the user is not initialized
+/// * `PlanNotFound` - If the challenge does not exist
+/// * `InvalidPlanConfig` - If the user is already enrolled in it
+/// * `MaxPlansExceeded` - If the user has `MAX_ACTIVE_CHALLENGES` unclaimed
+///   challenges, or the challenge is full
+pub fn enroll_in_challenge(
+    env: &Env,
+    user: Address,
+    challenge_id: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    get_challenge(env, challenge_id).ok_or(SavingsError::PlanNotFound)?;
+
+    let mut participants = get_participants(env, challenge_id);
+    if participants.contains(&user) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    let mut active = get_user_challenges(env, &user);
+    if active.len() >= MAX_ACTIVE_CHALLENGES || participants.len() >= MAX_CHALLENGE_PARTICIPANTS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+
+    let enrollment = ChallengeEnrollment {
+        challenge_id,
+        started_at: env.ledger().timestamp(),
+        period: 0,
+        period_deposited: 0,
+        periods_met: 0,
+        total_deposited: 0,
+        claimed: false,
+    };
+    set_enrollment(env, &user, &enrollment);
+
+    active.push_back(challenge_id);
+    set_user_challenges(env, &user, &active);
+    participants.push_back(user.clone());
+    env.storage().persistent().set(
+        &DataKey::Config(ConfigKey::ChallengeParticipants(challenge_id)),
+        &participants,
+    );
+
+    events::publish(env, events::ChallengeJoined { user, challenge_id });
+    Ok(())
+}
+
+/// Closes out a challenge whose last period has ended. A user who met every
+/// period earns the `ChallengeCompleted` badge and the challenge's bonus,
+/// as far as the yield pool covers it, credited to their Flexi balance.
+/// Returns the bonus paid.
+///
+/// # Errors
+/// * `PlanNotFound` - If the user is not enrolled in the challenge
+/// * `PlanCompleted` - If it was already claimed
+/// * `TooEarly` - If its last period has not ended yet
+pub fn claim_challenge(env: &Env, user: Address, challenge_id: u64) -> Result<i128, SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let template = get_challenge(env, challenge_id).ok_or(SavingsError::PlanNotFound)?;
+    let mut enrollment =
+        get_enrollment(env, &user, challenge_id).ok_or(SavingsError::PlanNotFound)?;
+    if enrollment.claimed {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    advance(&template, &mut enrollment, env.ledger().timestamp());
+    if enrollment.period < template.amounts.len() {
+        return Err(SavingsError::TooEarly);
+    }
+
+    enrollment.claimed = true;
+    set_enrollment(env, &user, &enrollment);
+    let mut active = get_user_challenges(env, &user);
+    if let Some(index) = active.first_index_of(challenge_id) {
+        active.remove(index);
+    }
+    set_user_challenges(env, &user, &active);
+    let claimed_key = DataKey::UserState(UserKey::ClaimedChallenges(user.clone()));
+    let mut claimed: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&claimed_key)
+        .unwrap_or_else(|| Vec::new(env));
+    claimed.push_back(challenge_id);
+    env.storage().persistent().set(&claimed_key, &claimed);
+
+    let completed = enrollment.periods_met == template.amounts.len();
+    let mut bonus = 0;
+    if completed {
+        achievements::award(env, &user, Badge::ChallengeCompleted);
+        bonus = yield_pool::draw_interest(env, &user, template.bonus)?;
+        if bonus > 0 {
+            flexi::credit_flexi(env, &user, bonus)?;
+            history::record(env, &user, TxKind::Credit, PlanKind::Flexi, 0, bonus, 0);
+        }
+    }
+
+    events::publish(
+        env,
+        events::ChallengeClaimed {
+            user,
+            challenge_id,
+            completed,
+            bonus,
+        },
+    );
+    Ok(bonus)
+}
+
+/// Counts a deposit towards the current period of each of the user's
+/// unclaimed challenges. Called for every deposit.
+pub(crate) fn on_deposit(env: &Env, user: &Address, amount: i128) {
+    record_net_deposit(env, user, amount);
+}
+
+/// Takes funds leaving the user's savings off the current period of each of
+/// their unclaimed challenges, so depositing and withdrawing the same funds
+/// makes no progress. Called for every withdrawal.
+pub(crate) fn on_withdraw(env: &Env, user: &Address, amount: i128) {
+    record_net_deposit(env, user, amount.saturating_neg());
+}
+
+fn record_net_deposit(env: &Env, user: &Address, amount: i128) {
+    let now = env.ledger().timestamp();
+    for challenge_id in get_user_challenges(env, user).iter() {
+        let (Some(template), Some(mut enrollment)) = (
+            get_challenge(env, challenge_id),
+            get_enrollment(env, user, challenge_id),
+        ) else {
+            continue;
+        };
+        advance(&template, &mut enrollment, now);
+        if enrollment.period < template.amounts.len() {
+            enrollment.period_deposited = enrollment.period_deposited.saturating_add(amount);
+            enrollment.total_deposited = enrollment.total_deposited.saturating_add(amount);
+        }
+        set_enrollment(env, user, &enrollment);
+    }
+}
+
+/// Moves the enrollment up to the period `now` falls in, scoring every
+/// period it leaves behind. Periods without deposits count as missed.
+fn advance(template: &ChallengeTemplate, enrollment: &mut ChallengeEnrollment, now: u64) {
+    let periods = template.amounts.len();
+    let elapsed = now.saturating_sub(enrollment.started_at) / template.period_seconds;
+    let current = elapsed.min(periods as u64) as u32;
+    while enrollment.period < current {
+        if enrollment.period_deposited >= template.amounts.get_unchecked(enrollment.period) {
+            enrollment.periods_met += 1;
+        }
+        enrollment.period += 1;
+        enrollment.period_deposited = 0;
+    }
+}
+
+pub fn get_challenge(env: &Env, challenge_id: u64) -> Option<ChallengeTemplate> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::Challenge(challenge_id)))
+}
+
+/// The user's progress in a challenge, scored up to the current period
+pub fn get_challenge_progress(
+    env: &Env,
+    user: &Address,
+    challenge_id: u64,
+) -> Option<ChallengeEnrollment> {
+    let template = get_challenge(env, challenge_id)?;
+    let mut enrollment = get_enrollment(env, user, challenge_id)?;
+    if !enrollment.claimed {
+        advance(&template, &mut enrollment, env.ledger().timestamp());
+    }
+    Some(enrollment)
+}
+
+/// Challenges the user is enrolled in and has not claimed yet
+pub fn get_user_challenges(env: &Env, user: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::Challenges(user.clone())))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// A page of the challenge's participants ranked by periods met, then by
+/// amount deposited; ties keep enrollment order
+pub fn get_challenge_leaderboard(
+    env: &Env,
+    challenge_id: u64,
+    cursor: Cursor,
+) -> (Vec<ChallengeStanding>, Option<Cursor>) {
+    let mut ranked: Vec<ChallengeStanding> = Vec::new(env);
+    for user in get_participants(env, challenge_id).iter() {
+        let Some(enrollment) = get_challenge_progress(env, &user, challenge_id) else {
+            continue;
+        };
+        let standing = ChallengeStanding {
+            user,
+            periods_met: enrollment.periods_met,
+            total_deposited: enrollment.total_deposited,
+        };
+        let position = ranked
+            .iter()
+            .position(|other| {
+                (standing.periods_met, standing.total_deposited)
+                    > (other.periods_met, other.total_deposited)
+            })
+            .unwrap_or(ranked.len() as usize);
+        ranked.insert(position as u32, standing);
+    }
+    pagination::paginate(env, &ranked, &cursor)
+}
+
+/// Drops every enrollment of the user, claimed or not, and takes them off
+/// each challenge's participants. Used when the account is closed.
+pub(crate) fn remove_user(env: &Env, user: &Address) {
+    let storage = env.storage().persistent();
+    for challenge_id in get_enrolled_challenges(env, user).iter() {
+        let mut participants = get_participants(env, challenge_id);
+        if let Some(index) = participants.first_index_of(user) {
+            participants.remove(index);
+            storage.set(
+                &DataKey::Config(ConfigKey::ChallengeParticipants(challenge_id)),
+                &participants,
+            );
+        }
+        storage.remove(&DataKey::UserState(UserKey::ChallengeEnrollment(
+            user.clone(),
+            challenge_id,
+        )));
+    }
+    storage.remove(&DataKey::UserState(UserKey::Challenges(user.clone())));
+    storage.remove(&DataKey::UserState(UserKey::ClaimedChallenges(
+        user.clone(),
+    )));
+}
+
+/// Every challenge the user is enrolled in, claimed ones first
+pub(crate) fn get_enrolled_challenges(env: &Env, user: &Address) -> Vec<u64> {
+    let mut enrolled: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::ClaimedChallenges(
+            user.clone(),
+        )))
+        .unwrap_or_else(|| Vec::new(env));
+    enrolled.append(&get_user_challenges(env, user));
+    enrolled
+}
+
+fn get_participants(env: &Env, challenge_id: u64) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::ChallengeParticipants(
+            challenge_id,
+        )))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn get_enrollment(env: &Env, user: &Address, challenge_id: u64) -> Option<ChallengeEnrollment> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::ChallengeEnrollment(
+            user.clone(),
+            challenge_id,
+        )))
+}
+
+fn set_enrollment(env: &Env, user: &Address, enrollment: &ChallengeEnrollment) {
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::ChallengeEnrollment(
+            user.clone(),
+            enrollment.challenge_id,
+        )),
+        enrollment,
+    );
+}
+
+fn set_user_challenges(env: &Env, user: &Address, challenges: &Vec<u64>) {
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::Challenges(user.clone())),
+        challenges,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::{Badge, Cursor};
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        token, vec, Address, BytesN, Env, Symbol,
+    };
+
+    const WEEK: u64 = 7 * 24 * 60 * 60;
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        env.mock_all_auths();
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &token,
+        );
+        token::StellarAssetClient::new(&env, &token).mint(&admin, &1_000);
+        client.fund_yield_pool(&admin, &1_000);
+        (env, client, admin)
+    }
+
+    #[test]
+    fn test_completed_challenge_pays_bonus_and_badge() {
+        let (env, client, admin) = setup();
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+
+        assert_eq!(
+            client.try_create_challenge(
+                &user,
+                &Symbol::new(&env, "weekly"),
+                &WEEK,
+                &vec![&env, 100i128, 200],
+                &50,
+            ),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+        let id = client.create_challenge(
+            &admin,
+            &Symbol::new(&env, "weekly"),
+            &WEEK,
+            &vec![&env, 100i128, 200],
+            &50,
+        );
+        client.enroll_in_challenge(&user, &id);
+        assert_eq!(
+            client.try_enroll_in_challenge(&user, &id),
+            Err(Ok(SavingsError::InvalidPlanConfig))
+        );
+
+        client.deposit_flexi(&user, &100);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        client.deposit_flexi(&user, &150);
+        client.deposit_flexi(&user, &50);
+        assert_eq!(
+            client.try_claim_challenge(&user, &id),
+            Err(Ok(SavingsError::TooEarly))
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        let progress = client.get_challenge_progress(&user, &id).unwrap();
+        assert_eq!(progress.periods_met, 2);
+        assert_eq!(progress.total_deposited, 300);
+
+        assert_eq!(client.claim_challenge(&user, &id), 50);
+        assert_eq!(client.get_flexi_balance(&user), 350);
+        assert!(client
+            .get_user_badges(&user)
+            .iter()
+            .any(|award| award.badge == Badge::ChallengeCompleted));
+        assert!(client.get_user_challenges(&user).is_empty());
+        assert_eq!(
+            client.try_claim_challenge(&user, &id),
+            Err(Ok(SavingsError::PlanCompleted))
+        );
+    }
+
+    #[test]
+    fn test_missed_period_forfeits_bonus_and_ranks_lower() {
+        let (env, client, admin) = setup();
+        let diligent = Address::generate(&env);
+        let lapsed = Address::generate(&env);
+        client.initialize_user(&diligent);
+        client.initialize_user(&lapsed);
+
+        let id = client.create_challenge(
+            &admin,
+            &Symbol::new(&env, "weekly"),
+            &WEEK,
+            &vec![&env, 100i128, 100],
+            &50,
+        );
+        client.enroll_in_challenge(&lapsed, &id);
+        client.enroll_in_challenge(&diligent, &id);
+
+        client.deposit_flexi(&lapsed, &500);
+        client.deposit_flexi(&diligent, &100);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        client.deposit_flexi(&diligent, &100);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+
+        let (board, next) = client.get_challenge_leaderboard(
+            &id,
+            &Cursor {
+                offset: 0,
+                limit: 10,
+            },
+        );
+        assert_eq!(next, None);
+        assert_eq!(board.len(), 2);
+        assert_eq!(board.get(0).unwrap().user, diligent);
+        assert_eq!(board.get(0).unwrap().periods_met, 2);
+        assert_eq!(board.get(1).unwrap().user, lapsed);
+        assert_eq!(board.get(1).unwrap().periods_met, 1);
+
+        assert_eq!(client.claim_challenge(&lapsed, &id), 0);
+        assert_eq!(client.get_flexi_balance(&lapsed), 500);
+    }
+
+    #[test]
+    fn test_withdrawals_count_against_progress() {
+        let (env, client, admin) = setup();
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+
+        let id = client.create_challenge(
+            &admin,
+            &Symbol::new(&env, "weekly"),
+            &WEEK,
+            &vec![&env, 100i128, 100],
+            &50,
+        );
+        client.enroll_in_challenge(&user, &id);
+
+        // Depositing and withdrawing the same funds saves nothing
+        client.deposit_flexi(&user, &100);
+        client.withdraw_flexi(&user, &100);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        client.deposit_flexi(&user, &200);
+        client.withdraw_flexi(&user, &50);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+
+        let progress = client.get_challenge_progress(&user, &id).unwrap();
+        assert_eq!(progress.periods_met, 1);
+        assert_eq!(progress.total_deposited, 150);
+        assert_eq!(client.claim_challenge(&user, &id), 0);
+    }
+}
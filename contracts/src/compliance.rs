@@ -0,0 +1,179 @@
+use soroban_sdk::{vec, Address, Env, IntoVal, Symbol};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::storage_types::{ComplianceConfig, ComplianceMode, ConfigKey, DataKey, UserKey};
+
+/// Calls `is_attested(user) -> bool` on an external KYC attestation registry
+fn is_attested(env: &Env, registry: &Address, user: &Address) -> bool {
+    env.invoke_contract(
+        registry,
+        &Symbol::new(env, "is_attested"),
+        vec![env, user.into_val(env)],
+    )
+}
+
+pub fn get_compliance_config(env: &Env) -> ComplianceConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::Compliance))
+        .unwrap_or(ComplianceConfig {
+            mode: ComplianceMode::Off,
+            threshold: 0,
+        })
+}
+
+/// Switches the compliance gate. Deposits above `threshold` are only
+/// accepted from allowed users while `mode` is not `Off`.
+///
+/// # Errors
+/// * `InvalidAmount` - If `threshold` is negative
+pub fn set_compliance_mode(
+    env: &Env,
+    mode: ComplianceMode,
+    threshold: i128,
+) -> Result<(), SavingsError> {
+    if threshold < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let config = ComplianceConfig { mode, threshold };
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::Compliance), &config);
+    events::publish(env, events::ComplianceModeSet { config });
+    Ok(())
+}
+
+pub fn set_allowlisted(env: &Env, user: &Address, allowed: bool) {
+    let key = DataKey::UserState(UserKey::Allowlisted(user.clone()));
+    if allowed {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+    events::publish(
+        env,
+        events::AllowlistUpdated {
+            user: user.clone(),
+            allowed,
+        },
+    );
+}
+
+/// Whether `user` passes the compliance gate under the current mode.
+///
+/// The allowlist is honoured in attestation mode too, so an admin can admit
+/// a user the registry does not know about.
+pub fn is_allowed(env: &Env, user: &Address) -> bool {
+    let allowlisted = || {
+        env.storage()
+            .persistent()
+            .has(&DataKey::UserState(UserKey::Allowlisted(user.clone())))
+    };
+    match get_compliance_config(env).mode {
+        ComplianceMode::Off => true,
+        ComplianceMode::Allowlist => allowlisted(),
+        ComplianceMode::Attestation(registry) => allowlisted() || is_attested(env, &registry, user),
+    }
+}
+
+/// Rejects a deposit of `amount` above the threshold from a user who is not
+/// allowed. Called by `guard::check` on every deposit path.
+pub(crate) fn ensure_allowed(env: &Env, user: &Address, amount: i128) -> Result<(), SavingsError> {
+    let config = get_compliance_config(env);
+    if config.mode == ComplianceMode::Off || amount <= config.threshold {
+        return Ok(());
+    }
+    if !is_allowed(env, user) {
+        return Err(SavingsError::NotAllowlisted);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ComplianceMode, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, vec, Address, BytesN, Env};
+
+    /// Registry attesting whichever single user the test last set
+    #[contract]
+    pub struct MockRegistry;
+
+    #[contractimpl]
+    impl MockRegistry {
+        pub fn attest(env: Env, user: Address) {
+            env.storage().instance().set(&user, &true);
+        }
+
+        pub fn is_attested(env: Env, user: Address) -> bool {
+            env.storage().instance().has(&user)
+        }
+    }
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        (env, client, admin)
+    }
+
+    #[test]
+    fn test_allowlist_gates_deposits_above_threshold() {
+        let (env, client, admin) = setup();
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        client.initialize_user(&alice);
+        client.initialize_user(&bob);
+
+        client.set_compliance_mode(&admin, &ComplianceMode::Allowlist, &1_000);
+        assert!(!client.is_allowed(&alice));
+
+        client.deposit_flexi(&alice, &1_000);
+        assert_eq!(
+            client.try_deposit_flexi(&alice, &1_001),
+            Err(Ok(SavingsError::NotAllowlisted))
+        );
+        assert!(client
+            .try_create_lock_save(&alice, &5_000, &86_400)
+            .is_err());
+
+        client.add_to_allowlist_batch(&admin, &vec![&env, alice.clone(), bob.clone()]);
+        assert!(client.is_allowed(&alice));
+        client.deposit_flexi(&alice, &5_000);
+        client.deposit_flexi(&bob, &5_000);
+
+        client.remove_from_allowlist(&admin, &bob);
+        assert!(client.try_deposit_flexi(&bob, &5_000).is_err());
+
+        client.set_compliance_mode(&admin, &ComplianceMode::Off, &0);
+        assert!(client.is_allowed(&bob));
+        client.deposit_flexi(&bob, &5_000);
+    }
+
+    #[test]
+    fn test_attestation_registry_admits_users() {
+        let (env, client, admin) = setup();
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+
+        let registry_id = env.register(MockRegistry, ());
+        let registry = MockRegistryClient::new(&env, &registry_id);
+        client.set_compliance_mode(&admin, &ComplianceMode::Attestation(registry_id), &0);
+
+        assert!(client.try_deposit_flexi(&user, &1).is_err());
+        registry.attest(&user);
+        assert!(client.is_allowed(&user));
+        client.deposit_flexi(&user, &1);
+    }
+}
@@ -0,0 +1,234 @@
+use soroban_sdk::{Address, Env};
+
+use crate::assets;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::flexi;
+use crate::goal;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::limits;
+use crate::lock;
+use crate::storage_types::{CompoundFrequency, PlanKind, TxKind};
+
+/// Moves `amount` of the user's Flexi balance into a new Lock Save of
+/// `duration` seconds. No tokens leave the contract, so no withdrawal fee
+/// is charged. Returns the new lock's ID.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If the Flexi balance is below `amount`
+pub fn convert_flexi_to_lock(
+    env: &Env,
+    user: Address,
+    amount: i128,
+    duration: u64,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &user, Op::CreatePlan, amount)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    flexi::debit_flexi(env, &user, amount)?;
+    history::record(env, &user, TxKind::Convert, PlanKind::Flexi, 0, amount, 0);
+    let lock_id = lock::apply_create_lock(env, &user, amount, duration, CompoundFrequency::None)?;
+
+    events::publish(
+        env,
+        events::FlexiConverted {
+            user,
+            plan_kind: PlanKind::Lock,
+            plan_id: lock_id,
+            amount,
+        },
+    );
+    Ok(lock_id)
+}
+
+/// Moves `amount` of the user's Flexi balance into one of their goals.
+/// No tokens leave the contract, so no withdrawal fee is charged. As with
+/// any goal deposit, the amount leaves `total_balance` until the goal pays out.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If the Flexi balance is below `amount`
+pub fn convert_flexi_to_goal(
+    env: &Env,
+    user: Address,
+    amount: i128,
+    goal_id: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Deposit, amount)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    flexi::debit_flexi(env, &user, amount)?;
+    history::record(env, &user, TxKind::Convert, PlanKind::Flexi, 0, amount, 0);
+    goal::apply_goal_deposit(env, &user, goal_id, amount)?;
+
+    events::publish(
+        env,
+        events::FlexiConverted {
+            user,
+            plan_kind: PlanKind::Goal,
+            plan_id: goal_id,
+            amount,
+        },
+    );
+    Ok(())
+}
+
+/// Sweeps every completed goal and matured lock of the user holding less
+/// than the dust threshold into their Flexi balance. Goals shared with
+/// co-owners are left alone. No tokens leave the contract, so no withdrawal
+/// fee is charged. Returns the total moved.
+pub fn consolidate_dust(env: &Env, user: Address) -> Result<i128, SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let threshold = assets::to_token_units(env, limits::get_dust_threshold(env));
+    let mut goals_swept: u32 = 0;
+    let mut locks_swept: u32 = 0;
+    let mut total: i128 = 0;
+
+    for goal_id in goal::get_user_goal_saves(env, &user).iter() {
+        let Some(goal_save) = goal::get_goal_save(env, goal_id) else {
+            continue;
+        };
+        if goal_save.owner != user
+            || !goal_save.is_completed
+            || goal_save.is_withdrawn
+            || goal_save.current_amount <= 0
+            || goal_save.current_amount >= threshold
+            || !goal::get_goal_co_owners(env, goal_id).is_empty()
+        {
+            continue;
+        }
+        total = total
+            .checked_add(goal::sweep_goal_to_flexi(env, goal_save)?)
+            .ok_or(SavingsError::Overflow)?;
+        goals_swept += 1;
+    }
+
+    let now = env.ledger().timestamp();
+    for lock_id in lock::get_user_lock_saves(env, &user).iter() {
+        let Some(lock_save) = lock::get_lock_save(env, lock_id) else {
+            continue;
+        };
+        // Dust is judged on what the lock pays out, interest included
+        if lock_save.owner != user
+            || lock_save.is_withdrawn
+            || lock_save.amount <= 0
+            || lock::calculate_lock_save_yield(&lock_save, now)? >= threshold
+            || !lock::is_unlocked(env, &lock_save)
+        {
+            continue;
+        }
+        total = total
+            .checked_add(lock::sweep_lock_to_flexi(env, lock_save)?)
+            .ok_or(SavingsError::Overflow)?;
+        locks_swept += 1;
+    }
+
+    events::publish(
+        env,
+        events::DustSwept {
+            user,
+            goals_swept,
+            locks_swept,
+            total,
+        },
+    );
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, Symbol,
+    };
+
+    #[test]
+    fn test_convert_flexi_moves_balance_between_plans() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &1_000);
+
+        let lock_id = client.convert_flexi_to_lock(&user, &400, &86_400);
+        assert_eq!(client.get_lock_save_detail(&lock_id).amount, 400);
+
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "car"), &5_000, &0);
+        client.convert_flexi_to_goal(&user, &250, &goal_id);
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 250);
+
+        assert_eq!(client.get_flexi_balance(&user), 350);
+        // Flexi and lock balances count towards the total; goal balances don't
+        assert_eq!(client.get_user(&user).total_balance, 750);
+        assert_eq!(
+            client.try_convert_flexi_to_goal(&user, &351, &goal_id),
+            Err(Ok(SavingsError::InsufficientBalance))
+        );
+    }
+
+    #[test]
+    fn test_consolidate_dust_sweeps_small_finished_plans() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+
+        let dust_goal = client.create_goal_save(&user, &Symbol::new(&env, "gift"), &40, &40);
+        let big_goal = client.create_goal_save(&user, &Symbol::new(&env, "car"), &500, &500);
+        let open_goal = client.create_goal_save(&user, &Symbol::new(&env, "trip"), &500, &20);
+        let dust_lock = client.create_lock_save(&user, &30, &100);
+        let young_lock = client.create_lock_save(&user, &30, &1_000);
+
+        // Nothing counts as dust until the admin sets a threshold
+        assert_eq!(client.consolidate_dust(&user), 0);
+
+        client.set_dust_threshold(&admin, &100);
+        assert_eq!(client.get_protocol_params().dust_threshold, 100);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        // The yield pool is empty, so the lock pays back its principal only
+        assert_eq!(client.consolidate_dust(&user), 70);
+
+        assert_eq!(client.get_flexi_balance(&user), 70);
+        assert!(client.get_goal_save_detail(&dust_goal).is_withdrawn);
+        assert!(!client.get_goal_save_detail(&big_goal).is_withdrawn);
+        assert!(!client.get_goal_save_detail(&open_goal).is_withdrawn);
+        assert!(client.get_lock_save_detail(&dust_lock).is_withdrawn);
+        assert!(!client.get_lock_save_detail(&young_lock).is_withdrawn);
+        assert_eq!(client.consolidate_dust(&user), 0);
+
+        // A lock whose interest lifts its payout over the threshold is no dust
+        let year = 365 * 24 * 60 * 60;
+        let grown_lock = client.create_lock_save(&user, &99, &year);
+        env.ledger().with_mut(|li| li.timestamp += year);
+        client.consolidate_dust(&user);
+        assert!(!client.get_lock_save_detail(&grown_lock).is_withdrawn);
+
+        assert_eq!(
+            client.try_set_dust_threshold(&admin, &-1),
+            Err(Ok(SavingsError::InvalidAmount))
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::guard::{self, Op};
+use crate::storage_types::{Custody, DataKey, UserKey};
+use crate::users;
+
+/// Opens an account for `minor` under `guardian`'s control.
+///
+/// The account works like any other for deposits, but until `release_time`
+/// every withdrawal from it also needs the guardian's signature. Only the
+/// guardian signs; the minor does not have to be present.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `guardian` and `minor` are the same address
+/// * `InvalidTimestamp` - If `release_time` is not in the future
+/// * `UserAlreadyExists` - If `minor` has already been initialized
+pub fn initialize_custodial_user(
+    env: &Env,
+    guardian: Address,
+    minor: Address,
+    release_time: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &guardian, Op::Manage, 0)?;
+    guardian.require_auth();
+
+    if guardian == minor {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if release_time <= env.ledger().timestamp() {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    users::insert_user(env, minor.clone())?;
+    set_custody(
+        env,
+        &minor,
+        &Custody {
+            guardian: guardian.clone(),
+            release_time,
+        },
+    );
+    add_ward(env, &guardian, &minor);
+
+    events::publish(
+        env,
+        events::CustodyOpened {
+            guardian,
+            minor,
+            release_time,
+        },
+    );
+
+    Ok(())
+}
+
+/// Moves the release time of a custodial account that has not been released
+/// yet. The guardian may bring it forward or push it back.
+///
+/// # Errors
+/// * `UserNotFound` - If `minor` is not a custodial account
+/// * `Unauthorized` - If `guardian` is not the account's guardian
+/// * `TooLate` - If the account has already been released
+/// * `InvalidTimestamp` - If `release_time` is not in the future
+pub fn set_release_time(
+    env: &Env,
+    guardian: Address,
+    minor: Address,
+    release_time: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &guardian, Op::Manage, 0)?;
+    guardian.require_auth();
+
+    let mut custody = get_custody(env, &minor).ok_or(SavingsError::UserNotFound)?;
+    if custody.guardian != guardian {
+        return Err(SavingsError::Unauthorized);
+    }
+    let now = env.ledger().timestamp();
+    if now >= custody.release_time {
+        return Err(SavingsError::TooLate);
+    }
+    if release_time <= now {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    custody.release_time = release_time;
+    set_custody(env, &minor, &custody);
+
+    events::publish(
+        env,
+        events::CustodyReleased {
+            guardian,
+            minor,
+            release_time,
+        },
+    );
+
+    Ok(())
+}
+
+/// Hands a custodial account to a new guardian. Both the current and the new
+/// guardian must sign.
+///
+/// # Errors
+/// * `UserNotFound` - If `minor` is not a custodial account
+/// * `Unauthorized` - If `guardian` is not the account's guardian
+/// * `InvalidPlanConfig` - If `new_guardian` is the current guardian or the
+///   minor
+pub fn change_guardian(
+    env: &Env,
+    guardian: Address,
+    minor: Address,
+    new_guardian: Address,
+) -> Result<(), SavingsError> {
+    guard::check(env, &guardian, Op::Manage, 0)?;
+    guardian.require_auth();
+    new_guardian.require_auth();
+
+    let mut custody = get_custody(env, &minor).ok_or(SavingsError::UserNotFound)?;
+    if custody.guardian != guardian {
+        return Err(SavingsError::Unauthorized);
+    }
+    if new_guardian == guardian || new_guardian == minor {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    remove_ward(env, &guardian, &minor);
+    add_ward(env, &new_guardian, &minor);
+    custody.guardian = new_guardian.clone();
+    set_custody(env, &minor, &custody);
+
+    events::publish(
+        env,
+        events::GuardianChanged {
+            guardian,
+            new_guardian,
+            minor,
+        },
+    );
+
+    Ok(())
+}
+
+/// Demands the guardian's signature if `user` is a custodial account that
+/// has not been released yet. Called on every withdrawal path and on every
+/// path that hands the account's plans or funds to someone else.
+pub(crate) fn require_guardian_consent(env: &Env, user: &Address) {
+    if let Some(custody) = get_custody(env, user) {
+        if env.ledger().timestamp() < custody.release_time {
+            custody.guardian.require_auth();
+        }
+    }
+}
+
+/// Releases a custodial account from its guardian's list and drops its
+/// custody record. Used when the account is closed.
+pub(crate) fn remove_custody(env: &Env, minor: &Address) {
+    if let Some(custody) = get_custody(env, minor) {
+        remove_ward(env, &custody.guardian, minor);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::UserState(UserKey::Custody(minor.clone())));
+    }
+}
+
+pub fn get_custody(env: &Env, minor: &Address) -> Option<Custody> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::Custody(minor.clone())))
+}
+
+/// Custodial accounts controlled by `guardian`, in the order they were taken on
+pub fn get_guardian_wards(env: &Env, guardian: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::GuardianWards(
+            guardian.clone(),
+        )))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn set_custody(env: &Env, minor: &Address, custody: &Custody) {
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::Custody(minor.clone())),
+        custody,
+    );
+}
+
+fn add_ward(env: &Env, guardian: &Address, minor: &Address) {
+    let mut wards = get_guardian_wards(env, guardian);
+    wards.push_back(minor.clone());
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::GuardianWards(guardian.clone())),
+        &wards,
+    );
+}
+
+fn remove_ward(env: &Env, guardian: &Address, minor: &Address) {
+    let mut wards = get_guardian_wards(env, guardian);
+    if let Some(index) = wards.first_index_of(minor) {
+        wards.remove(index);
+    }
+    let key = DataKey::UserState(UserKey::GuardianWards(guardian.clone()));
+    if wards.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &wards);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        symbol_short,
+        testutils::{Address as _, Ledger},
+        vec, Address, Env,
+    };
+
+    const RELEASE: u64 = 18 * 365 * 24 * 60 * 60;
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        env.mock_all_auths();
+
+        let guardian = Address::generate(&env);
+        let minor = Address::generate(&env);
+        client.initialize_custodial_user(&guardian, &minor, &RELEASE);
+        (env, client, guardian, minor)
+    }
+
+    #[test]
+    fn test_custodial_withdrawals_need_guardian_until_release() {
+        let (env, client, guardian, minor) = setup();
+        assert_eq!(
+            client.get_guardian_wards(&guardian),
+            vec![&env, minor.clone()]
+        );
+
+        client.deposit_flexi(&minor, &1_000);
+        assert_eq!(env.auths().len(), 1);
+
+        client.withdraw_flexi(&minor, &100);
+        let auths = env.auths();
+        assert!(auths.iter().any(|(signer, _)| *signer == guardian));
+        assert!(auths.iter().any(|(signer, _)| *signer == minor));
+
+        env.ledger().with_mut(|li| li.timestamp = RELEASE);
+        client.withdraw_flexi(&minor, &100);
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        assert_eq!(auths[0].0, minor);
+
+        assert_eq!(
+            client.try_set_release_time(&guardian, &minor, &(RELEASE * 2)),
+            Err(Ok(SavingsError::TooLate))
+        );
+    }
+
+    #[test]
+    fn test_handing_plans_away_needs_guardian_until_release() {
+        let (env, client, guardian, minor) = setup();
+        let friend = Address::generate(&env);
+        client.initialize_user(&friend);
+        client.deposit_flexi(&minor, &1_000);
+
+        let signed_by_guardian = |env: &Env| env.auths().iter().any(|(s, _)| *s == guardian);
+
+        let lock_id = client.create_lock_save(&minor, &500, &86_400);
+        client.transfer_lock_save(&minor, &friend, &lock_id);
+        assert!(signed_by_guardian(&env));
+
+        let goal_id = client.create_goal_save(&minor, &symbol_short!("bike"), &1_000, &100);
+        client.add_goal_co_owner(&minor, &goal_id, &friend);
+        assert!(signed_by_guardian(&env));
+
+        let gift_id = client.create_goal_save(&minor, &symbol_short!("gift"), &1_000, &100);
+        client.gift_goal_save(&minor, &friend, &gift_id);
+        assert!(signed_by_guardian(&env));
+
+        let public_id = client.create_goal_save(&friend, &symbol_short!("trip"), &1_000, &0);
+        client.set_goal_public(&friend, &public_id, &true);
+        client.contribute_to_goal(&minor, &public_id, &100);
+        assert!(signed_by_guardian(&env));
+
+        env.ledger().with_mut(|li| li.timestamp = RELEASE);
+        client.contribute_to_goal(&minor, &public_id, &100);
+        assert!(!signed_by_guardian(&env));
+    }
+
+    #[test]
+    fn test_change_guardian_and_release_time() {
+        let (env, client, guardian, minor) = setup();
+        let new_guardian = Address::generate(&env);
+
+        assert_eq!(
+            client.try_set_release_time(&new_guardian, &minor, &(RELEASE / 2)),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+        client.set_release_time(&guardian, &minor, &(RELEASE / 2));
+
+        client.change_guardian(&guardian, &minor, &new_guardian);
+        let custody = client.get_custody(&minor).unwrap();
+        assert_eq!(custody.guardian, new_guardian);
+        assert_eq!(custody.release_time, RELEASE / 2);
+        assert_eq!(client.get_guardian_wards(&guardian).len(), 0);
+        assert_eq!(client.get_guardian_wards(&new_guardian), vec![&env, minor]);
+    }
+}
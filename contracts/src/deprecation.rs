@@ -0,0 +1,180 @@
+use soroban_sdk::{Env, Symbol};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::storage_types::{ConfigKey, DataKey};
+
+/// Returns whether legacy entrypoints may still be called (enabled by default)
+pub fn legacy_entrypoints_enabled(env: &Env) -> bool {
+    !env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::LegacyEntrypointsDisabled))
+        .unwrap_or(false)
+}
+
+pub fn set_legacy_entrypoints_enabled(env: &Env, enabled: bool) {
+    env.storage().instance().set(
+        &DataKey::Config(ConfigKey::LegacyEntrypointsDisabled),
+        &!enabled,
+    );
+}
+
+/// Guards a legacy entrypoint.
+///
+/// While legacy entrypoints are enabled this emits a `deprecated` event naming
+/// the replacement so integrators can find remaining callers; once an admin
+/// disables them it fails with `DeprecatedOperation`.
+pub fn check_legacy(env: &Env, entrypoint: &str, replacement: &str) -> Result<(), SavingsError> {
+    if !legacy_entrypoints_enabled(env) {
+        return Err(SavingsError::DeprecatedOperation);
+    }
+    events::publish(
+        env,
+        events::DeprecatedCall {
+            entrypoint: Symbol::new(env, entrypoint),
+            replacement: Symbol::new(env, replacement),
+        },
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        GroupMetadata, NesteraContract, NesteraContractClient, PlanDetail, PlanKind, PlanRef,
+        PlanType,
+    };
+    use soroban_sdk::{
+        testutils::{Address as _, Events},
+        Address, BytesN, Env, IntoVal, String, Symbol, TryFromVal,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        (env, client, admin)
+    }
+
+    #[test]
+    fn test_legacy_entrypoint_emits_deprecated_event() {
+        let (env, client, _admin) = setup();
+        let user = Address::generate(&env);
+
+        client.init_user(&user);
+
+        let deprecated = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(0).unwrap())
+                    .map(|t| t == Symbol::new(&env, "deprecated"))
+                    .unwrap_or(false)
+            })
+            .expect("deprecated event not emitted");
+        assert_eq!(
+            deprecated.1,
+            (
+                Symbol::new(&env, "deprecated"),
+                Symbol::new(&env, "init_user")
+            )
+                .into_val(&env)
+        );
+        let (_, replacement): (u64, Symbol) = deprecated.2.into_val(&env);
+        assert_eq!(replacement, Symbol::new(&env, "initialize_user"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #85)")]
+    fn test_disabled_legacy_init_user_fails() {
+        let (env, client, _admin) = setup();
+        client.set_legacy_entrypoints_enabled(&false);
+        assert!(!client.legacy_entrypoints_enabled());
+
+        client.init_user(&Address::generate(&env));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #85)")]
+    fn test_disabled_legacy_create_savings_plan_fails() {
+        let (env, client, _admin) = setup();
+        client.set_legacy_entrypoints_enabled(&false);
+
+        client.create_savings_plan(&Address::generate(&env), &PlanType::Flexi, &100);
+    }
+
+    #[test]
+    fn test_reenabling_legacy_entrypoints() {
+        let (env, client, _admin) = setup();
+        client.set_legacy_entrypoints_enabled(&false);
+        client.set_legacy_entrypoints_enabled(&true);
+
+        let user = Address::generate(&env);
+        assert_eq!(client.init_user(&user).total_balance, 0);
+    }
+
+    #[test]
+    fn test_create_savings_plan_dispatches_to_plan_modules() {
+        let (env, client, _admin) = setup();
+        let user = Address::generate(&env);
+        let creator = Address::generate(&env);
+        client.initialize_user(&creator);
+
+        assert_eq!(client.create_savings_plan(&user, &PlanType::Flexi, &100), 0);
+        assert_eq!(client.get_flexi_balance(&user), 100);
+
+        let lock_id = client.create_savings_plan(&user, &PlanType::Lock(86_400), &200);
+        let lock_save = client.get_lock_save_detail(&lock_id);
+        assert_eq!((lock_save.amount, lock_save.maturity_time), (200, 86_400));
+
+        let goal_name = Symbol::new(&env, "trip");
+        let goal_id =
+            client.create_savings_plan(&user, &PlanType::Goal(goal_name.clone(), 1_000, 0), &50);
+        let goal_save = client.get_goal_save_detail(&goal_id);
+        assert_eq!(
+            (goal_save.goal_name, goal_save.current_amount),
+            (goal_name, 50)
+        );
+
+        let group_id = client.create_group_save(
+            &creator,
+            &GroupMetadata {
+                title: String::from_str(&env, "Club"),
+                description: String::from_str(&env, "Saving club"),
+                category: String::from_str(&env, "savings"),
+            },
+            &10_000,
+            &0,
+            &100,
+            &true,
+            &0,
+            &1_000,
+            &0,
+        );
+        assert_eq!(
+            client.create_savings_plan(&user, &PlanType::Group(group_id, true, 0, 10_000), &100),
+            group_id
+        );
+        let plan = PlanRef {
+            kind: PlanKind::Group,
+            id: group_id,
+        };
+        match client.get_plan(&user, &plan) {
+            PlanDetail::Group(group) => assert_eq!(group.current_amount, 100),
+            other => panic!("unexpected plan {:?}", other),
+        }
+    }
+}
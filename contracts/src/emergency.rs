@@ -0,0 +1,296 @@
+use soroban_sdk::{token, Address, Env, Vec};
+
+use crate::custody;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::fees;
+use crate::flexi;
+use crate::limits;
+use crate::lock;
+use crate::reentrancy;
+use crate::storage_types::{ConfigKey, DataKey};
+use crate::strategy;
+use crate::yield_pool;
+
+/// Funds the protocol owns outright: the current treasury's fees plus the
+/// yield pool. Everything else the contract holds is user principal.
+pub fn get_protocol_owned_balance(env: &Env) -> i128 {
+    let fees = treasury(env).map_or(0, |recipient| fees::get_fee_balance(env, &recipient));
+    fees + yield_pool::get_pool_balance(env)
+}
+
+fn treasury(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::FeeRecipient)
+}
+
+/// Moves `amount` of protocol-owned funds, in the deposit token, to
+/// `recovery`. Works while paused so funds can be rescued during an incident.
+///
+/// The amount is taken from the treasury's fees first and then from the yield
+/// pool, and both ledgers are debited before any tokens move, so user
+/// principal can never leave through this path. Returns the protocol-owned
+/// balance left. Callers are responsible for authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If `amount` exceeds the protocol-owned balance or
+///   the tokens the contract currently holds
+/// * `MissingParameter` - If no deposit token is configured
+pub fn emergency_withdraw(
+    env: &Env,
+    admin: &Address,
+    recovery: &Address,
+    amount: i128,
+) -> Result<i128, SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let owned_before = get_protocol_owned_balance(env);
+    if amount > owned_before {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    let token = strategy::get_token(env)?;
+    let held_before = token::Client::new(env, &token).balance(&env.current_contract_address());
+    if amount > held_before {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    let from_fees = match treasury(env) {
+        Some(recipient) => {
+            let from_fees = amount.min(fees::get_fee_balance(env, &recipient));
+            if from_fees > 0 {
+                fees::debit_treasury(env, &recipient, from_fees)?;
+            }
+            from_fees
+        }
+        None => 0,
+    };
+    let from_pool = amount - from_fees;
+    if from_pool > 0 {
+        yield_pool::debit_pool(env, from_pool)?;
+    }
+
+    // The ledgers must have shrunk by exactly what leaves the contract
+    let owned_after = get_protocol_owned_balance(env);
+    if owned_before - owned_after != amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    reentrancy::transfer(
+        env,
+        &token,
+        &env.current_contract_address(),
+        recovery,
+        amount,
+    )?;
+
+    events::publish(
+        env,
+        events::EmergencyWithdrawn {
+            admin: admin.clone(),
+            recovery: recovery.clone(),
+            amount,
+            from_fees,
+            from_pool,
+            owned_after,
+        },
+    );
+
+    Ok(owned_after)
+}
+
+/// Whether users may exit through `emergency_user_withdraw`
+pub fn is_withdrawal_only_mode(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::WithdrawalOnlyMode))
+        .unwrap_or(false)
+}
+
+pub fn set_withdrawal_only_mode(env: &Env, enabled: bool) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::WithdrawalOnlyMode), &enabled);
+}
+
+/// Withdraws the user's whole Flexi balance and every matured lock in one
+/// call. Returns the total paid out net of withdrawal fees.
+///
+/// Only available in withdrawal-only mode, and unaffected by the pause, so
+/// users are never locked out of funds that are already free to leave. The
+/// per-user withdrawal limit still applies.
+///
+/// # Errors
+/// * `WithdrawalOnlyModeDisabled` - If withdrawal-only mode is off
+/// * `InsufficientBalance` - If the user has nothing to withdraw
+pub fn emergency_user_withdraw(env: &Env, user: Address) -> Result<i128, SavingsError> {
+    if !is_withdrawal_only_mode(env) {
+        return Err(SavingsError::WithdrawalOnlyModeDisabled);
+    }
+    user.require_auth();
+
+    let flexi_balance = flexi::get_flexi_balance(env, user.clone())?;
+    let mut matured_locks = Vec::new(env);
+    let mut gross = flexi_balance;
+    for lock_id in lock::get_user_lock_saves(env, &user).iter() {
+        if let Some(lock_save) = lock::get_lock_save(env, lock_id) {
+            if !lock_save.is_withdrawn && lock::is_unlocked(env, &lock_save) {
+                gross = gross
+                    .checked_add(lock_save.amount)
+                    .ok_or(SavingsError::Overflow)?;
+                matured_locks.push_back(lock_save);
+            }
+        }
+    }
+    if gross == 0 {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    custody::require_guardian_consent(env, &user);
+    limits::consume_withdraw_allowance(env, &user, gross)?;
+
+    let mut paid = 0;
+    if flexi_balance > 0 {
+        paid += flexi::apply_flexi_withdraw(env, &user, flexi_balance)?;
+    }
+    for lock_save in matured_locks.iter() {
+        paid += lock::apply_lock_withdrawal(env, lock_save)?;
+    }
+
+    events::publish(
+        env,
+        events::EmergencyUserExit {
+            user,
+            flexi_balance,
+            matured_locks: matured_locks.len(),
+            paid,
+        },
+    );
+
+    Ok(paid)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Cursor, NesteraContract, NesteraContractClient, PlanKind, SavingsError, TxKind,
+        WithdrawalFeeConfig,
+    };
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        token, Address, BytesN, Env,
+    };
+
+    #[test]
+    fn test_emergency_withdraw_never_touches_principal() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &treasury,
+            &0,
+            &token,
+        );
+        client.initialize_user(&user);
+        token::StellarAssetClient::new(&env, &token).mint(&admin, &300);
+        token::StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+
+        // 1_000 of user principal, 10 of fees and 300 in the yield pool
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 0 });
+        client.deposit_flexi(&user, &2_000);
+        client.withdraw_flexi(&user, &1_000);
+        client.fund_yield_pool(&admin, &300);
+        assert_eq!(client.get_protocol_owned_balance(), 310);
+
+        let recovery = Address::generate(&env);
+        assert_eq!(
+            client.try_emergency_withdraw(&admin, &recovery, &311),
+            Err(Ok(SavingsError::InsufficientBalance))
+        );
+        assert_eq!(
+            client.try_emergency_withdraw(&Address::generate(&env), &recovery, &10),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+
+        client.pause(&admin);
+        assert_eq!(client.emergency_withdraw(&admin, &recovery, &50), 260);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+        assert_eq!(client.get_yield_pool_status().pool_balance, 260);
+        assert_eq!(token::Client::new(&env, &token).balance(&recovery), 50);
+        assert_eq!(client.get_flexi_balance(&user), 1_000);
+    }
+
+    #[test]
+    fn test_emergency_user_withdraw_while_paused() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+
+        client.deposit_flexi(&user, &500);
+        let matured = client.create_lock_save(&user, &1_000, &100);
+        let pending = client.create_lock_save(&user, &2_000, &1_000);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+
+        client.pause(&admin);
+        assert!(client.try_withdraw_flexi(&user, &500).is_err());
+        assert_eq!(
+            client.try_emergency_user_withdraw(&user),
+            Err(Ok(SavingsError::WithdrawalOnlyModeDisabled))
+        );
+
+        client.set_withdrawal_only_mode(&admin, &true);
+        assert!(client.get_protocol_params().withdrawal_only_mode);
+        assert_eq!(client.emergency_user_withdraw(&user), 1_500);
+
+        // One withdrawal each for the Flexi balance and the matured lock
+        let (history, _) = client.get_user_history(
+            &user,
+            &Cursor {
+                offset: 0,
+                limit: 2,
+            },
+        );
+        assert_eq!(history.get_unchecked(0).plan_kind, PlanKind::Lock);
+        assert_eq!(history.get_unchecked(1).plan_kind, PlanKind::Flexi);
+        assert_eq!(history.get_unchecked(1).amount, 500);
+        assert_eq!(history.get_unchecked(0).kind, TxKind::Withdraw);
+        assert_eq!(history.get_unchecked(1).kind, TxKind::Withdraw);
+        let (history, _) = client.get_user_history(
+            &user,
+            &Cursor {
+                offset: 2,
+                limit: 1,
+            },
+        );
+        assert_eq!(history.get_unchecked(0).kind, TxKind::Deposit);
+
+        assert_eq!(client.get_flexi_balance(&user), 0);
+        assert!(client.get_lock_save_detail(&matured).is_withdrawn);
+        assert!(!client.get_lock_save_detail(&pending).is_withdrawn);
+        assert_eq!(
+            client.try_emergency_user_withdraw(&user),
+            Err(Ok(SavingsError::InsufficientBalance))
+        );
+    }
+}
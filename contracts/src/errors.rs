@@ -20,6 +20,18 @@ pub enum SavingsError {
     /// - Invalid or missing authentication credentials
     Unauthorized = 1,
 
+    /// Returned when a critical admin change is made directly while a
+    /// timelock is configured.
+    ///
+    /// Such changes must go through `queue_admin_action`.
+    TimelockRequired = 2,
+
+    /// Returned when a critical admin change is made with a single key while
+    /// an m-of-n signer set is configured.
+    ///
+    /// Such changes must go through `propose_multisig_action`.
+    MultisigRequired = 3,
+
     // ========== User-Related Errors (10-19) ==========
     /// Returned when attempting to access a user that does not exist in the system.
     ///
@@ -33,6 +45,24 @@ pub enum SavingsError {
     /// This prevents duplicate user entries and maintains data integrity.
     UserAlreadyExists = 11,
 
+    /// Returned when a referral names an invalid referrer.
+    ///
+    /// This occurs when:
+    /// - A user attempts to refer themselves
+    /// - The referrer has not been initialized
+    InvalidReferrer = 12,
+
+    /// Returned when closing an account that still holds funds or open plans.
+    ///
+    /// Every plan must be withdrawn, every group left and every balance,
+    /// including pending referral rewards, be zero first.
+    AccountNotEmpty = 13,
+
+    /// Returned when initializing an address whose account was closed.
+    ///
+    /// Reopening a closed account goes through `reopen_account`.
+    AccountClosed = 14,
+
     // ========== Savings Plan Errors (20-39) ==========
     /// Returned when attempting to access a savings plan that does not exist.
     ///
@@ -149,6 +179,36 @@ pub enum SavingsError {
     /// This includes invalid member counts, contribution amounts, or schedules.
     InvalidGroupConfig = 73,
 
+    /// Returned when a user's reputation score is below a group's join requirement.
+    ///
+    /// Group creators may require a minimum reputation for public groups.
+    ReputationTooLow = 74,
+
+    /// Returned when a user's account is younger than a group's required tenure.
+    ///
+    /// Group creators may require a minimum account age for public groups.
+    AccountTooNew = 75,
+
+    /// Returned when a member flags a group they have already flagged.
+    ///
+    /// Each member counts once towards the dispute threshold.
+    AlreadyFlagged = 76,
+
+    /// Returned when funds are moved out of a group frozen by a dispute.
+    ///
+    /// Distributions resume once an admin resolves the dispute.
+    GroupFrozen = 77,
+
+    /// Returned when a member votes twice on the same group proposal.
+    ///
+    /// Each member has a single vote per proposal.
+    AlreadyVoted = 78,
+
+    /// Returned when executing a group proposal that lacks enough approvals.
+    ///
+    /// The approving share of members must reach the configured quorum.
+    QuorumNotReached = 79,
+
     // ========== General Contract Errors (80-99) ==========
     /// Returned when a required parameter is missing or null.
     ///
@@ -199,6 +259,40 @@ pub enum SavingsError {
     ///
     /// This prevents state transitions that would break core assumptions.
     InvariantViolation = 89,
+
+    /// Returned when a queued admin action does not exist.
+    ///
+    /// It may never have been queued, or was already executed or cancelled.
+    ActionNotFound = 90,
+
+    /// Returned when a user attempts an emergency exit outside
+    /// withdrawal-only mode.
+    ///
+    /// The admin must enable withdrawal-only mode first.
+    WithdrawalOnlyModeDisabled = 91,
+
+    /// Returned when a deposit above the compliance threshold comes from a
+    /// user who is not allowed.
+    ///
+    /// The user must be allowlisted or attested first.
+    NotAllowlisted = 92,
+
+    /// Returned when a Merkle proof does not match the checkpoint it names.
+    ///
+    /// The balances, position or sibling hashes were not the ones checkpointed.
+    InvalidProof = 93,
+
+    /// Returned when an operation is retried with an idempotency key the
+    /// user already consumed.
+    ///
+    /// The original operation went through; clients should not resubmit it.
+    DuplicateRequest = 94,
+
+    /// Returned when the contract is entered while one of its own external
+    /// calls is still in progress.
+    ///
+    /// A token or strategy contract tried to call back into this contract.
+    Reentrancy = 95,
 }
 
 #[cfg(test)]
@@ -211,8 +305,13 @@ mod tests {
         // Verify that each error has a unique code
         let errors = std::vec![
             SavingsError::Unauthorized as u32,
+            SavingsError::TimelockRequired as u32,
+            SavingsError::MultisigRequired as u32,
             SavingsError::UserNotFound as u32,
             SavingsError::UserAlreadyExists as u32,
+            SavingsError::InvalidReferrer as u32,
+            SavingsError::AccountNotEmpty as u32,
+            SavingsError::AccountClosed as u32,
             SavingsError::PlanNotFound as u32,
             SavingsError::DuplicatePlanId as u32,
             SavingsError::PlanLocked as u32,
@@ -232,6 +331,12 @@ mod tests {
             SavingsError::NotGroupMember as u32,
             SavingsError::GroupCycleIncomplete as u32,
             SavingsError::InvalidGroupConfig as u32,
+            SavingsError::ReputationTooLow as u32,
+            SavingsError::AccountTooNew as u32,
+            SavingsError::AlreadyFlagged as u32,
+            SavingsError::GroupFrozen as u32,
+            SavingsError::AlreadyVoted as u32,
+            SavingsError::QuorumNotReached as u32,
             SavingsError::MissingParameter as u32,
             SavingsError::DataCorruption as u32,
             SavingsError::Overflow as u32,
@@ -242,6 +347,12 @@ mod tests {
             SavingsError::UnsupportedAsset as u32,
             SavingsError::InvalidSignature as u32,
             SavingsError::InvariantViolation as u32,
+            SavingsError::ActionNotFound as u32,
+            SavingsError::WithdrawalOnlyModeDisabled as u32,
+            SavingsError::NotAllowlisted as u32,
+            SavingsError::InvalidProof as u32,
+            SavingsError::DuplicateRequest as u32,
+            SavingsError::Reentrancy as u32,
         ];
 
         let mut sorted = errors.clone();
@@ -0,0 +1,1216 @@
+use soroban_sdk::{contractevent, Address, BytesN, Env, Event, IntoVal, String, Symbol, Val, Vec};
+
+use crate::storage_types::{
+    AdminAction, AutoSaveCatchUp, AutoSaveLegResult, Badge, Campaign, ComplianceConfig, ConfigKey,
+    DataKey, DisputeOutcome, FeeBreakdown, FeeExemption, FeeTier, FlexiBucket, GoalWithdrawPolicy,
+    GroupChange, GroupDurationBounds, GroupJoinRequirements, InterestAccrual, KeeperTipConfig,
+    MultisigAction, MultisigConfig, PendingAdminAction, PlanKind, PlanLimits, RateChange, Role,
+    ScheduledRate, SimulatedPlan, StreakPeriod, WithdrawalFeeConfig,
+};
+
+/// Sequence number the next event will carry, which is also the number of
+/// events emitted so far
+pub fn get_event_cursor(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::EventSeq))
+        .unwrap_or(0)
+}
+
+/// Publishes `event` with its data wrapped as `(sequence, data)`, where the
+/// sequence increases by one with every event the contract emits. Indexers
+/// can spot a missed event as a gap in the sequence.
+pub fn publish<E: Event>(env: &Env, event: E) {
+    let seq = get_event_cursor(env);
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::EventSeq), &(seq + 1));
+    env.events().publish_event(&Sequenced { seq, event });
+}
+
+/// An event with the contract-wide sequence number prepended to its data
+struct Sequenced<E> {
+    seq: u64,
+    event: E,
+}
+
+impl<E: Event> Event for Sequenced<E> {
+    fn topics(&self, env: &Env) -> Vec<Val> {
+        self.event.topics(env)
+    }
+
+    fn data(&self, env: &Env) -> Val {
+        (self.seq, self.event.data(env)).into_val(env)
+    }
+}
+
+// --- Protocol Administration ---
+
+#[contractevent(topics = ["init"], data_format = "single-value")]
+pub struct Initialized {
+    pub admin_public_key: BytesN<32>,
+}
+
+#[contractevent(topics = ["set_admin"], data_format = "single-value")]
+pub struct AdminChanged {
+    pub new_admin: Address,
+}
+
+#[contractevent(topics = ["role_grt"], data_format = "single-value")]
+pub struct RoleGranted {
+    #[topic]
+    pub addr: Address,
+    pub role: Role,
+}
+
+#[contractevent(topics = ["role_rvk"], data_format = "single-value")]
+pub struct RoleRevoked {
+    #[topic]
+    pub addr: Address,
+    pub role: Role,
+}
+
+#[contractevent(topics = ["upgrade"], data_format = "single-value")]
+pub struct Upgraded {
+    #[topic]
+    pub caller: Address,
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent(topics = ["pause"], data_format = "single-value")]
+pub struct ContractPaused {
+    #[topic]
+    pub caller: Address,
+}
+
+#[contractevent(topics = ["unpause"], data_format = "single-value")]
+pub struct ContractUnpaused {
+    #[topic]
+    pub caller: Address,
+}
+
+#[contractevent(topics = ["wd_only"], data_format = "single-value")]
+pub struct WithdrawalOnlyModeSet {
+    pub enabled: bool,
+}
+
+#[contractevent(topics = ["set_legacy"], data_format = "single-value")]
+pub struct LegacyEntrypointsSet {
+    pub enabled: bool,
+}
+
+/// A deprecated entrypoint was called
+#[contractevent(topics = ["deprecated"], data_format = "single-value")]
+pub struct DeprecatedCall {
+    #[topic]
+    pub entrypoint: Symbol,
+    pub replacement: Symbol,
+}
+
+#[contractevent(topics = ["set_tlock"], data_format = "single-value")]
+pub struct TimelockSet {
+    pub seconds: u64,
+}
+
+#[contractevent(topics = ["adm_queue"], data_format = "single-value")]
+pub struct AdminActionQueued {
+    #[topic]
+    pub action_id: u64,
+    pub pending: PendingAdminAction,
+}
+
+#[contractevent(topics = ["adm_exec"], data_format = "single-value")]
+pub struct AdminActionExecuted {
+    #[topic]
+    pub action_id: u64,
+    pub action: AdminAction,
+}
+
+#[contractevent(topics = ["adm_cncl"], data_format = "single-value")]
+pub struct AdminActionCancelled {
+    #[topic]
+    pub action_id: u64,
+}
+
+#[contractevent(topics = ["msig_set"], data_format = "single-value")]
+pub struct MultisigConfigured {
+    pub config: MultisigConfig,
+}
+
+#[contractevent(topics = ["msig_prop"], data_format = "single-value")]
+pub struct MultisigProposed {
+    #[topic]
+    pub proposal_id: u64,
+    #[topic]
+    pub signer: Address,
+    pub action: MultisigAction,
+}
+
+#[contractevent(topics = ["msig_appr"], data_format = "single-value")]
+pub struct MultisigApproved {
+    #[topic]
+    pub proposal_id: u64,
+    #[topic]
+    pub signer: Address,
+    pub approvals: u32,
+}
+
+#[contractevent(topics = ["msig_exec"], data_format = "single-value")]
+pub struct MultisigExecuted {
+    #[topic]
+    pub proposal_id: u64,
+    pub action: MultisigAction,
+}
+
+#[contractevent(topics = ["migrate"], data_format = "single-value")]
+pub struct MigrationProgressed {
+    #[topic]
+    pub version: u32,
+    pub next_cursor: u64,
+}
+
+#[contractevent(topics = ["mig_done"], data_format = "single-value")]
+pub struct MigrationCompleted {
+    pub version: u32,
+}
+
+#[contractevent(topics = ["mig_plans"], data_format = "single-value")]
+pub struct LegacyPlansMigrated {
+    #[topic]
+    pub user: Address,
+    pub imported: u32,
+}
+
+#[contractevent(topics = ["ttl_user"], data_format = "single-value")]
+pub struct UserStorageExtended {
+    #[topic]
+    pub user: Address,
+    pub extended: u32,
+}
+
+#[contractevent(topics = ["ttl_bulk"], data_format = "single-value")]
+pub struct StorageBulkExtended {
+    #[topic]
+    pub keeper: Address,
+    pub extended: u32,
+}
+
+// --- Protocol Parameters ---
+
+#[contractevent(topics = ["set_dsp"], data_format = "single-value")]
+pub struct DisputeThresholdSet {
+    pub threshold: u32,
+}
+
+#[contractevent(topics = ["set_gdur"], data_format = "single-value")]
+pub struct GroupDurationBoundsSet {
+    pub bounds: GroupDurationBounds,
+}
+
+#[contractevent(topics = ["set_wlim"], data_format = "single-value")]
+pub struct WithdrawLimitSet {
+    pub limit: i128,
+}
+
+#[contractevent(topics = ["set_mind"], data_format = "single-value")]
+pub struct MinimumDepositSet {
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["set_dust"], data_format = "single-value")]
+pub struct DustThresholdSet {
+    pub threshold: i128,
+}
+
+#[contractevent(topics = ["set_plim"], data_format = "single-value")]
+pub struct PlanLimitsSet {
+    pub limits: PlanLimits,
+}
+
+#[contractevent(topics = ["set_qrm"], data_format = "single-value")]
+pub struct GroupQuorumSet {
+    pub bps: u32,
+}
+
+#[contractevent(topics = ["set_rep"], data_format = "single-value")]
+pub struct ReputationSet {
+    #[topic]
+    pub user: Address,
+    pub score: u32,
+}
+
+#[contractevent(topics = ["set_ref"], data_format = "single-value")]
+pub struct ReferralRewardSet {
+    pub bps: u32,
+}
+
+#[contractevent(topics = ["set_strk"], data_format = "single-value")]
+pub struct StreakPeriodSet {
+    pub period: StreakPeriod,
+}
+
+#[contractevent(topics = ["set_brk"], data_format = "single-value")]
+pub struct EarlyBreakFeeSet {
+    pub bps: u32,
+}
+
+#[contractevent(topics = ["set_fee"], data_format = "single-value")]
+pub struct FeeRecipientSet {
+    pub recipient: Address,
+}
+
+#[contractevent(topics = ["set_wfee"], data_format = "single-value")]
+pub struct WithdrawalFeeSet {
+    pub fee: WithdrawalFeeConfig,
+}
+
+#[contractevent(topics = ["set_ftier"], data_format = "single-value")]
+pub struct FeeTiersSet {
+    pub tiers: Vec<FeeTier>,
+}
+
+#[contractevent(topics = ["fee_exmpt"], data_format = "single-value")]
+pub struct FeeExemptionSet {
+    #[topic]
+    pub exempt: bool,
+    pub exemption: FeeExemption,
+}
+
+#[contractevent(topics = ["set_ktip"], data_format = "single-value")]
+pub struct KeeperTipSet {
+    pub tip: KeeperTipConfig,
+}
+
+#[contractevent(topics = ["set_gpen"], data_format = "single-value")]
+pub struct FailedGoalPenaltySet {
+    pub enabled: bool,
+}
+
+#[contractevent(topics = ["set_grace"], data_format = "single-value")]
+pub struct GoalBreakGraceSet {
+    pub seconds: u64,
+}
+
+#[contractevent(topics = ["set_orcl"], data_format = "single-value")]
+pub struct OracleSet {
+    pub oracle: Address,
+}
+
+#[contractevent(topics = ["set_tok"], data_format = "single-value")]
+pub struct TokenSet {
+    pub token: Address,
+}
+
+#[contractevent(topics = ["set_strt"], data_format = "single-value")]
+pub struct StrategySet {
+    pub strategy: Address,
+}
+
+#[contractevent(topics = ["set_liq"], data_format = "single-value")]
+pub struct TargetLiquiditySet {
+    pub bps: u32,
+}
+
+#[contractevent(topics = ["set_ysplt"], data_format = "single-value")]
+pub struct StrategyYieldSplitSet {
+    pub treasury_bps: u32,
+}
+
+#[contractevent(topics = ["set_cmpl"], data_format = "single-value")]
+pub struct ComplianceModeSet {
+    pub config: ComplianceConfig,
+}
+
+#[contractevent(topics = ["allowlist"], data_format = "single-value")]
+pub struct AllowlistUpdated {
+    #[topic]
+    pub user: Address,
+    pub allowed: bool,
+}
+
+#[contractevent(topics = ["asset_reg"], data_format = "single-value")]
+pub struct AssetRegistered {
+    #[topic]
+    pub token: Address,
+    pub decimals: u32,
+}
+
+#[contractevent(topics = ["rate_schd"], data_format = "single-value")]
+pub struct RateScheduled {
+    #[topic]
+    pub plan_kind: PlanKind,
+    pub scheduled: ScheduledRate,
+}
+
+#[contractevent(topics = ["rate_chg"], data_format = "single-value")]
+pub struct RateChanged {
+    #[topic]
+    pub plan_kind: PlanKind,
+    pub change: RateChange,
+}
+
+// --- Treasury, Fees and Yield ---
+
+#[contractevent(topics = ["wd_fee"], data_format = "single-value")]
+pub struct WithdrawalFeeCharged {
+    #[topic]
+    pub user: Address,
+    pub breakdown: FeeBreakdown,
+}
+
+#[contractevent(topics = ["brk_fee"], data_format = "single-value")]
+pub struct BreakFeeCharged {
+    #[topic]
+    pub fee_recipient: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub fee: i128,
+}
+
+#[contractevent(topics = ["kp_tip"], data_format = "single-value")]
+pub struct KeeperTipPaid {
+    #[topic]
+    pub keeper: Address,
+    pub tip: i128,
+}
+
+#[contractevent(topics = ["fee_wd"], data_format = "single-value")]
+pub struct FeesWithdrawn {
+    #[topic]
+    pub treasurer: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["yld_fund"], data_format = "single-value")]
+pub struct YieldPoolFunded {
+    pub amount: i128,
+}
+
+/// The yield pool could not cover all the interest owed to `user`
+#[contractevent(topics = ["yld_short"], data_format = "vec")]
+pub struct YieldShortfall {
+    #[topic]
+    pub user: Address,
+    pub due: i128,
+    pub paid: i128,
+}
+
+#[contractevent(topics = ["rebalance", "supply"], data_format = "single-value")]
+pub struct StrategySupplied {
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["rebalance", "withdraw"], data_format = "single-value")]
+pub struct StrategyWithdrawn {
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["harvest"], data_format = "vec")]
+pub struct StrategyHarvested {
+    pub realized: i128,
+    pub user_share: i128,
+    pub treasury_share: i128,
+}
+
+#[contractevent(topics = ["emrg_wd"], data_format = "vec")]
+pub struct EmergencyWithdrawn {
+    #[topic]
+    pub admin: Address,
+    #[topic]
+    pub recovery: Address,
+    pub amount: i128,
+    pub from_fees: i128,
+    pub from_pool: i128,
+    pub owned_after: i128,
+}
+
+#[contractevent(topics = ["emrg_usr"], data_format = "vec")]
+pub struct EmergencyUserExit {
+    #[topic]
+    pub user: Address,
+    pub flexi_balance: i128,
+    pub matured_locks: u32,
+    pub paid: i128,
+}
+
+#[contractevent(topics = ["bal_ckpt"], data_format = "vec")]
+pub struct BalancesCheckpointed {
+    #[topic]
+    pub checkpoint_id: u32,
+    pub root: BytesN<32>,
+    pub leaf_count: u32,
+}
+
+#[contractevent(topics = ["restored"], data_format = "vec")]
+pub struct UserRestored {
+    #[topic]
+    pub user: Address,
+    pub checkpoint_id: u32,
+    pub total_balance: i128,
+    pub flexi_balance: i128,
+}
+
+#[contractevent(topics = ["interest"], data_format = "single-value")]
+pub struct InterestAccrued {
+    #[topic]
+    pub plan_kind: Symbol,
+    #[topic]
+    pub user: Address,
+    pub accrual: InterestAccrual,
+}
+
+// --- Users and Accounts ---
+
+#[contractevent(topics = ["mint"], data_format = "vec")]
+pub struct Minted {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+#[contractevent(topics = ["create_plan"], data_format = "single-value")]
+pub struct PlanCreated {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub plan_id: u64,
+    pub initial_deposit: i128,
+}
+
+#[contractevent(topics = ["referral"], data_format = "single-value")]
+pub struct ReferralRegistered {
+    #[topic]
+    pub referrer: Address,
+    pub user: Address,
+}
+
+#[contractevent(topics = ["ref_clm"], data_format = "single-value")]
+pub struct ReferralRewardsClaimed {
+    #[topic]
+    pub referrer: Address,
+    pub payout: i128,
+}
+
+#[contractevent(topics = ["acct_cls"], data_format = "single-value")]
+pub struct AccountClosed {
+    #[topic]
+    pub user: Address,
+}
+
+#[contractevent(topics = ["acct_opn"], data_format = "single-value")]
+pub struct AccountReopened {
+    #[topic]
+    pub user: Address,
+}
+
+#[contractevent(topics = ["badge"], data_format = "single-value")]
+pub struct BadgeAwarded {
+    #[topic]
+    pub user: Address,
+    pub badge: Badge,
+}
+
+#[contractevent(topics = ["lb_optout"], data_format = "single-value")]
+pub struct LeaderboardOptOutSet {
+    #[topic]
+    pub user: Address,
+    pub opted_out: bool,
+}
+
+#[contractevent(topics = ["cust_init"], data_format = "single-value")]
+pub struct CustodyOpened {
+    #[topic]
+    pub guardian: Address,
+    #[topic]
+    pub minor: Address,
+    pub release_time: u64,
+}
+
+#[contractevent(topics = ["cust_rel"], data_format = "single-value")]
+pub struct CustodyReleased {
+    #[topic]
+    pub guardian: Address,
+    #[topic]
+    pub minor: Address,
+    pub release_time: u64,
+}
+
+#[contractevent(topics = ["cust_grd"], data_format = "single-value")]
+pub struct GuardianChanged {
+    #[topic]
+    pub guardian: Address,
+    #[topic]
+    pub new_guardian: Address,
+    pub minor: Address,
+}
+
+#[contractevent(topics = ["alw_grant"], data_format = "vec")]
+pub struct AllowanceGranted {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub spender: Address,
+    pub max_amount: i128,
+    pub expiry: u64,
+}
+
+#[contractevent(topics = ["alw_rvk"], data_format = "single-value")]
+pub struct AllowanceRevoked {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub spender: Address,
+}
+
+#[contractevent(topics = ["dep_for"], data_format = "vec")]
+pub struct DepositedFor {
+    #[topic]
+    pub spender: Address,
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub remaining: i128,
+}
+
+#[contractevent(topics = ["sp_dep"], data_format = "vec")]
+pub struct SponsoredDeposit {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub nonce: u64,
+}
+
+#[contractevent(topics = ["sp_goal"], data_format = "vec")]
+pub struct SponsoredGoalCreated {
+    #[topic]
+    pub user: Address,
+    pub goal_id: u64,
+    pub nonce: u64,
+}
+
+#[contractevent(topics = ["batch_dep"], data_format = "vec")]
+pub struct BatchDeposited {
+    #[topic]
+    pub user: Address,
+    pub count: u32,
+    pub total: i128,
+}
+
+#[contractevent(topics = ["roundup"], data_format = "vec")]
+pub struct RoundUpsDeposited {
+    #[topic]
+    pub user: Address,
+    pub count: u32,
+    pub total: i128,
+}
+
+#[contractevent(topics = ["bkt_new"], data_format = "single-value")]
+pub struct BucketCreated {
+    #[topic]
+    pub user: Address,
+    pub name: Symbol,
+}
+
+#[contractevent(topics = ["bkt_del"], data_format = "single-value")]
+pub struct BucketDeleted {
+    #[topic]
+    pub user: Address,
+    pub bucket: FlexiBucket,
+}
+
+#[contractevent(topics = ["bkt_move"], data_format = "vec")]
+pub struct BucketTransfer {
+    #[topic]
+    pub user: Address,
+    pub from: Option<Symbol>,
+    pub to: Option<Symbol>,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["archived"], data_format = "single-value")]
+pub struct PlanArchived {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub plan_kind: PlanKind,
+    pub plan_id: u64,
+}
+
+#[contractevent(topics = ["attach"], data_format = "single-value")]
+pub struct AttachmentSet {
+    #[topic]
+    pub plan_kind: PlanKind,
+    #[topic]
+    pub plan_id: u64,
+    pub hash: BytesN<32>,
+}
+
+#[contractevent(topics = ["convert"], data_format = "single-value")]
+pub struct FlexiConverted {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub plan_kind: PlanKind,
+    #[topic]
+    pub plan_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["dust_swp"], data_format = "vec")]
+pub struct DustSwept {
+    #[topic]
+    pub user: Address,
+    pub goals_swept: u32,
+    pub locks_swept: u32,
+    pub total: i128,
+}
+
+// --- Simulations ---
+
+#[contractevent(topics = ["sim", "create"], data_format = "single-value")]
+pub struct SimulationCreated {
+    #[topic]
+    pub user: Address,
+    pub plan: SimulatedPlan,
+}
+
+#[contractevent(topics = ["sim", "deposit"], data_format = "single-value")]
+pub struct SimulationDeposited {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["sim", "delete"], data_format = "single-value")]
+pub struct SimulationDeleted {
+    #[topic]
+    pub user: Address,
+    pub sim_id: u64,
+}
+
+// --- AutoSave ---
+
+#[contractevent(topics = ["as_dlg"], data_format = "single-value")]
+pub struct AutoSaveDelegated {
+    #[topic]
+    pub partner: Address,
+    #[topic]
+    pub user: Address,
+    pub schedule_id: u64,
+}
+
+#[contractevent(topics = ["as_exec"], data_format = "single-value")]
+pub struct AutoSaveExecuted {
+    #[topic]
+    pub keeper: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["as_split"], data_format = "single-value")]
+pub struct AutoSaveSplit {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub results: Vec<AutoSaveLegResult>,
+}
+
+#[contractevent(topics = ["as_pause"], data_format = "single-value")]
+pub struct AutoSavePaused {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+}
+
+#[contractevent(topics = ["as_resume"], data_format = "single-value")]
+pub struct AutoSaveResumed {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub next_execution_time: u64,
+}
+
+#[contractevent(topics = ["as_upd"], data_format = "vec")]
+pub struct AutoSaveUpdated {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub amount: i128,
+    pub interval: u64,
+}
+
+#[contractevent(topics = ["as_catch"], data_format = "single-value")]
+pub struct AutoSaveCatchUpSet {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub schedule_id: u64,
+    pub catch_up: AutoSaveCatchUp,
+}
+
+// --- Lock Save ---
+
+#[contractevent(topics = ["lock_tgt"], data_format = "single-value")]
+pub struct LockTargetSet {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub lock_id: u64,
+    pub target_amount: i128,
+}
+
+#[contractevent(topics = ["lock_top"], data_format = "single-value")]
+pub struct LockToppedUp {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub lock_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["withdraw"], data_format = "single-value")]
+pub struct LockWithdrawn {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub lock_id: u64,
+    pub net_amount: i128,
+}
+
+#[contractevent(topics = ["lock_roll"], data_format = "single-value")]
+pub struct LockRolloverSet {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub lock_id: u64,
+    pub enabled: bool,
+}
+
+#[contractevent(topics = ["rollover"], data_format = "single-value")]
+pub struct LockRolledOver {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub lock_id: u64,
+    pub new_id: u64,
+}
+
+#[contractevent(topics = ["lock_swp"], data_format = "single-value")]
+pub struct LockSwept {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub lock_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["swp_exec"], data_format = "single-value")]
+pub struct LockSweepExecuted {
+    #[topic]
+    pub keeper: Address,
+    #[topic]
+    pub lock_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["lock_xfer"], data_format = "single-value")]
+pub struct LockTransferred {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub lock_id: u64,
+}
+
+// --- Goal Save ---
+
+#[contractevent(topics = ["goal_fund"], data_format = "single-value")]
+pub struct GoalFunded {
+    #[topic]
+    pub contributor: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["goal_pub"], data_format = "single-value")]
+pub struct GoalVisibilitySet {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub is_public: bool,
+}
+
+#[contractevent(topics = ["goal_brk"], data_format = "single-value")]
+pub struct GoalBroken {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub net_amount: i128,
+}
+
+#[contractevent(topics = ["goal_fin"], data_format = "vec")]
+pub struct GoalFinalized {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub completed: bool,
+    pub net_amount: i128,
+}
+
+#[contractevent(topics = ["goal_rcl"], data_format = "single-value")]
+pub struct GoalReclaimed {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub net_amount: i128,
+}
+
+#[contractevent(topics = ["goal_gift"], data_format = "single-value")]
+pub struct GoalGifted {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub goal_id: u64,
+}
+
+#[contractevent(topics = ["goal_coow"], data_format = "single-value")]
+pub struct GoalCoOwnerAdded {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub co_owner: Address,
+    pub goal_id: u64,
+}
+
+#[contractevent(topics = ["goal_wpol"], data_format = "single-value")]
+pub struct GoalWithdrawPolicySet {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub policy: GoalWithdrawPolicy,
+}
+
+#[contractevent(topics = ["goal_ms"], data_format = "single-value")]
+pub struct GoalMilestoneReached {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub goal_id: u64,
+    pub percent: u32,
+}
+
+#[contractevent(topics = ["tmpl_add"], data_format = "single-value")]
+pub struct GoalTemplateAdded {
+    pub template_id: u64,
+}
+
+#[contractevent(topics = ["tmpl_rm"], data_format = "single-value")]
+pub struct GoalTemplateRemoved {
+    pub template_id: u64,
+}
+
+#[contractevent(topics = ["goal_tmpl"], data_format = "single-value")]
+pub struct GoalCreatedFromTemplate {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub template_id: u64,
+    pub goal_id: u64,
+}
+
+// --- Group Save ---
+
+#[contractevent(topics = ["grp_new"], data_format = "single-value")]
+pub struct GroupCreated {
+    #[topic]
+    pub creator: Address,
+    pub group_id: u64,
+}
+
+#[contractevent(topics = ["grp_join"], data_format = "single-value")]
+pub struct GroupJoined {
+    #[topic]
+    pub user: Address,
+    pub group_id: u64,
+}
+
+#[contractevent(topics = ["grp_req"], data_format = "single-value")]
+pub struct GroupRequirementsSet {
+    #[topic]
+    pub creator: Address,
+    #[topic]
+    pub group_id: u64,
+    pub requirements: GroupJoinRequirements,
+}
+
+#[contractevent(topics = ["grp_stake"], data_format = "single-value")]
+pub struct GroupStakeSet {
+    #[topic]
+    pub creator: Address,
+    #[topic]
+    pub group_id: u64,
+    pub stake: i128,
+}
+
+#[contractevent(topics = ["grp_cont"], data_format = "single-value")]
+pub struct GroupContributed {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub group_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["grp_leave"], data_format = "single-value")]
+pub struct GroupLeft {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub group_id: u64,
+    pub contribution: i128,
+}
+
+#[contractevent(topics = ["grp_rfnd"], data_format = "single-value")]
+pub struct GroupRefunded {
+    #[topic]
+    pub member: Address,
+    #[topic]
+    pub group_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["grp_fail"], data_format = "single-value")]
+pub struct GroupFailed {
+    pub group_id: u64,
+}
+
+#[contractevent(topics = ["grp_max"], data_format = "single-value")]
+pub struct GroupMaxMembersSet {
+    #[topic]
+    pub group_id: u64,
+    pub max_members: u32,
+}
+
+#[contractevent(topics = ["grp_upd"], data_format = "single-value")]
+pub struct GroupTargetUpdated {
+    #[topic]
+    pub group_id: u64,
+    pub target_amount: i128,
+}
+
+#[contractevent(topics = ["grp_cncl"], data_format = "single-value")]
+pub struct GroupCancelled {
+    #[topic]
+    pub creator: Address,
+    pub group_id: u64,
+}
+
+#[contractevent(topics = ["grp_sched"], data_format = "single-value")]
+pub struct GroupScheduleSet {
+    #[topic]
+    pub group_id: u64,
+    pub frequency: u64,
+}
+
+#[contractevent(topics = ["grp_dflt"], data_format = "single-value")]
+pub struct GroupDefaulters {
+    #[topic]
+    pub group_id: u64,
+    #[topic]
+    pub round: u32,
+    pub defaulters: Vec<Address>,
+}
+
+#[contractevent(topics = ["grp_annc"], data_format = "single-value")]
+pub struct GroupAnnounced {
+    #[topic]
+    pub group_id: u64,
+    pub message: String,
+}
+
+#[contractevent(topics = ["grp_adm"], data_format = "single-value")]
+pub struct GroupAdminSet {
+    #[topic]
+    pub group_id: u64,
+    #[topic]
+    pub admin: Address,
+    pub is_admin: bool,
+}
+
+#[contractevent(topics = ["grp_invt"], data_format = "single-value")]
+pub struct GroupInvited {
+    #[topic]
+    pub group_id: u64,
+    #[topic]
+    pub user: Address,
+    pub inviter: Address,
+}
+
+#[contractevent(topics = ["grp_kick"], data_format = "single-value")]
+pub struct GroupMemberRemoved {
+    #[topic]
+    pub group_id: u64,
+    #[topic]
+    pub member: Address,
+    pub refunded: i128,
+}
+
+#[contractevent(topics = ["grp_flag"], data_format = "single-value")]
+pub struct GroupFlagged {
+    #[topic]
+    pub member: Address,
+    #[topic]
+    pub group_id: u64,
+    pub reason_code: u32,
+}
+
+#[contractevent(topics = ["grp_frz"], data_format = "single-value")]
+pub struct GroupFrozen {
+    #[topic]
+    pub group_id: u64,
+    pub flaggers: u32,
+}
+
+#[contractevent(topics = ["grp_rslv"], data_format = "single-value")]
+pub struct GroupDisputeResolved {
+    #[topic]
+    pub group_id: u64,
+    pub outcome: DisputeOutcome,
+}
+
+#[contractevent(topics = ["grp_prop"], data_format = "single-value")]
+pub struct GroupProposalCreated {
+    #[topic]
+    pub group_id: u64,
+    #[topic]
+    pub proposal_id: u64,
+    pub change: GroupChange,
+}
+
+#[contractevent(topics = ["grp_vote"], data_format = "single-value")]
+pub struct GroupVoteCast {
+    #[topic]
+    pub member: Address,
+    #[topic]
+    pub proposal_id: u64,
+    pub approve: bool,
+}
+
+#[contractevent(topics = ["grp_exec"], data_format = "single-value")]
+pub struct GroupProposalExecuted {
+    #[topic]
+    pub group_id: u64,
+    #[topic]
+    pub proposal_id: u64,
+    pub change: GroupChange,
+}
+
+// --- Challenges and Campaigns ---
+
+#[contractevent(topics = ["chal_new"], data_format = "single-value")]
+pub struct ChallengeCreated {
+    #[topic]
+    pub creator: Address,
+    pub challenge_id: u64,
+}
+
+#[contractevent(topics = ["chal_join"], data_format = "single-value")]
+pub struct ChallengeJoined {
+    #[topic]
+    pub user: Address,
+    pub challenge_id: u64,
+}
+
+#[contractevent(topics = ["chal_done"], data_format = "vec")]
+pub struct ChallengeClaimed {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub challenge_id: u64,
+    pub completed: bool,
+    pub bonus: i128,
+}
+
+#[contractevent(topics = ["cmp_new"], data_format = "single-value")]
+pub struct CampaignCreated {
+    #[topic]
+    pub campaign_id: u64,
+    pub campaign: Campaign,
+}
+
+#[contractevent(topics = ["cmp_close"], data_format = "single-value")]
+pub struct CampaignClosed {
+    #[topic]
+    pub campaign_id: u64,
+    pub unused: i128,
+}
+
+#[contractevent(topics = ["cmp_empty"], data_format = "single-value")]
+pub struct CampaignExhausted {
+    #[topic]
+    pub campaign_id: u64,
+    pub budget: i128,
+}
+
+#[contractevent(topics = ["cmp_bonus"], data_format = "single-value")]
+pub struct CampaignBonusPaid {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub campaign_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["cmp_forf"], data_format = "single-value")]
+pub struct CampaignBonusForfeited {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub campaign_id: u64,
+    pub amount: i128,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Events},
+        Address, Env, TryFromVal,
+    };
+
+    #[test]
+    fn test_events_carry_consecutive_sequence_numbers() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        assert_eq!(client.get_event_cursor(), 0);
+
+        // The first deposit awards a badge
+        let lock_id = client.create_lock_save(&user, &100, &86_400);
+        assert_eq!(client.get_event_cursor(), 1);
+
+        client.set_lock_rollover(&user, &lock_id, &true);
+        let event = env.events().all().last().unwrap();
+        let (seq, enabled) = <(u64, bool)>::try_from_val(&env, &event.2).unwrap();
+        assert_eq!((seq, enabled), (1, true));
+        assert_eq!(client.get_event_cursor(), 2);
+    }
+}
@@ -0,0 +1,130 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::achievements;
+use crate::autosave;
+use crate::custody;
+use crate::errors::SavingsError;
+use crate::flexi;
+use crate::goal;
+use crate::group;
+use crate::lock;
+use crate::storage_types::{GroupMembership, UserStateExport};
+use crate::streaks;
+use crate::users;
+
+/// Gathers the user's record, balances, plans, schedules and activity into
+/// one snapshot.
+///
+/// Plans are listed in the order they appear in the user's plan lists,
+/// including withdrawn ones that have not been archived.
+///
+/// # Errors
+/// * `UserNotFound` - If the user has not been initialized
+pub fn export_user_state(env: &Env, user: &Address) -> Result<UserStateExport, SavingsError> {
+    let user_data = users::get_user(env, user)?;
+
+    let mut lock_saves = Vec::new(env);
+    for lock_id in lock::get_user_lock_saves(env, user).iter() {
+        if let Some(lock_save) = lock::get_lock_save(env, lock_id) {
+            lock_saves.push_back(lock_save);
+        }
+    }
+
+    let mut goal_saves = Vec::new(env);
+    for goal_id in goal::get_user_goal_saves(env, user).iter() {
+        if let Some(goal_save) = goal::get_goal_save(env, goal_id) {
+            goal_saves.push_back(goal_save);
+        }
+    }
+
+    let mut groups = Vec::new(env);
+    for group_id in group::get_user_groups(env, user).iter() {
+        if let Some(group_save) = group::get_group_save(env, group_id) {
+            groups.push_back(GroupMembership {
+                group: group_save,
+                contribution: group::get_member_contribution(env, group_id, user),
+            });
+        }
+    }
+
+    let mut autosaves = Vec::new(env);
+    for schedule_id in autosave::get_user_autosaves(env, user).iter() {
+        if let Some(schedule) = autosave::get_autosave(env, schedule_id) {
+            autosaves.push_back(schedule);
+        }
+    }
+
+    let custody = custody::get_custody(env, user);
+
+    Ok(UserStateExport {
+        user: user_data,
+        flexi_balance: flexi::get_flexi_balance(env, user.clone())?,
+        lock_saves,
+        goal_saves,
+        groups,
+        autosaves,
+        streak: streaks::get_user_streak(env, user),
+        badges: achievements::get_user_badges(env, user),
+        referrer: users::get_referrer(env, user),
+        guardian: custody.as_ref().map(|c| c.guardian.clone()),
+        release_time: custody.map_or(0, |c| c.release_time),
+        exported_at: env.ledger().timestamp(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GroupMetadata, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{testutils::Address as _, Address, Env, String, Symbol};
+
+    #[test]
+    fn test_export_user_state_gathers_every_plan() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+
+        assert_eq!(
+            client.try_export_user_state(&user),
+            Err(Ok(SavingsError::UserNotFound))
+        );
+
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &700);
+        let lock_id = client.create_lock_save(&user, &1_000, &86_400);
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "car"), &5_000, &200);
+        let group_id = client.create_group_save(
+            &user,
+            &GroupMetadata {
+                title: String::from_str(&env, "Club"),
+                description: String::from_str(&env, "Saving club"),
+                category: String::from_str(&env, "savings"),
+            },
+            &10_000,
+            &0,
+            &100,
+            &true,
+            &1,
+            &1_000,
+            &0,
+        );
+        client.contribute_to_group_save(&user, &group_id, &300);
+        let schedule_id = client.create_autosave(&user, &50, &86_400, &0);
+
+        let state = client.export_user_state(&user);
+        assert_eq!(state.user, client.get_user(&user));
+        assert_eq!(state.flexi_balance, 700);
+        assert_eq!(state.lock_saves.get(0).unwrap().id, lock_id);
+        assert_eq!(state.goal_saves.get(0).unwrap().id, goal_id);
+        let membership = state.groups.get(0).unwrap();
+        assert_eq!(membership.group.id, group_id);
+        assert_eq!(membership.contribution, 300);
+        assert_eq!(state.autosaves.get(0).unwrap().id, schedule_id);
+        assert_eq!(state.streak, client.get_user_streak(&user));
+        assert_eq!(state.badges, client.get_user_badges(&user));
+        assert_eq!(state.referrer, None);
+        assert_eq!(state.guardian, None);
+        assert_eq!(state.release_time, 0);
+    }
+}
@@ -0,0 +1,664 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::guard;
+use crate::pagination;
+use crate::reentrancy;
+use crate::storage_types::{
+    ConfigKey, Cursor, DataKey, FeeBreakdown, FeeExemption, FeeTier, KeeperTipConfig, ParamKey,
+    PlanKind, UserKey, WithdrawalFeeConfig, MAX_FEE_TIERS,
+};
+use crate::strategy;
+
+/// Adds `amount` to the user's lifetime fee total.
+///
+/// Every path that charges a user a protocol fee must call this once per fee.
+pub fn record_fee_paid(env: &Env, user: &Address, amount: i128) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Ok(());
+    }
+    let key = DataKey::UserState(UserKey::LifetimeFees(user.clone()));
+    let total = get_user_lifetime_fees(env, user)
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage().persistent().set(&key, &total);
+    Ok(())
+}
+
+/// Total protocol fees the user has paid across all operations
+pub fn get_user_lifetime_fees(env: &Env, user: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::LifetimeFees(user.clone())))
+        .unwrap_or(0)
+}
+
+pub fn get_withdrawal_fee(env: &Env) -> WithdrawalFeeConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::WithdrawalFee)
+        .unwrap_or_default()
+}
+
+/// Tiered withdrawal fee schedule, lowest tier first. Empty means the
+/// `WithdrawalFeeConfig` bps applies to every amount.
+pub fn get_fee_tiers(env: &Env) -> Vec<FeeTier> {
+    env.storage()
+        .instance()
+        .get(&DataKey::PlatformFee)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Checks a tier schedule: at most `MAX_FEE_TIERS` tiers, the first starting
+/// at 0, thresholds strictly rising and rates at most 10_000 bps and never
+/// rising with the amount. An empty schedule is valid.
+///
+/// # Errors
+/// * `InvalidAmount` - If any of the above does not hold
+pub fn validate_fee_tiers(tiers: &Vec<FeeTier>) -> Result<(), SavingsError> {
+    if tiers.len() > MAX_FEE_TIERS {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let mut previous: Option<FeeTier> = None;
+    for tier in tiers.iter() {
+        let valid = tier.bps <= 10_000
+            && match &previous {
+                None => tier.min_amount == 0,
+                Some(prev) => tier.min_amount > prev.min_amount && tier.bps <= prev.bps,
+            };
+        if !valid {
+            return Err(SavingsError::InvalidAmount);
+        }
+        previous = Some(tier);
+    }
+    Ok(())
+}
+
+/// Rate charged on a withdrawal of `amount` under `tiers`, or `default_bps`
+/// if there are none
+fn tier_bps(tiers: &Vec<FeeTier>, default_bps: u32, amount: i128) -> u32 {
+    let mut bps = default_bps;
+    for tier in tiers.iter() {
+        if tier.min_amount > amount {
+            break;
+        }
+        bps = tier.bps;
+    }
+    bps
+}
+
+/// Whether replacing the current schedule with `tiers` charges some amount
+/// more than it is charged now
+pub fn fee_tiers_raise(env: &Env, tiers: &Vec<FeeTier>) -> bool {
+    let current = get_fee_tiers(env);
+    let default_bps = get_withdrawal_fee(env).bps;
+    // Both schedules are step functions, so comparing at every step of
+    // either one covers every amount
+    current
+        .iter()
+        .chain(tiers.iter())
+        .map(|tier| tier.min_amount)
+        .chain(core::iter::once(0))
+        .any(|amount| {
+            tier_bps(tiers, default_bps, amount) > tier_bps(&current, default_bps, amount)
+        })
+}
+
+/// Withdrawal fee on `amount` before any exemption: the tier rate (or the
+/// configured `bps` without tiers) plus the flat fee, never more than `amount`
+pub fn get_fee_for_amount(env: &Env, amount: i128) -> Result<i128, SavingsError> {
+    let config = get_withdrawal_fee(env);
+    let bps = tier_bps(&get_fee_tiers(env), config.bps, amount);
+    let fee = amount
+        .checked_mul(bps as i128)
+        .ok_or(SavingsError::Overflow)?
+        / 10_000
+        + config.flat;
+    Ok(fee.clamp(0, amount.max(0)))
+}
+
+/// Fees held by the contract across every current and past fee recipient
+pub fn get_total_fee_balance(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::ProtocolFeeTotal))
+        .unwrap_or(0)
+}
+
+pub fn get_fee_balance(env: &Env, recipient: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TotalBalance(recipient.clone()))
+        .unwrap_or(0)
+}
+
+/// Adds `delta` to a recipient's fee balance and to the protocol-wide total
+fn adjust_fee_balance(env: &Env, recipient: &Address, delta: i128) -> Result<(), SavingsError> {
+    let balance = get_fee_balance(env, recipient)
+        .checked_add(delta)
+        .ok_or(SavingsError::Overflow)?;
+    let total = get_total_fee_balance(env)
+        .checked_add(delta)
+        .ok_or(SavingsError::Overflow)?;
+    if balance < 0 || total < 0 {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::TotalBalance(recipient.clone()), &balance);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::ProtocolFeeTotal), &total);
+    Ok(())
+}
+
+/// Adds `amount` to the fee recipient's balance.
+///
+/// Returns the recipient, or `None` if no treasury is configured.
+pub fn credit_treasury(env: &Env, amount: i128) -> Result<Option<Address>, SavingsError> {
+    let recipient: Option<Address> = env.storage().instance().get(&DataKey::FeeRecipient);
+    if let Some(recipient) = &recipient {
+        adjust_fee_balance(env, recipient, amount)?;
+    }
+    Ok(recipient)
+}
+
+/// Takes `amount` out of a recipient's fee balance, failing with
+/// `InsufficientBalance` if it holds less
+pub fn debit_treasury(env: &Env, recipient: &Address, amount: i128) -> Result<(), SavingsError> {
+    adjust_fee_balance(env, recipient, -amount)
+}
+
+/// Transfers `amount` of the current fee recipient's accumulated fees, in the
+/// deposit token, to `to`.
+///
+/// Callers are responsible for authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `InsufficientBalance` - If no treasury is set or it holds less than `amount`
+/// * `MissingParameter` - If no deposit token is configured
+pub fn withdraw_protocol_fees(env: &Env, amount: i128, to: &Address) -> Result<(), SavingsError> {
+    guard::check_protocol(env)?;
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let recipient: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::FeeRecipient)
+        .ok_or(SavingsError::InsufficientBalance)?;
+    debit_treasury(env, &recipient, amount)?;
+
+    let token = strategy::get_token(env)?;
+    reentrancy::transfer(env, &token, &env.current_contract_address(), to, amount)
+}
+
+/// Grants or revokes a withdrawal fee exemption. Returns whether anything
+/// changed.
+///
+/// Callers are responsible for authorization.
+pub fn set_fee_exemption(env: &Env, exemption: &FeeExemption, exempt: bool) -> bool {
+    let flag_key = DataKey::Config(ConfigKey::FeeExempt(exemption.clone()));
+    if env.storage().persistent().has(&flag_key) == exempt {
+        return false;
+    }
+
+    let mut exemptions = get_all_fee_exemptions(env);
+    if exempt {
+        env.storage().persistent().set(&flag_key, &true);
+        exemptions.push_back(exemption.clone());
+    } else {
+        env.storage().persistent().remove(&flag_key);
+        if let Some(index) = exemptions.first_index_of(exemption) {
+            exemptions.remove(index);
+        }
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::FeeExemptions), &exemptions);
+    true
+}
+
+/// Whether withdrawals by `user` out of a `plan_kind` plan skip the
+/// withdrawal fee and any early-break or failed-goal penalty
+pub fn is_fee_exempt(env: &Env, user: &Address, plan_kind: PlanKind) -> bool {
+    let storage = env.storage().persistent();
+    storage.has(&DataKey::Config(ConfigKey::FeeExempt(FeeExemption::User(
+        user.clone(),
+    )))) || storage.has(&DataKey::Config(ConfigKey::FeeExempt(FeeExemption::Kind(
+        plan_kind,
+    ))))
+}
+
+/// Returns a page of the fee exemptions in force, in the order they were
+/// granted
+pub fn get_fee_exemptions(env: &Env, cursor: Cursor) -> (Vec<FeeExemption>, Option<Cursor>) {
+    pagination::paginate(env, &get_all_fee_exemptions(env), &cursor)
+}
+
+fn get_all_fee_exemptions(env: &Env) -> Vec<FeeExemption> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::FeeExemptions))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Splits a withdrawal of `gross` by `user` out of a `plan_kind` plan into
+/// the configured fee and the net amount, without charging anything
+pub fn quote_withdrawal_fee(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    gross: i128,
+) -> Result<FeeBreakdown, SavingsError> {
+    if is_fee_exempt(env, user, plan_kind) {
+        return Ok(FeeBreakdown {
+            gross,
+            fee: 0,
+            net: gross,
+        });
+    }
+    let fee = get_fee_for_amount(env, gross)?;
+    Ok(FeeBreakdown {
+        gross,
+        fee,
+        net: gross - fee,
+    })
+}
+
+/// Splits a withdrawal of `gross` into the configured fee and the net amount
+/// paid to the user, routing the fee to the treasury.
+///
+/// Every Flexi, Lock and Goal withdrawal must call this once.
+pub fn charge_withdrawal_fee(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    gross: i128,
+) -> Result<FeeBreakdown, SavingsError> {
+    let breakdown = quote_withdrawal_fee(env, user, plan_kind, gross)?;
+    let fee = breakdown.fee;
+
+    if fee > 0 {
+        record_fee_paid(env, user, fee)?;
+        credit_treasury(env, fee)?;
+    }
+    events::publish(
+        env,
+        events::WithdrawalFeeCharged {
+            user: user.clone(),
+            breakdown: breakdown.clone(),
+        },
+    );
+    Ok(breakdown)
+}
+
+pub fn get_keeper_tip(env: &Env) -> KeeperTipConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::KeeperTip))
+        .unwrap_or_default()
+}
+
+/// Stores the keeper tip, failing with `InvalidAmount` if `flat` or
+/// `max_per_ledger` is negative
+pub fn set_keeper_tip(env: &Env, tip: &KeeperTipConfig) -> Result<(), SavingsError> {
+    if tip.flat < 0 || tip.max_per_ledger < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::KeeperTip), tip);
+    Ok(())
+}
+
+/// Pays `keeper` the flat tip for `executions` run on other users' plans, in
+/// the deposit token out of the treasury's fees.
+///
+/// Callers leave out executions on the keeper's own plans, so nobody earns
+/// tips for managing their own savings. The tip is capped at what is left of
+/// the per-ledger budget and at what the treasury holds, and nothing is paid
+/// if no treasury or token is configured, so a keeper batch never fails over
+/// its tip. Returns the amount paid.
+pub fn pay_keeper_tip(env: &Env, keeper: &Address, executions: u32) -> Result<i128, SavingsError> {
+    let config = get_keeper_tip(env);
+    let tip = config
+        .flat
+        .checked_mul(executions as i128)
+        .ok_or(SavingsError::Overflow)?;
+
+    let recipient: Option<Address> = env.storage().instance().get(&DataKey::FeeRecipient);
+    let (Some(recipient), Ok(token)) = (recipient, strategy::get_token(env)) else {
+        return Ok(0);
+    };
+    let paid_key = DataKey::Config(ConfigKey::Param(ParamKey::KeeperTipsPaid));
+    let sequence = env.ledger().sequence();
+    let paid_this_ledger = match env.storage().instance().get::<_, (u32, i128)>(&paid_key) {
+        Some((paid_sequence, paid)) if paid_sequence == sequence => paid,
+        _ => 0,
+    };
+    let tip = tip
+        .min(config.max_per_ledger - paid_this_ledger)
+        .min(get_fee_balance(env, &recipient));
+    if tip <= 0 {
+        return Ok(0);
+    }
+
+    debit_treasury(env, &recipient, tip)?;
+    env.storage()
+        .instance()
+        .set(&paid_key, &(sequence, paid_this_ledger + tip));
+    reentrancy::transfer(env, &token, &env.current_contract_address(), keeper, tip)?;
+    events::publish(
+        env,
+        events::KeeperTipPaid {
+            keeper: keeper.clone(),
+            tip,
+        },
+    );
+    Ok(tip)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Cursor, FeeExemption, FeeTier, KeeperTipConfig, NesteraContract, NesteraContractClient,
+        PlanKind, SavingsError, WithdrawalFeeConfig,
+    };
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        token, Address, BytesN, Env,
+    };
+
+    fn setup() -> (
+        Env,
+        NesteraContractClient<'static>,
+        Address,
+        Address,
+        Address,
+    ) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &treasury,
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+
+        (env, client, admin, treasury, user)
+    }
+
+    #[test]
+    fn test_withdrawal_fee_routed_to_treasury() {
+        let (env, client, admin, treasury, user) = setup();
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 5 });
+
+        client.deposit_flexi(&user, &1_000);
+        client.withdraw_flexi(&user, &500);
+        assert_eq!(client.get_flexi_balance(&user), 500);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 10);
+
+        let lock_id = client.create_lock_save(&user, &1_000, &100);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        // 1% of 1_000 plus the flat 5
+        assert_eq!(client.withdraw_lock_save(&user, &lock_id), 985);
+
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 25);
+        assert_eq!(client.get_user_lifetime_fees(&user), 25);
+    }
+
+    #[test]
+    fn test_withdrawal_fee_never_exceeds_amount() {
+        let (_env, client, admin, treasury, user) = setup();
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 0, flat: 50 });
+
+        client.deposit_flexi(&user, &30);
+        client.withdraw_flexi(&user, &30);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 30);
+    }
+
+    #[test]
+    fn test_fee_exemptions_by_user_and_plan_kind() {
+        let (env, client, admin, treasury, user) = setup();
+        let partner = Address::generate(&env);
+        client.initialize_user(&partner);
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 0 });
+
+        let by_user = FeeExemption::User(partner.clone());
+        let by_kind = FeeExemption::Kind(PlanKind::Lock);
+        client.set_fee_exemption(&admin, &by_user, &true);
+        client.set_fee_exemption(&admin, &by_kind, &true);
+        // Granting twice does not list the exemption twice
+        client.set_fee_exemption(&admin, &by_user, &true);
+        assert_eq!(
+            client.get_fee_exemptions(&Cursor {
+                offset: 0,
+                limit: 0
+            }),
+            (
+                soroban_sdk::vec![&env, by_user.clone(), by_kind.clone()],
+                None
+            )
+        );
+        assert!(client.is_fee_exempt(&partner, &PlanKind::Flexi));
+        assert!(client.is_fee_exempt(&user, &PlanKind::Lock));
+        assert!(!client.is_fee_exempt(&user, &PlanKind::Flexi));
+
+        client.deposit_flexi(&partner, &1_000);
+        client.withdraw_flexi(&partner, &1_000);
+        let lock_id = client.create_lock_save(&user, &1_000, &100);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        assert_eq!(client.withdraw_lock_save(&user, &lock_id), 1_000);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+
+        client.set_fee_exemption(&admin, &by_user, &false);
+        assert_eq!(
+            client
+                .get_fee_exemptions(&Cursor {
+                    offset: 0,
+                    limit: 0
+                })
+                .0,
+            soroban_sdk::vec![&env, by_kind.clone()]
+        );
+        client.deposit_flexi(&partner, &1_000);
+        client.withdraw_flexi(&partner, &1_000);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 10);
+
+        assert_eq!(
+            client
+                .try_set_fee_exemption(&Address::generate(&env), &by_user, &true)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_tiered_withdrawal_fee() {
+        let (env, client, admin, treasury, user) = setup();
+        let tier = |min_amount: i128, bps: u32| FeeTier { min_amount, bps };
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 0 });
+        let tiers = soroban_sdk::vec![&env, tier(0, 50), tier(1_000, 25), tier(10_000, 10)];
+        client.set_fee_tiers(&admin, &tiers);
+        assert_eq!(client.get_fee_tiers(), tiers);
+        assert_eq!(client.get_protocol_params().fee_tiers, tiers);
+
+        assert_eq!(client.get_fee_for_amount(&999), 4);
+        assert_eq!(client.get_fee_for_amount(&1_000), 2);
+        assert_eq!(client.get_fee_for_amount(&20_000), 20);
+
+        client.deposit_flexi(&user, &30_000);
+        client.withdraw_flexi(&user, &800);
+        let lock_id = client.create_lock_save(&user, &20_000, &100);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        assert_eq!(client.withdraw_lock_save(&user, &lock_id), 19_980);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 24);
+
+        for bad in [
+            soroban_sdk::vec![&env, tier(1, 50)],
+            soroban_sdk::vec![&env, tier(0, 50), tier(1_000, 60)],
+            soroban_sdk::vec![&env, tier(0, 50), tier(0, 25)],
+            soroban_sdk::vec![&env, tier(0, 10_001)],
+        ] {
+            assert_eq!(
+                client.try_set_fee_tiers(&admin, &bad).unwrap_err(),
+                Ok(SavingsError::InvalidAmount)
+            );
+        }
+
+        // Lowering is allowed directly, raising any tier waits for the timelock
+        client.set_admin_timelock(&admin, &3_600);
+        client.set_fee_tiers(
+            &admin,
+            &soroban_sdk::vec![&env, tier(0, 50), tier(1_000, 20), tier(10_000, 10)],
+        );
+        assert_eq!(
+            client
+                .try_set_fee_tiers(
+                    &admin,
+                    &soroban_sdk::vec![&env, tier(0, 50), tier(5_000, 20), tier(10_000, 10)]
+                )
+                .unwrap_err(),
+            Ok(SavingsError::TimelockRequired)
+        );
+        // Dropping the schedule falls back to the 100 bps flat config
+        assert_eq!(
+            client
+                .try_set_fee_tiers(&admin, &soroban_sdk::vec![&env])
+                .unwrap_err(),
+            Ok(SavingsError::TimelockRequired)
+        );
+    }
+
+    #[test]
+    fn test_set_withdrawal_fee_validation() {
+        let (env, client, admin, _treasury, _user) = setup();
+        assert_eq!(
+            client
+                .try_set_withdrawal_fee(
+                    &admin,
+                    &WithdrawalFeeConfig {
+                        bps: 10_001,
+                        flat: 0
+                    }
+                )
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+        assert_eq!(
+            client
+                .try_set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 0, flat: -1 })
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+        assert_eq!(
+            client
+                .try_set_withdrawal_fee(
+                    &Address::generate(&env),
+                    &WithdrawalFeeConfig { bps: 10, flat: 0 }
+                )
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+
+        client.set_admin_timelock(&admin, &3_600);
+        assert_eq!(
+            client
+                .try_set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 10, flat: 0 })
+                .unwrap_err(),
+            Ok(SavingsError::TimelockRequired)
+        );
+        assert_eq!(client.get_protocol_params().withdrawal_fee.bps, 0);
+    }
+
+    #[test]
+    fn test_keeper_tipped_from_treasury_for_autosave_batch() {
+        let (env, client, admin, treasury, user) = setup();
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        client.set_token(&admin, &token);
+        token::StellarAssetClient::new(&env, &token).mint(&client.address, &10_000);
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 0, flat: 15 });
+        client.set_keeper_tip(
+            &admin,
+            &KeeperTipConfig {
+                flat: 2,
+                max_per_ledger: 5,
+            },
+        );
+        assert_eq!(client.get_protocol_params().keeper_tip.flat, 2);
+
+        client.deposit_flexi(&user, &100);
+        client.withdraw_flexi(&user, &100);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 15);
+
+        let first = client.create_autosave(&user, &500, &100, &0);
+        let second = client.create_autosave(&user, &300, &100, &0);
+        let keeper = Address::generate(&env);
+        client.execute_autosave_batch(&keeper, &soroban_sdk::vec![&env, first, second]);
+
+        // 2 per execution, whatever the amounts moved
+        assert_eq!(token::Client::new(&env, &token).balance(&keeper), 4);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 11);
+
+        // Tips across batches in one ledger stop at the per-ledger budget
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.execute_autosave_batch(&keeper, &soroban_sdk::vec![&env, first, second]);
+        assert_eq!(token::Client::new(&env, &token).balance(&keeper), 5);
+
+        // Owners running their own schedules earn nothing
+        env.ledger().with_mut(|li| {
+            li.timestamp = 200;
+            li.sequence_number += 1;
+        });
+        client.execute_autosave_batch(&user, &soroban_sdk::vec![&env, first, second]);
+        assert_eq!(token::Client::new(&env, &token).balance(&user), 0);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 10);
+    }
+
+    #[test]
+    fn test_treasurer_withdraws_protocol_fees_in_tokens() {
+        let (env, client, admin, treasury, user) = setup();
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        client.set_token(&admin, &token);
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 0 });
+
+        client.deposit_flexi(&user, &1_000);
+        token::StellarAssetClient::new(&env, &token).mint(&client.address, &1_000);
+        client.withdraw_flexi(&user, &500);
+        assert_eq!(client.get_total_protocol_fee_balance(), 5);
+
+        let to = Address::generate(&env);
+        assert_eq!(
+            client
+                .try_withdraw_protocol_fees(&admin, &6, &to)
+                .unwrap_err(),
+            Ok(SavingsError::InsufficientBalance)
+        );
+        assert_eq!(
+            client
+                .try_withdraw_protocol_fees(&Address::generate(&env), &5, &to)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+
+        client.withdraw_protocol_fees(&admin, &5, &to);
+        assert_eq!(token::Client::new(&env, &token).balance(&to), 5);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+        assert_eq!(client.get_total_protocol_fee_balance(), 0);
+    }
+}
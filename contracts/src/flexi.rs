@@ -1,16 +1,34 @@
 // New/Correct
-use crate::ensure_not_paused;
+use crate::buckets;
+use crate::campaigns;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, User};
+use crate::fees;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::rates;
+use crate::storage_types::{DataKey, InterestStatement, PlanKind, TxKind, User, WithdrawalPreview};
+use crate::strategy;
+use crate::{record_deposit_activity, record_withdrawal_activity};
 use soroban_sdk::{Address, Env};
 
 /// Handles depositing funds into the Flexi Save pool.
 pub fn flexi_deposit(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-    ensure_not_paused(&env)?;
+    guard::check(&env, &user, Op::Deposit, amount)?;
 
     // 1. Verify the caller is the user
     user.require_auth();
 
+    apply_flexi_deposit(&env, &user, amount)
+}
+
+/// Adds a deposit to the user's Flexi Save balance.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn apply_flexi_deposit(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
     // 2. Validate the amount
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
@@ -37,16 +55,32 @@ pub fn flexi_deposit(env: Env, user: Address, amount: i128) -> Result<(), Saving
         return Err(SavingsError::UserNotFound);
     }
 
+    strategy::adjust_flexi_liabilities(env, amount)?;
+    record_deposit_activity(env, user, PlanKind::Flexi, 0, amount);
+
     Ok(())
 }
 
 /// Handles withdrawing funds from the Flexi Save pool.
 pub fn flexi_withdraw(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-    ensure_not_paused(&env)?;
+    guard::check(&env, &user, Op::Withdraw, amount)?;
 
     // 1. Verify the caller is the user
     user.require_auth();
 
+    apply_flexi_withdraw(&env, &user, amount)?;
+    Ok(())
+}
+
+/// Takes a withdrawal out of the user's Flexi Save balance, charging the
+/// withdrawal fee. Returns the amount paid out net of the fee.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn apply_flexi_withdraw(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+) -> Result<i128, SavingsError> {
     // 2. Validate the amount
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
@@ -64,6 +98,7 @@ pub fn flexi_withdraw(env: Env, user: Address, amount: i128) -> Result<(), Savin
     env.storage()
         .persistent()
         .set(&flexi_key, &new_flexi_balance);
+    buckets::fit_to_balance(env, user, new_flexi_balance);
 
     // 4. Sync with the main User struct (Total Balance)
     // This is necessary so that client.get_user() reflects the withdrawal
@@ -73,8 +108,76 @@ pub fn flexi_withdraw(env: Env, user: Address, amount: i128) -> Result<(), Savin
         env.storage().persistent().set(&user_key, &user_data);
     }
 
-    Ok(())
+    strategy::adjust_flexi_liabilities(env, -amount)?;
+    let breakdown = fees::charge_withdrawal_fee(env, user, PlanKind::Flexi, amount)?;
+    campaigns::settle_flexi(env, user)?;
+    history::record(
+        env,
+        user,
+        TxKind::Withdraw,
+        PlanKind::Flexi,
+        0,
+        amount,
+        breakdown.fee,
+    );
+    record_withdrawal_activity(env, user, amount);
+    Ok(breakdown.net)
+}
+/// Credits `amount` to a user's Flexi Save balance without an external deposit.
+///
+/// Used for internal payouts such as referral rewards; does not count towards
+/// streaks or badges.
+pub fn credit_flexi(env: &Env, user: &Address, amount: i128) -> Result<(), SavingsError> {
+    let user_key = DataKey::User(user.clone());
+    let mut user_data: User = env
+        .storage()
+        .persistent()
+        .get(&user_key)
+        .ok_or(SavingsError::UserNotFound)?;
+    user_data.total_balance = user_data
+        .total_balance
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage().persistent().set(&user_key, &user_data);
+
+    let flexi_key = DataKey::FlexiBalance(user.clone());
+    let current_flexi_balance: i128 = env.storage().persistent().get(&flexi_key).unwrap_or(0);
+    let new_flexi_balance = current_flexi_balance
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage()
+        .persistent()
+        .set(&flexi_key, &new_flexi_balance);
+
+    strategy::adjust_flexi_liabilities(env, amount)
+}
+
+/// Debits `amount` from a user's Flexi Save balance to fund another of their
+/// plans.
+///
+/// The funds stay in the protocol, so no withdrawal fee or limit applies.
+pub(crate) fn debit_flexi(env: &Env, user: &Address, amount: i128) -> Result<(), SavingsError> {
+    let flexi_key = DataKey::FlexiBalance(user.clone());
+    let current_flexi_balance: i128 = env.storage().persistent().get(&flexi_key).unwrap_or(0);
+    if current_flexi_balance < amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    env.storage()
+        .persistent()
+        .set(&flexi_key, &(current_flexi_balance - amount));
+    buckets::fit_to_balance(env, user, current_flexi_balance - amount);
+
+    let user_key = DataKey::User(user.clone());
+    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
+        user_data.total_balance -= amount;
+        env.storage().persistent().set(&user_key, &user_data);
+    }
+    // The funded plan counts the amount as a deposit
+    record_withdrawal_activity(env, user, amount);
+
+    strategy::adjust_flexi_liabilities(env, -amount)
 }
+
 /// Returns the user's Flexi Save balance.
 /// This is a read-only (view) function.
 pub fn get_flexi_balance(env: &Env, user: Address) -> Result<i128, SavingsError> {
@@ -101,3 +204,73 @@ pub fn has_flexi_balance(env: &Env, user: Address) -> bool {
 
     balance > 0
 }
+
+/// VIEW FUNCTION - What withdrawing `amount` from the user's Flexi Save would
+/// pay out if done now
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `UserNotFound` - If the user is not initialized
+/// * `InsufficientBalance` - If the Flexi balance is below `amount`
+pub fn preview_withdraw_flexi(
+    env: &Env,
+    user: Address,
+    amount: i128,
+) -> Result<WithdrawalPreview, SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if get_flexi_balance(env, user.clone())? < amount {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    let breakdown = fees::quote_withdrawal_fee(env, &user, PlanKind::Flexi, amount)?;
+    Ok(WithdrawalPreview {
+        gross: breakdown.gross,
+        interest: 0,
+        penalty: 0,
+        fee: breakdown.fee,
+        net: breakdown.net,
+    })
+}
+
+/// Year the Flexi rate is quoted over
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// VIEW FUNCTION - Interest statement of the user's Flexi Save at the current
+/// Flexi rate: interest earned since the balance last moved, interest over
+/// the next year, and the fee on withdrawing everything after that year.
+///
+/// # Errors
+/// * `UserNotFound` - If the user is not initialized
+pub fn get_flexi_statement(env: &Env, user: Address) -> Result<InterestStatement, SavingsError> {
+    let principal = get_flexi_balance(env, user.clone())?;
+    let rate = rates::get_flexi_rate(env);
+
+    let since = history::last_record(env, &user, PlanKind::Flexi, 0)
+        .map_or(env.ledger().timestamp(), |record| record.timestamp);
+    let accrued_interest = rates::calculate_flexi_interest(
+        principal,
+        rate,
+        env.ledger().timestamp().saturating_sub(since),
+    );
+    let projected_interest = rates::calculate_flexi_interest(principal, rate, SECONDS_PER_YEAR);
+    let projected_fee =
+        fees::quote_withdrawal_fee(env, &user, PlanKind::Flexi, principal + projected_interest)?
+            .fee;
+    let effective_apy_bps = if principal > 0 {
+        (projected_interest - projected_fee).saturating_mul(10_000) / principal
+    } else {
+        0
+    };
+
+    Ok(InterestStatement {
+        plan_kind: PlanKind::Flexi,
+        plan_id: 0,
+        principal,
+        interest_rate: rate,
+        accrued_interest,
+        projected_interest,
+        projected_fee,
+        effective_apy_bps,
+    })
+}
@@ -1,9 +1,36 @@
-use soroban_sdk::{symbol_short, Address, Env, Vec};
+use soroban_sdk::{Address, Env, Vec};
 
-use crate::ensure_not_paused;
+use crate::achievements;
+use crate::campaigns;
+use crate::custody;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, GoalSave, User};
+use crate::events;
+use crate::fees;
+use crate::flexi;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::limits;
+use crate::migrations;
+use crate::oracle;
+use crate::plan_ids;
+use crate::storage_types::{
+    Badge, ConfigKey, DataKey, GoalContribution, GoalMilestone, GoalSave, GoalWithdrawPolicy,
+    PlanKind, TxKind, User, UserKey, WithdrawalPreview,
+};
 use crate::users;
+use crate::{record_deposit_activity, record_withdrawal_activity};
+
+/// Progress thresholds, in percent of the target, tracked for every goal
+const MILESTONE_PERCENTS: [u32; 4] = [25, 50, 75, 100];
+
+/// Most users a goal can be shared with besides its owner
+pub const MAX_GOAL_CO_OWNERS: u32 = 3;
+
+/// Most distinct outside contributors a public goal can have
+pub const MAX_GOAL_CONTRIBUTORS: u32 = 100;
+
+/// Longest grace window that can be configured for free goal breaks
+pub const MAX_GOAL_BREAK_GRACE: u64 = 30 * 24 * 60 * 60;
 
 pub fn create_goal_save(
     env: &Env,
@@ -11,10 +38,63 @@ pub fn create_goal_save(
     goal_name: soroban_sdk::Symbol,
     target_amount: i128,
     initial_deposit: i128,
+    deadline: u64,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &user, Op::CreatePlan, initial_deposit)?;
+    user.require_auth();
+    insert_goal_save(
+        env,
+        user,
+        goal_name,
+        target_amount,
+        initial_deposit,
+        deadline,
+    )
+}
+
+/// Creates a Goal Save whose target is set in the oracle's reference unit
+/// (e.g. USD cents) rather than in tokens.
+///
+/// The token target is derived from the current price here and re-derived on
+/// every deposit, so the goal completes once the savings are worth
+/// `target_fiat`.
+pub fn create_goal_save_fiat(
+    env: &Env,
+    user: Address,
+    goal_name: soroban_sdk::Symbol,
+    target_fiat: i128,
+    initial_deposit: i128,
+    deadline: u64,
 ) -> Result<u64, SavingsError> {
-    ensure_not_paused(env)?;
+    guard::check(env, &user, Op::CreatePlan, initial_deposit)?;
     user.require_auth();
+    if target_fiat <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let target_amount = oracle::to_tokens(target_fiat, oracle::get_price(env)?)?;
+    let goal_id = insert_goal_save(
+        env,
+        user,
+        goal_name,
+        target_amount,
+        initial_deposit,
+        deadline,
+    )?;
+    oracle::set_fiat_target(env, goal_id, target_fiat);
+    Ok(goal_id)
+}
 
+/// Validates and stores a new Goal Save for `user`.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn insert_goal_save(
+    env: &Env,
+    user: Address,
+    goal_name: soroban_sdk::Symbol,
+    target_amount: i128,
+    initial_deposit: i128,
+    deadline: u64,
+) -> Result<u64, SavingsError> {
     if target_amount <= 0 {
         return Err(SavingsError::InvalidAmount);
     }
@@ -26,9 +106,16 @@ pub fn create_goal_save(
     if !users::user_exists(env, &user) {
         return Err(SavingsError::UserNotFound);
     }
+    limits::ensure_plan_capacity(
+        limits::get_plan_limits(env).max_goal_saves,
+        count_open_goal_saves(env, &user),
+    )?;
 
     let current_time = env.ledger().timestamp();
-    let goal_id = get_next_goal_id(env);
+    if deadline != 0 && deadline <= current_time {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    let goal_id = plan_ids::next_plan_id(env, &user, PlanKind::Goal);
 
     let goal_save = GoalSave {
         id: goal_id,
@@ -38,8 +125,10 @@ pub fn create_goal_save(
         current_amount: initial_deposit,
         interest_rate: 500,
         start_time: current_time,
+        deadline,
         is_completed: initial_deposit >= target_amount,
         is_withdrawn: false,
+        is_public: false,
     };
 
     env.storage()
@@ -47,7 +136,14 @@ pub fn create_goal_save(
         .set(&DataKey::GoalSave(goal_id), &goal_save);
 
     add_goal_to_user(env, &user, goal_id);
-    increment_next_goal_id(env);
+
+    record_milestones(env, &goal_save, 0);
+    if goal_save.is_completed {
+        achievements::award(env, &user, Badge::GoalCompleted);
+    }
+    if initial_deposit > 0 {
+        record_deposit_activity(env, &user, PlanKind::Goal, goal_id, initial_deposit);
+    }
 
     Ok(goal_id)
 }
@@ -58,23 +154,181 @@ pub fn deposit_to_goal_save(
     goal_id: u64,
     amount: i128,
 ) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
+    guard::check(env, &user, Op::Deposit, amount)?;
     user.require_auth();
 
+    apply_goal_deposit(env, &user, goal_id, amount)
+}
+
+/// Adds a deposit to one of the user's goals.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn apply_goal_deposit(
+    env: &Env,
+    user: &Address,
+    goal_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
     }
 
-    let mut goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    let goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+
+    if !is_goal_owner(env, &goal_save, user) {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    add_to_goal(env, user, goal_save, amount)
+}
+
+/// Lets any initialized user put `amount` towards a public goal. Only the
+/// goal's owners can ever withdraw it; contributions by an owner count as
+/// a regular deposit.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is not positive
+/// * `UserNotFound` - If `contributor` is not initialized
+/// * `PlanNotFound` - If the goal does not exist
+/// * `Unauthorized` - If the goal is not public
+/// * `PlanCompleted` - If the goal already reached its target
+/// * `TooLate` - If the goal's deadline has passed
+/// * `MaxPlansExceeded` - If the goal has `MAX_GOAL_CONTRIBUTORS` other
+///   contributors
+pub fn contribute_to_goal(
+    env: &Env,
+    contributor: Address,
+    goal_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    guard::check(env, &contributor, Op::Deposit, amount)?;
+    contributor.require_auth();
 
-    if goal_save.owner != user {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if !users::user_exists(env, &contributor) {
+        return Err(SavingsError::UserNotFound);
+    }
+    let goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    if is_goal_owner(env, &goal_save, &contributor) {
+        return add_to_goal(env, &contributor, goal_save, amount);
+    }
+    if !goal_save.is_public {
         return Err(SavingsError::Unauthorized);
     }
+    custody::require_guardian_consent(env, &contributor);
+
+    let mut contributions = get_goal_contributors(env, goal_id);
+    match contributions
+        .iter()
+        .position(|c| c.contributor == contributor)
+    {
+        Some(index) => {
+            let mut contribution = contributions.get_unchecked(index as u32);
+            contribution.amount = contribution
+                .amount
+                .checked_add(amount)
+                .ok_or(SavingsError::Overflow)?;
+            contributions.set(index as u32, contribution);
+        }
+        None if contributions.len() >= MAX_GOAL_CONTRIBUTORS => {
+            return Err(SavingsError::MaxPlansExceeded)
+        }
+        None => contributions.push_back(GoalContribution {
+            contributor: contributor.clone(),
+            amount,
+        }),
+    }
 
+    add_to_goal(env, &contributor, goal_save, amount)?;
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::GoalContributors(goal_id)),
+        &contributions,
+    );
+
+    events::publish(
+        env,
+        events::GoalFunded {
+            contributor,
+            goal_id,
+            amount,
+        },
+    );
+    Ok(())
+}
+
+/// Opens a goal to contributions from any initialized user, or closes it
+/// again. Contributions already made stay in the goal.
+///
+/// # Errors
+/// * `PlanNotFound` - If the goal does not exist
+/// * `Unauthorized` - If `owner` does not own the goal
+/// * `PlanCompleted` - If the goal's funds were already withdrawn
+pub fn set_goal_public(
+    env: &Env,
+    owner: Address,
+    goal_id: u64,
+    is_public: bool,
+) -> Result<(), SavingsError> {
+    guard::check(env, &owner, Op::Manage, 0)?;
+    owner.require_auth();
+
+    let mut goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    if goal_save.owner != owner {
+        return Err(SavingsError::Unauthorized);
+    }
+    if goal_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    goal_save.is_public = is_public;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalSave(goal_id), &goal_save);
+
+    events::publish(
+        env,
+        events::GoalVisibilitySet {
+            owner,
+            goal_id,
+            is_public,
+        },
+    );
+    Ok(())
+}
+
+/// Outside contributors of a goal and how much each gave, in the order they
+/// first contributed
+pub fn get_goal_contributors(env: &Env, goal_id: u64) -> Vec<GoalContribution> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::GoalContributors(goal_id)))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Adds a deposit by `depositor` to an open goal. The completion badge goes
+/// to the depositor if they own the goal, otherwise to its owner.
+fn add_to_goal(
+    env: &Env,
+    depositor: &Address,
+    mut goal_save: GoalSave,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    let goal_id = goal_save.id;
     if goal_save.is_completed {
         return Err(SavingsError::PlanCompleted);
     }
 
+    if is_goal_expired(env, &goal_save) {
+        return Err(SavingsError::TooLate);
+    }
+
+    if let Some(target_fiat) = oracle::get_fiat_target(env, goal_id) {
+        goal_save.target_amount = oracle::to_tokens(target_fiat, oracle::get_price(env)?)?;
+    }
+
+    let previous_amount = goal_save.current_amount;
     goal_save.current_amount = goal_save
         .current_amount
         .checked_add(amount)
@@ -88,6 +342,17 @@ pub fn deposit_to_goal_save(
         .persistent()
         .set(&DataKey::GoalSave(goal_id), &goal_save);
 
+    record_milestones(env, &goal_save, previous_amount);
+    if goal_save.is_completed {
+        let achiever = if is_goal_owner(env, &goal_save, depositor) {
+            depositor
+        } else {
+            &goal_save.owner
+        };
+        achievements::award(env, achiever, Badge::GoalCompleted);
+    }
+    record_deposit_activity(env, depositor, PlanKind::Goal, goal_id, amount);
+
     Ok(())
 }
 
@@ -96,18 +361,18 @@ pub fn withdraw_completed_goal_save(
     user: Address,
     goal_id: u64,
 ) -> Result<i128, SavingsError> {
-    ensure_not_paused(env)?;
+    let goal_save = get_goal_save(env, goal_id);
+    let amount = goal_save.as_ref().map_or(0, |g| g.current_amount);
+    guard::check(env, &user, Op::Withdraw, amount)?;
     user.require_auth();
 
     if !users::user_exists(env, &user) {
         return Err(SavingsError::UserNotFound);
     }
 
-    let mut goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    let mut goal_save = goal_save.ok_or(SavingsError::PlanNotFound)?;
 
-    if goal_save.owner != user {
-        return Err(SavingsError::Unauthorized);
-    }
+    authorize_goal_withdrawal(env, &goal_save, &user)?;
 
     if !goal_save.is_completed {
         return Err(SavingsError::TooEarly);
@@ -123,31 +388,80 @@ pub fn withdraw_completed_goal_save(
         .persistent()
         .set(&DataKey::GoalSave(goal_id), &goal_save);
 
+    let breakdown =
+        fees::charge_withdrawal_fee(env, &user, PlanKind::Goal, goal_save.current_amount)?;
+    settle_campaign_bonuses(env, &goal_save, true)?;
+    history::record(
+        env,
+        &user,
+        TxKind::Withdraw,
+        PlanKind::Goal,
+        goal_id,
+        goal_save.current_amount,
+        breakdown.fee,
+    );
+    record_withdrawal_activity(env, &user, goal_save.current_amount);
+
     let user_key = DataKey::User(user.clone());
     if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
         user_data.total_balance = user_data
             .total_balance
-            .checked_add(goal_save.current_amount)
+            .checked_add(breakdown.net)
             .ok_or(SavingsError::Overflow)?;
         env.storage().persistent().set(&user_key, &user_data);
     }
 
+    Ok(breakdown.net)
+}
+
+/// Moves a completed goal's balance into its owner's Flexi Save. No tokens
+/// leave the contract, so no withdrawal fee is charged. Returns the amount
+/// moved.
+///
+/// Callers are responsible for the guard check, authorization and ownership.
+pub(crate) fn sweep_goal_to_flexi(
+    env: &Env,
+    mut goal_save: GoalSave,
+) -> Result<i128, SavingsError> {
+    if !goal_save.is_completed {
+        return Err(SavingsError::TooEarly);
+    }
+    if goal_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    goal_save.is_withdrawn = true;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalSave(goal_save.id), &goal_save);
+
+    settle_campaign_bonuses(env, &goal_save, true)?;
+    history::record(
+        env,
+        &goal_save.owner,
+        TxKind::Withdraw,
+        PlanKind::Goal,
+        goal_save.id,
+        goal_save.current_amount,
+        0,
+    );
+    flexi::credit_flexi(env, &goal_save.owner, goal_save.current_amount)?;
     Ok(goal_save.current_amount)
 }
 
 pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, SavingsError> {
-    ensure_not_paused(env)?;
+    let goal_save = get_goal_save(env, goal_id);
+    let amount = goal_save.as_ref().map_or(0, |g| g.current_amount);
+    guard::check(env, &user, Op::Withdraw, amount)?;
     user.require_auth();
 
     if !users::user_exists(env, &user) {
         return Err(SavingsError::UserNotFound);
     }
 
-    let mut goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    let mut goal_save = goal_save.ok_or(SavingsError::PlanNotFound)?;
 
-    if goal_save.owner != user {
-        return Err(SavingsError::Unauthorized);
-    }
+    authorize_goal_withdrawal(env, &goal_save, &user)?;
 
     if goal_save.is_completed {
         return Err(SavingsError::PlanCompleted);
@@ -157,6 +471,107 @@ pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, S
         return Err(SavingsError::PlanCompleted);
     }
 
+    let fee_amount = break_penalty(env, &goal_save)?;
+
+    let net_amount = goal_save
+        .current_amount
+        .checked_sub(fee_amount)
+        .ok_or(SavingsError::Underflow)?;
+
+    goal_save.is_withdrawn = true;
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalSave(goal_id), &goal_save);
+
+    let user_key = DataKey::User(user.clone());
+    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
+        user_data.total_balance = user_data
+            .total_balance
+            .checked_add(net_amount)
+            .ok_or(SavingsError::Overflow)?;
+        env.storage().persistent().set(&user_key, &user_data);
+    }
+
+    if fee_amount > 0 {
+        fees::record_fee_paid(env, &user, fee_amount)?;
+        if let Some(fee_recipient) = fees::credit_treasury(env, fee_amount)? {
+            events::publish(
+                env,
+                events::BreakFeeCharged {
+                    fee_recipient,
+                    goal_id,
+                    fee: fee_amount,
+                },
+            );
+        }
+    }
+
+    settle_campaign_bonuses(env, &goal_save, false)?;
+
+    history::record(
+        env,
+        &user,
+        TxKind::Break,
+        PlanKind::Goal,
+        goal_id,
+        goal_save.current_amount,
+        fee_amount,
+    );
+    record_withdrawal_activity(env, &user, goal_save.current_amount);
+
+    events::publish(
+        env,
+        events::GoalBroken {
+            user: user.clone(),
+            goal_id,
+            net_amount,
+        },
+    );
+
+    remove_goal_from_owners(env, &goal_save);
+
+    Ok(net_amount)
+}
+
+/// Early-break fee owed on breaking the goal now; none within the grace
+/// period after it was created or for fee-exempt owners
+fn break_penalty(env: &Env, goal_save: &GoalSave) -> Result<i128, SavingsError> {
+    if fees::is_fee_exempt(env, &goal_save.owner, PlanKind::Goal) {
+        return Ok(0);
+    }
+    let in_grace = env.ledger().timestamp()
+        < goal_save
+            .start_time
+            .saturating_add(get_goal_break_grace(env));
+    if in_grace {
+        return Ok(0);
+    }
+    early_break_fee(env, goal_save.current_amount)
+}
+
+/// VIEW FUNCTION - What `break_goal_save` would pay out if called now
+///
+/// # Errors
+/// * `PlanNotFound` - If the goal does not exist
+/// * `PlanCompleted` - If the goal is completed or already withdrawn
+pub fn preview_break_goal(env: &Env, goal_id: u64) -> Result<WithdrawalPreview, SavingsError> {
+    let goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    if goal_save.is_completed || goal_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    let penalty = break_penalty(env, &goal_save)?;
+    Ok(WithdrawalPreview {
+        gross: goal_save.current_amount,
+        interest: 0,
+        penalty,
+        fee: 0,
+        net: goal_save.current_amount - penalty,
+    })
+}
+
+/// Early-break fee owed on `amount`
+fn early_break_fee(env: &Env, amount: i128) -> Result<i128, SavingsError> {
     let fee_bps: u32 = env
         .storage()
         .instance()
@@ -167,20 +582,173 @@ pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, S
         return Err(SavingsError::InvalidAmount);
     }
 
-    let fee_amount = if fee_bps == 0 {
-        0
-    } else {
+    if fee_bps == 0 {
+        return Ok(0);
+    }
+    Ok(amount
+        .checked_mul(fee_bps as i128)
+        .ok_or(SavingsError::Overflow)?
+        / 10_000)
+}
+
+/// Whether goals that miss their target forfeit the early-break fee when
+/// finalized
+pub fn get_failed_goal_penalty(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::FailedGoalPenalty))
+        .unwrap_or(false)
+}
+
+pub fn set_failed_goal_penalty(env: &Env, enabled: bool) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::FailedGoalPenalty), &enabled);
+}
+
+/// Seconds after a goal's creation during which `break_goal_save` charges
+/// no early-break fee (0 = no grace window)
+pub fn get_goal_break_grace(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::GoalBreakGrace))
+        .unwrap_or(0)
+}
+
+/// # Errors
+/// * `InvalidTimestamp` - If `seconds` exceeds `MAX_GOAL_BREAK_GRACE`
+pub fn set_goal_break_grace(env: &Env, seconds: u64) -> Result<(), SavingsError> {
+    if seconds > MAX_GOAL_BREAK_GRACE {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::GoalBreakGrace), &seconds);
+    Ok(())
+}
+
+/// Closes out a goal whose deadline has passed, on anyone's behalf, and
+/// releases its funds to the owner's balance. Returns the amount released.
+///
+/// A goal that reached its target is paid out as `withdraw_completed_goal_save`
+/// would. A failed goal is paid out as `reclaim_expired_goal_save` would, or
+/// as `break_goal_save` would if the failed-goal penalty is enabled.
+///
+/// # Errors
+/// * `PlanNotFound` - If the goal does not exist
+/// * `PlanCompleted` - If its funds were already withdrawn
+/// * `InvalidPlanConfig` - If the goal has no deadline
+/// * `TooEarly` - If the deadline has not passed yet
+pub fn finalize_goal(env: &Env, goal_id: u64) -> Result<i128, SavingsError> {
+    guard::check_protocol(env)?;
+
+    let mut goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+
+    if goal_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    if goal_save.deadline == 0 {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    if !is_goal_expired(env, &goal_save) {
+        return Err(SavingsError::TooEarly);
+    }
+
+    goal_save.is_withdrawn = true;
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalSave(goal_id), &goal_save);
+
+    let user = goal_save.owner.clone();
+    let net_amount = if !goal_save.is_completed
+        && get_failed_goal_penalty(env)
+        && !fees::is_fee_exempt(env, &user, PlanKind::Goal)
+    {
+        let penalty = early_break_fee(env, goal_save.current_amount)?;
+        if penalty > 0 {
+            fees::record_fee_paid(env, &user, penalty)?;
+            if let Some(fee_recipient) = fees::credit_treasury(env, penalty)? {
+                events::publish(
+                    env,
+                    events::BreakFeeCharged {
+                        fee_recipient,
+                        goal_id,
+                        fee: penalty,
+                    },
+                );
+            }
+        }
         goal_save
             .current_amount
-            .checked_mul(fee_bps as i128)
-            .ok_or(SavingsError::Overflow)?
-            / 10_000
+            .checked_sub(penalty)
+            .ok_or(SavingsError::Underflow)?
+    } else {
+        fees::charge_withdrawal_fee(env, &user, PlanKind::Goal, goal_save.current_amount)?.net
     };
 
-    let net_amount = goal_save
-        .current_amount
-        .checked_sub(fee_amount)
-        .ok_or(SavingsError::Underflow)?;
+    let user_key = DataKey::User(user.clone());
+    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
+        user_data.total_balance = user_data
+            .total_balance
+            .checked_add(net_amount)
+            .ok_or(SavingsError::Overflow)?;
+        env.storage().persistent().set(&user_key, &user_data);
+    }
+
+    settle_campaign_bonuses(env, &goal_save, goal_save.is_completed)?;
+
+    history::record(
+        env,
+        &user,
+        TxKind::Withdraw,
+        PlanKind::Goal,
+        goal_id,
+        goal_save.current_amount,
+        goal_save.current_amount - net_amount,
+    );
+    record_withdrawal_activity(env, &user, goal_save.current_amount);
+
+    events::publish(
+        env,
+        events::GoalFinalized {
+            user,
+            goal_id,
+            completed: goal_save.is_completed,
+            net_amount,
+        },
+    );
+
+    Ok(net_amount)
+}
+
+/// Reclaims the funds of a goal whose deadline has passed.
+///
+/// Unlike `break_goal_save`, no early-break fee is charged: the user did not
+/// break the goal, it simply ran out of time.
+pub fn reclaim_expired_goal_save(
+    env: &Env,
+    user: Address,
+    goal_id: u64,
+) -> Result<i128, SavingsError> {
+    let goal_save = get_goal_save(env, goal_id);
+    let amount = goal_save.as_ref().map_or(0, |g| g.current_amount);
+    guard::check(env, &user, Op::Withdraw, amount)?;
+    user.require_auth();
+
+    let mut goal_save = goal_save.ok_or(SavingsError::PlanNotFound)?;
+
+    authorize_goal_withdrawal(env, &goal_save, &user)?;
+
+    if goal_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    if !is_goal_expired(env, &goal_save) {
+        return Err(SavingsError::TooEarly);
+    }
 
     goal_save.is_withdrawn = true;
 
@@ -188,71 +756,380 @@ pub fn break_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<i128, S
         .persistent()
         .set(&DataKey::GoalSave(goal_id), &goal_save);
 
+    let breakdown =
+        fees::charge_withdrawal_fee(env, &user, PlanKind::Goal, goal_save.current_amount)?;
+
     let user_key = DataKey::User(user.clone());
     if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
         user_data.total_balance = user_data
             .total_balance
-            .checked_add(net_amount)
+            .checked_add(breakdown.net)
             .ok_or(SavingsError::Overflow)?;
         env.storage().persistent().set(&user_key, &user_data);
     }
 
-    if fee_amount > 0 {
-        if let Some(fee_recipient) = env
-            .storage()
-            .instance()
-            .get::<DataKey, Address>(&DataKey::FeeRecipient)
-        {
-            let fee_key = DataKey::TotalBalance(fee_recipient.clone());
-            let current_fee_balance = env
-                .storage()
-                .persistent()
-                .get::<DataKey, i128>(&fee_key)
-                .unwrap_or(0i128);
-            let new_fee_balance = current_fee_balance
-                .checked_add(fee_amount)
-                .ok_or(SavingsError::Overflow)?;
-            env.storage().persistent().set(&fee_key, &new_fee_balance);
-            env.events().publish(
-                (symbol_short!("brk_fee"), fee_recipient, goal_id),
-                fee_amount,
-            );
-        }
+    settle_campaign_bonuses(env, &goal_save, goal_save.is_completed)?;
+
+    history::record(
+        env,
+        &user,
+        TxKind::Withdraw,
+        PlanKind::Goal,
+        goal_id,
+        goal_save.current_amount,
+        breakdown.fee,
+    );
+    record_withdrawal_activity(env, &user, goal_save.current_amount);
+
+    events::publish(
+        env,
+        events::GoalReclaimed {
+            user,
+            goal_id,
+            net_amount: breakdown.net,
+        },
+    );
+
+    Ok(breakdown.net)
+}
+
+/// Hands an open goal, with everything saved towards it, to another
+/// initialized user, e.g. a parent gifting a child's education fund.
+///
+/// Goal balances are not part of a user's total balance until withdrawn, so
+/// no total balance changes hands.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `from` and `to` are the same user, or `to`
+///   already shares the goal
+/// * `UserNotFound` - If `to` is not initialized
+/// * `PlanNotFound` - If the goal does not exist
+/// * `Unauthorized` - If `from` does not own the goal
+/// * `PlanCompleted` - If the goal's funds were already withdrawn
+/// * `MaxPlansExceeded` - If `to` already holds the most goals allowed
+pub fn gift_goal_save(
+    env: &Env,
+    from: Address,
+    to: Address,
+    goal_id: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &from, Op::Manage, 0)?;
+    from.require_auth();
+    custody::require_guardian_consent(env, &from);
+
+    if from == to {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if !users::user_exists(env, &to) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let mut goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    if goal_save.owner != from {
+        return Err(SavingsError::Unauthorized);
+    }
+    if goal_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
     }
+    if get_goal_co_owners(env, goal_id).contains(&to) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    limits::ensure_plan_capacity(
+        limits::get_plan_limits(env).max_goal_saves,
+        count_open_goal_saves(env, &to),
+    )?;
+
+    remove_goal_from_user(env, &from, goal_id);
+    add_goal_to_user(env, &to, goal_id);
 
-    env.events().publish(
-        (symbol_short!("goal_brk"), user.clone(), goal_id),
-        net_amount,
+    goal_save.owner = to.clone();
+    env.storage()
+        .persistent()
+        .set(&DataKey::GoalSave(goal_id), &goal_save);
+    campaigns::transfer_plan(env, &from, &to, PlanKind::Goal, goal_id);
+    history::record_transfer(
+        env,
+        &from,
+        &to,
+        PlanKind::Goal,
+        goal_id,
+        goal_save.current_amount,
     );
+    record_withdrawal_activity(env, &from, goal_save.current_amount);
 
-    remove_goal_from_user(env, &user, goal_id);
+    events::publish(env, events::GoalGifted { from, to, goal_id });
 
-    Ok(net_amount)
+    Ok(())
 }
 
-pub fn get_goal_save(env: &Env, goal_id: u64) -> Option<GoalSave> {
-    env.storage().persistent().get(&DataKey::GoalSave(goal_id))
+/// Shares an open goal with another initialized user. Either of them can
+/// then deposit towards it, and the goal shows up in both users' lists.
+///
+/// Both the owner and the new co-owner must sign. Withdrawing needs every
+/// owner's signature until they agree on another `GoalWithdrawPolicy`.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `co_owner` is the owner or already shares
+///   the goal
+/// * `UserNotFound` - If `co_owner` is not initialized
+/// * `PlanNotFound` - If the goal does not exist
+/// * `Unauthorized` - If `owner` does not own the goal
+/// * `PlanCompleted` - If the goal's funds were already withdrawn
+/// * `MaxPlansExceeded` - If the goal has `MAX_GOAL_CO_OWNERS` co-owners, or
+///   `co_owner` already holds the most goals allowed
+pub fn add_goal_co_owner(
+    env: &Env,
+    owner: Address,
+    goal_id: u64,
+    co_owner: Address,
+) -> Result<(), SavingsError> {
+    guard::check(env, &owner, Op::Manage, 0)?;
+    owner.require_auth();
+    co_owner.require_auth();
+    custody::require_guardian_consent(env, &owner);
+
+    if owner == co_owner {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if !users::user_exists(env, &co_owner) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    if goal_save.owner != owner {
+        return Err(SavingsError::Unauthorized);
+    }
+    if goal_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let mut co_owners = get_goal_co_owners(env, goal_id);
+    if co_owners.contains(&co_owner) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if co_owners.len() >= MAX_GOAL_CO_OWNERS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+    limits::ensure_plan_capacity(
+        limits::get_plan_limits(env).max_goal_saves,
+        count_open_goal_saves(env, &co_owner),
+    )?;
+
+    co_owners.push_back(co_owner.clone());
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::GoalCoOwners(goal_id)),
+        &co_owners,
+    );
+    add_goal_to_user(env, &co_owner, goal_id);
+
+    events::publish(
+        env,
+        events::GoalCoOwnerAdded {
+            owner,
+            co_owner,
+            goal_id,
+        },
+    );
+
+    Ok(())
 }
 
-pub fn get_user_goal_saves(env: &Env, user: &Address) -> Vec<u64> {
+/// Changes whose signatures withdrawing from a co-owned goal takes. Every
+/// current owner must sign, so no one can loosen the policy alone.
+///
+/// # Errors
+/// * `PlanNotFound` - If the goal does not exist
+/// * `Unauthorized` - If `owner` does not own the goal
+/// * `InvalidPlanConfig` - If the goal has no co-owners
+pub fn set_goal_withdraw_policy(
+    env: &Env,
+    owner: Address,
+    goal_id: u64,
+    policy: GoalWithdrawPolicy,
+) -> Result<(), SavingsError> {
+    guard::check(env, &owner, Op::Manage, 0)?;
+    owner.require_auth();
+
+    let goal_save = get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    if goal_save.owner != owner {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    let co_owners = get_goal_co_owners(env, goal_id);
+    if co_owners.is_empty() {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    for co_owner in co_owners.iter() {
+        co_owner.require_auth();
+    }
+
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::GoalWithdrawPolicy(goal_id)),
+        &policy,
+    );
+
+    events::publish(
+        env,
+        events::GoalWithdrawPolicySet {
+            owner,
+            goal_id,
+            policy,
+        },
+    );
+
+    Ok(())
+}
+
+/// Settles the campaign bonuses the owners and outside contributors earned
+/// on a goal being paid out (`paid`) or broken
+fn settle_campaign_bonuses(
+    env: &Env,
+    goal_save: &GoalSave,
+    paid: bool,
+) -> Result<(), SavingsError> {
+    campaigns::settle_plan(env, &goal_save.owner, PlanKind::Goal, goal_save.id, paid)?;
+    for co_owner in get_goal_co_owners(env, goal_save.id).iter() {
+        campaigns::settle_plan(env, &co_owner, PlanKind::Goal, goal_save.id, paid)?;
+    }
+    for contribution in get_goal_contributors(env, goal_save.id).iter() {
+        campaigns::settle_plan(
+            env,
+            &contribution.contributor,
+            PlanKind::Goal,
+            goal_save.id,
+            paid,
+        )?;
+    }
+    Ok(())
+}
+
+/// Users sharing the goal with its owner, in the order they were added
+pub fn get_goal_co_owners(env: &Env, goal_id: u64) -> Vec<Address> {
     env.storage()
         .persistent()
-        .get(&DataKey::UserGoalSaves(user.clone()))
+        .get(&DataKey::UserState(UserKey::GoalCoOwners(goal_id)))
         .unwrap_or_else(|| Vec::new(env))
 }
 
-fn get_next_goal_id(env: &Env) -> u64 {
+pub fn get_goal_withdraw_policy(env: &Env, goal_id: u64) -> GoalWithdrawPolicy {
     env.storage()
         .persistent()
-        .get(&DataKey::NextGoalId)
-        .unwrap_or(1u64)
+        .get(&DataKey::UserState(UserKey::GoalWithdrawPolicy(goal_id)))
+        .unwrap_or(GoalWithdrawPolicy::AllOwners)
 }
 
-fn increment_next_goal_id(env: &Env) {
-    let current_id = get_next_goal_id(env);
+/// Returns `true` if `user` owns the goal or shares it with its owner
+fn is_goal_owner(env: &Env, goal_save: &GoalSave, user: &Address) -> bool {
+    goal_save.owner == *user || get_goal_co_owners(env, goal_save.id).contains(user)
+}
+
+/// Checks that `user`, already authorized by the caller, may withdraw from
+/// the goal, and collects the other owners' signatures if its policy
+/// needs them.
+fn authorize_goal_withdrawal(
+    env: &Env,
+    goal_save: &GoalSave,
+    user: &Address,
+) -> Result<(), SavingsError> {
+    let co_owners = get_goal_co_owners(env, goal_save.id);
+    if goal_save.owner != *user && !co_owners.contains(user) {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if get_goal_withdraw_policy(env, goal_save.id) == GoalWithdrawPolicy::AllOwners {
+        if goal_save.owner != *user {
+            goal_save.owner.require_auth();
+        }
+        for co_owner in co_owners.iter() {
+            if co_owner != *user {
+                co_owner.require_auth();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the goal has a deadline and the ledger time is past it.
+pub fn is_goal_expired(env: &Env, goal_save: &GoalSave) -> bool {
+    goal_save.deadline != 0 && env.ledger().timestamp() > goal_save.deadline
+}
+
+/// Returns the user's goals whose deadline has passed but whose funds have
+/// not been withdrawn or reclaimed yet.
+pub fn get_user_expired_goal_saves(env: &Env, user: &Address) -> Vec<GoalSave> {
+    let mut expired = Vec::new(env);
+    for goal_id in get_user_goal_saves(env, user).iter() {
+        if let Some(goal_save) = get_goal_save(env, goal_id) {
+            if !goal_save.is_withdrawn && is_goal_expired(env, &goal_save) {
+                expired.push_back(goal_save);
+            }
+        }
+    }
+    expired
+}
+
+/// Returns the milestones a goal has reached, in the order they were crossed
+pub fn get_goal_milestones(env: &Env, goal_id: u64) -> Vec<GoalMilestone> {
     env.storage()
         .persistent()
-        .set(&DataKey::NextGoalId, &(current_id + 1));
+        .get(&DataKey::GoalMilestones(goal_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Records and announces every milestone crossed by moving the goal's balance
+/// from `previous_amount` to its current amount.
+fn record_milestones(env: &Env, goal_save: &GoalSave, previous_amount: i128) {
+    let mut milestones = get_goal_milestones(env, goal_save.id);
+    let mut crossed_any = false;
+
+    for percent in MILESTONE_PERCENTS {
+        // Compare in percent space to avoid rounding the threshold amount
+        let threshold = goal_save.target_amount.saturating_mul(percent as i128);
+        let before = previous_amount.saturating_mul(100);
+        let after = goal_save.current_amount.saturating_mul(100);
+        if before < threshold && after >= threshold {
+            let reached_at = env.ledger().timestamp();
+            milestones.push_back(GoalMilestone {
+                percent,
+                reached_at,
+            });
+            events::publish(
+                env,
+                events::GoalMilestoneReached {
+                    owner: goal_save.owner.clone(),
+                    goal_id: goal_save.id,
+                    percent,
+                },
+            );
+            crossed_any = true;
+        }
+    }
+
+    if crossed_any {
+        env.storage()
+            .persistent()
+            .set(&DataKey::GoalMilestones(goal_save.id), &milestones);
+    }
+}
+
+pub fn get_goal_save(env: &Env, goal_id: u64) -> Option<GoalSave> {
+    migrations::read_goal_save(env, goal_id)
+}
+
+/// Number of the user's goals that have not been withdrawn or broken
+pub fn count_open_goal_saves(env: &Env, user: &Address) -> u32 {
+    get_user_goal_saves(env, user)
+        .iter()
+        .filter(|id| get_goal_save(env, *id).is_some_and(|g| !g.is_withdrawn))
+        .count() as u32
+}
+
+pub fn get_user_goal_saves(env: &Env, user: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserGoalSaves(user.clone()))
+        .unwrap_or_else(|| Vec::new(env))
 }
 
 fn add_goal_to_user(env: &Env, user: &Address, goal_id: u64) {
@@ -263,6 +1140,54 @@ fn add_goal_to_user(env: &Env, user: &Address, goal_id: u64) {
         .set(&DataKey::UserGoalSaves(user.clone()), &user_goals);
 }
 
+/// Deletes a goal record and its milestones and drops it from its owners'
+/// lists
+pub(crate) fn remove_goal_save(env: &Env, goal_save: &GoalSave) {
+    remove_goal_from_owners(env, goal_save);
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GoalSave(goal_save.id));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GoalMilestones(goal_save.id));
+    oracle::remove_fiat_target(env, goal_save.id);
+}
+
+/// Takes the user off the co-owners of every goal in their list they do not
+/// own. Used when the account is closed, once all those goals are settled.
+pub(crate) fn remove_co_ownerships(env: &Env, user: &Address) {
+    for goal_id in get_user_goal_saves(env, user).iter() {
+        let mut co_owners = get_goal_co_owners(env, goal_id);
+        let Some(index) = co_owners.first_index_of(user) else {
+            continue;
+        };
+        co_owners.remove(index);
+        let key = DataKey::UserState(UserKey::GoalCoOwners(goal_id));
+        if co_owners.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &co_owners);
+        }
+    }
+}
+
+/// Drops the goal from the lists of its owner and every co-owner, along with
+/// its co-ownership records
+fn remove_goal_from_owners(env: &Env, goal_save: &GoalSave) {
+    remove_goal_from_user(env, &goal_save.owner, goal_save.id);
+    for co_owner in get_goal_co_owners(env, goal_save.id).iter() {
+        remove_goal_from_user(env, &co_owner, goal_save.id);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::UserState(UserKey::GoalCoOwners(goal_save.id)));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::UserState(UserKey::GoalWithdrawPolicy(
+            goal_save.id,
+        )));
+}
+
 fn remove_goal_from_user(env: &Env, user: &Address, goal_id: u64) {
     let user_goals = get_user_goal_saves(env, user);
     let mut new_goals = Vec::new(env);
@@ -282,8 +1207,14 @@ fn remove_goal_from_user(env: &Env, user: &Address, goal_id: u64) {
 
 #[cfg(test)]
 mod tests {
-    use crate::{NesteraContract, NesteraContractClient};
-    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+    use crate::{
+        FeeExemption, GoalWithdrawPolicy, NesteraContract, NesteraContractClient, PlanKind,
+        SavingsError,
+    };
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec, Address, Env, Symbol,
+    };
 
     fn setup_test_env() -> (Env, NesteraContractClient<'static>) {
         let env = Env::default();
@@ -300,7 +1231,13 @@ mod tests {
         let admin_pk = soroban_sdk::BytesN::from_array(&env, &[1u8; 32]);
 
         env.mock_all_auths();
-        client.initialize(&admin, &admin_pk);
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
 
         (env, client, admin)
     }
@@ -317,8 +1254,9 @@ mod tests {
         let target = 10000i128;
         let initial = 1000i128;
 
+        let expected_id = client.get_next_plan_id(&user, &PlanKind::Goal);
         let goal_id = client.create_goal_save(&user, &goal_name, &target, &initial);
-        assert_eq!(goal_id, 1);
+        assert_eq!(goal_id, expected_id);
 
         let goal_save = client.get_goal_save_detail(&goal_id);
         assert_eq!(goal_save.owner, user);
@@ -489,14 +1427,14 @@ mod tests {
 
     #[test]
     fn test_break_goal_save_applies_fee_and_routes() {
-        let (env, client, _admin) = setup_admin_env();
+        let (env, client, admin) = setup_admin_env();
         let user = Address::generate(&env);
         let treasury = Address::generate(&env);
 
         env.mock_all_auths();
         client.initialize_user(&user);
-        assert!(client.try_set_fee_recipient(&treasury).is_ok());
-        assert!(client.try_set_early_break_fee_bps(&500).is_ok()); // 5%
+        assert!(client.try_set_fee_recipient(&admin, &treasury).is_ok());
+        assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok()); // 5%
 
         let goal_name = Symbol::new(&env, "emergency");
         let target = 10_000i128;
@@ -509,16 +1447,61 @@ mod tests {
         assert_eq!(client.get_protocol_fee_balance(&treasury), 100);
     }
 
+    #[test]
+    fn test_break_goal_save_free_within_grace_window() {
+        let (env, client, admin) = setup_admin_env();
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok());
+        assert!(client
+            .try_set_goal_break_grace(&admin, &(31 * 24 * 60 * 60))
+            .is_err());
+        client.set_goal_break_grace(&admin, &(48 * 60 * 60));
+        assert_eq!(client.get_protocol_params().goal_break_grace, 48 * 60 * 60);
+
+        let goal_name = Symbol::new(&env, "regret");
+        let first = client.create_goal_save(&user, &goal_name, &10_000, &2_000);
+        let second = client.create_goal_save(&user, &goal_name, &10_000, &2_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 48 * 60 * 60 - 1);
+        assert_eq!(client.break_goal_save(&user, &first), 2_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        assert_eq!(client.break_goal_save(&user, &second), 1_900);
+    }
+
+    #[test]
+    fn test_break_fees_accumulate_in_lifetime_fees() {
+        let (env, client, admin) = setup_admin_env();
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        assert_eq!(client.get_user_lifetime_fees(&user), 0);
+        assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok());
+
+        let goal_name = Symbol::new(&env, "emergency");
+        let first = client.create_goal_save(&user, &goal_name, &10_000, &2_000);
+        client.break_goal_save(&user, &first);
+        let second = client.create_goal_save(&user, &goal_name, &10_000, &1_000);
+        client.break_goal_save(&user, &second);
+
+        // 5% of 2_000 plus 5% of 1_000, charged even without a fee recipient
+        assert_eq!(client.get_user_lifetime_fees(&user), 150);
+    }
+
     #[test]
     fn test_break_goal_save_fee_rounds_down() {
-        let (env, client, _admin) = setup_admin_env();
+        let (env, client, admin) = setup_admin_env();
         let user = Address::generate(&env);
         let treasury = Address::generate(&env);
 
         env.mock_all_auths();
         client.initialize_user(&user);
-        assert!(client.try_set_fee_recipient(&treasury).is_ok());
-        assert!(client.try_set_early_break_fee_bps(&125).is_ok()); // 1.25%
+        assert!(client.try_set_fee_recipient(&admin, &treasury).is_ok());
+        assert!(client.try_set_early_break_fee_bps(&admin, &125).is_ok()); // 1.25%
 
         let goal_name = Symbol::new(&env, "rounding");
         let target = 10_000i128;
@@ -581,4 +1564,312 @@ mod tests {
 
         client.create_goal_save(&user, &goal_name, &target, &initial);
     }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #52)")]
+    fn test_deposit_after_deadline_fails() {
+        let (env, client) = setup_test_env();
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        let goal_name = Symbol::new(&env, "trip");
+        let goal_id = client.create_goal_save_with_deadline(&user, &goal_name, &5000, &1000, &100);
+
+        env.ledger().with_mut(|li| li.timestamp = 101);
+        client.deposit_to_goal_save(&user, &goal_id, &500);
+    }
+
+    #[test]
+    fn test_reclaim_expired_goal_skips_break_fee() {
+        let (env, client, admin) = setup_admin_env();
+        let user = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        assert!(client.try_set_fee_recipient(&admin, &treasury).is_ok());
+        assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok());
+
+        let goal_name = Symbol::new(&env, "wedding");
+        let goal_id = client.create_goal_save_with_deadline(&user, &goal_name, &5000, &2000, &100);
+        assert_eq!(client.get_user_expired_goal_saves(&user).len(), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = 200);
+        let expired = client.get_user_expired_goal_saves(&user);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired.get(0).unwrap().id, goal_id);
+
+        let reclaimed = client.reclaim_expired_goal_save(&user, &goal_id);
+        assert_eq!(reclaimed, 2000);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+        assert_eq!(client.get_user_expired_goal_saves(&user).len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #51)")]
+    fn test_reclaim_before_deadline_fails() {
+        let (env, client) = setup_test_env();
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        let goal_name = Symbol::new(&env, "early");
+        let goal_id = client.create_goal_save_with_deadline(&user, &goal_name, &5000, &1000, &100);
+        client.reclaim_expired_goal_save(&user, &goal_id);
+    }
+
+    #[test]
+    fn test_finalize_goal_by_anyone_after_deadline() {
+        let (env, client, admin) = setup_admin_env();
+        let user = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        assert!(client.try_set_fee_recipient(&admin, &treasury).is_ok());
+        assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok());
+
+        let goal_name = Symbol::new(&env, "trip");
+        let goal_id = client.create_goal_save_with_deadline(&user, &goal_name, &5000, &2000, &100);
+        assert!(client.try_finalize_goal(&goal_id).is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 200);
+        assert_eq!(client.finalize_goal(&goal_id), 2000);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+        assert!(client.get_goal_save_detail(&goal_id).is_withdrawn);
+        assert!(client.try_finalize_goal(&goal_id).is_err());
+    }
+
+    #[test]
+    fn test_finalize_failed_goal_applies_penalty_when_enabled() {
+        let (env, client, admin) = setup_admin_env();
+        let user = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        assert!(client.try_set_fee_recipient(&admin, &treasury).is_ok());
+        assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok());
+        assert!(client.try_set_failed_goal_penalty(&admin, &true).is_ok());
+
+        let goal_name = Symbol::new(&env, "car");
+        let failed = client.create_goal_save_with_deadline(&user, &goal_name, &5000, &2000, &100);
+        let met = client.create_goal_save_with_deadline(&user, &goal_name, &1000, &1000, &100);
+
+        env.ledger().with_mut(|li| li.timestamp = 200);
+        assert_eq!(client.finalize_goal(&failed), 1900);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 100);
+        assert_eq!(client.finalize_goal(&met), 1000);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 100);
+    }
+
+    #[test]
+    fn test_fee_exempt_owner_pays_no_break_or_failed_goal_penalty() {
+        let (env, client, admin) = setup_admin_env();
+        let user = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        assert!(client.try_set_fee_recipient(&admin, &treasury).is_ok());
+        assert!(client.try_set_early_break_fee_bps(&admin, &500).is_ok());
+        assert!(client.try_set_failed_goal_penalty(&admin, &true).is_ok());
+        client.set_fee_exemption(&admin, &FeeExemption::User(user.clone()), &true);
+
+        let goal_name = Symbol::new(&env, "car");
+        let broken = client.create_goal_save(&user, &goal_name, &5000, &2000);
+        let failed = client.create_goal_save_with_deadline(&user, &goal_name, &5000, &2000, &100);
+
+        assert_eq!(client.preview_break_goal(&broken).penalty, 0);
+        assert_eq!(client.break_goal_save(&user, &broken), 2000);
+
+        env.ledger().with_mut(|li| li.timestamp = 200);
+        assert_eq!(client.finalize_goal(&failed), 2000);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+    }
+
+    #[test]
+    fn test_gift_goal_save_to_another_user() {
+        let (env, client) = setup_test_env();
+        let parent = Address::generate(&env);
+        let child = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&parent);
+        client.initialize_user(&child);
+
+        let goal_name = Symbol::new(&env, "college");
+        let goal_id = client.create_goal_save(&parent, &goal_name, &5000, &1000);
+        client.gift_goal_save(&parent, &child, &goal_id);
+
+        assert_eq!(client.get_goal_save_detail(&goal_id).owner, child);
+        assert_eq!(client.get_user_goal_saves(&parent).len(), 0);
+        assert_eq!(client.get_user_goal_saves(&child).len(), 1);
+        assert!(client
+            .try_gift_goal_save(&parent, &child, &goal_id)
+            .is_err());
+
+        client.deposit_to_goal_save(&child, &goal_id, &4000);
+        assert_eq!(client.withdraw_completed_goal_save(&child, &goal_id), 5000);
+    }
+
+    #[test]
+    fn test_co_owned_goal_shared_deposits_and_joint_withdrawal() {
+        let (env, client) = setup_test_env();
+        let owner = Address::generate(&env);
+        let partner = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&owner);
+        client.initialize_user(&partner);
+
+        let goal_name = Symbol::new(&env, "house");
+        let goal_id = client.create_goal_save(&owner, &goal_name, &3000, &1000);
+        assert!(client
+            .try_deposit_to_goal_save(&partner, &goal_id, &500)
+            .is_err());
+
+        client.add_goal_co_owner(&owner, &goal_id, &partner);
+        assert!(client
+            .try_add_goal_co_owner(&owner, &goal_id, &partner)
+            .is_err());
+        assert_eq!(
+            client.get_goal_co_owners(&goal_id),
+            vec![&env, partner.clone()]
+        );
+        assert_eq!(client.get_user_goal_saves(&owner), vec![&env, goal_id]);
+        assert_eq!(client.get_user_goal_saves(&partner), vec![&env, goal_id]);
+
+        client.deposit_to_goal_save(&partner, &goal_id, &2000);
+
+        // By default the partner cannot withdraw without the owner signing too
+        client.withdraw_completed_goal_save(&partner, &goal_id);
+        let auths = env.auths();
+        assert!(auths.iter().any(|(signer, _)| *signer == owner));
+        assert!(auths.iter().any(|(signer, _)| *signer == partner));
+        assert_eq!(client.get_user(&partner).total_balance, 3000);
+    }
+
+    #[test]
+    fn test_co_owned_goal_any_owner_policy_needs_one_signer() {
+        let (env, client) = setup_test_env();
+        let owner = Address::generate(&env);
+        let partner = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&owner);
+        client.initialize_user(&partner);
+
+        let goal_name = Symbol::new(&env, "trip");
+        let goal_id = client.create_goal_save(&owner, &goal_name, &5000, &1000);
+        client.add_goal_co_owner(&owner, &goal_id, &partner);
+        client.set_goal_withdraw_policy(&owner, &goal_id, &GoalWithdrawPolicy::AnyOwner);
+
+        client.break_goal_save(&partner, &goal_id);
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        assert_eq!(auths[0].0, partner);
+        assert_eq!(client.get_user_goal_saves(&owner).len(), 0);
+        assert_eq!(client.get_user_goal_saves(&partner).len(), 0);
+    }
+
+    #[test]
+    fn test_goal_milestones_recorded_on_crossing() {
+        let (env, client) = setup_test_env();
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        let goal_name = Symbol::new(&env, "house");
+        let goal_id = client.create_goal_save(&user, &goal_name, &1000, &100);
+        assert_eq!(client.get_goal_milestones(&goal_id).len(), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = 50);
+        client.deposit_to_goal_save(&user, &goal_id, &450);
+
+        let milestones = client.get_goal_milestones(&goal_id);
+        assert_eq!(milestones.len(), 2);
+        assert_eq!(milestones.get(0).unwrap().percent, 25);
+        assert_eq!(milestones.get(1).unwrap().percent, 50);
+        assert_eq!(milestones.get(1).unwrap().reached_at, 50);
+
+        env.ledger().with_mut(|li| li.timestamp = 90);
+        client.deposit_to_goal_save(&user, &goal_id, &450);
+
+        let milestones = client.get_goal_milestones(&goal_id);
+        assert_eq!(milestones.len(), 4);
+        assert_eq!(milestones.get(3).unwrap().percent, 100);
+        assert_eq!(milestones.get(3).unwrap().reached_at, 90);
+    }
+
+    #[test]
+    fn test_goal_milestones_from_initial_deposit() {
+        let (env, client) = setup_test_env();
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        let goal_name = Symbol::new(&env, "bike");
+        let goal_id = client.create_goal_save(&user, &goal_name, &1000, &760);
+
+        let milestones = client.get_goal_milestones(&goal_id);
+        assert_eq!(milestones.len(), 3);
+        assert_eq!(milestones.get(2).unwrap().percent, 75);
+    }
+
+    #[test]
+    fn test_public_goal_accepts_outside_contributions() {
+        let (env, client) = setup_test_env();
+        let owner = Address::generate(&env);
+        let friend = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&owner);
+        client.initialize_user(&friend);
+
+        let goal_id = client.create_goal_save(&owner, &Symbol::new(&env, "wedding"), &1_000, &100);
+        assert_eq!(
+            client.try_contribute_to_goal(&friend, &goal_id, &200),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_set_goal_public(&friend, &goal_id, &true),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+
+        client.set_goal_public(&owner, &goal_id, &true);
+        assert!(client.get_goal_save_detail(&goal_id).is_public);
+        client.contribute_to_goal(&friend, &goal_id, &200);
+        client.contribute_to_goal(&friend, &goal_id, &300);
+        assert_eq!(
+            client.try_contribute_to_goal(&stranger, &goal_id, &100),
+            Err(Ok(SavingsError::UserNotFound))
+        );
+        // The owner's own contributions are regular deposits
+        client.contribute_to_goal(&owner, &goal_id, &50);
+
+        let contributors = client.get_goal_contributors(&goal_id);
+        assert_eq!(contributors.len(), 1);
+        let contribution = contributors.get(0).unwrap();
+        assert_eq!(
+            (contribution.contributor, contribution.amount),
+            (friend.clone(), 500)
+        );
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 650);
+
+        client.contribute_to_goal(&friend, &goal_id, &350);
+        assert!(client.get_goal_save_detail(&goal_id).is_completed);
+        assert_eq!(
+            client.try_withdraw_completed_goal_save(&friend, &goal_id),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                SavingsError::Unauthorized as u32
+            )))
+        );
+        assert_eq!(client.withdraw_completed_goal_save(&owner, &goal_id), 1_000);
+    }
 }
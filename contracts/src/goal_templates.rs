@@ -0,0 +1,220 @@
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::goal;
+use crate::guard::{self, Op};
+use crate::storage_types::{ConfigKey, DataKey, GoalTemplate, GoalTemplateOverrides};
+
+/// Most templates the catalog holds, so listing it stays within budget
+pub const MAX_GOAL_TEMPLATES: u32 = 50;
+
+/// Adds a template to the catalog. Callers are responsible for checking the
+/// caller is the admin.
+///
+/// # Errors
+/// * `InvalidAmount` - If `suggested_target` is not positive
+/// * `MaxPlansExceeded` - If the catalog holds `MAX_GOAL_TEMPLATES` templates
+pub fn add_goal_template(
+    env: &Env,
+    name: Symbol,
+    suggested_target: i128,
+    suggested_duration: u64,
+    category: Symbol,
+) -> Result<u64, SavingsError> {
+    if suggested_target <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let mut templates = list_goal_templates(env);
+    if templates.len() >= MAX_GOAL_TEMPLATES {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+
+    let id: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::NextGoalTemplateId))
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::NextGoalTemplateId), &(id + 1));
+
+    templates.push_back(GoalTemplate {
+        id,
+        name,
+        suggested_target,
+        suggested_duration,
+        category,
+    });
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::GoalTemplates), &templates);
+
+    events::publish(env, events::GoalTemplateAdded { template_id: id });
+    Ok(id)
+}
+
+/// Drops a template from the catalog; goals already created from it are
+/// unaffected. Callers are responsible for checking the caller is the admin.
+///
+/// # Errors
+/// * `PlanNotFound` - If no template has `template_id`
+pub fn remove_goal_template(env: &Env, template_id: u64) -> Result<(), SavingsError> {
+    let mut templates = list_goal_templates(env);
+    let index = templates
+        .iter()
+        .position(|template| template.id == template_id)
+        .ok_or(SavingsError::PlanNotFound)?;
+    templates.remove(index as u32);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::GoalTemplates), &templates);
+
+    events::publish(env, events::GoalTemplateRemoved { template_id });
+    Ok(())
+}
+
+/// Creates a Goal Save from a catalog template, taking any field set in
+/// `overrides` in place of the template's suggestion. The deadline is the
+/// chosen duration from now, or none for a zero duration.
+///
+/// # Errors
+/// * `PlanNotFound` - If no template has `template_id`
+/// * `InvalidTimestamp` - If the deadline overflows
+pub fn create_goal_from_template(
+    env: &Env,
+    user: Address,
+    template_id: u64,
+    overrides: GoalTemplateOverrides,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &user, Op::CreatePlan, overrides.initial_deposit)?;
+    user.require_auth();
+
+    let template = get_goal_template(env, template_id).ok_or(SavingsError::PlanNotFound)?;
+    let duration = overrides.duration.unwrap_or(template.suggested_duration);
+    let deadline = if duration == 0 {
+        0
+    } else {
+        env.ledger()
+            .timestamp()
+            .checked_add(duration)
+            .ok_or(SavingsError::InvalidTimestamp)?
+    };
+
+    let goal_id = goal::insert_goal_save(
+        env,
+        user.clone(),
+        overrides.goal_name.unwrap_or(template.name),
+        overrides.target_amount.unwrap_or(template.suggested_target),
+        overrides.initial_deposit,
+        deadline,
+    )?;
+
+    events::publish(
+        env,
+        events::GoalCreatedFromTemplate {
+            user,
+            template_id,
+            goal_id,
+        },
+    );
+    Ok(goal_id)
+}
+
+pub fn get_goal_template(env: &Env, template_id: u64) -> Option<GoalTemplate> {
+    list_goal_templates(env)
+        .iter()
+        .find(|template| template.id == template_id)
+}
+
+/// VIEW FUNCTION - Every template in the catalog, in creation order
+pub fn list_goal_templates(env: &Env) -> Vec<GoalTemplate> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::GoalTemplates))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::GoalTemplateOverrides;
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, Symbol,
+    };
+
+    #[test]
+    fn test_goal_from_template_uses_suggestions_and_overrides() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+        assert_eq!(
+            client.try_add_goal_template(
+                &user,
+                &Symbol::new(&env, "laptop"),
+                &2_000,
+                &0,
+                &Symbol::new(&env, "tech"),
+            ),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+        let template_id = client.add_goal_template(
+            &admin,
+            &Symbol::new(&env, "emergency"),
+            &5_000,
+            &86_400,
+            &Symbol::new(&env, "safety"),
+        );
+        assert_eq!(client.list_goal_templates().len(), 1);
+
+        let suggested = client.create_goal_from_template(
+            &user,
+            &template_id,
+            &GoalTemplateOverrides {
+                goal_name: None,
+                target_amount: None,
+                duration: None,
+                initial_deposit: 100,
+            },
+        );
+        let goal = client.get_goal_save_detail(&suggested);
+        assert_eq!(goal.goal_name, Symbol::new(&env, "emergency"));
+        assert_eq!((goal.target_amount, goal.deadline), (5_000, 87_400));
+        assert_eq!(goal.current_amount, 100);
+
+        let custom = client.create_goal_from_template(
+            &user,
+            &template_id,
+            &GoalTemplateOverrides {
+                goal_name: Some(Symbol::new(&env, "rainy")),
+                target_amount: Some(8_000),
+                duration: Some(0),
+                initial_deposit: 0,
+            },
+        );
+        let goal = client.get_goal_save_detail(&custom);
+        assert_eq!(goal.goal_name, Symbol::new(&env, "rainy"));
+        assert_eq!((goal.target_amount, goal.deadline), (8_000, 0));
+
+        client.remove_goal_template(&admin, &template_id);
+        assert_eq!(client.list_goal_templates().len(), 0);
+        assert_eq!(
+            client.try_remove_goal_template(&admin, &template_id),
+            Err(Ok(SavingsError::PlanNotFound))
+        );
+    }
+}
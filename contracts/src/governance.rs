@@ -0,0 +1,372 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::group::{self, CONTRIBUTION_PERCENTAGE};
+use crate::guard::{self, Op};
+use crate::storage_types::{ConfigKey, DataKey, GroupChange, GroupKey, GroupProposal, GroupSave};
+
+/// How long members may vote on a group proposal (3 days)
+pub const GROUP_VOTING_PERIOD: u64 = 3 * 24 * 60 * 60;
+
+/// Approval share required when no quorum is configured: a simple majority
+pub const DEFAULT_GROUP_QUORUM_BPS: u32 = 5_001;
+
+pub fn get_quorum_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::GroupQuorumBps))
+        .unwrap_or(DEFAULT_GROUP_QUORUM_BPS)
+}
+
+pub fn set_quorum_bps(env: &Env, bps: u32) -> Result<(), SavingsError> {
+    if bps == 0 || bps > 10_000 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::GroupQuorumBps), &bps);
+    Ok(())
+}
+
+pub fn get_proposal(env: &Env, proposal_id: u64) -> Option<GroupProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::Proposal(proposal_id)))
+}
+
+/// VIEW FUNCTION - IDs of a group's proposals, oldest first
+pub fn get_group_proposals(env: &Env, group_id: u64) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::Proposals(group_id)))
+        .unwrap_or(Vec::new(env))
+}
+
+fn is_member(env: &Env, group_id: u64, user: &Address) -> bool {
+    group::get_group_members(env, group_id).contains(user)
+}
+
+/// Returns `group` with `change` applied, validated like a new group would be
+fn apply_change(
+    env: &Env,
+    mut group: GroupSave,
+    change: &GroupChange,
+) -> Result<GroupSave, SavingsError> {
+    match change {
+        GroupChange::TargetAmount(target_amount) => {
+            if *target_amount <= 0 {
+                return Err(SavingsError::InvalidAmount);
+            }
+            group.target_amount = *target_amount;
+            group.is_completed = group.current_amount >= group.target_amount;
+        }
+        GroupChange::EndTime(end_time) => {
+            let bounds = group::get_duration_bounds(env);
+            let now = env.ledger().timestamp();
+            if *end_time <= group.start_time
+                || *end_time <= now
+                || *end_time - group.start_time < bounds.min_duration
+                || *end_time > now.saturating_add(bounds.max_horizon)
+                || group.contribution_frequency > *end_time - group.start_time
+            {
+                return Err(SavingsError::InvalidTimestamp);
+            }
+            group.end_time = *end_time;
+        }
+        GroupChange::ContributionAmount(amount) => {
+            if *amount <= 0
+                || (group.contribution_type == CONTRIBUTION_PERCENTAGE && *amount > 10_000)
+            {
+                return Err(SavingsError::InvalidAmount);
+            }
+            group.contribution_amount = *amount;
+        }
+    }
+    Ok(group)
+}
+
+/// Opens a vote on changing one of a group's parameters. Only the creator may
+/// propose; the change is validated up front so members vote on something
+/// that can actually be executed.
+pub fn propose_group_change(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    change: GroupChange,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let group = group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    apply_change(env, group, &change)?;
+
+    let id_key = DataKey::Config(ConfigKey::NextGroupProposalId);
+    let proposal_id: u64 = env.storage().instance().get(&id_key).unwrap_or(1);
+    env.storage().instance().set(&id_key, &(proposal_id + 1));
+
+    let proposal = GroupProposal {
+        id: proposal_id,
+        group_id,
+        change,
+        votes_for: 0,
+        votes_against: 0,
+        voters: Vec::new(env),
+        deadline: env.ledger().timestamp() + GROUP_VOTING_PERIOD,
+        executed: false,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Proposal(proposal_id)), &proposal);
+
+    let mut proposals = get_group_proposals(env, group_id);
+    proposals.push_back(proposal_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Proposals(group_id)), &proposals);
+
+    events::publish(
+        env,
+        events::GroupProposalCreated {
+            group_id,
+            proposal_id,
+            change: proposal.change,
+        },
+    );
+    Ok(proposal_id)
+}
+
+pub fn vote_group_change(
+    env: &Env,
+    member: Address,
+    proposal_id: u64,
+    approve: bool,
+) -> Result<(), SavingsError> {
+    guard::check(env, &member, Op::Manage, 0)?;
+    member.require_auth();
+
+    let mut proposal = get_proposal(env, proposal_id).ok_or(SavingsError::PlanNotFound)?;
+    if proposal.executed {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if env.ledger().timestamp() > proposal.deadline {
+        return Err(SavingsError::TooLate);
+    }
+    if !is_member(env, proposal.group_id, &member) {
+        return Err(SavingsError::NotGroupMember);
+    }
+    if proposal.voters.contains(&member) {
+        return Err(SavingsError::AlreadyVoted);
+    }
+
+    proposal.voters.push_back(member.clone());
+    if approve {
+        proposal.votes_for += 1;
+    } else {
+        proposal.votes_against += 1;
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Proposal(proposal_id)), &proposal);
+
+    events::publish(
+        env,
+        events::GroupVoteCast {
+            member,
+            proposal_id,
+            approve,
+        },
+    );
+    Ok(())
+}
+
+/// Applies a proposal once approvals reach the quorum of current members.
+/// Callable by anyone.
+pub fn execute_group_change(env: &Env, proposal_id: u64) -> Result<GroupSave, SavingsError> {
+    guard::check_protocol(env)?;
+
+    let mut proposal = get_proposal(env, proposal_id).ok_or(SavingsError::PlanNotFound)?;
+    if proposal.executed {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let group_id = proposal.group_id;
+    if group::get_dispute(env, group_id).frozen {
+        return Err(SavingsError::GroupFrozen);
+    }
+    let group = group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+
+    let approvals = proposal.votes_for as u64 * 10_000;
+    let required = group.member_count as u64 * get_quorum_bps(env) as u64;
+    if approvals < required {
+        return Err(SavingsError::QuorumNotReached);
+    }
+
+    let group = apply_change(env, group, &proposal.change)?;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(group_id), &group);
+
+    proposal.executed = true;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Proposal(proposal_id)), &proposal);
+
+    events::publish(
+        env,
+        events::GroupProposalExecuted {
+            group_id,
+            proposal_id,
+            change: proposal.change,
+        },
+    );
+    Ok(group)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GroupChange, GroupMetadata, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, String,
+    };
+
+    fn setup() -> (
+        Env,
+        NesteraContractClient<'static>,
+        Address,
+        u64,
+        [Address; 3],
+    ) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let members = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        for member in members.iter() {
+            client.initialize_user(member);
+        }
+        let group_id = client.create_group_save(
+            &members[0],
+            &GroupMetadata {
+                title: String::from_str(&env, "Club"),
+                description: String::from_str(&env, "Savings club"),
+                category: String::from_str(&env, "community"),
+            },
+            &10_000,
+            &1,
+            &100,
+            &true,
+            &0,
+            &1_000_000,
+            &0,
+        );
+        client.join_group_save(&members[1], &group_id);
+        client.join_group_save(&members[2], &group_id);
+
+        (env, client, admin, group_id, members)
+    }
+
+    #[test]
+    fn test_approved_proposal_changes_group() {
+        let (_env, client, _admin, group_id, members) = setup();
+        let proposal_id =
+            client.propose_group_change(&members[0], &group_id, &GroupChange::TargetAmount(20_000));
+        assert_eq!(client.get_group_proposals(&group_id).len(), 1);
+
+        client.vote_group_change(&members[0], &proposal_id, &true);
+        assert_eq!(
+            client.try_execute_group_change(&proposal_id).unwrap_err(),
+            Ok(SavingsError::QuorumNotReached)
+        );
+
+        client.vote_group_change(&members[1], &proposal_id, &true);
+        let group = client.execute_group_change(&proposal_id);
+        assert_eq!(group.target_amount, 20_000);
+        assert!(client.get_group_proposal(&proposal_id).executed);
+        assert_eq!(
+            client.try_execute_group_change(&proposal_id).unwrap_err(),
+            Ok(SavingsError::PlanCompleted)
+        );
+    }
+
+    #[test]
+    fn test_voting_rules() {
+        let (env, client, _admin, group_id, members) = setup();
+        let outsider = Address::generate(&env);
+        let proposal_id = client.propose_group_change(
+            &members[0],
+            &group_id,
+            &GroupChange::ContributionAmount(200),
+        );
+
+        client.vote_group_change(&members[1], &proposal_id, &false);
+        assert_eq!(
+            client
+                .try_vote_group_change(&members[1], &proposal_id, &true)
+                .unwrap_err(),
+            Ok(SavingsError::AlreadyVoted)
+        );
+        assert_eq!(
+            client
+                .try_vote_group_change(&outsider, &proposal_id, &true)
+                .unwrap_err(),
+            Ok(SavingsError::NotGroupMember)
+        );
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = super::GROUP_VOTING_PERIOD + 1);
+        assert_eq!(
+            client
+                .try_vote_group_change(&members[2], &proposal_id, &true)
+                .unwrap_err(),
+            Ok(SavingsError::TooLate)
+        );
+    }
+
+    #[test]
+    fn test_only_creator_proposes_valid_changes() {
+        let (_env, client, admin, group_id, members) = setup();
+        assert_eq!(
+            client
+                .try_propose_group_change(&members[1], &group_id, &GroupChange::EndTime(2_000_000))
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+        assert_eq!(
+            client
+                .try_propose_group_change(&members[0], &group_id, &GroupChange::EndTime(0))
+                .unwrap_err(),
+            Ok(SavingsError::InvalidTimestamp)
+        );
+
+        // With a unanimous quorum every member must approve
+        client.set_group_quorum_bps(&admin, &10_000);
+        let proposal_id =
+            client.propose_group_change(&members[0], &group_id, &GroupChange::EndTime(2_000_000));
+        client.vote_group_change(&members[0], &proposal_id, &true);
+        client.vote_group_change(&members[1], &proposal_id, &true);
+        assert!(client.try_execute_group_change(&proposal_id).is_err());
+        client.vote_group_change(&members[2], &proposal_id, &true);
+        assert_eq!(
+            client.execute_group_change(&proposal_id).end_time,
+            2_000_000
+        );
+    }
+}
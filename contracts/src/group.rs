@@ -1,8 +1,52 @@
-use crate::ensure_not_paused;
+use crate::achievements;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, GroupSave};
+use crate::events;
+use crate::flexi;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::limits;
+use crate::migrations;
+use crate::pagination;
+use crate::rates;
+use crate::record_deposit_activity;
+use crate::storage_types::{
+    Badge, ChunkedList, ChunkedListId, ConfigKey, Cursor, DataKey, DisputeOutcome,
+    DueGroupContribution, GroupAnnouncement, GroupContribution, GroupDispute, GroupDurationBounds,
+    GroupInterest, GroupJoinRequirements, GroupKey, GroupMetadata, GroupRoundStatus, GroupSave,
+    GroupStatus, InterestAccrual, PlanKind, TxKind,
+};
 use crate::users;
-use soroban_sdk::{Address, Env, String, Vec};
+use crate::yield_pool;
+use soroban_sdk::{symbol_short, Address, Env, String, Vec};
+
+/// Contribution type: every contribution is a multiple of `contribution_amount`
+pub const CONTRIBUTION_FIXED: u32 = 0;
+/// Contribution type: any contribution of at least `contribution_amount`
+pub const CONTRIBUTION_FLEXIBLE: u32 = 1;
+/// Contribution type: `contribution_amount` is a share of `target_amount` in
+/// basis points, and every contribution is a multiple of that share
+pub const CONTRIBUTION_PERCENTAGE: u32 = 2;
+
+fn percentage_share(target_amount: i128, bps: i128) -> i128 {
+    target_amount.saturating_mul(bps) / 10_000
+}
+
+/// Checks `amount` against the group's contribution rules
+fn validate_contribution_amount(group: &GroupSave, amount: i128) -> Result<(), SavingsError> {
+    let valid = match group.contribution_type {
+        CONTRIBUTION_FIXED => amount % group.contribution_amount == 0,
+        CONTRIBUTION_FLEXIBLE => amount >= group.contribution_amount,
+        CONTRIBUTION_PERCENTAGE => {
+            let share = percentage_share(group.target_amount, group.contribution_amount);
+            share > 0 && amount % share == 0
+        }
+        _ => false,
+    };
+    if !valid {
+        return Err(SavingsError::InvalidAmount);
+    }
+    Ok(())
+}
 
 /// Creates a new group savings plan.
 ///
@@ -16,11 +60,13 @@ use soroban_sdk::{Address, Env, String, Vec};
 /// * `description` - Description of the group savings goal
 /// * `category` - Category of the group savings (e.g., "education", "emergency")
 /// * `target_amount` - Target amount to save (must be > 0)
-/// * `contribution_type` - Type of contribution (0 = fixed, 1 = flexible, etc.)
-/// * `contribution_amount` - Contribution amount or minimum (must be > 0)
+/// * `contribution_type` - Type of contribution (0 = fixed, 1 = flexible, 2 = percentage)
+/// * `contribution_amount` - Fixed amount, minimum, or share of the target in
+///   basis points, depending on `contribution_type` (must be > 0)
 /// * `is_public` - Whether the group is public or private
 /// * `start_time` - Unix timestamp when the group starts
 /// * `end_time` - Unix timestamp when the group ends (must be > start_time)
+/// * `max_members` - Member cap including the creator (0 = no cap)
 ///
 /// # Returns
 /// `Ok(u64)` - The unique ID of the created group
@@ -28,7 +74,8 @@ use soroban_sdk::{Address, Env, String, Vec};
 ///
 /// # Errors
 /// * `InvalidAmount` - If target_amount or contribution_amount <= 0
-/// * `InvalidTimestamp` - If start_time >= end_time
+/// * `InvalidTimestamp` - If start_time >= end_time, or the window is outside
+///   the configured `GroupDurationBounds`
 /// * `InvalidGroupConfig` - If other parameters are invalid
 pub fn create_group_save(
     env: &Env,
@@ -42,8 +89,9 @@ pub fn create_group_save(
     is_public: bool,
     start_time: u64,
     end_time: u64,
+    max_members: u32,
 ) -> Result<u64, SavingsError> {
-    ensure_not_paused(env)?;
+    guard::check(env, &creator, Op::CreatePlan, 0)?;
     // Validate target_amount > 0
     if target_amount <= 0 {
         return Err(SavingsError::InvalidAmount);
@@ -59,11 +107,27 @@ pub fn create_group_save(
         return Err(SavingsError::InvalidTimestamp);
     }
 
+    // Validate the window against the admin-set duration bounds
+    let bounds = get_duration_bounds(env);
+    if end_time - start_time < bounds.min_duration
+        || end_time > env.ledger().timestamp().saturating_add(bounds.max_horizon)
+    {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
     // Validate contribution_type is reasonable (0-2 for fixed/flexible/percentage)
     if contribution_type > 2 {
         return Err(SavingsError::InvalidGroupConfig);
     }
 
+    // A percentage group's share must be a valid share of the target
+    if contribution_type == CONTRIBUTION_PERCENTAGE
+        && (contribution_amount > 10_000
+            || percentage_share(target_amount, contribution_amount) == 0)
+    {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
     // Validate title and description are not empty
     if title.is_empty() || description.is_empty() {
         return Err(SavingsError::InvalidGroupConfig);
@@ -76,7 +140,11 @@ pub fn create_group_save(
 
     // Get the next group ID
     let next_id_key = DataKey::NextGroupId;
-    let group_id: u64 = env.storage().persistent().get(&next_id_key).unwrap_or(1u64);
+    let group_id = get_next_group_id(env);
+
+    if is_public {
+        index_public_group(env, group_id, &category);
+    }
 
     // Create the GroupSave struct with initial values
     let new_group = GroupSave {
@@ -94,6 +162,8 @@ pub fn create_group_save(
         start_time,
         end_time,
         is_completed: false,
+        contribution_frequency: 0,
+        max_members,
     };
 
     // Store the GroupSave in persistent storage
@@ -111,6 +181,8 @@ pub fn create_group_save(
     members.push_back(creator.clone());
     env.storage().persistent().set(&members_key, &members);
 
+    record_member_joined(env, group_id, &creator);
+
     // Initialize creator's contribution to 0
     let contribution_key = DataKey::GroupMemberContribution(group_id, creator.clone());
     env.storage().persistent().set(&contribution_key, &0i128);
@@ -118,31 +190,10 @@ pub fn create_group_save(
     // Add group_id to the creator's UserGroupSaves list
     add_group_to_user_list(env, &creator, group_id)?;
 
-    // Create a SavingsPlan for the creator so they can retrieve it via get_group_save
-    let now = env.ledger().timestamp();
-    let savings_plan = crate::storage_types::SavingsPlan {
-        plan_id: group_id,
-        plan_type: crate::storage_types::PlanType::Group(
-            group_id,
-            is_public,
-            contribution_type,
-            target_amount,
-        ),
-        balance: 0,
-        start_time: now,
-        last_deposit: 0,
-        last_withdraw: 0,
-        interest_rate: 500, // Default 5%
-        is_completed: false,
-        is_withdrawn: false,
-    };
-
-    let plan_key = DataKey::SavingsPlan(creator.clone(), group_id);
-    env.storage().persistent().set(&plan_key, &savings_plan);
+    achievements::award(env, &creator, Badge::GroupFounder);
 
     // Emit event for group creation
-    env.events()
-        .publish((soroban_sdk::symbol_short!("grp_new"), creator), group_id);
+    events::publish(env, events::GroupCreated { creator, group_id });
 
     Ok(group_id)
 }
@@ -160,6 +211,14 @@ pub fn get_group_save(env: &Env, group_id: u64) -> Option<GroupSave> {
     env.storage().persistent().get(&key)
 }
 
+/// ID the next group created will get; every lower ID from 1 has been issued
+pub fn get_next_group_id(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::NextGroupId)
+        .unwrap_or(1)
+}
+
 /// Checks if a group exists.
 ///
 /// # Arguments
@@ -197,7 +256,8 @@ pub fn get_user_groups(env: &Env, user: &Address) -> Vec<u64> {
 /// * `group_id` - The group ID to add
 ///
 /// # Returns
-/// `Ok(())` on success
+/// `Ok(())` on success, or `MaxPlansExceeded` if the user is already in as
+/// many groups as the plan limits allow
 fn add_group_to_user_list(env: &Env, user: &Address, group_id: u64) -> Result<(), SavingsError> {
     let key = DataKey::UserGroupSaves(user.clone());
     let mut groups = env
@@ -205,6 +265,7 @@ fn add_group_to_user_list(env: &Env, user: &Address, group_id: u64) -> Result<()
         .persistent()
         .get(&key)
         .unwrap_or(Vec::new(env));
+    limits::ensure_plan_capacity(limits::get_plan_limits(env).max_group_saves, groups.len())?;
 
     groups.push_back(group_id);
     env.storage().persistent().set(&key, &groups);
@@ -224,10 +285,16 @@ fn add_group_to_user_list(env: &Env, user: &Address, group_id: u64) -> Result<()
 /// `Err(SavingsError)` if:
 /// - User doesn't exist
 /// - Group doesn't exist
-/// - Group is not public
+/// - Group is not public and the user was not invited
 /// - User is already a member
+/// - The user's Flexi Save cannot cover the group's join stake
+///
+/// Users joining without an invite contribute the group's join stake, if
+/// it has one, straight away out of their Flexi Save. It counts as their
+/// first contribution.
 pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
     // Ensure user exists
     if !users::user_exists(env, &user) {
         return Err(SavingsError::UserNotFound);
@@ -241,11 +308,26 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
         .get(&group_key)
         .ok_or(SavingsError::PlanNotFound)?;
 
-    // Validate that the group is public
-    if !group.is_public {
+    // Private groups take invited users only
+    let invite_key = DataKey::Group(GroupKey::Invited(group_id, user.clone()));
+    let invited = env.storage().persistent().has(&invite_key);
+    if !group.is_public && !invited {
         return Err(SavingsError::InvalidGroupConfig);
     }
 
+    // Enforce the creator's reputation and tenure requirements
+    let requirements = get_join_requirements(env, group_id);
+    if users::get_reputation(env, &user) < requirements.min_reputation {
+        return Err(SavingsError::ReputationTooLow);
+    }
+    if users::get_account_age(env, &user) < requirements.min_account_age {
+        return Err(SavingsError::AccountTooNew);
+    }
+
+    if group.max_members > 0 && group.member_count >= group.max_members {
+        return Err(SavingsError::GroupFull);
+    }
+
     // Check if user is already a member
     let members_key = DataKey::GroupMembers(group_id);
     let mut members: Vec<Address> = env
@@ -266,6 +348,9 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
     // Add user to members list
     members.push_back(user.clone());
     env.storage().persistent().set(&members_key, &members);
+    if invited {
+        env.storage().persistent().remove(&invite_key);
+    }
 
     // Increment member count
     group.member_count += 1;
@@ -273,37 +358,152 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
 
     // Add group to user's list of groups
     add_group_to_user_list(env, &user, group_id)?;
+    record_member_joined(env, group_id, &user);
 
     // Initialize user's contribution to 0
     let contribution_key = DataKey::GroupMemberContribution(group_id, user.clone());
     env.storage().persistent().set(&contribution_key, &0i128);
 
-    // Create a SavingsPlan for the joining user
-    let now = env.ledger().timestamp();
-    let savings_plan = crate::storage_types::SavingsPlan {
-        plan_id: group_id,
-        plan_type: crate::storage_types::PlanType::Group(
+    // Emit event for joining group
+    events::publish(
+        env,
+        events::GroupJoined {
+            user: user.clone(),
             group_id,
-            group.is_public,
-            group.contribution_type,
-            group.target_amount,
-        ),
-        balance: 0,
-        start_time: now,
-        last_deposit: 0,
-        last_withdraw: 0,
-        interest_rate: 500, // Default 5%
-        is_completed: group.is_completed,
-        is_withdrawn: false,
+        },
+    );
+
+    if requirements.join_stake > 0 && !invited {
+        guard::check(env, &user, Op::Deposit, requirements.join_stake)?;
+        flexi::debit_flexi(env, &user, requirements.join_stake)?;
+        apply_group_contribution(env, user, group_id, requirements.join_stake)?;
+    }
+
+    Ok(())
+}
+
+/// Sets the minimum reputation and account age required to join a group.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `creator` - The group creator (must authorize)
+/// * `group_id` - The ID of the group
+/// * `min_reputation` - Minimum reputation score (0 disables the check)
+/// * `min_account_age` - Minimum account age in seconds (0 disables the check)
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+pub fn set_join_requirements(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    min_reputation: u32,
+    min_account_age: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    let requirements = GroupJoinRequirements {
+        min_reputation,
+        min_account_age,
+        join_stake: get_join_requirements(env, group_id).join_stake,
     };
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupJoinRequirements(group_id), &requirements);
 
-    let plan_key = DataKey::SavingsPlan(user.clone(), group_id);
-    env.storage().persistent().set(&plan_key, &savings_plan);
+    events::publish(
+        env,
+        events::GroupRequirementsSet {
+            creator,
+            group_id,
+            requirements,
+        },
+    );
 
-    // Emit event for joining group
-    env.events()
-        .publish((soroban_sdk::symbol_short!("grp_join"), user), group_id);
+    Ok(())
+}
+
+/// Sets the stake users joining a group without an invite must contribute
+/// out of their Flexi Save on joining, making spam joins cost real funds.
+/// 0 removes it.
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `Unauthorized` - If the caller is not the group creator
+/// * `InvalidAmount` - If `stake` is negative or breaks the group's
+///   contribution rules
+pub fn set_join_stake(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    stake: i128,
+) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if stake < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if stake > 0 {
+        validate_contribution_amount(&group, stake)?;
+    }
+
+    let mut requirements = get_join_requirements(env, group_id);
+    requirements.join_stake = stake;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupJoinRequirements(group_id), &requirements);
+
+    events::publish(
+        env,
+        events::GroupStakeSet {
+            creator,
+            group_id,
+            stake,
+        },
+    );
+
+    Ok(())
+}
+
+/// VIEW FUNCTION - Gets the join requirements of a group
+///
+/// Groups without configured requirements report zero for every threshold.
+pub fn get_join_requirements(env: &Env, group_id: u64) -> GroupJoinRequirements {
+    migrations::read_join_requirements(env, group_id).unwrap_or(GroupJoinRequirements {
+        min_reputation: 0,
+        min_account_age: 0,
+        join_stake: 0,
+    })
+}
 
+/// Joins the group unless the user is already a member, then contributes
+/// `amount` to it if positive. Backs the legacy `create_savings_plan`.
+pub fn join_and_contribute(
+    env: &Env,
+    user: &Address,
+    group_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    if get_group_members(env, group_id).contains(user) {
+        user.require_auth();
+    } else {
+        join_group_save(env, user.clone(), group_id)?;
+    }
+    if amount > 0 {
+        apply_group_contribution(env, user.clone(), group_id, amount)?;
+    }
     Ok(())
 }
 
@@ -318,7 +518,7 @@ pub fn join_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), Sa
 /// # Returns
 /// `Ok(())` on success
 /// `Err(SavingsError)` if:
-/// - Amount is invalid (<= 0)
+/// - Amount is invalid (<= 0) or breaks the group's contribution type rules
 /// - User is not a member
 /// - Group doesn't exist
 pub fn contribute_to_group_save(
@@ -327,7 +527,21 @@ pub fn contribute_to_group_save(
     group_id: u64,
     amount: i128,
 ) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
+    guard::check(env, &user, Op::Deposit, amount)?;
+    user.require_auth();
+
+    apply_group_contribution(env, user, group_id, amount)
+}
+
+/// Adds a member's contribution to a group.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn apply_group_contribution(
+    env: &Env,
+    user: Address,
+    group_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
     // Validate amount > 0
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
@@ -341,6 +555,11 @@ pub fn contribute_to_group_save(
         .get(&group_key)
         .ok_or(SavingsError::PlanNotFound)?;
 
+    if get_group_status(env, &group) == GroupStatus::Failed {
+        return Err(SavingsError::PlanCompleted);
+    }
+    validate_contribution_amount(&group, amount)?;
+
     // Check if user is a member
     let members_key = DataKey::GroupMembers(group_id);
     let members: Vec<Address> = env
@@ -375,7 +594,15 @@ pub fn contribute_to_group_save(
         .persistent()
         .set(&contribution_key, &new_contribution);
 
+    // Count the contribution towards the current round, if the group has a schedule
+    if let Some(round) = current_round(env, &group) {
+        let round_key = DataKey::Group(GroupKey::RoundPaid(group_id, round, user.clone()));
+        let paid: i128 = env.storage().persistent().get(&round_key).unwrap_or(0);
+        env.storage().persistent().set(&round_key, &(paid + amount));
+    }
+
     // Update group's current_amount
+    accrue_interest(env, &group)?;
     group.current_amount += amount;
 
     // Check if goal is reached
@@ -386,43 +613,26 @@ pub fn contribute_to_group_save(
     // Save updated group
     env.storage().persistent().set(&group_key, &group);
 
-    // Update the user's SavingsPlan to reflect the new balance
-    let plan_key = DataKey::SavingsPlan(user.clone(), group_id);
-    if let Some(mut plan) = env
-        .storage()
-        .persistent()
-        .get::<DataKey, crate::storage_types::SavingsPlan>(&plan_key)
-    {
-        plan.balance += amount;
-        plan.is_completed = group.is_completed;
-        plan.last_deposit = env.ledger().timestamp();
-        env.storage().persistent().set(&plan_key, &plan);
-    } else {
-        // If the user doesn't have a SavingsPlan yet (shouldn't happen for group members), create one
-        let now = env.ledger().timestamp();
-        let plan = crate::storage_types::SavingsPlan {
-            plan_id: group_id,
-            plan_type: crate::storage_types::PlanType::Group(
-                group_id,
-                group.is_public,
-                group.contribution_type,
-                group.target_amount,
-            ),
-            balance: amount,
-            start_time: now,
-            last_deposit: now,
-            last_withdraw: 0,
-            interest_rate: 500,
-            is_completed: group.is_completed,
-            is_withdrawn: false,
-        };
-        env.storage().persistent().set(&plan_key, &plan);
-    }
+    record_deposit_activity(env, &user, PlanKind::Group, group_id, amount);
+
+    // Append to the group's contribution history
+    ChunkedList::new(ChunkedListId::GroupContributions(group_id)).push(
+        env,
+        GroupContribution {
+            contributor: user.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
 
     // Emit event for contribution
-    env.events().publish(
-        (soroban_sdk::symbol_short!("grp_cont"), user, group_id),
-        amount,
+    events::publish(
+        env,
+        events::GroupContributed {
+            user,
+            group_id,
+            amount,
+        },
     );
 
     Ok(())
@@ -461,6 +671,107 @@ pub fn get_group_members(env: &Env, group_id: u64) -> Vec<Address> {
         .unwrap_or(Vec::new(env))
 }
 
+/// VIEW FUNCTION - Gets a page of a group's contribution history, oldest first
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `group_id` - The group ID
+/// * `cursor` - Position and size of the page
+///
+/// # Returns
+/// The page of records and the cursor for the next page, if any
+pub fn get_contribution_history(
+    env: &Env,
+    group_id: u64,
+    cursor: Cursor,
+) -> (Vec<GroupContribution>, Option<Cursor>) {
+    let list = ChunkedList::new(ChunkedListId::GroupContributions(group_id));
+    pagination::paginate_chunked(env, &list, &cursor)
+}
+
+/// Adds a public group to the discovery index and its category bucket.
+fn index_public_group(env: &Env, group_id: u64, category: &String) {
+    ChunkedList::new(ChunkedListId::PublicGroups).push(env, group_id);
+    index_in_category(env, group_id, Vec::new(env), category);
+}
+
+/// Adds a public group to `category`'s bucket unless it is already there.
+///
+/// The buckets are append-only, so a group moved back to an earlier category
+/// keeps its first entry.
+fn index_in_category(env: &Env, group_id: u64, mut indexed: Vec<String>, category: &String) {
+    if indexed.contains(category) {
+        return;
+    }
+    ChunkedList::new(ChunkedListId::PublicGroupsByCategory(category.clone())).push(env, group_id);
+    indexed.push_back(category.clone());
+    env.storage().persistent().set(
+        &DataKey::Group(GroupKey::IndexedCategories(group_id)),
+        &indexed,
+    );
+}
+
+/// Category buckets a public group is indexed in. Groups indexed before these
+/// were recorded are known to be in their current category.
+fn get_indexed_categories(env: &Env, group: &GroupSave) -> Vec<String> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::IndexedCategories(group.id)))
+        .unwrap_or(Vec::from_array(env, [group.category.clone()]))
+}
+
+/// Loads the groups behind a page of indexed group IDs.
+fn load_groups(env: &Env, ids: Vec<u64>) -> Vec<GroupSave> {
+    let mut groups = Vec::new(env);
+    for id in ids.iter() {
+        if let Some(group) = get_group_save(env, id) {
+            groups.push_back(group);
+        }
+    }
+    groups
+}
+
+/// Returns a page of public groups, oldest first.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `cursor` - Position and size of the page
+///
+/// # Returns
+/// The page of groups and the cursor for the next page, if any
+pub fn list_public_groups(env: &Env, cursor: Cursor) -> (Vec<GroupSave>, Option<Cursor>) {
+    let list = ChunkedList::new(ChunkedListId::PublicGroups);
+    let (ids, next) = pagination::paginate_chunked(env, &list, &cursor);
+    (load_groups(env, ids), next)
+}
+
+/// Returns a page of the public groups in `category`, oldest first.
+///
+/// # Arguments
+/// * `env` - The contract environment
+/// * `category` - Category the groups are currently in
+/// * `cursor` - Position and size of the page
+///
+/// # Returns
+/// The page of groups and the cursor for the next page, if any
+pub fn list_public_groups_by_category(
+    env: &Env,
+    category: String,
+    cursor: Cursor,
+) -> (Vec<GroupSave>, Option<Cursor>) {
+    let list = ChunkedList::new(ChunkedListId::PublicGroupsByCategory(category.clone()));
+    let (ids, next) = pagination::paginate_chunked(env, &list, &cursor);
+    // Groups moved to another category by `update_group_save` stay indexed
+    // under the old one
+    let mut groups = Vec::new(env);
+    for group in load_groups(env, ids).iter() {
+        if group.category == category {
+            groups.push_back(group);
+        }
+    }
+    (groups, next)
+}
+
 /// Helper function to remove a group ID from a user's list of groups.
 ///
 /// # Arguments
@@ -500,10 +811,13 @@ fn remove_group_from_user_list(
 ///
 /// This function handles:
 /// - Removing the user from the group member list
-/// - Refunding the user's contributions
+/// - Refunding the user's contributions, including any join stake, to their
+///   Flexi Save balance
 /// - Updating group state (member count, current amount)
 /// - Cleaning up all related storage entries
 ///
+/// The user's share of the interest earned so far stays with the group.
+///
 /// # Arguments
 /// * `env` - The contract environment
 /// * `user` - The address of the user leaving the group
@@ -517,7 +831,9 @@ fn remove_group_from_user_list(
 /// - User is not a member of the group
 /// - Group is already completed
 pub fn break_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
+    let refund = get_member_contribution(env, group_id, &user);
+    guard::check(env, &user, Op::Withdraw, refund)?;
+    user.require_auth();
 
     // Ensure user exists
     if !users::user_exists(env, &user) {
@@ -532,8 +848,14 @@ pub fn break_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), S
         .get(&group_key)
         .ok_or(SavingsError::PlanNotFound)?;
 
-    // Check that the group is not already completed
-    if group.is_completed {
+    let dispute = get_dispute(env, group_id);
+    if dispute.frozen {
+        return Err(SavingsError::GroupFrozen);
+    }
+
+    // Check that the group is not already completed, unless an upheld
+    // dispute has opened it up for refunds
+    if group.is_completed && !dispute.refunds_enabled {
         return Err(SavingsError::PlanCompleted);
     }
 
@@ -584,7 +906,9 @@ pub fn break_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), S
         .get(&contribution_key)
         .unwrap_or(0i128);
 
-    // Update group's current_amount
+    // Update group's current_amount. A member who leaves forfeits their
+    // share of the interest earned so far to the members who stay.
+    accrue_interest(env, &group)?;
     group.current_amount = group.current_amount.saturating_sub(user_contribution);
 
     // Save updated group
@@ -596,15 +920,1081 @@ pub fn break_group_save(env: &Env, user: Address, group_id: u64) -> Result<(), S
     // Remove group from user's list of groups
     remove_group_from_user_list(env, &user, group_id)?;
 
-    // Delete user's SavingsPlan for this group
-    let plan_key = DataKey::SavingsPlan(user.clone(), group_id);
-    env.storage().persistent().remove(&plan_key);
+    if user_contribution > 0 {
+        flexi::credit_flexi(env, &user, user_contribution)?;
+    }
+    history::record(
+        env,
+        &user,
+        TxKind::Break,
+        PlanKind::Group,
+        group_id,
+        user_contribution,
+        0,
+    );
 
     // Emit event for leaving group
-    env.events().publish(
-        (soroban_sdk::symbol_short!("grp_leave"), user, group_id),
-        user_contribution,
+    events::publish(
+        env,
+        events::GroupLeft {
+            user,
+            group_id,
+            contribution: user_contribution,
+        },
+    );
+
+    Ok(())
+}
+
+/// Whether a group's contributions should be returned to its members: either
+/// it ended without reaching its target, or an upheld dispute cancelled it
+pub fn is_refundable(env: &Env, group: &GroupSave) -> bool {
+    let failed = !group.is_completed && env.ledger().timestamp() >= group.end_time;
+    failed || get_dispute(env, group.id).refunds_enabled
+}
+
+/// Keeper batch: credits each member's contribution back to their Flexi Save
+/// balance, one page of the member list at a time.
+///
+/// Members stay in the list, so pages keep stable offsets, and members already
+/// refunded are skipped, so re-running a page is harmless. Returns the number
+/// of members refunded in this page.
+pub fn refund_group_members(
+    env: &Env,
+    group_id: u64,
+    offset: u32,
+    limit: u32,
+) -> Result<u32, SavingsError> {
+    guard::check_protocol(env)?;
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if get_dispute(env, group_id).frozen {
+        return Err(SavingsError::GroupFrozen);
+    }
+    if !is_refundable(env, &group) {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let members = get_group_members(env, group_id);
+    let limit = pagination::effective_limit(&Cursor { offset, limit });
+    let end = offset.saturating_add(limit).min(members.len());
+
+    let mut refunded = 0u32;
+    for i in offset..end {
+        if refund_member(env, &mut group, &members.get(i).unwrap())? > 0 {
+            refunded += 1;
+        }
+    }
+
+    mark_failed(env, group_id);
+    env.storage().persistent().set(&group_key, &group);
+    Ok(refunded)
+}
+
+/// Reclaims the member's own contribution to a failed or cancelled group into
+/// their Flexi Save balance, without waiting for the keeper. Returns the
+/// amount refunded.
+///
+/// # Errors
+/// * `PlanNotFound` - If the group does not exist
+/// * `GroupFrozen` - If a dispute on the group is pending
+/// * `TooEarly` - If the group is still running or reached its target
+/// * `InsufficientBalance` - If the member has nothing left to reclaim
+pub fn refund_group_contribution(
+    env: &Env,
+    member: Address,
+    group_id: u64,
+) -> Result<i128, SavingsError> {
+    guard::check(env, &member, Op::Manage, 0)?;
+    member.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group: GroupSave = env
+        .storage()
+        .persistent()
+        .get(&group_key)
+        .ok_or(SavingsError::PlanNotFound)?;
+
+    if get_dispute(env, group_id).frozen {
+        return Err(SavingsError::GroupFrozen);
+    }
+    if !is_refundable(env, &group) {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let amount = refund_member(env, &mut group, &member)?;
+    if amount == 0 {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    mark_failed(env, group_id);
+    env.storage().persistent().set(&group_key, &group);
+    Ok(amount)
+}
+
+/// Credits the member's contribution, and their share of the group's interest
+/// as far as the yield pool covers it, back to their Flexi Save balance and
+/// closes their plan. Returns the amount refunded, 0 if there was none.
+///
+/// The caller persists `group`.
+fn refund_member(env: &Env, group: &mut GroupSave, member: &Address) -> Result<i128, SavingsError> {
+    let group_id = group.id;
+    let contribution_key = DataKey::GroupMemberContribution(group_id, member.clone());
+    let amount: i128 = env
+        .storage()
+        .persistent()
+        .get(&contribution_key)
+        .unwrap_or(0);
+    if amount <= 0 {
+        return Ok(0);
+    }
+
+    env.storage().persistent().remove(&contribution_key);
+    accrue_interest(env, group)?;
+    let due = member_share(env, group, amount);
+    let mut state = get_interest_state(env, group);
+    state.accrued -= due;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Interest(group_id)), &state);
+    yield_pool::adjust_outstanding_interest(env, -due)?;
+    group.current_amount = group.current_amount.saturating_sub(amount);
+
+    flexi::credit_flexi(env, member, amount)?;
+    let interest = yield_pool::draw_interest(env, member, due)?;
+    if interest > 0 {
+        flexi::credit_flexi(env, member, interest)?;
+        rates::emit_interest_accrued(
+            env,
+            symbol_short!("group"),
+            member,
+            InterestAccrual {
+                plan_id: group_id,
+                principal: amount,
+                rate_bps: rates::get_group_rate(env),
+                period_seconds: env.ledger().timestamp().saturating_sub(group.start_time),
+                amount: interest,
+            },
+        );
+    }
+    history::record(
+        env,
+        member,
+        TxKind::Refund,
+        PlanKind::Group,
+        group_id,
+        amount,
+        0,
     );
+    events::publish(
+        env,
+        events::GroupRefunded {
+            member: member.clone(),
+            group_id,
+            amount,
+        },
+    );
+    Ok(amount)
+}
+
+/// Interest the group has earned on its `current_amount` at the group rate
+/// and not paid out, brought up to now
+pub fn get_accrued_interest(env: &Env, group: &GroupSave) -> i128 {
+    let state = get_interest_state(env, group);
+    let elapsed = env.ledger().timestamp().saturating_sub(state.last_accrual);
+    state
+        .accrued
+        .saturating_add(rates::calculate_flexi_interest(
+            group.current_amount,
+            rates::get_group_rate(env),
+            elapsed,
+        ))
+}
+
+/// The member's share of the group's accrued interest, in proportion to
+/// their contribution
+pub fn get_member_interest(env: &Env, group: &GroupSave, member: &Address) -> i128 {
+    member_share(env, group, get_member_contribution(env, group.id, member))
+}
+
+fn member_share(env: &Env, group: &GroupSave, contribution: i128) -> i128 {
+    if group.current_amount <= 0 || contribution <= 0 {
+        return 0;
+    }
+    get_accrued_interest(env, group).saturating_mul(contribution) / group.current_amount
+}
+
+/// Books the interest earned so far and reserves it in the yield pool's
+/// outstanding interest. Must run before every change to the group's
+/// `current_amount`.
+fn accrue_interest(env: &Env, group: &GroupSave) -> Result<(), SavingsError> {
+    let previous = get_interest_state(env, group).accrued;
+    let state = GroupInterest {
+        accrued: get_accrued_interest(env, group),
+        last_accrual: env.ledger().timestamp(),
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Interest(group.id)), &state);
+    yield_pool::adjust_outstanding_interest(env, state.accrued - previous)
+}
+
+/// Groups that never had funds move start accruing from now
+fn get_interest_state(env: &Env, group: &GroupSave) -> GroupInterest {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::Interest(group.id)))
+        .unwrap_or(GroupInterest {
+            accrued: 0,
+            last_accrual: env.ledger().timestamp(),
+        })
+}
+
+/// Records that refunds have started, so the group stays failed for good
+fn mark_failed(env: &Env, group_id: u64) {
+    let key = DataKey::Group(GroupKey::Failed(group_id));
+    if !env.storage().persistent().has(&key) {
+        env.storage().persistent().set(&key, &true);
+        events::publish(env, events::GroupFailed { group_id });
+    }
+}
+
+/// Where a group is in its lifecycle
+pub fn get_group_status(env: &Env, group: &GroupSave) -> GroupStatus {
+    let failed: bool = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::Failed(group.id)))
+        .unwrap_or(false);
+    if failed || is_refundable(env, group) {
+        GroupStatus::Failed
+    } else if group.is_completed {
+        GroupStatus::Completed
+    } else {
+        GroupStatus::Active
+    }
+}
+
+/// Places left in a group before it is full (u32::MAX if it has no cap)
+pub fn remaining_slots(group: &GroupSave) -> u32 {
+    if group.max_members == 0 {
+        return u32::MAX;
+    }
+    group.max_members.saturating_sub(group.member_count)
+}
+
+/// Raises a group's member cap. Only the creator may do so, and only before
+/// the group starts; a cap can't be lowered or added once members rely on it.
+pub fn set_max_members(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    max_members: u32,
+) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let mut group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if env.ledger().timestamp() >= group.start_time {
+        return Err(SavingsError::TooLate);
+    }
+    let raises_cap = group.max_members > 0 && (max_members == 0 || max_members > group.max_members);
+    if !raises_cap {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    group.max_members = max_members;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(group_id), &group);
+
+    events::publish(
+        env,
+        events::GroupMaxMembersSet {
+            group_id,
+            max_members,
+        },
+    );
+    Ok(())
+}
+
+/// Replaces a group's title, description, category and target before it
+/// starts. Every member's plan follows the new target.
+///
+/// # Errors
+/// * `PlanNotFound` - If the group does not exist
+/// * `Unauthorized` - If the caller is not the creator
+/// * `TooLate` - If the group has started
+/// * `InvalidAmount` - If the target is not above what was already contributed
+/// * `InvalidGroupConfig` - If a text field is empty, or the target leaves a
+///   percentage group without a valid share
+pub fn update_group_save(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    metadata: GroupMetadata,
+    target_amount: i128,
+) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let mut group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if env.ledger().timestamp() >= group.start_time {
+        return Err(SavingsError::TooLate);
+    }
+    if target_amount <= 0 || target_amount <= group.current_amount {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if metadata.title.is_empty() || metadata.description.is_empty() || metadata.category.is_empty()
+    {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if group.contribution_type == CONTRIBUTION_PERCENTAGE
+        && percentage_share(target_amount, group.contribution_amount) == 0
+    {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    if group.is_public {
+        let indexed = get_indexed_categories(env, &group);
+        index_in_category(env, group_id, indexed, &metadata.category);
+    }
+    group.title = metadata.title;
+    group.description = metadata.description;
+    group.category = metadata.category;
+    group.target_amount = target_amount;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(group_id), &group);
+
+    events::publish(
+        env,
+        events::GroupTargetUpdated {
+            group_id,
+            target_amount,
+        },
+    );
+    Ok(())
+}
+
+/// Deletes a group nobody has contributed to, along with its membership
+/// records. Nothing is refunded since nothing was paid in.
+///
+/// The public listings are append-only, so the group's entries there stay
+/// but no longer resolve to a group and are skipped.
+///
+/// # Errors
+/// * `PlanNotFound` - If the group does not exist
+/// * `Unauthorized` - If the caller is not the creator
+/// * `InvalidGroupConfig` - If the group holds any contributions
+pub fn cancel_group_save(env: &Env, creator: Address, group_id: u64) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if group.current_amount > 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    let storage = env.storage().persistent();
+    for member in get_group_members(env, group_id).iter() {
+        remove_group_from_user_list(env, &member, group_id)?;
+        storage.remove(&DataKey::GroupMemberContribution(group_id, member.clone()));
+        storage.remove(&DataKey::Group(GroupKey::MemberJoinedAt(group_id, member)));
+    }
+    storage.remove(&DataKey::GroupMembers(group_id));
+    storage.remove(&DataKey::GroupJoinRequirements(group_id));
+    storage.remove(&DataKey::Group(GroupKey::Dispute(group_id)));
+    storage.remove(&DataKey::Group(GroupKey::Announcements(group_id)));
+    storage.remove(&DataKey::Group(GroupKey::Admins(group_id)));
+    storage.remove(&DataKey::Group(GroupKey::IndexedCategories(group_id)));
+    storage.remove(&DataKey::GroupSave(group_id));
+
+    events::publish(env, events::GroupCancelled { creator, group_id });
+    Ok(())
+}
+
+fn record_member_joined(env: &Env, group_id: u64, member: &Address) {
+    env.storage().persistent().set(
+        &DataKey::Group(GroupKey::MemberJoinedAt(group_id, member.clone())),
+        &env.ledger().timestamp(),
+    );
+}
+
+/// Sets the length of a group's contribution rounds; 0 removes the schedule.
+///
+/// Only the creator may set it, and only before anyone has contributed.
+pub fn set_group_schedule(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    frequency: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let mut group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if group.current_amount > 0 {
+        return Err(SavingsError::PlanLocked);
+    }
+    if frequency > group.end_time - group.start_time {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    group.contribution_frequency = frequency;
+    env.storage()
+        .persistent()
+        .set(&DataKey::GroupSave(group_id), &group);
+
+    events::publish(
+        env,
+        events::GroupScheduleSet {
+            group_id,
+            frequency,
+        },
+    );
+    Ok(())
+}
+
+/// Number of rounds in a scheduled group (0 if it has no schedule)
+fn total_rounds(group: &GroupSave) -> u32 {
+    if group.contribution_frequency == 0 {
+        return 0;
+    }
+    (group.end_time - group.start_time).div_ceil(group.contribution_frequency) as u32
+}
+
+/// Number of rounds whose due time has passed
+fn ended_rounds(env: &Env, group: &GroupSave) -> u32 {
+    let now = env.ledger().timestamp();
+    if group.contribution_frequency == 0 || now < group.start_time {
+        return 0;
+    }
+    let elapsed = (now - group.start_time) / group.contribution_frequency;
+    (elapsed.min(u32::MAX as u64) as u32).min(total_rounds(group))
+}
+
+/// Index of the round currently open for payments, if any
+fn current_round(env: &Env, group: &GroupSave) -> Option<u32> {
+    let now = env.ledger().timestamp();
+    let round = ended_rounds(env, group);
+    if now < group.start_time || round >= total_rounds(group) {
+        return None;
+    }
+    Some(round)
+}
+
+fn round_window(group: &GroupSave, round: u32) -> (u64, u64) {
+    let start = group.start_time + round as u64 * group.contribution_frequency;
+    let due = (start + group.contribution_frequency).min(group.end_time);
+    (start, due)
+}
+
+/// Amount each member owes per round under the group's contribution type
+fn round_required_amount(group: &GroupSave) -> i128 {
+    if group.contribution_type == CONTRIBUTION_PERCENTAGE {
+        percentage_share(group.target_amount, group.contribution_amount)
+    } else {
+        group.contribution_amount
+    }
+}
+
+/// Whether `member` owed and failed to pay in full for `round`
+fn missed_round(env: &Env, group: &GroupSave, round: u32, member: &Address) -> bool {
+    let (_, due) = round_window(group, round);
+    let joined_at: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::MemberJoinedAt(
+            group.id,
+            member.clone(),
+        )))
+        .unwrap_or(0);
+    if joined_at >= due {
+        return false;
+    }
+    let paid: i128 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::RoundPaid(
+            group.id,
+            round,
+            member.clone(),
+        )))
+        .unwrap_or(0);
+    paid < round_required_amount(group)
+}
+
+/// VIEW FUNCTION - Payment status of the open round, or of the last round once
+/// the schedule has run out
+pub fn get_round_status(env: &Env, group_id: u64) -> Result<GroupRoundStatus, SavingsError> {
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    let total = total_rounds(&group);
+    if total == 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    let round = ended_rounds(env, &group).min(total - 1);
+    let (round_start, due_time) = round_window(&group, round);
+    let required_amount = round_required_amount(&group);
+
+    let mut paid = Vec::new(env);
+    let mut unpaid = Vec::new(env);
+    for member in get_group_members(env, group_id).iter() {
+        let amount: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Group(GroupKey::RoundPaid(
+                group_id,
+                round,
+                member.clone(),
+            )))
+            .unwrap_or(0);
+        if amount >= required_amount {
+            paid.push_back(member);
+        } else {
+            unpaid.push_back(member);
+        }
+    }
+
+    Ok(GroupRoundStatus {
+        round,
+        total_rounds: total,
+        round_start,
+        due_time,
+        required_amount,
+        paid,
+        unpaid,
+    })
+}
+
+/// VIEW FUNCTION - Open rounds of `user`'s active groups that fall due within
+/// `within` seconds and that the user has not yet paid in full
+pub fn get_groups_with_due_contributions(
+    env: &Env,
+    user: &Address,
+    within: u64,
+) -> Vec<DueGroupContribution> {
+    let horizon = env.ledger().timestamp().saturating_add(within);
+    let mut due = Vec::new(env);
+    for group_id in get_user_groups(env, user).iter() {
+        let Some(group) = get_group_save(env, group_id) else {
+            continue;
+        };
+        if get_group_status(env, &group) != GroupStatus::Active {
+            continue;
+        }
+        let Some(round) = current_round(env, &group) else {
+            continue;
+        };
+        let (_, due_time) = round_window(&group, round);
+        if due_time > horizon {
+            continue;
+        }
+        let paid: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Group(GroupKey::RoundPaid(
+                group_id,
+                round,
+                user.clone(),
+            )))
+            .unwrap_or(0);
+        let amount_owed = round_required_amount(&group) - paid;
+        if amount_owed > 0 {
+            due.push_back(DueGroupContribution {
+                group_id,
+                round,
+                due_time,
+                amount_owed,
+            });
+        }
+    }
+    due
+}
+
+/// VIEW FUNCTION - Indices of the ended rounds `member` did not pay in full
+pub fn get_member_missed_rounds(
+    env: &Env,
+    group_id: u64,
+    member: &Address,
+) -> Result<Vec<u32>, SavingsError> {
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    let mut missed = Vec::new(env);
+    for round in 0..ended_rounds(env, &group) {
+        if missed_round(env, &group, round, member) {
+            missed.push_back(round);
+        }
+    }
+    Ok(missed)
+}
+
+/// Keeper entrypoint: closes every ended round not yet closed, emitting a
+/// `grp_dflt` event listing the defaulters of each round that had any.
+/// Returns the number of rounds closed.
+pub fn close_group_rounds(env: &Env, group_id: u64) -> Result<u32, SavingsError> {
+    guard::check_protocol(env)?;
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    let closed_key = DataKey::Group(GroupKey::ClosedRounds(group_id));
+    let closed: u32 = env.storage().persistent().get(&closed_key).unwrap_or(0);
+    let ended = ended_rounds(env, &group);
+    if ended <= closed {
+        return Ok(0);
+    }
+
+    let members = get_group_members(env, group_id);
+    for round in closed..ended {
+        let mut defaulters = Vec::new(env);
+        for member in members.iter() {
+            if missed_round(env, &group, round, &member) {
+                defaulters.push_back(member);
+            }
+        }
+        if !defaulters.is_empty() {
+            events::publish(
+                env,
+                events::GroupDefaulters {
+                    group_id,
+                    round,
+                    defaulters,
+                },
+            );
+        }
+    }
+
+    env.storage().persistent().set(&closed_key, &ended);
+    Ok(ended - closed)
+}
+
+/// Number of announcements kept per group; older ones are dropped
+pub const MAX_GROUP_ANNOUNCEMENTS: u32 = 10;
+
+/// Longest announcement accepted, in bytes
+pub const MAX_ANNOUNCEMENT_LEN: u32 = 280;
+
+/// Posts a message from the creator or a group admin to the group, keeping
+/// only the latest `MAX_GROUP_ANNOUNCEMENTS`
+pub fn post_group_announcement(
+    env: &Env,
+    caller: Address,
+    group_id: u64,
+    message: String,
+) -> Result<(), SavingsError> {
+    guard::check(env, &caller, Op::Manage, 0)?;
+    caller.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if !is_group_manager(env, &group, &caller) {
+        return Err(SavingsError::Unauthorized);
+    }
+    if message.is_empty() || message.len() > MAX_ANNOUNCEMENT_LEN {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    let mut announcements = get_group_announcements(env, group_id);
+    if announcements.len() >= MAX_GROUP_ANNOUNCEMENTS {
+        announcements.pop_front();
+    }
+    announcements.push_back(GroupAnnouncement {
+        message: message.clone(),
+        timestamp: env.ledger().timestamp(),
+    });
+    env.storage().persistent().set(
+        &DataKey::Group(GroupKey::Announcements(group_id)),
+        &announcements,
+    );
+
+    events::publish(env, events::GroupAnnounced { group_id, message });
+    Ok(())
+}
+
+/// Most co-admins a group can have besides its creator
+pub const MAX_GROUP_ADMINS: u32 = 5;
+
+/// Makes a member a co-admin, allowed to invite, remove members and post
+/// announcements. Only the creator may appoint admins.
+///
+/// # Errors
+/// * `Unauthorized` - If the caller is not the creator
+/// * `NotGroupMember` - If `admin` is not a member
+/// * `InvalidGroupConfig` - If `admin` is the creator or already an admin
+/// * `MaxPlansExceeded` - If the group has `MAX_GROUP_ADMINS` admins
+pub fn add_group_admin(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    admin: Address,
+) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    if !get_group_members(env, group_id).contains(&admin) {
+        return Err(SavingsError::NotGroupMember);
+    }
+    let mut admins = get_group_admins(env, group_id);
+    if admin == creator || admins.contains(&admin) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    if admins.len() >= MAX_GROUP_ADMINS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+
+    admins.push_back(admin.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Admins(group_id)), &admins);
+
+    events::publish(
+        env,
+        events::GroupAdminSet {
+            group_id,
+            admin,
+            is_admin: true,
+        },
+    );
+    Ok(())
+}
+
+/// Revokes a co-admin; creator only
+pub fn remove_group_admin(
+    env: &Env,
+    creator: Address,
+    group_id: u64,
+    admin: Address,
+) -> Result<(), SavingsError> {
+    guard::check(env, &creator, Op::Manage, 0)?;
+    creator.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if group.creator != creator {
+        return Err(SavingsError::Unauthorized);
+    }
+    let mut admins = get_group_admins(env, group_id);
+    let index = admins
+        .first_index_of(&admin)
+        .ok_or(SavingsError::InvalidGroupConfig)?;
+    admins.remove(index);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Admins(group_id)), &admins);
+
+    events::publish(
+        env,
+        events::GroupAdminSet {
+            group_id,
+            admin,
+            is_admin: false,
+        },
+    );
+    Ok(())
+}
+
+/// VIEW FUNCTION - Co-admins of a group, in appointment order
+pub fn get_group_admins(env: &Env, group_id: u64) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::Admins(group_id)))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Whether `addr` is the group's creator or one of its admins
+pub fn is_group_manager(env: &Env, group: &GroupSave, addr: &Address) -> bool {
+    group.creator == *addr || get_group_admins(env, group.id).contains(addr)
+}
+
+/// Lets `user` join the group with `join_group_save`, even if it is private.
+/// The invitation is used up on joining.
+///
+/// # Errors
+/// * `Unauthorized` - If the caller is not the creator or an admin
+/// * `InvalidGroupConfig` - If `user` is already a member
+pub fn invite_to_group(
+    env: &Env,
+    caller: Address,
+    group_id: u64,
+    user: Address,
+) -> Result<(), SavingsError> {
+    guard::check(env, &caller, Op::Manage, 0)?;
+    caller.require_auth();
+
+    let group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if !is_group_manager(env, &group, &caller) {
+        return Err(SavingsError::Unauthorized);
+    }
+    if get_group_members(env, group_id).contains(&user) {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+
+    env.storage().persistent().set(
+        &DataKey::Group(GroupKey::Invited(group_id, user.clone())),
+        &true,
+    );
+    events::publish(
+        env,
+        events::GroupInvited {
+            group_id,
+            user,
+            inviter: caller,
+        },
+    );
+    Ok(())
+}
+
+/// Removes a member from the group, crediting whatever they contributed back
+/// to their Flexi Save balance. Returns the amount refunded.
+///
+/// As when leaving, the member's share of the interest stays with the group.
+/// The creator cannot be removed, and admins can only be removed by the
+/// creator once demoted.
+///
+/// # Errors
+/// * `Unauthorized` - If the caller is not the creator or an admin, or the
+///   member is the creator or an admin
+/// * `GroupFrozen` - If a dispute on the group is pending
+/// * `PlanCompleted` - If the group already reached its target
+/// * `NotGroupMember` - If `member` is not in the group
+pub fn remove_group_member(
+    env: &Env,
+    caller: Address,
+    group_id: u64,
+    member: Address,
+) -> Result<i128, SavingsError> {
+    guard::check(env, &caller, Op::Manage, 0)?;
+    caller.require_auth();
+
+    let group_key = DataKey::GroupSave(group_id);
+    let mut group = get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    if !is_group_manager(env, &group, &caller) || is_group_manager(env, &group, &member) {
+        return Err(SavingsError::Unauthorized);
+    }
+    if get_dispute(env, group_id).frozen {
+        return Err(SavingsError::GroupFrozen);
+    }
+    if group.is_completed {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let members_key = DataKey::GroupMembers(group_id);
+    let mut members = get_group_members(env, group_id);
+    let index = members
+        .first_index_of(&member)
+        .ok_or(SavingsError::NotGroupMember)?;
+    members.remove(index);
+    env.storage().persistent().set(&members_key, &members);
+
+    let contribution_key = DataKey::GroupMemberContribution(group_id, member.clone());
+    let refunded = get_member_contribution(env, group_id, &member);
+    accrue_interest(env, &group)?;
+    group.current_amount = group.current_amount.saturating_sub(refunded);
+    group.member_count = group.member_count.saturating_sub(1);
+    env.storage().persistent().set(&group_key, &group);
+    if refunded > 0 {
+        flexi::credit_flexi(env, &member, refunded)?;
+        history::record(
+            env,
+            &member,
+            TxKind::Refund,
+            PlanKind::Group,
+            group_id,
+            refunded,
+            0,
+        );
+    }
+
+    remove_group_from_user_list(env, &member, group_id)?;
+    let storage = env.storage().persistent();
+    storage.remove(&contribution_key);
+    storage.remove(&DataKey::Group(GroupKey::MemberJoinedAt(
+        group_id,
+        member.clone(),
+    )));
+
+    events::publish(
+        env,
+        events::GroupMemberRemoved {
+            group_id,
+            member,
+            refunded,
+        },
+    );
+    Ok(refunded)
+}
+
+/// VIEW FUNCTION - Latest announcements of a group, oldest first
+pub fn get_group_announcements(env: &Env, group_id: u64) -> Vec<GroupAnnouncement> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::Announcements(group_id)))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Shortest group window allowed when no bounds are configured
+pub const DEFAULT_MIN_GROUP_DURATION: u64 = 0;
+
+/// Furthest ahead a group may end when no bounds are configured (2 years)
+pub const DEFAULT_MAX_GROUP_HORIZON: u64 = 2 * 365 * 24 * 60 * 60;
+
+pub fn get_duration_bounds(env: &Env) -> GroupDurationBounds {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::GroupDurationBounds))
+        .unwrap_or(GroupDurationBounds {
+            min_duration: DEFAULT_MIN_GROUP_DURATION,
+            max_horizon: DEFAULT_MAX_GROUP_HORIZON,
+        })
+}
+
+pub fn set_duration_bounds(env: &Env, bounds: GroupDurationBounds) -> Result<(), SavingsError> {
+    if bounds.max_horizon == 0 || bounds.min_duration > bounds.max_horizon {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::GroupDurationBounds), &bounds);
+    Ok(())
+}
+
+/// Number of distinct flaggers that freezes a group when no threshold is configured
+pub const DEFAULT_DISPUTE_THRESHOLD: u32 = 2;
+
+pub fn get_dispute_threshold(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::DisputeThreshold))
+        .unwrap_or(DEFAULT_DISPUTE_THRESHOLD)
+}
+
+pub fn set_dispute_threshold(env: &Env, threshold: u32) -> Result<(), SavingsError> {
+    if threshold == 0 {
+        return Err(SavingsError::InvalidGroupConfig);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::DisputeThreshold), &threshold);
+    Ok(())
+}
+
+/// VIEW FUNCTION - Gets the dispute state of a group (empty if never flagged)
+pub fn get_dispute(env: &Env, group_id: u64) -> GroupDispute {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Group(GroupKey::Dispute(group_id)))
+        .unwrap_or_else(|| GroupDispute {
+            flaggers: Vec::new(env),
+            reason_codes: Vec::new(env),
+            frozen: false,
+            refunds_enabled: false,
+        })
+}
+
+/// Flags a group as disputed on behalf of one of its members.
+///
+/// Once the number of distinct flaggers reaches the dispute threshold, the
+/// group is frozen: no funds can leave it until an admin resolves the dispute.
+///
+/// # Errors
+/// * `PlanNotFound` - If the group doesn't exist
+/// * `NotGroupMember` - If `member` is not in the group
+/// * `AlreadyFlagged` - If `member` has already flagged the current dispute
+pub fn flag_group_dispute(
+    env: &Env,
+    member: Address,
+    group_id: u64,
+    reason_code: u32,
+) -> Result<(), SavingsError> {
+    guard::check(env, &member, Op::Manage, 0)?;
+    member.require_auth();
+
+    if !group_exists(env, group_id) {
+        return Err(SavingsError::PlanNotFound);
+    }
+    if !get_group_members(env, group_id).contains(&member) {
+        return Err(SavingsError::NotGroupMember);
+    }
+
+    let mut dispute = get_dispute(env, group_id);
+    if dispute.flaggers.contains(&member) {
+        return Err(SavingsError::AlreadyFlagged);
+    }
+
+    dispute.flaggers.push_back(member.clone());
+    dispute.reason_codes.push_back(reason_code);
+
+    events::publish(
+        env,
+        events::GroupFlagged {
+            member,
+            group_id,
+            reason_code,
+        },
+    );
+
+    if !dispute.frozen && dispute.flaggers.len() >= get_dispute_threshold(env) {
+        dispute.frozen = true;
+        events::publish(
+            env,
+            events::GroupFrozen {
+                group_id,
+                flaggers: dispute.flaggers.len(),
+            },
+        );
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Dispute(group_id)), &dispute);
+
+    Ok(())
+}
+
+/// Settles a group's dispute, unfreezing it and clearing the flags.
+///
+/// An `Upheld` outcome additionally lets members leave with a refund even if
+/// the group has completed.
+pub fn resolve_dispute(
+    env: &Env,
+    group_id: u64,
+    outcome: DisputeOutcome,
+) -> Result<(), SavingsError> {
+    if !group_exists(env, group_id) {
+        return Err(SavingsError::PlanNotFound);
+    }
+
+    let previous = get_dispute(env, group_id);
+    let dispute = GroupDispute {
+        flaggers: Vec::new(env),
+        reason_codes: Vec::new(env),
+        frozen: false,
+        refunds_enabled: previous.refunds_enabled || outcome == DisputeOutcome::Upheld,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::Group(GroupKey::Dispute(group_id)), &dispute);
+
+    events::publish(env, events::GroupDisputeResolved { group_id, outcome });
 
     Ok(())
 }
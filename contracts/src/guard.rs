@@ -0,0 +1,103 @@
+use soroban_sdk::{Address, Env};
+
+use crate::compliance;
+use crate::custody;
+use crate::errors::SavingsError;
+use crate::limits;
+use crate::reentrancy;
+use crate::storage_types::DataKey;
+
+/// Kind of state change a guarded call performs
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Op {
+    /// Adds funds to an existing balance or plan
+    Deposit,
+    /// Moves funds out of a balance or plan
+    Withdraw,
+    /// Opens a new plan, optionally with an initial deposit
+    CreatePlan,
+    /// Changes plan or account settings without moving funds
+    Manage,
+}
+
+/// Single safety gate for every state-changing entrypoint.
+///
+/// Every reentrancy, pause, freeze, limit, custody and compliance check lives
+/// here so that a new code path only has to call `check` to get all of them.
+/// `amount` is the value moved by the operation (0 for `Manage`).
+pub fn check(env: &Env, user: &Address, op: Op, amount: i128) -> Result<(), SavingsError> {
+    reentrancy::ensure_not_entered(env)?;
+    ensure_not_paused(env)?;
+    match op {
+        Op::Withdraw => {
+            custody::require_guardian_consent(env, user);
+            limits::consume_withdraw_allowance(env, user, amount)?
+        }
+        Op::Deposit | Op::CreatePlan => {
+            limits::ensure_minimum_deposit(env, amount)?;
+            compliance::ensure_allowed(env, user, amount)?
+        }
+        Op::Manage => {}
+    }
+    Ok(())
+}
+
+/// Protocol-level check for calls that act on no particular user, such as
+/// keeper rebalancing
+pub fn check_protocol(env: &Env) -> Result<(), SavingsError> {
+    reentrancy::ensure_not_entered(env)?;
+    ensure_not_paused(env)
+}
+
+fn ensure_not_paused(env: &Env) -> Result<(), SavingsError> {
+    let is_paused: bool = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Paused)
+        .unwrap_or(false);
+    if is_paused {
+        Err(SavingsError::ContractPaused)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::{testutils::Address as _, BytesN};
+
+    #[test]
+    fn test_check_blocks_every_op_while_paused() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(check(&env, &user, Op::Deposit, 100), Ok(()));
+            assert_eq!(check_protocol(&env), Ok(()));
+        });
+
+        client.pause(&admin);
+        env.as_contract(&contract_id, || {
+            for op in [Op::Deposit, Op::Withdraw, Op::CreatePlan, Op::Manage] {
+                assert_eq!(
+                    check(&env, &user, op, 100),
+                    Err(SavingsError::ContractPaused)
+                );
+            }
+            assert_eq!(check_protocol(&env), Err(SavingsError::ContractPaused));
+        });
+    }
+}
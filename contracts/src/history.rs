@@ -0,0 +1,228 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::pagination;
+use crate::storage_types::{Cursor, DataKey, PlanKind, TxKind, TxRecord, UserKey};
+
+/// Number of records kept per user; older ones are dropped as new ones land
+pub const MAX_TX_HISTORY: u32 = 50;
+
+/// Appends a record to the user's history, dropping the oldest once it
+/// holds `MAX_TX_HISTORY`. Sequence numbers keep counting across drops.
+///
+/// Every path that moves funds into, out of or between a user's plans must
+/// call this once per plan it touches.
+pub(crate) fn record(
+    env: &Env,
+    user: &Address,
+    kind: TxKind,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    amount: i128,
+    fee: i128,
+) {
+    let key = DataKey::UserState(UserKey::TxHistory(user.clone()));
+    let mut history: Vec<TxRecord> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(env));
+
+    let seq = history.last().map_or(1, |last| last.seq + 1);
+    history.push_back(TxRecord {
+        seq,
+        kind,
+        plan_kind,
+        plan_id,
+        amount,
+        fee,
+        timestamp: env.ledger().timestamp(),
+    });
+    while history.len() > MAX_TX_HISTORY {
+        history.pop_front();
+    }
+    env.storage().persistent().set(&key, &history);
+}
+
+/// Records a plan changing hands in both users' histories
+pub(crate) fn record_transfer(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    amount: i128,
+) {
+    record(
+        env,
+        from,
+        TxKind::TransferOut,
+        plan_kind,
+        plan_id,
+        amount,
+        0,
+    );
+    record(env, to, TxKind::TransferIn, plan_kind, plan_id, amount, 0);
+}
+
+/// The user's most recent record for the given plan, if still kept
+pub fn last_record(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+) -> Option<TxRecord> {
+    let history: Vec<TxRecord> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::TxHistory(user.clone())))
+        .unwrap_or_else(|| Vec::new(env));
+    history
+        .iter()
+        .rev()
+        .find(|record| record.plan_kind == plan_kind && record.plan_id == plan_id)
+}
+
+/// A page of the user's history, newest first
+pub fn get_user_history(
+    env: &Env,
+    user: &Address,
+    cursor: Cursor,
+) -> (Vec<TxRecord>, Option<Cursor>) {
+    let history: Vec<TxRecord> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::TxHistory(user.clone())))
+        .unwrap_or_else(|| Vec::new(env));
+
+    let mut newest_first = Vec::new(env);
+    for record in history.iter().rev() {
+        newest_first.push_back(record);
+    }
+    pagination::paginate(env, &newest_first, &cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_TX_HISTORY;
+    use crate::storage_types::{Cursor, PlanKind, TxKind};
+    use crate::{NesteraContract, NesteraContractClient, WithdrawalFeeConfig};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, Symbol,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        (env, client, admin)
+    }
+
+    fn page(offset: u32, limit: u32) -> Cursor {
+        Cursor { offset, limit }
+    }
+
+    #[test]
+    fn test_history_records_every_flow_newest_first() {
+        let (env, client, admin) = setup();
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 0 });
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+        client.deposit_flexi(&user, &1_000);
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "car"), &5_000, &200);
+        client.withdraw_flexi(&user, &500);
+        client.break_goal_save(&user, &goal_id);
+
+        let (records, next) = client.get_user_history(&user, &page(0, 10));
+        assert_eq!(next, None);
+        assert_eq!(records.len(), 4);
+
+        let broke = records.get(0).unwrap();
+        assert_eq!(broke.seq, 4);
+        assert_eq!(broke.kind, TxKind::Break);
+        assert_eq!((broke.plan_kind, broke.plan_id), (PlanKind::Goal, goal_id));
+        assert_eq!(broke.amount, 200);
+
+        let withdrew = records.get(1).unwrap();
+        assert_eq!(withdrew.kind, TxKind::Withdraw);
+        assert_eq!((withdrew.amount, withdrew.fee), (500, 5));
+
+        let created = records.get(2).unwrap();
+        assert_eq!(created.kind, TxKind::Deposit);
+        assert_eq!(
+            (created.plan_kind, created.plan_id),
+            (PlanKind::Goal, goal_id)
+        );
+
+        let first = records.get(3).unwrap();
+        assert_eq!((first.seq, first.kind), (1, TxKind::Deposit));
+        assert_eq!((first.plan_kind, first.amount), (PlanKind::Flexi, 1_000));
+        assert_eq!(first.timestamp, 1_000);
+    }
+
+    #[test]
+    fn test_history_records_conversions_and_transfers() {
+        let (env, client, _admin) = setup();
+        let user = Address::generate(&env);
+        let friend = Address::generate(&env);
+        client.initialize_user(&user);
+        client.initialize_user(&friend);
+
+        client.deposit_flexi(&user, &1_000);
+        let lock_id = client.convert_flexi_to_lock(&user, &400, &86_400);
+        client.transfer_lock_save(&user, &friend, &lock_id);
+
+        let (records, _) = client.get_user_history(&user, &page(0, 10));
+        let kinds: soroban_sdk::Vec<TxKind> =
+            soroban_sdk::Vec::from_iter(&env, records.iter().map(|r| r.kind));
+        assert_eq!(
+            kinds,
+            soroban_sdk::vec![
+                &env,
+                TxKind::TransferOut,
+                TxKind::Deposit,
+                TxKind::Convert,
+                TxKind::Deposit
+            ]
+        );
+        assert_eq!(records.get(2).unwrap().plan_kind, PlanKind::Flexi);
+
+        let (records, _) = client.get_user_history(&friend, &page(0, 10));
+        let received = records.get(0).unwrap();
+        assert_eq!(received.kind, TxKind::TransferIn);
+        assert_eq!((received.plan_id, received.amount), (lock_id, 400));
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_paged() {
+        let (env, client, _admin) = setup();
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+
+        for amount in 1..=(MAX_TX_HISTORY as i128 + 5) {
+            client.deposit_flexi(&user, &amount);
+        }
+
+        let (records, next) = client.get_user_history(&user, &page(0, 20));
+        assert_eq!(records.len(), 20);
+        assert_eq!(records.get(0).unwrap().seq, MAX_TX_HISTORY as u64 + 5);
+        assert_eq!(next, Some(page(20, 20)));
+
+        let (records, next) = client.get_user_history(&user, &page(40, 20));
+        assert_eq!(records.len(), 10);
+        assert_eq!(next, None);
+        // The five oldest records were dropped
+        assert_eq!(records.last().unwrap().seq, 6);
+    }
+}
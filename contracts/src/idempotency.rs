@@ -0,0 +1,92 @@
+use soroban_sdk::{Address, BytesN, Env};
+
+use crate::errors::SavingsError;
+use crate::storage_types::{DataKey, UserKey};
+use crate::ttl::DAY_IN_LEDGERS;
+
+/// Ledgers a consumed key is remembered for; a retry arriving later than
+/// this is treated as a new operation
+pub const IDEMPOTENCY_KEY_TTL: u32 = DAY_IN_LEDGERS;
+
+/// Marks `key` as consumed by `user`, so the operation it guards runs at most
+/// once. Keys are scoped per user.
+///
+/// Call this before the guarded operation: if the operation fails the whole
+/// invocation reverts and the key stays unused, so the client may retry it.
+///
+/// # Errors
+/// * `DuplicateRequest` - If the user already consumed `key`
+pub fn consume(env: &Env, user: &Address, key: BytesN<32>) -> Result<(), SavingsError> {
+    let storage_key = DataKey::UserState(UserKey::IdempotencyKey(user.clone(), key));
+    if env.storage().temporary().has(&storage_key) {
+        return Err(SavingsError::DuplicateRequest);
+    }
+    env.storage().temporary().set(&storage_key, &true);
+    env.storage()
+        .temporary()
+        .extend_ttl(&storage_key, IDEMPOTENCY_KEY_TTL, IDEMPOTENCY_KEY_TTL);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Symbol};
+
+    #[test]
+    fn test_retried_deposit_is_applied_once() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        let other = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        client.initialize_user(&other);
+
+        let key = BytesN::from_array(&env, &[7u8; 32]);
+        client.deposit_flexi_idempotent(&user, &500, &key);
+        assert_eq!(
+            client.try_deposit_flexi_idempotent(&user, &500, &key),
+            Err(Ok(SavingsError::DuplicateRequest))
+        );
+        assert_eq!(client.get_flexi_balance(&user), 500);
+
+        // Keys are per user, and any operation consumes them
+        client.deposit_flexi_idempotent(&other, &500, &key);
+        assert_eq!(
+            client.try_create_goal_save_idempotent(
+                &user,
+                &Symbol::new(&env, "car"),
+                &5_000,
+                &100,
+                &key
+            ),
+            Err(Ok(SavingsError::DuplicateRequest))
+        );
+    }
+
+    #[test]
+    fn test_failed_operation_leaves_key_unused() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+
+        let key = BytesN::from_array(&env, &[9u8; 32]);
+        assert_eq!(
+            client.try_deposit_to_goal_save_idempotent(&user, &42, &100, &key),
+            Err(Ok(SavingsError::PlanNotFound))
+        );
+        let goal_id = client.create_goal_save_idempotent(
+            &user,
+            &Symbol::new(&env, "car"),
+            &5_000,
+            &100,
+            &key,
+        );
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 100);
+    }
+}
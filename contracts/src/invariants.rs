@@ -0,0 +1,172 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::buckets;
+use crate::errors::SavingsError;
+use crate::flexi;
+use crate::group;
+use crate::lock;
+use crate::storage_types::{DataKey, InvariantReport, PlanType, SavingsPlan, User, MAX_PAGE_LIMIT};
+
+/// VIEW FUNCTION - Checks that the user's recorded total matches their Flexi,
+/// Lock and not yet migrated legacy plan balances, that no withdrawn legacy
+/// plan still holds a balance, that each of their groups' totals matches its
+/// members' contributions, and that their Flexi buckets fit within their
+/// Flexi balance.
+///
+/// Goal and group funds are tracked on their plans and are not part of
+/// `total_balance`.
+///
+/// # Errors
+/// * `UserNotFound` - If the user is not initialized
+pub fn check_invariants(env: &Env, user: Address) -> Result<InvariantReport, SavingsError> {
+    let user_data: User = env
+        .storage()
+        .persistent()
+        .get(&DataKey::User(user.clone()))
+        .ok_or(SavingsError::UserNotFound)?;
+
+    let mut computed_total = flexi::get_flexi_balance(env, user.clone())?;
+    for lock_id in lock::get_user_lock_saves(env, &user).iter() {
+        if let Some(lock_save) = lock::get_lock_save(env, lock_id) {
+            if !lock_save.is_withdrawn {
+                computed_total = computed_total.saturating_add(lock_save.amount);
+            }
+        }
+    }
+
+    let mut withdrawn_with_balance = Vec::new(env);
+    for plan_id in 1..=user_data.savings_count as u64 {
+        let Some(plan) = get_plan(env, &user, plan_id) else {
+            continue;
+        };
+        if plan.is_withdrawn && plan.balance != 0 {
+            withdrawn_with_balance.push_back(plan_id);
+        }
+        // Group plans written before migration mirror a contribution held by
+        // the group
+        if !plan.is_withdrawn && !matches!(plan.plan_type, PlanType::Group(..)) {
+            computed_total = computed_total.saturating_add(plan.balance);
+        }
+    }
+
+    let mut group_mismatches = Vec::new(env);
+    for group_id in group::get_user_groups(env, &user).iter() {
+        let Some(group_save) = group::get_group_save(env, group_id) else {
+            continue;
+        };
+        let contributed =
+            group::get_group_members(env, group_id)
+                .iter()
+                .fold(0i128, |sum, member| {
+                    sum.saturating_add(group::get_member_contribution(env, group_id, &member))
+                });
+        if contributed != group_save.current_amount {
+            group_mismatches.push_back(group_id);
+        }
+    }
+
+    let bucketed_total = buckets::get_bucketed_total(env, &user);
+    let ok = user_data.total_balance == computed_total
+        && withdrawn_with_balance.is_empty()
+        && group_mismatches.is_empty()
+        && bucketed_total <= flexi::get_flexi_balance(env, user.clone())?;
+    Ok(InvariantReport {
+        user,
+        recorded_total: user_data.total_balance,
+        computed_total,
+        withdrawn_with_balance,
+        group_mismatches,
+        bucketed_total,
+        ok,
+    })
+}
+
+/// VIEW FUNCTION - Runs `check_invariants` for a sample of addresses and
+/// returns the reports of those failing a check. Unknown addresses are
+/// skipped.
+///
+/// # Errors
+/// * `AmountExceedsLimit` - If more than `MAX_PAGE_LIMIT` addresses are given
+pub fn check_invariants_sample(
+    env: &Env,
+    users: Vec<Address>,
+) -> Result<Vec<InvariantReport>, SavingsError> {
+    if users.len() > MAX_PAGE_LIMIT {
+        return Err(SavingsError::AmountExceedsLimit);
+    }
+
+    let mut failing = Vec::new(env);
+    for user in users.iter() {
+        match check_invariants(env, user) {
+            Ok(report) if !report.ok => failing.push_back(report),
+            Ok(_) | Err(SavingsError::UserNotFound) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(failing)
+}
+
+fn get_plan(env: &Env, user: &Address, plan_id: u64) -> Option<SavingsPlan> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SavingsPlan(user.clone(), plan_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::{DataKey, User};
+    use crate::{GroupMetadata, NesteraContract, NesteraContractClient};
+    use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
+
+    #[test]
+    fn test_invariants_hold_then_flag_drift() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        let other = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        client.initialize_user(&other);
+
+        client.deposit_flexi(&user, &1_000);
+        client.create_lock_save(&user, &400, &86_400);
+        let group_id = client.create_group_save(
+            &user,
+            &GroupMetadata {
+                title: String::from_str(&env, "Club"),
+                description: String::from_str(&env, "Saving club"),
+                category: String::from_str(&env, "savings"),
+            },
+            &10_000,
+            &1,
+            &100,
+            &true,
+            &0,
+            &1_000,
+            &0,
+        );
+        client.contribute_to_group_save(&user, &group_id, &300);
+
+        let report = client.check_invariants(&user);
+        assert!(report.ok);
+        assert_eq!(report.computed_total, 1_400);
+        assert_eq!(
+            client.check_invariants_sample(&vec![&env, user.clone(), other.clone()]),
+            vec![&env]
+        );
+
+        env.as_contract(&contract_id, || {
+            let key = DataKey::User(user.clone());
+            let mut user_data: User = env.storage().persistent().get(&key).unwrap();
+            user_data.total_balance += 5;
+            env.storage().persistent().set(&key, &user_data);
+        });
+        let failing = client.check_invariants_sample(&vec![&env, user.clone(), other]);
+        assert_eq!(failing.len(), 1);
+        let report = failing.get(0).unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.recorded_total - report.computed_total, 5);
+        assert!(report.group_mismatches.is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::achievements;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::guard::{self, Op};
+use crate::storage_types::{
+    ConfigKey, DataKey, LeaderboardEntry, LeaderboardKind, UserKey, UserStreak,
+};
+
+/// Number of entries kept on each leaderboard
+pub const LEADERBOARD_SIZE: u32 = 10;
+
+/// Both scores only ever grow, so updating a board on the saver's own
+/// deposits keeps it exact without rescanning every user.
+pub(crate) fn on_deposit(env: &Env, user: &Address, streak: &UserStreak) {
+    if is_opted_out(env, user) {
+        return;
+    }
+    submit(
+        env,
+        LeaderboardKind::TotalSaved,
+        user,
+        achievements::get_total_deposited(env, user),
+    );
+    submit(
+        env,
+        LeaderboardKind::LongestStreak,
+        user,
+        streak.longest_streak as i128,
+    );
+}
+
+/// Places the user on the board with the given score, if it ranks within
+/// `LEADERBOARD_SIZE`. Ties keep whoever reached the score first ahead.
+fn submit(env: &Env, kind: LeaderboardKind, user: &Address, score: i128) {
+    let mut board = get_leaderboard(env, kind);
+    if let Some(index) = board.iter().position(|entry| entry.user == *user) {
+        if board.get_unchecked(index as u32).score == score {
+            return;
+        }
+        board.remove(index as u32);
+    }
+
+    let position = board
+        .iter()
+        .position(|entry| score > entry.score)
+        .unwrap_or(board.len() as usize) as u32;
+    if position >= LEADERBOARD_SIZE {
+        return;
+    }
+    board.insert(
+        position,
+        LeaderboardEntry {
+            user: user.clone(),
+            score,
+        },
+    );
+    while board.len() > LEADERBOARD_SIZE {
+        board.pop_back();
+    }
+    set_leaderboard(env, kind, &board);
+}
+
+/// Hides the user from every leaderboard, or lets their next deposit put
+/// them back on.
+pub fn set_leaderboard_opt_out(
+    env: &Env,
+    user: Address,
+    opted_out: bool,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let key = DataKey::UserState(UserKey::LeaderboardOptOut(user.clone()));
+    if opted_out {
+        env.storage().persistent().set(&key, &true);
+        remove_user(env, &user);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+
+    events::publish(env, events::LeaderboardOptOutSet { user, opted_out });
+    Ok(())
+}
+
+/// Takes the user's entries off every leaderboard
+pub(crate) fn remove_user(env: &Env, user: &Address) {
+    for kind in [LeaderboardKind::TotalSaved, LeaderboardKind::LongestStreak] {
+        let mut board = get_leaderboard(env, kind);
+        if let Some(index) = board.iter().position(|entry| entry.user == *user) {
+            board.remove(index as u32);
+            set_leaderboard(env, kind, &board);
+        }
+    }
+}
+
+pub fn is_opted_out(env: &Env, user: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::LeaderboardOptOut(
+            user.clone(),
+        )))
+        .unwrap_or(false)
+}
+
+/// Entries of a leaderboard, best first
+pub fn get_leaderboard(env: &Env, kind: LeaderboardKind) -> Vec<LeaderboardEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::Leaderboard(kind)))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn set_leaderboard(env: &Env, kind: LeaderboardKind, board: &Vec<LeaderboardEntry>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::Leaderboard(kind)), board);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LEADERBOARD_SIZE;
+    use crate::storage_types::LeaderboardKind;
+    use crate::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, Env,
+    };
+
+    const WEEK: u64 = 7 * 24 * 60 * 60;
+
+    fn setup() -> (Env, NesteraContractClient<'static>) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        env.mock_all_auths();
+        (env, client)
+    }
+
+    #[test]
+    fn test_top_savers_stay_sorted_and_bounded() {
+        let (env, client) = setup();
+
+        let mut savers = soroban_sdk::Vec::new(&env);
+        for i in 1..=(LEADERBOARD_SIZE as i128 + 2) {
+            let user = Address::generate(&env);
+            client.initialize_user(&user);
+            client.deposit_flexi(&user, &(i * 100));
+            savers.push_back(user);
+        }
+
+        let board = client.get_leaderboard(&LeaderboardKind::TotalSaved);
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        assert_eq!(board.get(0).unwrap().user, savers.last().unwrap());
+        assert_eq!(board.get(0).unwrap().score, 1_200);
+        assert_eq!(board.last().unwrap().score, 300);
+
+        // A later deposit moves the saver up rather than adding a second row
+        let first = savers.get(0).unwrap();
+        client.deposit_flexi(&first, &1_500);
+        let board = client.get_leaderboard(&LeaderboardKind::TotalSaved);
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        assert_eq!(board.get(0).unwrap().user, first);
+        assert_eq!(board.get(0).unwrap().score, 1_600);
+    }
+
+    #[test]
+    fn test_streak_board_and_opt_out() {
+        let (env, client) = setup();
+        let steady = Address::generate(&env);
+        let casual = Address::generate(&env);
+        client.initialize_user(&steady);
+        client.initialize_user(&casual);
+
+        client.deposit_flexi(&steady, &10);
+        client.deposit_flexi(&casual, &10);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        client.deposit_flexi(&steady, &10);
+
+        let board = client.get_leaderboard(&LeaderboardKind::LongestStreak);
+        assert_eq!(board.get(0).unwrap().user, steady);
+        assert_eq!(board.get(0).unwrap().score, 2);
+        assert_eq!(board.get(1).unwrap().user, casual);
+
+        client.set_leaderboard_opt_out(&steady, &true);
+        assert!(client.is_leaderboard_opted_out(&steady));
+        for kind in [LeaderboardKind::TotalSaved, LeaderboardKind::LongestStreak] {
+            let board = client.get_leaderboard(&kind);
+            assert!(board.iter().all(|entry| entry.user != steady));
+        }
+        client.deposit_flexi(&steady, &10);
+        assert_eq!(
+            client.get_leaderboard(&LeaderboardKind::TotalSaved).len(),
+            1
+        );
+
+        client.set_leaderboard_opt_out(&steady, &false);
+        client.deposit_flexi(&steady, &10);
+        let board = client.get_leaderboard(&LeaderboardKind::TotalSaved);
+        assert_eq!(board.get(0).unwrap().user, steady);
+        assert_eq!(board.get(0).unwrap().score, 40);
+    }
+}
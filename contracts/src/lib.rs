@@ -1,26 +1,86 @@
 #![no_std]
 #![allow(non_snake_case)]
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, symbol_short, xdr::ToXdr, Address, Bytes, BytesN,
-    Env, String, Symbol, Vec,
+    contract, contractimpl, panic_with_error, xdr::ToXdr, Address, Bytes, BytesN, Env, String,
+    Symbol, Vec,
 };
 
+mod achievements;
+mod allowances;
+mod archive;
+mod assets;
+mod attachments;
 mod autosave;
+mod batch;
+mod buckets;
+mod campaigns;
+mod challenges;
+mod compliance;
+mod convert;
+mod custody;
+mod deprecation;
+mod emergency;
 mod errors;
+mod events;
+mod export;
+mod fees;
 mod flexi;
 mod goal;
+mod goal_templates;
+mod governance;
 mod group;
+mod guard;
+mod history;
+mod idempotency;
+mod invariants;
+mod leaderboard;
+mod limits;
 mod lock;
+mod migrations;
+mod multisig;
+mod oracle;
+mod pagination;
+mod params;
+mod plan_ids;
 mod storage_types;
+mod strategy;
+mod streaks;
+mod timelock;
+mod ttl;
 mod users;
 
 mod rates;
+mod recovery;
+mod reentrancy;
+mod roles;
+mod roundup;
+mod signing;
+mod simulation;
+mod sponsored;
 mod views;
+mod yield_pool;
 
 pub use crate::errors::SavingsError;
+use crate::guard::Op;
 pub use crate::storage_types::{
-    AutoSave, DataKey, GoalSave, GoalSaveView, GroupSave, GroupSaveView, LockSave, LockSaveView,
-    MintPayload, PlanType, SavingsPlan, User,
+    AdminAction, ArchivedPlanSummary, AssetConfig, AutoSave, AutoSaveCatchUp, AutoSaveConsent,
+    AutoSaveLeg, AutoSaveLegResult, AutoSaveSource, AutoSaveTarget, Badge, BadgeAward,
+    BalanceCheckpoint, BalanceProof, Campaign, CampaignAccrual, ChallengeEnrollment,
+    ChallengeStanding, ChallengeTemplate, ChunkedListId, ComplianceConfig, ComplianceMode,
+    CompoundFrequency, ConfigKey, Cursor, Custody, DataKey, DepositAllowance, DepositOp,
+    DepositPayload, DisputeOutcome, DueGroupContribution, FeeBreakdown, FeeExemption, FeeTier,
+    FlexiBucket, GoalContribution, GoalFiatProgress, GoalMilestone, GoalSave, GoalSavePayload,
+    GoalSaveView, GoalTemplate, GoalTemplateOverrides, GoalWithdrawPolicy, GroupAnnouncement,
+    GroupChange, GroupContribution, GroupDispute, GroupDurationBounds, GroupJoinRequirements,
+    GroupKey, GroupMembership, GroupMetadata, GroupProposal, GroupRoundStatus, GroupSave,
+    GroupSaveView, GroupStatus, InterestStatement, InvariantReport, KeeperTipConfig,
+    LeaderboardEntry, LeaderboardKind, LiquidityStatus, LockRateEntry, LockSave, LockSaveView,
+    MintPayload, MultisigAction, MultisigConfig, MultisigKey, MultisigProposal, ParamKey,
+    PendingAdminAction, PlanDetail, PlanKind, PlanLimits, PlanRef, PlanType, ProtocolParams,
+    RateChange, ReferralStats, Role, RoundupPreview, SavingsPlan, ScheduledRate, SignedEnvelope,
+    SimulatedPlan, SimulationProjection, StrategyHarvest, StreakPeriod, TargetLockProgress, TxKind,
+    TxRecord, User, UserBalances, UserConsent, UserKey, UserStateExport, UserStreak,
+    WithdrawalFeeConfig, WithdrawalPreview, YieldPoolStatus,
 };
 
 /// Custom error codes for the contract administration
@@ -42,41 +102,109 @@ impl From<ContractError> for soroban_sdk::Error {
 #[contract]
 pub struct NesteraContract;
 
-pub(crate) fn ensure_not_paused(env: &Env) -> Result<(), SavingsError> {
-    let is_paused: bool = env
-        .storage()
-        .persistent()
-        .get(&DataKey::Paused)
-        .unwrap_or(false);
-    if is_paused {
-        Err(SavingsError::ContractPaused)
-    } else {
-        Ok(())
+/// Verifies `admin` signed the call and is the stored contract admin
+pub(crate) fn ensure_admin(env: &Env, admin: &Address) -> Result<(), SavingsError> {
+    admin.require_auth();
+    let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    if stored_admin.as_ref() != Some(admin) {
+        return Err(SavingsError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Records the admin and mint signing key and marks the contract initialized.
+///
+/// Shared by `initialize` and its legacy shim; panics if already initialized.
+fn bootstrap_admin(env: &Env, admin: &Address, admin_public_key: &BytesN<32>) {
+    if env.storage().instance().has(&DataKey::Initialized) {
+        panic_with_error!(env, ContractError::AlreadyInitialized);
     }
+    admin.require_auth();
+    env.storage().instance().set(&DataKey::Admin, admin);
+    env.storage()
+        .instance()
+        .set(&DataKey::AdminPublicKey, admin_public_key);
+    env.storage().instance().set(&DataKey::Initialized, &true);
+    env.storage().persistent().set(&DataKey::Paused, &false);
+    migrations::set_storage_version(env, migrations::CURRENT_STORAGE_VERSION);
+}
+
+/// Updates every per-user deposit tracker (history, streaks, badges,
+/// challenges, campaigns, leaderboards) after a deposit.
+///
+/// Every deposit path must call this exactly once per deposit.
+pub(crate) fn record_deposit_activity(
+    env: &Env,
+    user: &Address,
+    plan_kind: PlanKind,
+    plan_id: u64,
+    amount: i128,
+) {
+    history::record(env, user, TxKind::Deposit, plan_kind, plan_id, amount, 0);
+    let streak = streaks::record_deposit(env, user);
+    achievements::on_deposit(env, user, amount, &streak);
+    challenges::on_deposit(env, user, amount);
+    campaigns::on_deposit(env, user, plan_kind, plan_id, amount);
+    leaderboard::on_deposit(env, user, &streak);
+    users::accrue_referral_reward(env, user, amount);
+}
+
+/// Updates the per-user trackers that count savings net of withdrawals
+/// (challenges) after `amount` of principal leaves a user's savings.
+///
+/// Every payout, break, debit of Flexi Save into another plan and plan
+/// handed to another user must call this once. Payouts into the user's own
+/// Flexi Save keep the funds saved and don't.
+pub(crate) fn record_withdrawal_activity(env: &Env, user: &Address, amount: i128) {
+    challenges::on_withdraw(env, user, amount);
 }
 
 #[contractimpl]
 impl NesteraContract {
     /// Initialize a new user in the system
+    ///
+    /// Deprecated: use `initialize_user`.
     pub fn init_user(env: Env, user: Address) -> User {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        deprecation::check_legacy(&env, "init_user", "initialize_user")
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
         users::initialize_user(&env, user.clone()).unwrap_or_else(|e| panic_with_error!(&env, e));
         users::get_user(&env, &user).unwrap_or_else(|e| panic_with_error!(&env, e))
     }
 
-    pub fn initialize(env: Env, admin: Address, admin_public_key: BytesN<32>) {
-        if env.storage().instance().has(&DataKey::Initialized) {
-            panic_with_error!(&env, ContractError::AlreadyInitialized);
-        }
-        admin.require_auth();
-        env.storage().instance().set(&DataKey::Admin, &admin);
+    /// Bootstraps the contract in a single call.
+    ///
+    /// Sets the admin and the key that signs mint payloads, the treasury that
+    /// receives protocol fees, the early break fee in basis points and the
+    /// deposit token. Nothing is written unless every setting is valid.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        admin_public_key: BytesN<32>,
+        treasury: Address,
+        fee_bps: u32,
+        token: Address,
+    ) {
+        bootstrap_admin(&env, &admin, &admin_public_key);
+        timelock::apply(&env, &AdminAction::SetEarlyBreakFeeBps(fee_bps))
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        timelock::apply(&env, &AdminAction::SetFeeRecipient(treasury))
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
         env.storage()
             .instance()
-            .set(&DataKey::AdminPublicKey, &admin_public_key);
-        env.storage().instance().set(&DataKey::Initialized, &true);
-        env.storage().persistent().set(&DataKey::Paused, &false);
-        env.events()
-            .publish((symbol_short!("init"),), admin_public_key);
+            .set(&DataKey::Config(ConfigKey::Token), &token);
+        events::publish(&env, events::Initialized { admin_public_key });
+    }
+
+    /// Sets only the admin and the mint signing key.
+    ///
+    /// Deprecated: use `initialize`, which also configures the treasury, fee
+    /// and token. Deployments bootstrapped this way must set those through
+    /// `set_fee_recipient`, `set_early_break_fee_bps` and `set_token`.
+    pub fn initialize_admin(env: Env, admin: Address, admin_public_key: BytesN<32>) {
+        deprecation::check_legacy(&env, "initialize_admin", "initialize")
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        bootstrap_admin(&env, &admin, &admin_public_key);
+        events::publish(&env, events::Initialized { admin_public_key });
     }
 
     pub fn verify_signature(env: Env, payload: MintPayload, signature: BytesN<64>) -> bool {
@@ -93,17 +221,92 @@ impl NesteraContract {
             .instance()
             .get(&DataKey::AdminPublicKey)
             .expect("Admin PK not found");
+        let nonce = payload.timestamp;
         let payload_bytes: Bytes = payload.to_xdr(&env);
-        env.crypto()
-            .ed25519_verify(&admin_public_key, &payload_bytes, &signature);
+        signing::verify(
+            &env,
+            &admin_public_key,
+            signing::MINT,
+            nonce,
+            payload_bytes,
+            &signature,
+        );
         true
     }
 
+    /// Returns the bytes the admin must sign to authorize a `mint` payload.
+    ///
+    /// Every signed payload is wrapped in a `SignedEnvelope` naming this
+    /// contract, the network and the entrypoint, so off-chain signers should
+    /// sign these bytes rather than the bare payload XDR.
+    pub fn mint_signing_message(env: Env, payload: MintPayload) -> Bytes {
+        signing::message(&env, signing::MINT, payload.timestamp, payload.to_xdr(&env))
+    }
+
+    /// Returns the bytes the admin must sign for `deposit_flexi_signed`
+    pub fn deposit_signing_message(env: Env, payload: DepositPayload) -> Bytes {
+        let nonce = payload.nonce;
+        signing::message(&env, signing::DEPOSIT_FLEXI, nonce, payload.to_xdr(&env))
+    }
+
+    /// Returns the bytes the admin must sign for `create_goal_save_signed`
+    pub fn goal_signing_message(env: Env, payload: GoalSavePayload) -> Bytes {
+        let nonce = payload.nonce;
+        signing::message(&env, signing::CREATE_GOAL, nonce, payload.to_xdr(&env))
+    }
+
+    /// Returns the bytes a user must sign for `create_autosave_with_consent`
+    pub fn consent_signing_message(env: Env, consent: AutoSaveConsent) -> Bytes {
+        let nonce = consent.nonce;
+        signing::message(&env, signing::AUTOSAVE_CONSENT, nonce, consent.to_xdr(&env))
+    }
+
+    /// Returns the full envelope a payload is wrapped in before signing
+    pub fn get_signing_envelope(
+        env: Env,
+        function: Symbol,
+        nonce: u64,
+        payload: Bytes,
+    ) -> SignedEnvelope {
+        signing::envelope(&env, function, nonce, payload)
+    }
+
+    /// Credits an admin-signed mint payload to the user's Flexi Save balance.
+    ///
+    /// Payloads are redeemed in order: each must carry a newer `timestamp` than
+    /// the last one the user redeemed, so a signature can't be replayed.
+    /// Returns the amount credited.
     pub fn mint(env: Env, payload: MintPayload, signature: BytesN<64>) -> i128 {
         Self::verify_signature(env.clone(), payload.clone(), signature);
+        let user = payload.user;
         let amount = payload.amount;
-        env.events()
-            .publish((symbol_short!("mint"), payload.user), amount);
+        guard::check(&env, &user, Op::Deposit, amount)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        if amount <= 0 {
+            panic_with_error!(&env, SavingsError::InvalidAmount);
+        }
+
+        let last_mint_key = DataKey::UserState(UserKey::LastMintTimestamp(user.clone()));
+        let last_mint: Option<u64> = env.storage().persistent().get(&last_mint_key);
+        if last_mint.is_some_and(|last| payload.timestamp <= last) {
+            panic_with_error!(&env, ContractError::InvalidSignature);
+        }
+        env.storage()
+            .persistent()
+            .set(&last_mint_key, &payload.timestamp);
+
+        flexi::credit_flexi(&env, &user, amount).unwrap_or_else(|e| panic_with_error!(&env, e));
+        history::record(&env, &user, TxKind::Credit, PlanKind::Flexi, 0, amount, 0);
+        let balance = flexi::get_flexi_balance(&env, user.clone())
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        events::publish(
+            &env,
+            events::Minted {
+                user,
+                amount,
+                balance,
+            },
+        );
         amount
     }
 
@@ -111,43 +314,69 @@ impl NesteraContract {
         env.storage().instance().has(&DataKey::Initialized)
     }
 
+    /// Deprecated: use the plan-specific entrypoints (`deposit_flexi`,
+    /// `create_lock_save`, `create_goal_save`, `join_group_save`).
+    ///
+    /// Dispatches to the plan module `plan_type` names and returns the new
+    /// plan's ID, or 0 for a Flexi deposit. `Lock` takes the unlock time,
+    /// and `Group` joins the named group (if not already a member) and
+    /// contributes `initial_deposit` to it.
     pub fn create_savings_plan(
         env: Env,
         user: Address,
         plan_type: PlanType,
         initial_deposit: i128,
     ) -> u64 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let replacement = match plan_type {
+            PlanType::Flexi => "deposit_flexi",
+            PlanType::Lock(_) => "create_lock_save",
+            PlanType::Goal(..) => "create_goal_save",
+            PlanType::Group(..) => "join_group_save",
+        };
+        deprecation::check_legacy(&env, "create_savings_plan", replacement)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
+        guard::check(&env, &user, Op::CreatePlan, initial_deposit)
+            .unwrap_or_else(|e| panic_with_error!(&env, e));
         if !Self::is_initialized(env.clone()) {
             panic_with_error!(&env, ContractError::NotInitialized);
         }
-        let mut user_data = Self::get_user(env.clone(), user.clone()).unwrap_or(User {
-            total_balance: 0,
-            savings_count: 0,
-        });
-        user_data.savings_count += 1;
-        user_data.total_balance += initial_deposit;
-        let plan_id = user_data.savings_count as u64;
-        let new_plan = SavingsPlan {
-            plan_id,
-            plan_type,
-            balance: initial_deposit,
-            start_time: env.ledger().timestamp(),
-            last_deposit: env.ledger().timestamp(),
-            last_withdraw: 0,
-            interest_rate: 500,
-            is_completed: false,
-            is_withdrawn: false,
-        };
-        env.storage()
-            .persistent()
-            .set(&DataKey::User(user.clone()), &user_data);
-        env.storage()
-            .persistent()
-            .set(&DataKey::SavingsPlan(user.clone(), plan_id), &new_plan);
-        env.events().publish(
-            (Symbol::new(&env, "create_plan"), user, plan_id),
-            initial_deposit,
+        if !users::user_exists(&env, &user) {
+            users::insert_user(&env, user.clone()).unwrap_or_else(|e| panic_with_error!(&env, e));
+        }
+
+        let plan_id = match plan_type {
+            PlanType::Flexi => {
+                flexi::flexi_deposit(env.clone(), user.clone(), initial_deposit).map(|()| 0)
+            }
+            PlanType::Lock(locked_until) => lock::create_lock_save(
+                &env,
+                user.clone(),
+                initial_deposit,
+                locked_until.saturating_sub(env.ledger().timestamp()),
+                CompoundFrequency::None,
+            ),
+            PlanType::Goal(goal_name, target_amount, _) => goal::create_goal_save(
+                &env,
+                user.clone(),
+                goal_name,
+                target_amount,
+                initial_deposit,
+                0,
+            ),
+            PlanType::Group(group_id, ..) => {
+                group::join_and_contribute(&env, &user, group_id, initial_deposit)
+                    .map(|()| group_id)
+            }
+        }
+        .unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        events::publish(
+            &env,
+            events::PlanCreated {
+                user,
+                plan_id,
+                initial_deposit,
+            },
         );
         plan_id
     }
@@ -159,314 +388,2327 @@ impl NesteraContract {
     }
 
     pub fn initialize_user(env: Env, user: Address) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
         users::initialize_user(&env, user)
     }
 
-    pub fn user_exists(env: Env, user: Address) -> bool {
-        users::user_exists(&env, &user)
+    /// Closes the user's emptied account and deletes the data kept about them
+    pub fn close_account(env: Env, user: Address) -> Result<(), SavingsError> {
+        users::close_account(&env, user)
     }
 
-    pub fn deposit_flexi(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        flexi::flexi_deposit(env, user, amount)
+    /// Reinitializes an account its owner closed
+    pub fn reopen_account(env: Env, user: Address) -> Result<(), SavingsError> {
+        users::reopen_account(&env, user)
     }
 
-    pub fn withdraw_flexi(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        flexi::flexi_withdraw(env, user, amount)
+    /// Initializes a new user and records `referrer` as the account that referred them
+    pub fn register_with_referrer(
+        env: Env,
+        user: Address,
+        referrer: Address,
+    ) -> Result<(), SavingsError> {
+        users::register_with_referrer(&env, user, referrer)
     }
 
-    pub fn get_flexi_balance(env: Env, user: Address) -> i128 {
-        flexi::get_flexi_balance(&env, user).unwrap_or(0)
+    /// Opens an account for a minor whose withdrawals need the guardian's
+    /// signature until `release_time`
+    pub fn initialize_custodial_user(
+        env: Env,
+        guardian: Address,
+        minor: Address,
+        release_time: u64,
+    ) -> Result<(), SavingsError> {
+        custody::initialize_custodial_user(&env, guardian, minor, release_time)
     }
 
-    // --- Lock Save Logic ---
+    /// Moves the release time of a custodial account that is still held
+    pub fn set_release_time(
+        env: Env,
+        guardian: Address,
+        minor: Address,
+        release_time: u64,
+    ) -> Result<(), SavingsError> {
+        custody::set_release_time(&env, guardian, minor, release_time)
+    }
 
-    pub fn create_lock_save(env: Env, user: Address, amount: i128, duration: u64) -> u64 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        user.require_auth();
-        lock::create_lock_save(&env, user, amount, duration)
-            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    /// Hands a custodial account to a new guardian; both guardians must sign
+    pub fn change_guardian(
+        env: Env,
+        guardian: Address,
+        minor: Address,
+        new_guardian: Address,
+    ) -> Result<(), SavingsError> {
+        custody::change_guardian(&env, guardian, minor, new_guardian)
     }
 
-    pub fn withdraw_lock_save(env: Env, user: Address, lock_id: u64) -> i128 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        user.require_auth();
-        lock::withdraw_lock_save(&env, user, lock_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn get_custody(env: Env, minor: Address) -> Option<Custody> {
+        custody::get_custody(&env, &minor)
     }
 
-    pub fn check_matured_lock(env: Env, lock_id: u64) -> bool {
-        lock::check_matured_lock(&env, lock_id)
+    /// Lists the custodial accounts a guardian controls
+    pub fn get_guardian_wards(env: Env, guardian: Address) -> Vec<Address> {
+        custody::get_guardian_wards(&env, &guardian)
     }
 
-    pub fn get_user_lock_saves(env: Env, user: Address) -> Vec<u64> {
-        lock::get_user_lock_saves(&env, &user)
+    pub fn claim_referral_rewards(env: Env, referrer: Address) -> Result<i128, SavingsError> {
+        users::claim_referral_rewards(&env, referrer)
     }
 
-    // ========== Goal Save Functions ==========
+    pub fn get_referral_stats(env: Env, referrer: Address) -> ReferralStats {
+        users::get_referral_stats(&env, &referrer)
+    }
 
-    pub fn create_goal_save(
-        env: Env,
-        user: Address,
-        goal_name: Symbol,
-        target_amount: i128,
-        initial_deposit: i128,
-    ) -> u64 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        goal::create_goal_save(&env, user, goal_name, target_amount, initial_deposit)
-            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
+        users::get_referrer(&env, &user)
     }
 
-    pub fn deposit_to_goal_save(env: Env, user: Address, goal_id: u64, amount: i128) {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        goal::deposit_to_goal_save(&env, user, goal_id, amount)
-            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn user_exists(env: Env, user: Address) -> bool {
+        users::user_exists(&env, &user)
     }
 
-    pub fn withdraw_completed_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        goal::withdraw_completed_goal_save(&env, user, goal_id)
-            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    pub fn deposit_flexi(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
+        flexi::flexi_deposit(env, user, amount)
     }
 
-    pub fn break_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
-        ensure_not_paused(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
-        goal::break_goal_save(&env, user, goal_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    /// Makes several deposits across the user's plans in one all-or-nothing
+    /// call. Returns the total deposited
+    pub fn batch_deposit(
+        env: Env,
+        user: Address,
+        ops: Vec<DepositOp>,
+    ) -> Result<i128, SavingsError> {
+        batch::batch_deposit(&env, user, ops)
     }
 
-    pub fn get_goal_save_detail(env: Env, goal_id: u64) -> GoalSave {
-        goal::get_goal_save(&env, goal_id)
-            .unwrap_or_else(|| panic_with_error!(&env, SavingsError::PlanNotFound))
+    /// Round-up spare change the given purchases would deposit into Flexi
+    pub fn preview_roundup(
+        env: Env,
+        user: Address,
+        purchase_amounts: Vec<i128>,
+        round_to: i128,
+    ) -> Result<RoundupPreview, SavingsError> {
+        roundup::preview_roundup(&env, user, purchase_amounts, round_to)
     }
 
-    pub fn get_user_goal_saves(env: Env, user: Address) -> Vec<u64> {
-        goal::get_user_goal_saves(&env, &user)
+    /// Deposits the round-up spare change of the given purchases into Flexi.
+    /// Returns the amount deposited
+    pub fn deposit_roundups(
+        env: Env,
+        user: Address,
+        purchase_amounts: Vec<i128>,
+        round_to: i128,
+    ) -> Result<i128, SavingsError> {
+        roundup::deposit_roundups(&env, user, purchase_amounts, round_to)
     }
 
-    // --- Group Save Logic ---
+    /// Same as `deposit_flexi`, but applied at most once per `key`, so a
+    /// client can safely retry after a timeout
+    pub fn deposit_flexi_idempotent(
+        env: Env,
+        user: Address,
+        amount: i128,
+        key: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        idempotency::consume(&env, &user, key)?;
+        flexi::flexi_deposit(env, user, amount)
+    }
 
-    pub fn create_group_save(
+    /// Deposits into a user's Flexi Save from an admin-signed payload, so the
+    /// backend can sponsor the transaction without the user's Soroban auth
+    pub fn deposit_flexi_signed(
         env: Env,
-        creator: Address,
-        title: String,
-        description: String,
-        category: String,
-        target_amount: i128,
-        contribution_type: u32,
-        contribution_amount: i128,
-        is_public: bool,
-        start_time: u64,
-        end_time: u64,
-    ) -> Result<u64, SavingsError> {
-        ensure_not_paused(&env)?;
-        group::create_group_save(
-            &env,
-            creator,
-            title,
-            description,
-            category,
-            target_amount,
-            contribution_type,
-            contribution_amount,
-            is_public,
-            start_time,
-            end_time,
-        )
+        payload: DepositPayload,
+        signature: BytesN<64>,
+    ) -> Result<(), SavingsError> {
+        sponsored::deposit_flexi(&env, payload, signature)
     }
 
-    pub fn join_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        group::join_group_save(&env, user, group_id)
+    /// Returns the nonce the user's next admin-signed payload must carry
+    pub fn get_sponsored_nonce(env: Env, user: Address) -> u64 {
+        sponsored::get_nonce(&env, &user)
     }
 
-    pub fn contribute_to_group_save(
+    /// Lets `spender` deposit up to `max_amount` into the user's Flexi Save
+    /// until `expiry`, without a signature per deposit. Replaces any earlier
+    /// allowance for the same spender.
+    pub fn grant_allowance(
         env: Env,
         user: Address,
-        group_id: u64,
-        amount: i128,
+        spender: Address,
+        max_amount: i128,
+        expiry: u64,
     ) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        group::contribute_to_group_save(&env, user, group_id, amount)
+        allowances::grant_allowance(&env, user, spender, max_amount, expiry)
     }
 
-    pub fn break_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
-        ensure_not_paused(&env)?;
-        group::break_group_save(&env, user, group_id)
+    pub fn revoke_allowance(env: Env, user: Address, spender: Address) -> Result<(), SavingsError> {
+        allowances::revoke_allowance(&env, user, spender)
     }
 
-    // --- Admin Control Functions ---
+    pub fn get_allowance(env: Env, user: Address, spender: Address) -> Option<DepositAllowance> {
+        allowances::get_allowance(&env, &user, &spender)
+    }
 
-    pub fn set_admin(
+    /// Deposits into the user's Flexi Save on the spender's authorization,
+    /// consuming the allowance the user granted them
+    pub fn deposit_flexi_for(
         env: Env,
-        current_admin: Address,
-        new_admin: Address,
+        spender: Address,
+        user: Address,
+        amount: i128,
     ) -> Result<(), SavingsError> {
-        current_admin.require_auth();
-        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
-        if let Some(admin) = stored_admin {
-            if admin != current_admin {
-                return Err(SavingsError::Unauthorized);
-            }
-        }
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
-        env.events()
-            .publish((symbol_short!("set_admin"),), new_admin);
-        Ok(())
+        allowances::deposit_flexi_for(&env, spender, user, amount)
     }
 
-    pub fn set_flexi_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
-        let admin = env.storage().instance().get(&DataKey::Admin).unwrap();
-        let admin_address: Address = admin; // Type casting for clarity, though get returns generic
-        admin_address.require_auth();
-        rates::set_flexi_rate(&env, rate)
+    pub fn withdraw_flexi(env: Env, user: Address, amount: i128) -> Result<(), SavingsError> {
+        flexi::flexi_withdraw(env, user, amount)
     }
 
-    pub fn set_goal_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-        rates::set_goal_rate(&env, rate)
+    pub fn get_flexi_balance(env: Env, user: Address) -> i128 {
+        flexi::get_flexi_balance(&env, user).unwrap_or(0)
     }
 
-    pub fn set_group_rate(env: Env, rate: i128) -> Result<(), SavingsError> {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-        rates::set_group_rate(&env, rate)
+    /// Adds an empty named bucket for organizing the user's Flexi balance.
+    /// Buckets don't change how withdrawals work: they draw on the whole
+    /// balance, taking from unallocated funds first, then the newest bucket.
+    pub fn create_bucket(env: Env, user: Address, name: Symbol) -> Result<(), SavingsError> {
+        buckets::create_bucket(&env, user, name)
     }
 
-    pub fn set_lock_rate(env: Env, duration_days: u64, rate: i128) -> Result<(), SavingsError> {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-        rates::set_lock_rate(&env, duration_days, rate)
+    pub fn delete_bucket(env: Env, user: Address, name: Symbol) -> Result<(), SavingsError> {
+        buckets::delete_bucket(&env, user, name)
     }
 
-    pub fn set_early_break_fee_bps(env: Env, bps: u32) -> Result<(), SavingsError> {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-        if bps > 10_000 {
-            return Err(SavingsError::InvalidAmount);
-        }
-        env.storage()
-            .instance()
-            .set(&DataKey::EarlyBreakFeeBps, &bps);
-        env.events().publish((symbol_short!("set_brk"),), bps);
-        Ok(())
+    /// Moves `amount` between buckets; `None` is the unallocated balance
+    pub fn move_between_buckets(
+        env: Env,
+        user: Address,
+        from: Option<Symbol>,
+        to: Option<Symbol>,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        buckets::move_between_buckets(&env, user, from, to, amount)
     }
 
-    pub fn set_fee_recipient(env: Env, recipient: Address) -> Result<(), SavingsError> {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
-        env.storage()
-            .instance()
-            .set(&DataKey::FeeRecipient, &recipient);
-        env.events().publish((symbol_short!("set_fee"),), recipient);
-        Ok(())
+    pub fn get_flexi_buckets(env: Env, user: Address) -> Vec<FlexiBucket> {
+        buckets::get_buckets(&env, &user)
     }
 
-    pub fn pause(env: Env, admin: Address) -> Result<(), SavingsError> {
-        admin.require_auth();
-        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    /// Returns the part of the user's Flexi balance not in any bucket
+    pub fn get_unallocated_flexi(env: Env, user: Address) -> i128 {
+        buckets::get_unallocated(&env, &user).unwrap_or(0)
+    }
 
-        // Use .clone() here so 'admin' isn't moved
-        if stored_admin != Some(admin.clone()) {
-            return Err(SavingsError::Unauthorized);
-        }
+    /// Returns what withdrawing `amount` from the Flexi Save would pay out now
+    pub fn preview_withdraw_flexi(
+        env: Env,
+        user: Address,
+        amount: i128,
+    ) -> Result<WithdrawalPreview, SavingsError> {
+        flexi::preview_withdraw_flexi(&env, user, amount)
+    }
 
-        env.storage().persistent().set(&DataKey::Paused, &true);
-        env.events().publish((symbol_short!("pause"), admin), ());
-        Ok(())
+    /// Returns the interest statement of the user's Flexi Save
+    pub fn get_flexi_statement(env: Env, user: Address) -> Result<InterestStatement, SavingsError> {
+        flexi::get_flexi_statement(&env, user)
     }
 
-    pub fn unpause(env: Env, admin: Address) -> Result<(), SavingsError> {
-        admin.require_auth();
-        let stored_admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    /// Returns the flexi and total balance of up to `MAX_PAGE_LIMIT` users,
+    /// in input order
+    pub fn get_balances_bulk(
+        env: Env,
+        users: Vec<Address>,
+    ) -> Result<Vec<UserBalances>, SavingsError> {
+        views::get_balances_bulk(&env, users)
+    }
 
-        // Use .clone() here too
-        if stored_admin != Some(admin.clone()) {
-            return Err(SavingsError::Unauthorized);
-        }
+    /// Cross-checks the user's stored balances, for audits and monitoring
+    pub fn check_invariants(env: Env, user: Address) -> Result<InvariantReport, SavingsError> {
+        invariants::check_invariants(&env, user)
+    }
 
-        env.storage().persistent().set(&DataKey::Paused, &false);
-        env.events().publish((symbol_short!("unpause"), admin), ());
-        Ok(())
+    /// Runs `check_invariants` over up to `MAX_PAGE_LIMIT` users and returns
+    /// the failing reports
+    pub fn check_invariants_sample(
+        env: Env,
+        users: Vec<Address>,
+    ) -> Result<Vec<InvariantReport>, SavingsError> {
+        invariants::check_invariants_sample(&env, users)
     }
 
-    // --- Remaining views and utilities ---
-    pub fn get_savings_plan(env: Env, user: Address, plan_id: u64) -> Option<SavingsPlan> {
-        env.storage()
-            .persistent()
-            .get(&DataKey::SavingsPlan(user, plan_id))
+    /// Returns the user's deposit streak (current, longest, last period)
+    pub fn get_user_streak(env: Env, user: Address) -> UserStreak {
+        streaks::get_user_streak(&env, &user)
     }
 
-    pub fn is_paused(env: Env) -> bool {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Paused)
-            .unwrap_or(false)
+    /// Returns the badges a user has earned with their award timestamps
+    pub fn get_user_badges(env: Env, user: Address) -> Vec<BadgeAward> {
+        achievements::get_user_badges(&env, &user)
     }
 
-    pub fn get_flexi_rate(env: Env) -> i128 {
-        rates::get_flexi_rate(&env)
+    /// Refreshes the TTL of every entry stored for a user so dormant savings
+    /// are not archived. Callable by anyone; returns the entries extended
+    pub fn extend_user_storage(env: Env, user: Address) -> u32 {
+        ttl::extend_user_storage(&env, &user)
     }
 
-    pub fn get_goal_rate(env: Env) -> i128 {
-        rates::get_goal_rate(&env)
+    /// Refreshes the TTL of a page of entries and of the contract instance.
+    /// Returns the entries extended
+    pub fn bulk_extend(env: Env, keeper: Address, keys: Vec<DataKey>) -> Result<u32, SavingsError> {
+        ttl::bulk_extend(&env, keeper, keys)
     }
 
-    pub fn get_group_rate(env: Env) -> i128 {
-        rates::get_group_rate(&env)
+    /// Ledgers a persistent entry is guaranteed to live for, as of its last
+    /// extension through this contract
+    pub fn get_entry_ttl(env: Env, key: DataKey) -> Option<u32> {
+        ttl::get_entry_ttl(&env, &key)
     }
 
-    pub fn get_lock_rate(env: Env, duration_days: u64) -> Result<i128, SavingsError> {
-        rates::get_lock_rate(&env, duration_days)
+    /// Records a Merkle root of the given users' balances for recovering
+    /// accounts archived after their TTL expired. Returns the checkpoint ID
+    pub fn checkpoint_balances(
+        env: Env,
+        admin: Address,
+        users: Vec<Address>,
+    ) -> Result<u32, SavingsError> {
+        ensure_admin(&env, &admin)?;
+        recovery::checkpoint_balances(&env, users)
     }
 
-    pub fn get_early_break_fee_bps(env: Env) -> u32 {
-        env.storage()
-            .instance()
-            .get(&DataKey::EarlyBreakFeeBps)
-            .unwrap_or(0)
+    /// Recreates an archived user's account from balances proven against a
+    /// checkpoint
+    pub fn restore_user(
+        env: Env,
+        admin: Address,
+        user: Address,
+        proof: BalanceProof,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        recovery::restore_user(&env, user, proof)
     }
 
-    pub fn get_fee_recipient(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::FeeRecipient)
+    pub fn get_balance_checkpoints(env: Env) -> Vec<BalanceCheckpoint> {
+        recovery::get_balance_checkpoints(&env)
     }
 
-    pub fn get_protocol_fee_balance(env: Env, recipient: Address) -> i128 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::TotalBalance(recipient))
-            .unwrap_or(0)
+    /// Publishes a savings challenge: an amount to save, net of
+    /// withdrawals, in each period. Only the admin may attach a completion
+    /// bonus
+    pub fn create_challenge(
+        env: Env,
+        creator: Address,
+        name: Symbol,
+        period_seconds: u64,
+        amounts: Vec<i128>,
+        bonus: i128,
+    ) -> Result<u64, SavingsError> {
+        challenges::create_challenge(&env, creator, name, period_seconds, amounts, bonus)
     }
 
-    // ========== AutoSave Functions ==========
+    pub fn enroll_in_challenge(
+        env: Env,
+        user: Address,
+        challenge_id: u64,
+    ) -> Result<(), SavingsError> {
+        challenges::enroll_in_challenge(&env, user, challenge_id)
+    }
 
-    /// Creates a new AutoSave schedule for recurring Flexi deposits
-    pub fn create_autosave(
+    /// Closes out a finished challenge, paying its bonus if every period was
+    /// met. Returns the bonus paid
+    pub fn claim_challenge(
         env: Env,
         user: Address,
-        amount: i128,
-        interval_seconds: u64,
-        start_time: u64,
-    ) -> Result<u64, SavingsError> {
-        autosave::create_autosave(&env, user, amount, interval_seconds, start_time)
+        challenge_id: u64,
+    ) -> Result<i128, SavingsError> {
+        challenges::claim_challenge(&env, user, challenge_id)
     }
 
-    /// Executes an AutoSave schedule if it's due
-    pub fn execute_autosave(env: Env, schedule_id: u64) -> Result<(), SavingsError> {
-        autosave::execute_autosave(&env, schedule_id)
+    pub fn get_challenge(env: Env, challenge_id: u64) -> Option<ChallengeTemplate> {
+        challenges::get_challenge(&env, challenge_id)
     }
 
-    /// Cancels an AutoSave schedule
-    pub fn cancel_autosave(env: Env, user: Address, schedule_id: u64) -> Result<(), SavingsError> {
+    /// Returns the user's progress in a challenge, scored up to now
+    pub fn get_challenge_progress(
+        env: Env,
+        user: Address,
+        challenge_id: u64,
+    ) -> Option<ChallengeEnrollment> {
+        challenges::get_challenge_progress(&env, &user, challenge_id)
+    }
+
+    /// Lists the challenges a user is enrolled in and has not claimed
+    pub fn get_user_challenges(env: Env, user: Address) -> Vec<u64> {
+        challenges::get_user_challenges(&env, &user)
+    }
+
+    /// Returns a page of a challenge's participants, best first
+    pub fn get_challenge_leaderboard(
+        env: Env,
+        challenge_id: u64,
+        cursor: Cursor,
+    ) -> (Vec<ChallengeStanding>, Option<Cursor>) {
+        challenges::get_challenge_leaderboard(&env, challenge_id, cursor)
+    }
+
+    /// Starts a promotional campaign funded with `budget` out of the
+    /// treasury's fees, paying `bonus_bps` of qualifying deposits into
+    /// `plan_kind` plans between `start_time` and `end_time`
+    pub fn create_campaign(
+        env: Env,
+        caller: Address,
+        plan_kind: PlanKind,
+        start_time: u64,
+        end_time: u64,
+        bonus_bps: u32,
+        budget: i128,
+    ) -> Result<u64, SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        campaigns::create_campaign(&env, plan_kind, start_time, end_time, bonus_bps, budget)
+    }
+
+    /// Returns the unearned budget of an ended campaign to the treasury
+    pub fn close_campaign(
+        env: Env,
+        caller: Address,
+        campaign_id: u64,
+    ) -> Result<i128, SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        campaigns::close_campaign(&env, campaign_id)
+    }
+
+    pub fn get_campaign(env: Env, campaign_id: u64) -> Option<Campaign> {
+        campaigns::get_campaign(&env, campaign_id)
+    }
+
+    /// Returns a page of every campaign, in creation order
+    pub fn get_campaigns(env: Env, cursor: Cursor) -> (Vec<Campaign>, Option<Cursor>) {
+        campaigns::get_campaigns(&env, cursor)
+    }
+
+    /// Returns the campaign bonuses the user has earned on a plan and not
+    /// been paid yet
+    pub fn get_campaign_bonuses(
+        env: Env,
+        user: Address,
+        plan_kind: PlanKind,
+        plan_id: u64,
+    ) -> Vec<CampaignAccrual> {
+        campaigns::get_campaign_bonuses(&env, &user, plan_kind, plan_id)
+    }
+
+    /// Returns a page of the user's recent deposits and withdrawals, newest
+    /// first. Only the last `MAX_TX_HISTORY` are kept
+    pub fn get_user_history(
+        env: Env,
+        user: Address,
+        cursor: Cursor,
+    ) -> (Vec<TxRecord>, Option<Cursor>) {
+        history::get_user_history(&env, &user, cursor)
+    }
+
+    /// Returns a protocol-wide leaderboard, best first
+    pub fn get_leaderboard(env: Env, kind: LeaderboardKind) -> Vec<LeaderboardEntry> {
+        leaderboard::get_leaderboard(&env, kind)
+    }
+
+    /// Hides the user from the leaderboards, or lets them back on from their
+    /// next deposit
+    pub fn set_leaderboard_opt_out(
+        env: Env,
+        user: Address,
+        opted_out: bool,
+    ) -> Result<(), SavingsError> {
+        leaderboard::set_leaderboard_opt_out(&env, user, opted_out)
+    }
+
+    pub fn is_leaderboard_opted_out(env: Env, user: Address) -> bool {
+        leaderboard::is_opted_out(&env, &user)
+    }
+
+    /// Returns everything stored for a user in one response, for backup and
+    /// migration tooling
+    pub fn export_user_state(env: Env, user: Address) -> Result<UserStateExport, SavingsError> {
+        export::export_user_state(&env, &user)
+    }
+
+    pub fn get_streak_period(env: Env) -> StreakPeriod {
+        streaks::get_streak_period(&env)
+    }
+
+    // --- Attachments ---
+
+    /// Attaches a content hash (e.g. IPFS CID digest) to a goal or group owned by `user`
+    pub fn set_attachment_hash(
+        env: Env,
+        user: Address,
+        plan_kind: PlanKind,
+        plan_id: u64,
+        hash: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        attachments::set_attachment_hash(&env, user, plan_kind, plan_id, hash)
+    }
+
+    pub fn get_attachment_hash(env: Env, plan_kind: PlanKind, plan_id: u64) -> Option<BytesN<32>> {
+        attachments::get_attachment_hash(&env, plan_kind, plan_id)
+    }
+
+    // --- Simulation ---
+
+    /// Opens a zero-value demo plan that mirrors a real plan's interest
+    pub fn create_simulation(
+        env: Env,
+        user: Address,
+        plan_kind: PlanKind,
+        amount: i128,
+        duration_days: u64,
+    ) -> Result<u64, SavingsError> {
+        simulation::create_simulation(&env, user, plan_kind, amount, duration_days)
+    }
+
+    pub fn simulate_deposit(
+        env: Env,
+        user: Address,
+        sim_id: u64,
+        amount: i128,
+    ) -> Result<SimulatedPlan, SavingsError> {
+        simulation::simulate_deposit(&env, user, sim_id, amount)
+    }
+
+    /// Projects a simulated plan's value at timestamp `at`
+    pub fn project_simulation(
+        env: Env,
+        user: Address,
+        sim_id: u64,
+        at: u64,
+    ) -> Result<SimulationProjection, SavingsError> {
+        simulation::project_simulation(&env, &user, sim_id, at)
+    }
+
+    pub fn get_simulations(env: Env, user: Address) -> Vec<SimulatedPlan> {
+        simulation::get_simulations(&env, &user)
+    }
+
+    pub fn delete_simulation(env: Env, user: Address, sim_id: u64) -> Result<(), SavingsError> {
+        simulation::delete_simulation(&env, user, sim_id)
+    }
+
+    // --- Plan Conversion ---
+
+    /// Moves Flexi balance into a new Lock Save without leaving the contract
+    pub fn convert_flexi_to_lock(
+        env: Env,
+        user: Address,
+        amount: i128,
+        duration: u64,
+    ) -> Result<u64, SavingsError> {
+        convert::convert_flexi_to_lock(&env, user, amount, duration)
+    }
+
+    /// Moves Flexi balance into one of the user's goals without leaving the
+    /// contract
+    pub fn convert_flexi_to_goal(
+        env: Env,
+        user: Address,
+        amount: i128,
+        goal_id: u64,
+    ) -> Result<(), SavingsError> {
+        convert::convert_flexi_to_goal(&env, user, amount, goal_id)
+    }
+
+    // --- Lock Save Logic ---
+
+    pub fn create_lock_save(env: Env, user: Address, amount: i128, duration: u64) -> u64 {
+        lock::create_lock_save(&env, user, amount, duration, CompoundFrequency::None)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Creates a Lock Save whose interest compounds into the principal at
+    /// the given frequency
+    pub fn create_lock_save_compounding(
+        env: Env,
+        user: Address,
+        amount: i128,
+        duration: u64,
+        compounding: CompoundFrequency,
+    ) -> Result<u64, SavingsError> {
+        lock::create_lock_save(&env, user, amount, duration, compounding)
+    }
+
+    /// Creates a Lock Save that unlocks once its balance reaches
+    /// `target_amount`, or after a long-stop date
+    pub fn create_target_lock(
+        env: Env,
+        user: Address,
+        target_amount: i128,
+        initial: i128,
+    ) -> Result<u64, SavingsError> {
+        lock::create_target_lock(&env, user, target_amount, initial)
+    }
+
+    /// Same as `create_lock_save`, but applied at most once per `key`
+    pub fn create_lock_save_idempotent(
+        env: Env,
+        user: Address,
+        amount: i128,
+        duration: u64,
+        key: BytesN<32>,
+    ) -> Result<u64, SavingsError> {
+        idempotency::consume(&env, &user, key)?;
+        lock::create_lock_save(&env, user, amount, duration, CompoundFrequency::None)
+    }
+
+    /// Amount a Lock Save created now would pay out at maturity, before fees
+    pub fn preview_maturity_amount(
+        env: Env,
+        amount: i128,
+        duration: u64,
+        compounding: CompoundFrequency,
+    ) -> Result<i128, SavingsError> {
+        lock::preview_maturity_amount(&env, amount, duration, compounding)
+    }
+
+    pub fn withdraw_lock_save(env: Env, user: Address, lock_id: u64) -> i128 {
+        lock::withdraw_lock_save(&env, user, lock_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Returns what withdrawing the lock would pay out now
+    pub fn preview_withdraw_lock(
+        env: Env,
+        lock_id: u64,
+    ) -> Result<WithdrawalPreview, SavingsError> {
+        lock::preview_withdraw_lock(&env, lock_id)
+    }
+
+    /// Returns the interest statement of an open lock
+    pub fn get_lock_statement(env: Env, lock_id: u64) -> Result<InterestStatement, SavingsError> {
+        lock::get_lock_statement(&env, lock_id)
+    }
+
+    /// Reassigns an open lock to another initialized user
+    pub fn transfer_lock_save(
+        env: Env,
+        from: Address,
+        to: Address,
+        lock_id: u64,
+    ) -> Result<(), SavingsError> {
+        lock::transfer_lock_save(&env, from, to, lock_id)
+    }
+
+    pub fn deposit_to_lock_save(env: Env, user: Address, lock_id: u64, amount: i128) {
+        lock::deposit_to_lock_save(&env, user, lock_id, amount)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Same as `deposit_to_lock_save`, but applied at most once per `key`
+    pub fn deposit_to_lock_save_idempotent(
+        env: Env,
+        user: Address,
+        lock_id: u64,
+        amount: i128,
+        key: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        idempotency::consume(&env, &user, key)?;
+        lock::deposit_to_lock_save(&env, user, lock_id, amount)
+    }
+
+    pub fn set_lock_rollover(env: Env, user: Address, lock_id: u64, enabled: bool) {
+        lock::set_lock_rollover(&env, user, lock_id, enabled)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Rolls matured, rollover-enabled locks into new locks; callable by anyone
+    pub fn process_matured_locks(env: Env, lock_ids: Vec<u64>) -> Vec<u64> {
+        lock::process_matured_locks(&env, lock_ids).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Moves matured, non-rollover locks into their owners' Flexi Save
+    /// balances; callable by anyone, who earns the keeper tip. Returns the IDs
+    /// of the swept locks
+    pub fn sweep_matured_locks(env: Env, keeper: Address, lock_ids: Vec<u64>) -> Vec<u64> {
+        lock::sweep_matured_locks(&env, keeper, lock_ids)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Deletes a withdrawn Lock or Goal Save to reclaim its storage, keeping
+    /// a compact summary in the owner's archived history
+    pub fn archive_plan(
+        env: Env,
+        user: Address,
+        plan_kind: PlanKind,
+        plan_id: u64,
+    ) -> Result<ArchivedPlanSummary, SavingsError> {
+        archive::archive_plan(&env, user, plan_kind, plan_id)
+    }
+
+    /// Returns a page of a user's archived plan summaries, oldest first
+    pub fn get_archived_plans(
+        env: Env,
+        user: Address,
+        cursor: Cursor,
+    ) -> (Vec<ArchivedPlanSummary>, Option<Cursor>) {
+        archive::get_archived_plans(&env, user, cursor)
+    }
+
+    pub fn check_matured_lock(env: Env, lock_id: u64) -> bool {
+        lock::check_matured_lock(&env, lock_id)
+    }
+
+    /// Balance, target and unlock state of a target-based Lock Save
+    pub fn get_target_lock_progress(
+        env: Env,
+        lock_id: u64,
+    ) -> Result<TargetLockProgress, SavingsError> {
+        lock::get_target_lock_progress(&env, lock_id)
+    }
+
+    pub fn get_lock_save_detail(env: Env, lock_id: u64) -> LockSave {
+        lock::get_lock_save(&env, lock_id)
+            .unwrap_or_else(|| panic_with_error!(&env, SavingsError::PlanNotFound))
+    }
+
+    pub fn get_user_lock_saves(env: Env, user: Address) -> Vec<u64> {
+        lock::get_user_lock_saves(&env, &user)
+    }
+
+    /// Returns a page of the user's Lock Save IDs
+    pub fn get_user_lock_saves_page(
+        env: Env,
+        user: Address,
+        cursor: Cursor,
+    ) -> (Vec<u64>, Option<Cursor>) {
+        pagination::paginate(&env, &lock::get_user_lock_saves(&env, &user), &cursor)
+    }
+
+    // ========== Goal Save Functions ==========
+
+    pub fn create_goal_save(
+        env: Env,
+        user: Address,
+        goal_name: Symbol,
+        target_amount: i128,
+        initial_deposit: i128,
+    ) -> u64 {
+        goal::create_goal_save(&env, user, goal_name, target_amount, initial_deposit, 0)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Same as `create_goal_save`, but applied at most once per `key`
+    pub fn create_goal_save_idempotent(
+        env: Env,
+        user: Address,
+        goal_name: Symbol,
+        target_amount: i128,
+        initial_deposit: i128,
+        key: BytesN<32>,
+    ) -> Result<u64, SavingsError> {
+        idempotency::consume(&env, &user, key)?;
+        goal::create_goal_save(&env, user, goal_name, target_amount, initial_deposit, 0)
+    }
+
+    /// Creates a Goal Save from an admin-signed payload on the user's behalf
+    pub fn create_goal_save_signed(
+        env: Env,
+        payload: GoalSavePayload,
+        signature: BytesN<64>,
+    ) -> Result<u64, SavingsError> {
+        sponsored::create_goal_save(&env, payload, signature)
+    }
+
+    /// Creates a Goal Save that stops accepting deposits after `deadline`
+    pub fn create_goal_save_with_deadline(
+        env: Env,
+        user: Address,
+        goal_name: Symbol,
+        target_amount: i128,
+        initial_deposit: i128,
+        deadline: u64,
+    ) -> u64 {
+        goal::create_goal_save(
+            &env,
+            user,
+            goal_name,
+            target_amount,
+            initial_deposit,
+            deadline,
+        )
+        .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Creates a Goal Save with its target in the oracle's reference unit
+    pub fn create_goal_save_fiat(
+        env: Env,
+        user: Address,
+        goal_name: Symbol,
+        target_fiat: i128,
+        initial_deposit: i128,
+        deadline: u64,
+    ) -> Result<u64, SavingsError> {
+        goal::create_goal_save_fiat(
+            &env,
+            user,
+            goal_name,
+            target_fiat,
+            initial_deposit,
+            deadline,
+        )
+    }
+
+    /// Goal progress in both tokens and the oracle's reference unit
+    pub fn get_goal_progress_fiat(
+        env: Env,
+        goal_id: u64,
+    ) -> Result<GoalFiatProgress, SavingsError> {
+        oracle::get_goal_progress_fiat(&env, goal_id)
+    }
+
+    pub fn deposit_to_goal_save(env: Env, user: Address, goal_id: u64, amount: i128) {
+        goal::deposit_to_goal_save(&env, user, goal_id, amount)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Same as `deposit_to_goal_save`, but applied at most once per `key`
+    pub fn deposit_to_goal_save_idempotent(
+        env: Env,
+        user: Address,
+        goal_id: u64,
+        amount: i128,
+        key: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        idempotency::consume(&env, &user, key)?;
+        goal::deposit_to_goal_save(&env, user, goal_id, amount)
+    }
+
+    pub fn withdraw_completed_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
+        goal::withdraw_completed_goal_save(&env, user, goal_id)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Returns what breaking the goal now would pay out
+    pub fn preview_break_goal(env: Env, goal_id: u64) -> Result<WithdrawalPreview, SavingsError> {
+        goal::preview_break_goal(&env, goal_id)
+    }
+
+    pub fn break_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
+        goal::break_goal_save(&env, user, goal_id).unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Hands an open goal and its savings to another initialized user
+    pub fn gift_goal_save(
+        env: Env,
+        from: Address,
+        to: Address,
+        goal_id: u64,
+    ) -> Result<(), SavingsError> {
+        goal::gift_goal_save(&env, from, to, goal_id)
+    }
+
+    /// Shares an open goal with another user, who can then deposit towards it
+    pub fn add_goal_co_owner(
+        env: Env,
+        owner: Address,
+        goal_id: u64,
+        co_owner: Address,
+    ) -> Result<(), SavingsError> {
+        goal::add_goal_co_owner(&env, owner, goal_id, co_owner)
+    }
+
+    /// Sets whose signatures withdrawing from a co-owned goal takes; every
+    /// owner must sign
+    pub fn set_goal_withdraw_policy(
+        env: Env,
+        owner: Address,
+        goal_id: u64,
+        policy: GoalWithdrawPolicy,
+    ) -> Result<(), SavingsError> {
+        goal::set_goal_withdraw_policy(&env, owner, goal_id, policy)
+    }
+
+    pub fn get_goal_co_owners(env: Env, goal_id: u64) -> Vec<Address> {
+        goal::get_goal_co_owners(&env, goal_id)
+    }
+
+    pub fn get_goal_withdraw_policy(env: Env, goal_id: u64) -> GoalWithdrawPolicy {
+        goal::get_goal_withdraw_policy(&env, goal_id)
+    }
+
+    /// Opens a goal to contributions from any initialized user, or closes it
+    pub fn set_goal_public(
+        env: Env,
+        owner: Address,
+        goal_id: u64,
+        is_public: bool,
+    ) -> Result<(), SavingsError> {
+        goal::set_goal_public(&env, owner, goal_id, is_public)
+    }
+
+    /// Puts `amount` towards someone else's public goal; only its owners can
+    /// withdraw it
+    pub fn contribute_to_goal(
+        env: Env,
+        contributor: Address,
+        goal_id: u64,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        goal::contribute_to_goal(&env, contributor, goal_id, amount)
+    }
+
+    /// Returns the outside contributors of a goal and how much each gave
+    pub fn get_goal_contributors(env: Env, goal_id: u64) -> Vec<GoalContribution> {
+        goal::get_goal_contributors(&env, goal_id)
+    }
+
+    /// Returns the funds of an expired goal without the early-break fee
+    pub fn reclaim_expired_goal_save(env: Env, user: Address, goal_id: u64) -> i128 {
+        goal::reclaim_expired_goal_save(&env, user, goal_id)
+            .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Closes out a goal past its deadline and releases its funds to the
+    /// owner. Callable by anyone, so keepers can settle stale goals.
+    pub fn finalize_goal(env: Env, goal_id: u64) -> Result<i128, SavingsError> {
+        goal::finalize_goal(&env, goal_id)
+    }
+
+    /// Adds a goal template to the catalog offered to every client
+    pub fn add_goal_template(
+        env: Env,
+        admin: Address,
+        name: Symbol,
+        suggested_target: i128,
+        suggested_duration: u64,
+        category: Symbol,
+    ) -> Result<u64, SavingsError> {
+        ensure_admin(&env, &admin)?;
+        goal_templates::add_goal_template(
+            &env,
+            name,
+            suggested_target,
+            suggested_duration,
+            category,
+        )
+    }
+
+    pub fn remove_goal_template(
+        env: Env,
+        admin: Address,
+        template_id: u64,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        goal_templates::remove_goal_template(&env, template_id)
+    }
+
+    /// Creates a Goal Save from a catalog template, with optional overrides
+    pub fn create_goal_from_template(
+        env: Env,
+        user: Address,
+        template_id: u64,
+        overrides: GoalTemplateOverrides,
+    ) -> Result<u64, SavingsError> {
+        goal_templates::create_goal_from_template(&env, user, template_id, overrides)
+    }
+
+    pub fn list_goal_templates(env: Env) -> Vec<GoalTemplate> {
+        goal_templates::list_goal_templates(&env)
+    }
+
+    pub fn get_goal_save_detail(env: Env, goal_id: u64) -> GoalSave {
+        goal::get_goal_save(&env, goal_id)
+            .unwrap_or_else(|| panic_with_error!(&env, SavingsError::PlanNotFound))
+    }
+
+    pub fn get_user_goal_saves(env: Env, user: Address) -> Vec<u64> {
+        goal::get_user_goal_saves(&env, &user)
+    }
+
+    /// Returns a page of the user's Goal Save IDs
+    pub fn get_user_goal_saves_page(
+        env: Env,
+        user: Address,
+        cursor: Cursor,
+    ) -> (Vec<u64>, Option<Cursor>) {
+        pagination::paginate(&env, &goal::get_user_goal_saves(&env, &user), &cursor)
+    }
+
+    /// Returns the progress milestones (25/50/75/100%) a goal has reached
+    pub fn get_goal_milestones(env: Env, goal_id: u64) -> Vec<GoalMilestone> {
+        goal::get_goal_milestones(&env, goal_id)
+    }
+
+    /// Lists the user's goals that passed their deadline and are still unclaimed
+    pub fn get_user_expired_goal_saves(env: Env, user: Address) -> Vec<GoalSave> {
+        goal::get_user_expired_goal_saves(&env, &user)
+    }
+
+    // --- Group Save Logic ---
+
+    pub fn create_group_save(
+        env: Env,
+        creator: Address,
+        metadata: GroupMetadata,
+        target_amount: i128,
+        contribution_type: u32,
+        contribution_amount: i128,
+        is_public: bool,
+        start_time: u64,
+        end_time: u64,
+        max_members: u32,
+    ) -> Result<u64, SavingsError> {
+        group::create_group_save(
+            &env,
+            creator,
+            metadata.title,
+            metadata.description,
+            metadata.category,
+            target_amount,
+            contribution_type,
+            contribution_amount,
+            is_public,
+            start_time,
+            end_time,
+            max_members,
+        )
+    }
+
+    /// Raises a group's member cap; only the creator, and only before it starts
+    pub fn set_group_max_members(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        max_members: u32,
+    ) -> Result<(), SavingsError> {
+        group::set_max_members(&env, creator, group_id, max_members)
+    }
+
+    /// Edits a group's descriptive fields and target before it starts
+    pub fn update_group_save(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        metadata: GroupMetadata,
+        target_amount: i128,
+    ) -> Result<(), SavingsError> {
+        group::update_group_save(&env, creator, group_id, metadata, target_amount)
+    }
+
+    /// Deletes a group nobody has contributed to yet
+    pub fn cancel_group_save(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+    ) -> Result<(), SavingsError> {
+        group::cancel_group_save(&env, creator, group_id)
+    }
+
+    pub fn join_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
+        group::join_group_save(&env, user, group_id)
+    }
+
+    /// Sets the minimum reputation and account age needed to join a public group
+    pub fn set_group_join_requirements(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        min_reputation: u32,
+        min_account_age: u64,
+    ) -> Result<(), SavingsError> {
+        group::set_join_requirements(&env, creator, group_id, min_reputation, min_account_age)
+    }
+
+    /// Sets the stake uninvited users contribute on joining a group
+    pub fn set_group_join_stake(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        stake: i128,
+    ) -> Result<(), SavingsError> {
+        group::set_join_stake(&env, creator, group_id, stake)
+    }
+
+    pub fn get_group_join_requirements(env: Env, group_id: u64) -> GroupJoinRequirements {
+        group::get_join_requirements(&env, group_id)
+    }
+
+    pub fn contribute_to_group_save(
+        env: Env,
+        user: Address,
+        group_id: u64,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        group::contribute_to_group_save(&env, user, group_id, amount)
+    }
+
+    /// Same as `contribute_to_group_save`, but applied at most once per `key`
+    pub fn contribute_to_group_idempotent(
+        env: Env,
+        user: Address,
+        group_id: u64,
+        amount: i128,
+        key: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        idempotency::consume(&env, &user, key)?;
+        group::contribute_to_group_save(&env, user, group_id, amount)
+    }
+
+    /// Returns a page of a group's contribution history, oldest first
+    pub fn get_group_contribution_history(
+        env: Env,
+        group_id: u64,
+        cursor: Cursor,
+    ) -> (Vec<GroupContribution>, Option<Cursor>) {
+        group::get_contribution_history(&env, group_id, cursor)
+    }
+
+    /// Returns a page of a group's member addresses
+    pub fn get_group_members_page(
+        env: Env,
+        group_id: u64,
+        cursor: Cursor,
+    ) -> (Vec<Address>, Option<Cursor>) {
+        pagination::paginate(&env, &group::get_group_members(&env, group_id), &cursor)
+    }
+
+    /// Returns a page of the group IDs a user participates in
+    pub fn get_user_groups_page(
+        env: Env,
+        user: Address,
+        cursor: Cursor,
+    ) -> (Vec<u64>, Option<Cursor>) {
+        pagination::paginate(&env, &group::get_user_groups(&env, &user), &cursor)
+    }
+
+    /// Returns a page of public groups, oldest first
+    pub fn list_public_groups(env: Env, cursor: Cursor) -> (Vec<GroupSave>, Option<Cursor>) {
+        group::list_public_groups(&env, cursor)
+    }
+
+    /// Returns a page of the public groups in a category, oldest first
+    pub fn list_public_groups_by_category(
+        env: Env,
+        category: String,
+        cursor: Cursor,
+    ) -> (Vec<GroupSave>, Option<Cursor>) {
+        group::list_public_groups_by_category(&env, category, cursor)
+    }
+
+    /// Flags a group as disputed; enough distinct flags freeze its funds
+    pub fn flag_group_dispute(
+        env: Env,
+        member: Address,
+        group_id: u64,
+        reason_code: u32,
+    ) -> Result<(), SavingsError> {
+        group::flag_group_dispute(&env, member, group_id, reason_code)
+    }
+
+    pub fn resolve_dispute(
+        env: Env,
+        admin: Address,
+        group_id: u64,
+        outcome: DisputeOutcome,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        group::resolve_dispute(&env, group_id, outcome)
+    }
+
+    pub fn get_group_dispute(env: Env, group_id: u64) -> GroupDispute {
+        group::get_dispute(&env, group_id)
+    }
+
+    pub fn set_dispute_threshold(
+        env: Env,
+        admin: Address,
+        threshold: u32,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        group::set_dispute_threshold(&env, threshold)?;
+        events::publish(&env, events::DisputeThresholdSet { threshold });
+        Ok(())
+    }
+
+    pub fn get_dispute_threshold(env: Env) -> u32 {
+        group::get_dispute_threshold(&env)
+    }
+
+    /// Sets the shortest window and furthest end date allowed for new groups
+    pub fn set_group_duration_bounds(
+        env: Env,
+        admin: Address,
+        min_duration: u64,
+        max_horizon: u64,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        let bounds = GroupDurationBounds {
+            min_duration,
+            max_horizon,
+        };
+        group::set_duration_bounds(&env, bounds.clone())?;
+        events::publish(&env, events::GroupDurationBoundsSet { bounds });
+        Ok(())
+    }
+
+    pub fn get_group_duration_bounds(env: Env) -> GroupDurationBounds {
+        group::get_duration_bounds(&env)
+    }
+
+    /// Sets the most a user may withdraw per 24h window, in reference units
+    /// (see `register_asset`); 0 removes the cap
+    pub fn set_withdraw_limit(env: Env, admin: Address, limit: i128) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        limits::set_withdraw_limit(&env, limit)?;
+        events::publish(&env, events::WithdrawLimitSet { limit });
+        Ok(())
+    }
+
+    /// Sets the smallest amount any deposit or initial plan deposit may add,
+    /// in reference units (see `register_asset`); 0 removes the minimum
+    pub fn set_minimum_deposit(env: Env, admin: Address, amount: i128) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        limits::set_minimum_deposit(&env, amount)?;
+        events::publish(&env, events::MinimumDepositSet { amount });
+        Ok(())
+    }
+
+    pub fn get_minimum_deposit(env: Env) -> i128 {
+        limits::get_minimum_deposit(&env)
+    }
+
+    /// Sets the balance below which finished plans are swept into Flexi by
+    /// `consolidate_dust`, in reference units; 0 disables sweeping
+    pub fn set_dust_threshold(
+        env: Env,
+        admin: Address,
+        threshold: i128,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        limits::set_dust_threshold(&env, threshold)?;
+        events::publish(&env, events::DustThresholdSet { threshold });
+        Ok(())
+    }
+
+    pub fn get_dust_threshold(env: Env) -> i128 {
+        limits::get_dust_threshold(&env)
+    }
+
+    /// Sweeps the user's completed goals and matured locks holding less than
+    /// the dust threshold into their Flexi balance. Returns the total moved
+    pub fn consolidate_dust(env: Env, user: Address) -> Result<i128, SavingsError> {
+        convert::consolidate_dust(&env, user)
+    }
+
+    /// Caps how many open plans of each kind a single user may hold
+    pub fn set_plan_limits(
+        env: Env,
+        admin: Address,
+        limits: PlanLimits,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        limits::set_plan_limits(&env, &limits);
+        events::publish(&env, events::PlanLimitsSet { limits });
+        Ok(())
+    }
+
+    /// Returns the per-user plan caps (0 = no cap)
+    pub fn get_plan_limits(env: Env) -> PlanLimits {
+        limits::get_plan_limits(&env)
+    }
+
+    pub fn get_withdraw_limit(env: Env) -> i128 {
+        limits::get_withdraw_limit(&env)
+    }
+
+    /// Amount `user` can still withdraw in the current window
+    pub fn get_remaining_withdraw_allowance(env: Env, user: Address) -> i128 {
+        limits::get_remaining_withdraw_allowance(&env, &user)
+    }
+
+    /// Posts an on-chain message from the creator or a group admin to the
+    /// group's members
+    pub fn post_group_announcement(
+        env: Env,
+        caller: Address,
+        group_id: u64,
+        message: String,
+    ) -> Result<(), SavingsError> {
+        group::post_group_announcement(&env, caller, group_id, message)
+    }
+
+    /// Makes a member a co-admin of the group; creator only
+    pub fn add_group_admin(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        admin: Address,
+    ) -> Result<(), SavingsError> {
+        group::add_group_admin(&env, creator, group_id, admin)
+    }
+
+    pub fn remove_group_admin(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        admin: Address,
+    ) -> Result<(), SavingsError> {
+        group::remove_group_admin(&env, creator, group_id, admin)
+    }
+
+    pub fn get_group_admins(env: Env, group_id: u64) -> Vec<Address> {
+        group::get_group_admins(&env, group_id)
+    }
+
+    /// Lets a user join the group even if it is private
+    pub fn invite_to_group(
+        env: Env,
+        caller: Address,
+        group_id: u64,
+        user: Address,
+    ) -> Result<(), SavingsError> {
+        group::invite_to_group(&env, caller, group_id, user)
+    }
+
+    /// Removes a member from an open group, refunding their contribution to
+    /// their Flexi Save
+    pub fn remove_group_member(
+        env: Env,
+        caller: Address,
+        group_id: u64,
+        member: Address,
+    ) -> Result<i128, SavingsError> {
+        group::remove_group_member(&env, caller, group_id, member)
+    }
+
+    pub fn get_group_announcements(env: Env, group_id: u64) -> Vec<GroupAnnouncement> {
+        group::get_group_announcements(&env, group_id)
+    }
+
+    // --- Group Governance ---
+
+    /// Creator opens a member vote on changing a group parameter
+    pub fn propose_group_change(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        change: GroupChange,
+    ) -> Result<u64, SavingsError> {
+        governance::propose_group_change(&env, creator, group_id, change)
+    }
+
+    pub fn vote_group_change(
+        env: Env,
+        member: Address,
+        proposal_id: u64,
+        approve: bool,
+    ) -> Result<(), SavingsError> {
+        governance::vote_group_change(&env, member, proposal_id, approve)
+    }
+
+    /// Applies a proposal whose approvals have reached the quorum
+    pub fn execute_group_change(env: Env, proposal_id: u64) -> Result<GroupSave, SavingsError> {
+        governance::execute_group_change(&env, proposal_id)
+    }
+
+    pub fn get_group_proposal(env: Env, proposal_id: u64) -> Result<GroupProposal, SavingsError> {
+        governance::get_proposal(&env, proposal_id).ok_or(SavingsError::PlanNotFound)
+    }
+
+    pub fn get_group_proposals(env: Env, group_id: u64) -> Vec<u64> {
+        governance::get_group_proposals(&env, group_id)
+    }
+
+    /// Sets the share of members, in basis points, that must approve a proposal
+    pub fn set_group_quorum_bps(env: Env, admin: Address, bps: u32) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        governance::set_quorum_bps(&env, bps)?;
+        events::publish(&env, events::GroupQuorumSet { bps });
+        Ok(())
+    }
+
+    pub fn get_group_quorum_bps(env: Env) -> u32 {
+        governance::get_quorum_bps(&env)
+    }
+
+    /// Sets the length of a group's contribution rounds (0 = no schedule)
+    pub fn set_group_schedule(
+        env: Env,
+        creator: Address,
+        group_id: u64,
+        frequency: u64,
+    ) -> Result<(), SavingsError> {
+        group::set_group_schedule(&env, creator, group_id, frequency)
+    }
+
+    pub fn get_group_round_status(
+        env: Env,
+        group_id: u64,
+    ) -> Result<GroupRoundStatus, SavingsError> {
+        group::get_round_status(&env, group_id)
+    }
+
+    /// Groups of `user` with an unpaid contribution due within `within`
+    /// seconds, for reminder notifications
+    pub fn get_due_group_contributions(
+        env: Env,
+        user: Address,
+        within: u64,
+    ) -> Vec<DueGroupContribution> {
+        group::get_groups_with_due_contributions(&env, &user, within)
+    }
+
+    pub fn get_member_missed_rounds(
+        env: Env,
+        group_id: u64,
+        member: Address,
+    ) -> Result<Vec<u32>, SavingsError> {
+        group::get_member_missed_rounds(&env, group_id, &member)
+    }
+
+    /// Keeper entrypoint: closes ended rounds and reports their defaulters
+    pub fn close_group_rounds(env: Env, group_id: u64) -> Result<u32, SavingsError> {
+        group::close_group_rounds(&env, group_id)
+    }
+
+    /// Keeper entrypoint: refunds a page of members of a failed or cancelled
+    /// group into their Flexi Save balances; returns how many were refunded
+    pub fn refund_group_members(
+        env: Env,
+        group_id: u64,
+        offset: u32,
+        limit: u32,
+    ) -> Result<u32, SavingsError> {
+        group::refund_group_members(&env, group_id, offset, limit)
+    }
+
+    /// Reclaims the member's contribution to a failed or cancelled group into
+    /// their Flexi Save balance; returns the amount refunded
+    pub fn refund_group_contribution(
+        env: Env,
+        member: Address,
+        group_id: u64,
+    ) -> Result<i128, SavingsError> {
+        group::refund_group_contribution(&env, member, group_id)
+    }
+
+    /// Returns whether a group is active, completed or failed
+    pub fn get_group_status(env: Env, group_id: u64) -> Result<GroupStatus, SavingsError> {
+        let group = group::get_group_save(&env, group_id).ok_or(SavingsError::PlanNotFound)?;
+        Ok(group::get_group_status(&env, &group))
+    }
+
+    /// Returns a member's view of a group: their contribution, the places
+    /// left and the group's status
+    pub fn get_group_save_view(
+        env: Env,
+        user: Address,
+        group_id: u64,
+    ) -> Result<GroupSaveView, SavingsError> {
+        views::get_group_save(&env, user, group_id)
+    }
+
+    pub fn break_group_save(env: Env, user: Address, group_id: u64) -> Result<(), SavingsError> {
+        group::break_group_save(&env, user, group_id)
+    }
+
+    // --- Admin Control Functions ---
+
+    pub fn set_admin(
+        env: Env,
+        current_admin: Address,
+        new_admin: Address,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &current_admin)?;
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        events::publish(&env, events::AdminChanged { new_admin });
+        Ok(())
+    }
+
+    pub fn grant_role(
+        env: Env,
+        admin: Address,
+        addr: Address,
+        role: Role,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        roles::grant_role(&env, &addr, role);
+        events::publish(&env, events::RoleGranted { addr, role });
+        Ok(())
+    }
+
+    pub fn revoke_role(
+        env: Env,
+        admin: Address,
+        addr: Address,
+        role: Role,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        roles::revoke_role(&env, &addr, role);
+        events::publish(&env, events::RoleRevoked { addr, role });
+        Ok(())
+    }
+
+    pub fn has_role(env: Env, addr: Address, role: Role) -> bool {
+        roles::has_role(&env, &addr, role)
+    }
+
+    /// Replaces the contract code with the uploaded WASM identified by `new_wasm_hash`
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Upgrader)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        timelock::ensure_direct_allowed(&env)?;
+        timelock::apply(&env, &AdminAction::Upgrade(new_wasm_hash.clone()))?;
+        events::publish(
+            &env,
+            events::Upgraded {
+                caller,
+                new_wasm_hash,
+            },
+        );
+        Ok(())
+    }
+
+    /// Migrates up to `batch_size` entries towards the current storage
+    /// layout. Call repeatedly after an upgrade until the returned version
+    /// reaches the current one.
+    pub fn run_migration(env: Env, admin: Address, batch_size: u32) -> Result<u32, SavingsError> {
+        ensure_admin(&env, &admin)?;
+        migrations::run_migration(&env, batch_size)
+    }
+
+    /// Moves the user's legacy `SavingsPlan` records onto the dedicated plan
+    /// structs. Returns the number of records imported.
+    pub fn migrate_legacy_plans(
+        env: Env,
+        admin: Address,
+        user: Address,
+    ) -> Result<u32, SavingsError> {
+        ensure_admin(&env, &admin)?;
+        migrations::migrate_legacy_plans(&env, &user)
+    }
+
+    /// ID the next Lock or Goal Save of `plan_kind` created by `user` will get
+    pub fn get_next_plan_id(
+        env: Env,
+        user: Address,
+        plan_kind: PlanKind,
+    ) -> Result<u64, SavingsError> {
+        plan_ids::peek_plan_id(&env, &user, plan_kind)
+    }
+
+    /// Sequence number the next event will carry. Every event's data is
+    /// `(sequence, payload)`, so indexers can detect missed events.
+    pub fn get_event_cursor(env: Env) -> u64 {
+        events::get_event_cursor(&env)
+    }
+
+    pub fn get_storage_version(env: Env) -> u32 {
+        migrations::get_storage_version(&env)
+    }
+
+    /// Sets the delay critical admin actions must wait between being queued
+    /// and executed. Once non-zero, changing it is itself timelocked.
+    pub fn set_admin_timelock(env: Env, admin: Address, seconds: u64) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        timelock::ensure_direct_allowed(&env)?;
+        timelock::apply(&env, &AdminAction::SetTimelock(seconds))?;
+        events::publish(&env, events::TimelockSet { seconds });
+        Ok(())
+    }
+
+    pub fn get_admin_timelock(env: Env) -> u64 {
+        timelock::get_timelock(&env)
+    }
+
+    /// Announces a critical admin action; returns its ID for later execution
+    pub fn queue_admin_action(
+        env: Env,
+        admin: Address,
+        action: AdminAction,
+    ) -> Result<u64, SavingsError> {
+        ensure_admin(&env, &admin)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        timelock::queue(&env, action)
+    }
+
+    /// Applies a queued action once its timelock has elapsed. Callable by anyone.
+    pub fn execute_admin_action(env: Env, action_id: u64) -> Result<AdminAction, SavingsError> {
+        timelock::execute(&env, action_id)
+    }
+
+    pub fn cancel_admin_action(
+        env: Env,
+        admin: Address,
+        action_id: u64,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        timelock::cancel(&env, action_id)
+    }
+
+    pub fn get_pending_admin_action(env: Env, action_id: u64) -> Option<PendingAdminAction> {
+        timelock::get_pending(&env, action_id)
+    }
+
+    /// Hands upgrades, fee and treasury changes, campaigns and emergency
+    /// withdrawals over to an m-of-n signer set. Once set, those single-key
+    /// entrypoints fail with `MultisigRequired` and the set can only change
+    /// through a proposal.
+    pub fn init_multisig(
+        env: Env,
+        admin: Address,
+        config: MultisigConfig,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        multisig::init(&env, config)
+    }
+
+    pub fn get_multisig_config(env: Env) -> Option<MultisigConfig> {
+        multisig::get_config(&env)
+    }
+
+    /// Opens a multisig proposal carrying the proposer's approval; returns its ID
+    pub fn propose_multisig_action(
+        env: Env,
+        signer: Address,
+        action: MultisigAction,
+    ) -> Result<u64, SavingsError> {
+        multisig::propose(&env, signer, action)
+    }
+
+    /// Approves a multisig proposal; returns whether this executed it
+    pub fn approve_multisig_action(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<bool, SavingsError> {
+        multisig::approve(&env, signer, proposal_id)
+    }
+
+    pub fn get_multisig_proposal(env: Env, proposal_id: u64) -> Option<MultisigProposal> {
+        multisig::get_proposal(&env, proposal_id)
+    }
+
+    pub fn set_user_reputation(env: Env, user: Address, score: u32) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        users::set_reputation(&env, &user, score)?;
+        events::publish(&env, events::ReputationSet { user, score });
+        Ok(())
+    }
+
+    pub fn get_user_reputation(env: Env, user: Address) -> u32 {
+        users::get_reputation(&env, &user)
+    }
+
+    pub fn set_referral_reward_bps(
+        env: Env,
+        caller: Address,
+        bps: u32,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        users::set_referral_reward_bps(&env, bps)?;
+        events::publish(&env, events::ReferralRewardSet { bps });
+        Ok(())
+    }
+
+    pub fn get_referral_reward_bps(env: Env) -> u32 {
+        users::get_referral_reward_bps(&env)
+    }
+
+    pub fn set_streak_period(env: Env, period: StreakPeriod) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        streaks::set_streak_period(&env, period.clone());
+        events::publish(&env, events::StreakPeriodSet { period });
+        Ok(())
+    }
+
+    /// Enables or disables every deprecated entrypoint at once
+    pub fn set_legacy_entrypoints_enabled(env: Env, enabled: bool) -> Result<(), SavingsError> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        deprecation::set_legacy_entrypoints_enabled(&env, enabled);
+        events::publish(&env, events::LegacyEntrypointsSet { enabled });
+        Ok(())
+    }
+
+    pub fn legacy_entrypoints_enabled(env: Env) -> bool {
+        deprecation::legacy_entrypoints_enabled(&env)
+    }
+
+    pub fn set_flexi_rate(env: Env, caller: Address, rate: i128) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::RateManager)?;
+        rates::set_flexi_rate(&env, rate)
+    }
+
+    pub fn set_goal_rate(env: Env, caller: Address, rate: i128) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::RateManager)?;
+        rates::set_goal_rate(&env, rate)
+    }
+
+    pub fn set_group_rate(env: Env, caller: Address, rate: i128) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::RateManager)?;
+        rates::set_group_rate(&env, rate)
+    }
+
+    pub fn set_lock_rate(
+        env: Env,
+        caller: Address,
+        duration_days: u64,
+        rate: i128,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::RateManager)?;
+        rates::set_lock_rate(&env, duration_days, rate)
+    }
+
+    /// Schedules the rate new Lock Saves get from `effective_at`; open locks
+    /// keep their original rate
+    pub fn schedule_rate_change(
+        env: Env,
+        caller: Address,
+        rate_bps: u32,
+        effective_at: u64,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::RateManager)?;
+        rates::schedule_rate_change(&env, rate_bps, effective_at)
+    }
+
+    /// Rate, in basis points, a Lock Save created at `timestamp` is fixed at
+    pub fn get_rate_at(env: Env, timestamp: u64) -> u32 {
+        rates::get_rate_at(&env, timestamp)
+    }
+
+    pub fn get_lock_rate_schedule(env: Env) -> Vec<ScheduledRate> {
+        rates::get_lock_rate_schedule(&env)
+    }
+
+    pub fn set_early_break_fee_bps(
+        env: Env,
+        caller: Address,
+        bps: u32,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        if bps > Self::get_early_break_fee_bps(env.clone()) {
+            timelock::ensure_direct_allowed(&env)?;
+        }
+        timelock::apply(&env, &AdminAction::SetEarlyBreakFeeBps(bps))?;
+        events::publish(&env, events::EarlyBreakFeeSet { bps });
+        Ok(())
+    }
+
+    pub fn set_fee_recipient(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        timelock::ensure_direct_allowed(&env)?;
+        timelock::apply(&env, &AdminAction::SetFeeRecipient(recipient.clone()))?;
+        events::publish(&env, events::FeeRecipientSet { recipient });
+        Ok(())
+    }
+
+    pub fn pause(env: Env, caller: Address) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Pauser)?;
+
+        env.storage().persistent().set(&DataKey::Paused, &true);
+        events::publish(&env, events::ContractPaused { caller });
+        Ok(())
+    }
+
+    pub fn unpause(env: Env, caller: Address) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Pauser)?;
+
+        env.storage().persistent().set(&DataKey::Paused, &false);
+        events::publish(&env, events::ContractUnpaused { caller });
+        Ok(())
+    }
+
+    /// Moves protocol-owned funds (treasury fees, then the yield pool) to
+    /// `recovery`, never user principal. Works while paused. Returns the
+    /// protocol-owned balance left
+    pub fn emergency_withdraw(
+        env: Env,
+        admin: Address,
+        recovery: Address,
+        amount: i128,
+    ) -> Result<i128, SavingsError> {
+        ensure_admin(&env, &admin)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        emergency::emergency_withdraw(&env, &admin, &recovery, amount)
+    }
+
+    /// Turns withdrawal-only mode on or off; while on, users can exit through
+    /// `emergency_user_withdraw` even when the contract is paused
+    pub fn set_withdrawal_only_mode(
+        env: Env,
+        caller: Address,
+        enabled: bool,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Pauser)?;
+        emergency::set_withdrawal_only_mode(&env, enabled);
+        events::publish(&env, events::WithdrawalOnlyModeSet { enabled });
+        Ok(())
+    }
+
+    pub fn is_withdrawal_only_mode(env: Env) -> bool {
+        emergency::is_withdrawal_only_mode(&env)
+    }
+
+    /// Withdraws the user's Flexi balance and matured locks in withdrawal-only
+    /// mode, even while paused. Returns the total paid out net of fees
+    pub fn emergency_user_withdraw(env: Env, user: Address) -> Result<i128, SavingsError> {
+        emergency::emergency_user_withdraw(&env, user)
+    }
+
+    /// Treasury fees plus the yield pool: the most `emergency_withdraw` can move
+    pub fn get_protocol_owned_balance(env: Env) -> i128 {
+        emergency::get_protocol_owned_balance(&env)
+    }
+
+    /// Sets who may deposit more than `threshold` in a single deposit
+    pub fn set_compliance_mode(
+        env: Env,
+        admin: Address,
+        mode: ComplianceMode,
+        threshold: i128,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        compliance::set_compliance_mode(&env, mode, threshold)
+    }
+
+    pub fn get_compliance_config(env: Env) -> ComplianceConfig {
+        compliance::get_compliance_config(&env)
+    }
+
+    pub fn add_to_allowlist(env: Env, admin: Address, user: Address) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        compliance::set_allowlisted(&env, &user, true);
+        Ok(())
+    }
+
+    /// Adds every user in `users` to the compliance allowlist
+    pub fn add_to_allowlist_batch(
+        env: Env,
+        admin: Address,
+        users: Vec<Address>,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        for user in users.iter() {
+            compliance::set_allowlisted(&env, &user, true);
+        }
+        Ok(())
+    }
+
+    pub fn remove_from_allowlist(
+        env: Env,
+        admin: Address,
+        user: Address,
+    ) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        compliance::set_allowlisted(&env, &user, false);
+        Ok(())
+    }
+
+    /// Whether the user may currently make deposits above the compliance
+    /// threshold
+    pub fn is_allowed(env: Env, user: Address) -> bool {
+        compliance::is_allowed(&env, &user)
+    }
+
+    // --- Yield Pool ---
+
+    /// Deposits `amount` of the deposit token from `admin` into the pool
+    /// interest payouts are drawn from
+    pub fn fund_yield_pool(env: Env, admin: Address, amount: i128) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        yield_pool::fund(&env, &admin, amount)
+    }
+
+    /// Moves accumulated protocol fees into the yield pool
+    pub fn fund_yield_pool_from_fees(
+        env: Env,
+        treasurer: Address,
+        amount: i128,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &treasurer, Role::Treasurer)?;
+        yield_pool::fund_from_fees(&env, amount)
+    }
+
+    /// Pool balance against the interest promised to open plans
+    pub fn get_yield_pool_status(env: Env) -> YieldPoolStatus {
+        yield_pool::get_status(&env)
+    }
+
+    // --- Liquidity Management ---
+
+    /// Sets the price oracle fiat-denominated goals are valued with
+    pub fn set_oracle(env: Env, admin: Address, oracle: Address) -> Result<(), SavingsError> {
+        ensure_admin(&env, &admin)?;
+        oracle::set_oracle(&env, &oracle);
+        events::publish(&env, events::OracleSet { oracle });
+        Ok(())
+    }
+
+    /// Records the decimals `token` reports so amount thresholds, which are
+    /// set in 7-decimal reference units, are scaled to its base units
+    pub fn register_asset(
+        env: Env,
+        caller: Address,
+        token: Address,
+    ) -> Result<AssetConfig, SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        assets::register_asset(&env, token)
+    }
+
+    /// Returns the decimals recorded for `token`, for formatting its amounts
+    pub fn get_asset_config(env: Env, token: Address) -> Option<AssetConfig> {
+        assets::get_asset_config(&env, &token)
+    }
+
+    pub fn set_token(env: Env, caller: Address, token: Address) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        timelock::ensure_direct_allowed(&env)?;
+        timelock::apply(&env, &AdminAction::SetToken(token.clone()))?;
+        events::publish(&env, events::TokenSet { token });
+        Ok(())
+    }
+
+    pub fn set_strategy(env: Env, caller: Address, strategy: Address) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        timelock::ensure_direct_allowed(&env)?;
+        timelock::apply(&env, &AdminAction::SetStrategy(strategy.clone()))?;
+        events::publish(&env, events::StrategySet { strategy });
+        Ok(())
+    }
+
+    pub fn set_target_liquidity_bps(
+        env: Env,
+        caller: Address,
+        bps: u32,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        strategy::set_target_liquidity_bps(&env, bps)?;
+        events::publish(&env, events::TargetLiquiditySet { bps });
+        Ok(())
+    }
+
+    /// Keeper entrypoint: sweeps idle flexi funds into the strategy, or pulls
+    /// them back, until the target liquidity ratio is restored
+    pub fn rebalance(env: Env) -> Result<LiquidityStatus, SavingsError> {
+        strategy::rebalance(&env)
+    }
+
+    /// Supplies `amount` to the strategy, or withdraws `-amount` from it,
+    /// bypassing the liquidity target
+    pub fn move_strategy_funds(
+        env: Env,
+        caller: Address,
+        amount: i128,
+    ) -> Result<LiquidityStatus, SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        strategy::move_funds(&env, amount)
+    }
+
+    /// Sets the share of realized strategy yield, in basis points, that goes
+    /// to the treasury; the rest funds the yield pool
+    pub fn set_strategy_yield_split(
+        env: Env,
+        caller: Address,
+        treasury_bps: u32,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::Treasurer)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        strategy::set_yield_treasury_bps(&env, treasury_bps)?;
+        events::publish(&env, events::StrategyYieldSplitSet { treasury_bps });
+        Ok(())
+    }
+
+    pub fn get_strategy_yield_split(env: Env) -> u32 {
+        strategy::get_yield_treasury_bps(&env)
+    }
+
+    /// Collects strategy earnings above the invested principal
+    pub fn harvest_strategy_yield(env: Env) -> Result<StrategyHarvest, SavingsError> {
+        strategy::harvest(&env)
+    }
+
+    pub fn get_liquidity_status(env: Env) -> LiquidityStatus {
+        strategy::get_liquidity_status(&env)
+    }
+
+    // --- Remaining views and utilities ---
+    /// Returns any one of the user's plans by kind and ID, so clients need
+    /// not pick between the per-kind getters
+    pub fn get_plan(
+        env: Env,
+        user: Address,
+        plan_ref: PlanRef,
+    ) -> Result<PlanDetail, SavingsError> {
+        views::get_plan(&env, user, plan_ref)
+    }
+
+    /// Returns a legacy plan record that `migrate_legacy_plans` has not yet
+    /// moved onto the dedicated plan structs
+    pub fn get_savings_plan(env: Env, user: Address, plan_id: u64) -> Option<SavingsPlan> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SavingsPlan(user, plan_id))
+    }
+
+    /// Returns every admin-tunable setting in one struct, for config drift checks
+    pub fn get_protocol_params(env: Env) -> ProtocolParams {
+        params::get_protocol_params(&env)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    pub fn get_flexi_rate(env: Env) -> i128 {
+        rates::get_flexi_rate(&env)
+    }
+
+    pub fn get_goal_rate(env: Env) -> i128 {
+        rates::get_goal_rate(&env)
+    }
+
+    pub fn get_group_rate(env: Env) -> i128 {
+        rates::get_group_rate(&env)
+    }
+
+    pub fn get_lock_rate(env: Env, duration_days: u64) -> Result<i128, SavingsError> {
+        rates::get_lock_rate(&env, duration_days)
+    }
+
+    /// Returns the log of default rate changes for a plan kind, oldest first
+    pub fn get_rate_history(env: Env, plan_kind: PlanKind) -> Vec<RateChange> {
+        rates::get_rate_history(&env, plan_kind)
+    }
+
+    /// Sets the fee charged on Flexi, Lock and Goal withdrawals. Raising it is
+    /// timelocked once a timelock is configured.
+    pub fn set_withdrawal_fee(
+        env: Env,
+        caller: Address,
+        fee: WithdrawalFeeConfig,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        let current = fees::get_withdrawal_fee(&env);
+        if fee.bps > current.bps || fee.flat > current.flat {
+            timelock::ensure_direct_allowed(&env)?;
+        }
+        timelock::apply(&env, &AdminAction::SetWithdrawalFee(fee.clone()))?;
+        events::publish(&env, events::WithdrawalFeeSet { fee });
+        Ok(())
+    }
+
+    pub fn get_withdrawal_fee(env: Env) -> WithdrawalFeeConfig {
+        fees::get_withdrawal_fee(&env)
+    }
+
+    /// Replaces the tiered withdrawal fee schedule; an empty schedule falls
+    /// back to the flat config's rate. Raising the fee on any amount is
+    /// timelocked once a timelock is configured.
+    pub fn set_fee_tiers(
+        env: Env,
+        caller: Address,
+        tiers: Vec<FeeTier>,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        if fees::fee_tiers_raise(&env, &tiers) {
+            timelock::ensure_direct_allowed(&env)?;
+        }
+        timelock::apply(&env, &AdminAction::SetFeeTiers(tiers.clone()))?;
+        events::publish(&env, events::FeeTiersSet { tiers });
+        Ok(())
+    }
+
+    pub fn get_fee_tiers(env: Env) -> Vec<FeeTier> {
+        fees::get_fee_tiers(&env)
+    }
+
+    /// Returns the withdrawal fee charged on `amount`, before exemptions
+    pub fn get_fee_for_amount(env: Env, amount: i128) -> Result<i128, SavingsError> {
+        fees::get_fee_for_amount(&env, amount)
+    }
+
+    /// Grants or revokes an exemption from the withdrawal fee for a user or
+    /// for every plan of a kind
+    pub fn set_fee_exemption(
+        env: Env,
+        caller: Address,
+        exemption: FeeExemption,
+        exempt: bool,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        if fees::set_fee_exemption(&env, &exemption, exempt) {
+            events::publish(&env, events::FeeExemptionSet { exempt, exemption });
+        }
+        Ok(())
+    }
+
+    /// Returns a page of the withdrawal fee exemptions in force
+    pub fn get_fee_exemptions(env: Env, cursor: Cursor) -> (Vec<FeeExemption>, Option<Cursor>) {
+        fees::get_fee_exemptions(&env, cursor)
+    }
+
+    /// Whether withdrawals by `user` out of a `plan_kind` plan skip the
+    /// withdrawal fee
+    pub fn is_fee_exempt(env: Env, user: Address, plan_kind: PlanKind) -> bool {
+        fees::is_fee_exempt(&env, &user, plan_kind)
+    }
+
+    /// Sets the tip paid from the treasury to callers of keeper batches.
+    /// Raising it is timelocked once a timelock is configured.
+    pub fn set_keeper_tip(
+        env: Env,
+        caller: Address,
+        tip: KeeperTipConfig,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        let current = fees::get_keeper_tip(&env);
+        if tip.flat > current.flat || tip.max_per_ledger > current.max_per_ledger {
+            timelock::ensure_direct_allowed(&env)?;
+        }
+        fees::set_keeper_tip(&env, &tip)?;
+        events::publish(&env, events::KeeperTipSet { tip });
+        Ok(())
+    }
+
+    pub fn get_keeper_tip(env: Env) -> KeeperTipConfig {
+        fees::get_keeper_tip(&env)
+    }
+
+    /// Sets whether goals finalized short of their target forfeit the
+    /// early-break fee. Enabling it is timelocked once a timelock is configured.
+    pub fn set_failed_goal_penalty(
+        env: Env,
+        caller: Address,
+        enabled: bool,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        if enabled && !goal::get_failed_goal_penalty(&env) {
+            timelock::ensure_direct_allowed(&env)?;
+        }
+        goal::set_failed_goal_penalty(&env, enabled);
+        events::publish(&env, events::FailedGoalPenaltySet { enabled });
+        Ok(())
+    }
+
+    pub fn get_failed_goal_penalty(env: Env) -> bool {
+        goal::get_failed_goal_penalty(&env)
+    }
+
+    /// Sets how long after creation a goal can be broken without the
+    /// early-break fee. Shortening it is timelocked once a timelock is
+    /// configured.
+    pub fn set_goal_break_grace(
+        env: Env,
+        caller: Address,
+        seconds: u64,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &caller, Role::FeeManager)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        if seconds < goal::get_goal_break_grace(&env) {
+            timelock::ensure_direct_allowed(&env)?;
+        }
+        timelock::apply(&env, &AdminAction::SetGoalBreakGrace(seconds))?;
+        events::publish(&env, events::GoalBreakGraceSet { seconds });
+        Ok(())
+    }
+
+    pub fn get_goal_break_grace(env: Env) -> u64 {
+        goal::get_goal_break_grace(&env)
+    }
+
+    pub fn get_early_break_fee_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::EarlyBreakFeeBps)
+            .unwrap_or(0)
+    }
+
+    pub fn get_fee_recipient(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::FeeRecipient)
+    }
+
+    /// Total protocol fees `user` has paid across all operations
+    pub fn get_user_lifetime_fees(env: Env, user: Address) -> i128 {
+        fees::get_user_lifetime_fees(&env, &user)
+    }
+
+    pub fn get_protocol_fee_balance(env: Env, recipient: Address) -> i128 {
+        fees::get_fee_balance(&env, &recipient)
+    }
+
+    /// Fees held by the contract across every current and past fee recipient
+    pub fn get_total_protocol_fee_balance(env: Env) -> i128 {
+        fees::get_total_fee_balance(&env)
+    }
+
+    /// Transfers accumulated protocol fees of the current fee recipient out of
+    /// the contract, in the deposit token
+    pub fn withdraw_protocol_fees(
+        env: Env,
+        treasurer: Address,
+        amount: i128,
+        to: Address,
+    ) -> Result<(), SavingsError> {
+        roles::require_role(&env, &treasurer, Role::Treasurer)?;
+        multisig::ensure_single_key_allowed(&env)?;
+        fees::withdraw_protocol_fees(&env, amount, &to)?;
+        events::publish(
+            &env,
+            events::FeesWithdrawn {
+                treasurer,
+                to,
+                amount,
+            },
+        );
+        Ok(())
+    }
+
+    // ========== AutoSave Functions ==========
+
+    /// Creates a new AutoSave schedule for recurring Flexi deposits
+    pub fn create_autosave(
+        env: Env,
+        user: Address,
+        amount: i128,
+        interval_seconds: u64,
+        start_time: u64,
+    ) -> Result<u64, SavingsError> {
+        autosave::create_autosave(&env, user, amount, interval_seconds, start_time)
+    }
+
+    /// Creates an AutoSave schedule whose executions are split across Flexi,
+    /// goals, groups and locks by percent, funded by new deposits or the
+    /// user's Flexi balance
+    pub fn create_split_autosave(
+        env: Env,
+        user: Address,
+        amount: i128,
+        interval_seconds: u64,
+        start_time: u64,
+        source: AutoSaveSource,
+        split: Vec<AutoSaveLeg>,
+    ) -> Result<u64, SavingsError> {
+        autosave::create_split_autosave(
+            &env,
+            user,
+            amount,
+            interval_seconds,
+            start_time,
+            source,
+            split,
+        )
+    }
+
+    /// Registers the key a user signs delegated autosave consents with
+    pub fn set_consent_key(
+        env: Env,
+        user: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), SavingsError> {
+        autosave::set_consent_key(&env, user, public_key)
+    }
+
+    pub fn get_consent(env: Env, user: Address) -> Option<UserConsent> {
+        autosave::get_consent(&env, &user)
+    }
+
+    /// Lets a partner app create an AutoSave schedule from a user-signed consent
+    pub fn create_autosave_with_consent(
+        env: Env,
+        partner: Address,
+        consent: AutoSaveConsent,
+        signature: BytesN<64>,
+    ) -> Result<u64, SavingsError> {
+        autosave::create_autosave_with_consent(&env, partner, consent, signature)
+    }
+
+    /// Executes an AutoSave schedule if it's due
+    pub fn execute_autosave(env: Env, schedule_id: u64) -> Result<(), SavingsError> {
+        autosave::execute_autosave(&env, schedule_id)
+    }
+
+    /// Executes every due schedule in `schedule_ids`, reporting `false` for
+    /// those skipped. The keeper earns the keeper tip for those executed
+    pub fn execute_autosave_batch(
+        env: Env,
+        keeper: Address,
+        schedule_ids: Vec<u64>,
+    ) -> Result<Vec<bool>, SavingsError> {
+        autosave::execute_autosave_batch(&env, keeper, schedule_ids)
+    }
+
+    /// Cancels an AutoSave schedule
+    pub fn cancel_autosave(env: Env, user: Address, schedule_id: u64) -> Result<(), SavingsError> {
         autosave::cancel_autosave(&env, user, schedule_id)
     }
 
+    /// Pauses an AutoSave schedule until its owner resumes it
+    pub fn pause_autosave(env: Env, user: Address, schedule_id: u64) -> Result<(), SavingsError> {
+        autosave::pause_autosave(&env, user, schedule_id)
+    }
+
+    /// Resumes a paused AutoSave schedule, skipping executions missed while paused
+    pub fn resume_autosave(env: Env, user: Address, schedule_id: u64) -> Result<(), SavingsError> {
+        autosave::resume_autosave(&env, user, schedule_id)
+    }
+
+    /// Changes the amount and interval of an AutoSave schedule
+    pub fn update_autosave(
+        env: Env,
+        user: Address,
+        schedule_id: u64,
+        new_amount: i128,
+        new_interval: u64,
+    ) -> Result<(), SavingsError> {
+        autosave::update_autosave(&env, user, schedule_id, new_amount, new_interval)
+    }
+
+    /// Sets how an AutoSave schedule handles executions missed by a late run
+    pub fn set_autosave_catch_up(
+        env: Env,
+        user: Address,
+        schedule_id: u64,
+        catch_up: AutoSaveCatchUp,
+    ) -> Result<(), SavingsError> {
+        autosave::set_autosave_catch_up(&env, user, schedule_id, catch_up)
+    }
+
     /// Gets an AutoSave schedule by ID
     pub fn get_autosave(env: Env, schedule_id: u64) -> Option<AutoSave> {
         autosave::get_autosave(&env, schedule_id)
     }
 
+    /// Lists runnable AutoSave schedules due before `before_timestamp`, so
+    /// keepers can discover them on-chain
+    pub fn get_due_autosaves(
+        env: Env,
+        before_timestamp: u64,
+        cursor: Cursor,
+    ) -> (Vec<u64>, Option<Cursor>) {
+        autosave::get_due_autosaves(&env, before_timestamp, cursor)
+    }
+
     /// Gets all AutoSave schedule IDs for a user
     pub fn get_user_autosaves(env: Env, user: Address) -> Vec<u64> {
         autosave::get_user_autosaves(&env, &user)
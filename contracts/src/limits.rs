@@ -0,0 +1,382 @@
+use soroban_sdk::{Address, Env};
+
+use crate::assets;
+use crate::errors::SavingsError;
+use crate::storage_types::{ConfigKey, DataKey, ParamKey, PlanLimits, UserKey, WithdrawWindow};
+
+/// Length of a withdrawal limit window
+pub const WITHDRAW_WINDOW_SECONDS: u64 = 24 * 60 * 60;
+
+/// Per-user withdrawal cap per window in reference units; 0 means no cap
+pub fn get_withdraw_limit(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::WithdrawLimit))
+        .unwrap_or(0)
+}
+
+pub fn set_withdraw_limit(env: &Env, limit: i128) -> Result<(), SavingsError> {
+    if limit < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::WithdrawLimit), &limit);
+    Ok(())
+}
+
+/// Returns the user's window, or a fresh one if it has expired
+fn current_window(env: &Env, user: &Address) -> WithdrawWindow {
+    let now = env.ledger().timestamp();
+    env.storage()
+        .persistent()
+        .get::<DataKey, WithdrawWindow>(&DataKey::UserState(UserKey::WithdrawWindow(user.clone())))
+        .filter(|w| now < w.start.saturating_add(WITHDRAW_WINDOW_SECONDS))
+        .unwrap_or(WithdrawWindow {
+            start: now,
+            withdrawn: 0,
+        })
+}
+
+/// Amount `user` may still withdraw before the current window closes.
+///
+/// Returns `i128::MAX` when no limit is configured.
+pub fn get_remaining_withdraw_allowance(env: &Env, user: &Address) -> i128 {
+    let limit = assets::to_token_units(env, get_withdraw_limit(env));
+    if limit == 0 {
+        return i128::MAX;
+    }
+    (limit - current_window(env, user).withdrawn).max(0)
+}
+
+/// Counts `amount` against the user's window, failing with
+/// `AmountExceedsLimit` if it would go over the configured limit
+pub fn consume_withdraw_allowance(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    let limit = assets::to_token_units(env, get_withdraw_limit(env));
+    if limit == 0 || amount <= 0 {
+        return Ok(());
+    }
+
+    let mut window = current_window(env, user);
+    let withdrawn = window
+        .withdrawn
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    if withdrawn > limit {
+        return Err(SavingsError::AmountExceedsLimit);
+    }
+
+    window.withdrawn = withdrawn;
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::WithdrawWindow(user.clone())),
+        &window,
+    );
+    Ok(())
+}
+
+/// Smallest amount a single deposit may add, in reference units; 0 means
+/// no minimum
+pub fn get_minimum_deposit(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MinimumDeposit)
+        .unwrap_or(0)
+}
+
+pub fn set_minimum_deposit(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    if amount < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::MinimumDeposit, &amount);
+    Ok(())
+}
+
+/// Plan balances below this are swept into Flexi by `consolidate_dust`, in
+/// reference units; 0 means nothing counts as dust
+pub fn get_dust_threshold(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::Param(ParamKey::DustThreshold)))
+        .unwrap_or(0)
+}
+
+pub fn set_dust_threshold(env: &Env, threshold: i128) -> Result<(), SavingsError> {
+    if threshold < 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage().instance().set(
+        &DataKey::Config(ConfigKey::Param(ParamKey::DustThreshold)),
+        &threshold,
+    );
+    Ok(())
+}
+
+/// Fails with `AmountBelowMinimum` if a non-zero deposit is smaller than the
+/// configured minimum. Zero amounts (e.g. a goal opened without a first
+/// deposit) are left to the caller's own validation.
+pub fn ensure_minimum_deposit(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    if amount > 0 && amount < assets::to_token_units(env, get_minimum_deposit(env)) {
+        return Err(SavingsError::AmountBelowMinimum);
+    }
+    Ok(())
+}
+
+pub fn get_plan_limits(env: &Env) -> PlanLimits {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::PlanLimits))
+        .unwrap_or_default()
+}
+
+pub fn set_plan_limits(env: &Env, limits: &PlanLimits) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::PlanLimits), limits);
+}
+
+/// Fails with `MaxPlansExceeded` if a user already holding `open` plans has
+/// reached `max` (0 means no cap)
+pub fn ensure_plan_capacity(max: u32, open: u32) -> Result<(), SavingsError> {
+    if max > 0 && open >= max {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WITHDRAW_WINDOW_SECONDS;
+    use crate::{GroupMetadata, NesteraContract, NesteraContractClient, PlanLimits, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, String, Symbol,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+
+        (env, client, admin, user)
+    }
+
+    #[test]
+    fn test_no_limit_by_default() {
+        let (_env, client, _admin, user) = setup();
+        assert_eq!(client.get_withdraw_limit(), 0);
+        assert_eq!(client.get_remaining_withdraw_allowance(&user), i128::MAX);
+
+        client.deposit_flexi(&user, &10_000);
+        client.withdraw_flexi(&user, &10_000);
+    }
+
+    #[test]
+    fn test_flexi_withdrawals_capped_per_window() {
+        let (env, client, admin, user) = setup();
+        client.set_withdraw_limit(&admin, &1_000);
+        client.deposit_flexi(&user, &5_000);
+
+        client.withdraw_flexi(&user, &600);
+        assert_eq!(client.get_remaining_withdraw_allowance(&user), 400);
+        assert_eq!(
+            client.try_withdraw_flexi(&user, &401).unwrap_err(),
+            Ok(SavingsError::AmountExceedsLimit)
+        );
+        client.withdraw_flexi(&user, &400);
+        assert_eq!(client.get_remaining_withdraw_allowance(&user), 0);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp += WITHDRAW_WINDOW_SECONDS);
+        assert_eq!(client.get_remaining_withdraw_allowance(&user), 1_000);
+        client.withdraw_flexi(&user, &1_000);
+    }
+
+    #[test]
+    fn test_goal_withdrawal_counts_against_limit() {
+        let (env, client, admin, user) = setup();
+        client.set_withdraw_limit(&admin, &1_000);
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "car"), &2_000, &2_000);
+
+        let result = client.try_withdraw_completed_goal_save(&user, &goal_id);
+        assert!(result.is_err());
+        assert_eq!(client.get_remaining_withdraw_allowance(&user), 1_000);
+
+        client.set_withdraw_limit(&admin, &2_000);
+        assert_eq!(client.withdraw_completed_goal_save(&user, &goal_id), 2_000);
+        assert_eq!(client.get_remaining_withdraw_allowance(&user), 0);
+    }
+
+    #[test]
+    fn test_set_withdraw_limit_validation() {
+        let (env, client, admin, _user) = setup();
+        assert_eq!(
+            client.try_set_withdraw_limit(&admin, &-1).unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_set_withdraw_limit(&stranger, &1).unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_plan_limits_cap_open_plans() {
+        let (env, client, admin, user) = setup();
+        assert_eq!(client.get_plan_limits(), PlanLimits::default());
+        client.set_plan_limits(
+            &admin,
+            &PlanLimits {
+                max_lock_saves: 1,
+                max_goal_saves: 1,
+                max_group_saves: 0,
+                max_autosaves: 1,
+            },
+        );
+
+        let lock_id = client.create_lock_save(&user, &1_000, &100);
+        assert_eq!(
+            client
+                .try_create_lock_save(&user, &1_000, &100)
+                .unwrap_err(),
+            Ok(SavingsError::MaxPlansExceeded.into())
+        );
+        // Withdrawing a lock frees its slot
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.withdraw_lock_save(&user, &lock_id);
+        client.create_lock_save(&user, &1_000, &100);
+
+        client.create_goal_save(&user, &Symbol::new(&env, "car"), &2_000, &0);
+        assert_eq!(
+            client
+                .try_create_goal_save(&user, &Symbol::new(&env, "bike"), &2_000, &0)
+                .unwrap_err(),
+            Ok(SavingsError::MaxPlansExceeded.into())
+        );
+
+        let schedule_id = client.create_autosave(&user, &100, &86_400, &0);
+        assert_eq!(
+            client
+                .try_create_autosave(&user, &100, &86_400, &0)
+                .unwrap_err(),
+            Ok(SavingsError::MaxPlansExceeded)
+        );
+        client.cancel_autosave(&user, &schedule_id);
+        client.create_autosave(&user, &100, &86_400, &0);
+    }
+
+    #[test]
+    fn test_plan_limits_cap_group_memberships() {
+        let (env, client, admin, user) = setup();
+        let creator = Address::generate(&env);
+        client.initialize_user(&creator);
+        let metadata = GroupMetadata {
+            title: String::from_str(&env, "Circle"),
+            description: String::from_str(&env, "Friends"),
+            category: String::from_str(&env, "savings"),
+        };
+        let group_id =
+            client.create_group_save(&creator, &metadata, &5000, &1, &100, &true, &1, &500, &0);
+
+        client.set_plan_limits(
+            &admin,
+            &PlanLimits {
+                max_group_saves: 1,
+                ..PlanLimits::default()
+            },
+        );
+        assert_eq!(
+            client
+                .try_create_group_save(&creator, &metadata, &5000, &1, &100, &true, &1, &500, &0)
+                .unwrap_err(),
+            Ok(SavingsError::MaxPlansExceeded)
+        );
+        client.join_group_save(&user, &group_id);
+        assert_eq!(client.get_protocol_params().plan_limits.max_group_saves, 1);
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client
+                .try_set_plan_limits(&stranger, &PlanLimits::default())
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_minimum_deposit_enforced_on_every_deposit_path() {
+        let (env, client, admin, user) = setup();
+        assert_eq!(client.get_minimum_deposit(), 0);
+        client.set_minimum_deposit(&admin, &100);
+        assert_eq!(client.get_protocol_params().minimum_deposit, 100);
+
+        let below = Ok(SavingsError::AmountBelowMinimum);
+        assert_eq!(client.try_deposit_flexi(&user, &99).unwrap_err(), below);
+        client.deposit_flexi(&user, &100);
+
+        assert_eq!(
+            client.try_create_lock_save(&user, &99, &100).unwrap_err(),
+            Ok(SavingsError::AmountBelowMinimum.into())
+        );
+
+        // Opening a goal without a first deposit is still allowed
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "car"), &1_000, &0);
+        assert_eq!(
+            client
+                .try_deposit_to_goal_save(&user, &goal_id, &50)
+                .unwrap_err(),
+            Ok(SavingsError::AmountBelowMinimum.into())
+        );
+
+        let group_id = client.create_group_save(
+            &user,
+            &GroupMetadata {
+                title: String::from_str(&env, "Circle"),
+                description: String::from_str(&env, "Friends"),
+                category: String::from_str(&env, "savings"),
+            },
+            &5000,
+            &1,
+            &10,
+            &true,
+            &0,
+            &500,
+            &0,
+        );
+        assert_eq!(
+            client
+                .try_contribute_to_group_save(&user, &group_id, &50)
+                .unwrap_err(),
+            below
+        );
+        assert_eq!(
+            client
+                .try_create_autosave(&user, &50, &86_400, &0)
+                .unwrap_err(),
+            below
+        );
+
+        assert_eq!(
+            client.try_set_minimum_deposit(&admin, &-1).unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+    }
+}
@@ -1,80 +1,268 @@
-use crate::ensure_not_paused;
+use crate::campaigns;
+use crate::custody;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, LockSave, User};
+use crate::events;
+use crate::fees;
+use crate::flexi;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::limits;
+use crate::migrations;
+use crate::plan_ids;
+use crate::rates;
+use crate::storage_types::{
+    CompoundFrequency, DataKey, InterestAccrual, InterestStatement, LockSave, PlanKind,
+    TargetLockProgress, TxKind, User, WithdrawalPreview,
+};
 use crate::users;
+use crate::yield_pool;
+use crate::{record_deposit_activity, record_withdrawal_activity};
 use soroban_sdk::{symbol_short, Address, Env, Vec};
 
+/// Rate, in basis points, new Lock Saves earn until a rate change is
+/// scheduled
+pub const LOCK_RATE_BPS: u32 = 500;
+
+/// Seconds in the 365.25-day year interest rates are quoted over
+const SECONDS_PER_YEAR: u64 = 31_557_600;
+
+/// How long a target-based lock stays locked at most if its target is never
+/// reached
+pub const TARGET_LOCK_LONG_STOP: u64 = 5 * SECONDS_PER_YEAR;
+
+/// Longest duration a lock can be created or previewed with
+pub const MAX_LOCK_DURATION: u64 = 50 * SECONDS_PER_YEAR;
+
 /// Creates a new Lock Save plan for a user
 pub fn create_lock_save(
     env: &Env,
     user: Address,
     amount: i128,
     duration: u64,
+    compounding: CompoundFrequency,
 ) -> Result<u64, SavingsError> {
-    ensure_not_paused(env)?;
+    guard::check(env, &user, Op::CreatePlan, amount)?;
     user.require_auth();
 
+    apply_create_lock(env, &user, amount, duration, compounding)
+}
+
+/// Opens a new lock for the user at the current rate.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn apply_create_lock(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+    duration: u64,
+    compounding: CompoundFrequency,
+) -> Result<u64, SavingsError> {
     // Validate inputs
     if amount <= 0 {
         return Err(SavingsError::InvalidAmount);
     }
-    if duration == 0 {
+    if duration == 0 || duration > MAX_LOCK_DURATION {
         // Aligned with the test expectation of a generic invalid duration error
         return Err(SavingsError::InvalidTimestamp);
     }
 
     // Ensure user exists using your users module
-    if !users::user_exists(env, &user) {
+    if !users::user_exists(env, user) {
         return Err(SavingsError::UserNotFound);
     }
+    limits::ensure_plan_capacity(
+        limits::get_plan_limits(env).max_lock_saves,
+        count_open_lock_saves(env, user),
+    )?;
 
-    // ID Logic
-    let lock_id = get_next_lock_id(env);
-    increment_next_lock_id(env);
+    let lock_id = insert_lock_save(
+        env,
+        user,
+        amount,
+        duration,
+        rates::get_rate_at(env, env.ledger().timestamp()),
+        false,
+        compounding,
+    )?;
 
-    let start_time = env.ledger().timestamp();
-    let maturity_time = start_time
-        .checked_add(duration)
-        .ok_or(SavingsError::Overflow)?;
+    record_deposit_activity(env, user, PlanKind::Lock, lock_id, amount);
 
-    let lock_save = LockSave {
-        id: lock_id,
-        owner: user.clone(),
-        amount,
-        interest_rate: 500, // Matching your test expectation of 500 (5%)
-        start_time,
-        maturity_time,
-        is_withdrawn: false,
-    };
+    Ok(lock_id)
+}
 
-    // Store the LockSave
+/// Creates a Lock Save that unlocks once top-ups bring its balance to
+/// `target_amount`, or at the latest `TARGET_LOCK_LONG_STOP` seconds from now
+///
+/// # Errors
+/// * `InvalidAmount` - If `initial` is not positive or `target_amount` does
+///   not exceed it
+pub fn create_target_lock(
+    env: &Env,
+    user: Address,
+    target_amount: i128,
+    initial: i128,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &user, Op::CreatePlan, initial)?;
+    user.require_auth();
+
+    if initial <= 0 || target_amount <= initial {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if !users::user_exists(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    limits::ensure_plan_capacity(
+        limits::get_plan_limits(env).max_lock_saves,
+        count_open_lock_saves(env, &user),
+    )?;
+
+    let lock_id = insert_lock_save(
+        env,
+        &user,
+        initial,
+        TARGET_LOCK_LONG_STOP,
+        rates::get_rate_at(env, env.ledger().timestamp()),
+        false,
+        CompoundFrequency::None,
+    )?;
+    let mut lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
+    lock_save.target_amount = target_amount;
     env.storage()
         .persistent()
         .set(&DataKey::LockSave(lock_id), &lock_save);
 
-    // Update user's lock list
-    add_lock_to_user(env, &user, lock_id);
+    record_deposit_activity(env, &user, PlanKind::Lock, lock_id, initial);
 
-    // Update user's profile stats
-    let user_key = DataKey::User(user.clone());
-    let mut user_data: User = env.storage().persistent().get(&user_key).unwrap();
-    user_data.total_balance += amount;
-    user_data.savings_count += 1;
-    env.storage().persistent().set(&user_key, &user_data);
+    events::publish(
+        env,
+        events::LockTargetSet {
+            user,
+            lock_id,
+            target_amount,
+        },
+    );
 
     Ok(lock_id)
 }
 
-pub fn withdraw_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<i128, SavingsError> {
-    ensure_not_paused(env)?;
+/// Adds principal to an existing lock before it matures.
+///
+/// The lock's start time becomes the amount-weighted average of the existing
+/// principal's start and now, so simple interest accrues on the top-up only
+/// from the moment it was deposited. Maturity is unchanged.
+pub fn deposit_to_lock_save(
+    env: &Env,
+    user: Address,
+    lock_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Deposit, amount)?;
     user.require_auth();
 
+    apply_lock_deposit(env, &user, lock_id, amount)
+}
+
+/// Tops up one of the user's open locks.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn apply_lock_deposit(
+    env: &Env,
+    user: &Address,
+    lock_id: u64,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
     let mut lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
 
+    if lock_save.owner != *user {
+        return Err(SavingsError::Unauthorized);
+    }
+
+    if lock_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let now = env.ledger().timestamp();
+    if now >= lock_save.maturity_time {
+        return Err(SavingsError::TooLate);
+    }
+
+    let new_amount = lock_save
+        .amount
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    let old_weight = lock_save
+        .amount
+        .checked_mul(lock_save.interest_start as i128)
+        .ok_or(SavingsError::Overflow)?;
+    let new_weight = amount
+        .checked_mul(now as i128)
+        .ok_or(SavingsError::Overflow)?;
+    let weighted_start = old_weight
+        .checked_add(new_weight)
+        .ok_or(SavingsError::Overflow)?
+        / new_amount;
+
+    let old_interest = interest_to_maturity(&lock_save)?;
+    lock_save.amount = new_amount;
+    lock_save.interest_start = weighted_start as u64;
+    yield_pool::adjust_outstanding_interest(env, interest_to_maturity(&lock_save)? - old_interest)?;
+    env.storage()
+        .persistent()
+        .set(&DataKey::LockSave(lock_id), &lock_save);
+
+    let user_key = DataKey::User(user.clone());
+    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
+        user_data.total_balance = user_data
+            .total_balance
+            .checked_add(amount)
+            .ok_or(SavingsError::Overflow)?;
+        env.storage().persistent().set(&user_key, &user_data);
+    }
+
+    record_deposit_activity(env, user, PlanKind::Lock, lock_id, amount);
+
+    events::publish(
+        env,
+        events::LockToppedUp {
+            user: user.clone(),
+            lock_id,
+            amount,
+        },
+    );
+
+    Ok(())
+}
+
+pub fn withdraw_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<i128, SavingsError> {
+    let lock_save = get_lock_save(env, lock_id);
+    let amount = lock_save.as_ref().map_or(0, |l| l.amount);
+    guard::check(env, &user, Op::Withdraw, amount)?;
+    user.require_auth();
+
+    let lock_save = lock_save.ok_or(SavingsError::PlanNotFound)?;
+
     if lock_save.owner != user {
         return Err(SavingsError::Unauthorized);
     }
 
+    apply_lock_withdrawal(env, lock_save)
+}
+
+/// Pays out a matured lock to its owner, charging the withdrawal fee.
+/// Returns the amount paid out net of the fee.
+///
+/// Callers are responsible for the guard check, authorization and ownership.
+pub(crate) fn apply_lock_withdrawal(
+    env: &Env,
+    mut lock_save: LockSave,
+) -> Result<i128, SavingsError> {
+    let user = lock_save.owner.clone();
+    let lock_id = lock_save.id;
+
     if lock_save.is_withdrawn {
         return Err(SavingsError::PlanCompleted);
     }
@@ -83,7 +271,8 @@ pub fn withdraw_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<i128
         return Err(SavingsError::TooEarly);
     }
 
-    let final_amount = calculate_lock_save_yield(&lock_save, env.ledger().timestamp());
+    let now = env.ledger().timestamp();
+    let final_amount = settle_lock_payout(env, &lock_save, now)?;
 
     lock_save.is_withdrawn = true;
     env.storage()
@@ -97,22 +286,432 @@ pub fn withdraw_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<i128
         env.storage().persistent().set(&user_key, &user_data);
     }
 
-    env.events()
-        .publish((symbol_short!("withdraw"), user, lock_id), final_amount);
+    rates::emit_interest_accrued(
+        env,
+        symbol_short!("lock"),
+        &user,
+        InterestAccrual {
+            plan_id: lock_id,
+            principal: lock_save.amount,
+            rate_bps: lock_save.interest_rate as i128,
+            period_seconds: now.saturating_sub(lock_save.start_time),
+            amount: final_amount - lock_save.amount,
+        },
+    );
+
+    let breakdown = fees::charge_withdrawal_fee(env, &user, PlanKind::Lock, final_amount)?;
+    campaigns::settle_plan(env, &user, PlanKind::Lock, lock_id, true)?;
+    history::record(
+        env,
+        &user,
+        TxKind::Withdraw,
+        PlanKind::Lock,
+        lock_id,
+        final_amount,
+        breakdown.fee,
+    );
+    record_withdrawal_activity(env, &user, lock_save.amount);
+
+    events::publish(
+        env,
+        events::LockWithdrawn {
+            user,
+            lock_id,
+            net_amount: breakdown.net,
+        },
+    );
+
+    Ok(breakdown.net)
+}
+
+/// Moves a matured lock's payout, interest included, into its owner's Flexi
+/// Save. No tokens leave the contract, so no withdrawal fee is charged.
+/// Returns the amount moved.
+///
+/// Callers are responsible for the guard check, authorization and ownership.
+pub(crate) fn sweep_lock_to_flexi(
+    env: &Env,
+    mut lock_save: LockSave,
+) -> Result<i128, SavingsError> {
+    let user = lock_save.owner.clone();
+    if lock_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if !is_unlocked(env, &lock_save) {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let now = env.ledger().timestamp();
+    let final_amount = settle_lock_payout(env, &lock_save, now)?;
+    lock_save.is_withdrawn = true;
+    env.storage()
+        .persistent()
+        .set(&DataKey::LockSave(lock_save.id), &lock_save);
+
+    // The principal moves from the lock to Flexi; credit_flexi adds it back
+    let user_key = DataKey::User(user.clone());
+    if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
+        user_data.total_balance -= lock_save.amount;
+        env.storage().persistent().set(&user_key, &user_data);
+    }
 
+    rates::emit_interest_accrued(
+        env,
+        symbol_short!("lock"),
+        &user,
+        InterestAccrual {
+            plan_id: lock_save.id,
+            principal: lock_save.amount,
+            rate_bps: lock_save.interest_rate as i128,
+            period_seconds: now.saturating_sub(lock_save.start_time),
+            amount: final_amount - lock_save.amount,
+        },
+    );
+    campaigns::settle_plan(env, &user, PlanKind::Lock, lock_save.id, true)?;
+    history::record(
+        env,
+        &user,
+        TxKind::Withdraw,
+        PlanKind::Lock,
+        lock_save.id,
+        final_amount,
+        0,
+    );
+    flexi::credit_flexi(env, &user, final_amount)?;
     Ok(final_amount)
 }
 
+/// Enables or disables auto-rollover for a lock owned by `user`
+pub fn set_lock_rollover(
+    env: &Env,
+    user: Address,
+    lock_id: u64,
+    enabled: bool,
+) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let mut lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
+    if lock_save.owner != user {
+        return Err(SavingsError::Unauthorized);
+    }
+    if lock_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    // A target lock's long-stop duration is not a term worth renewing
+    if enabled && lock_save.target_amount > 0 {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+
+    lock_save.rollover = enabled;
+    env.storage()
+        .persistent()
+        .set(&DataKey::LockSave(lock_id), &lock_save);
+
+    events::publish(
+        env,
+        events::LockRolloverSet {
+            user,
+            lock_id,
+            enabled,
+        },
+    );
+
+    Ok(())
+}
+
+/// Rolls every matured, rollover-enabled lock in `lock_ids` into a new lock of
+/// the same duration funded with its principal plus interest.
+///
+/// IDs that are unknown, already withdrawn, not yet matured or without
+/// rollover enabled are skipped. Returns the IDs of the newly created locks.
+pub fn process_matured_locks(env: &Env, lock_ids: Vec<u64>) -> Result<Vec<u64>, SavingsError> {
+    let now = env.ledger().timestamp();
+    let mut new_ids = Vec::new(env);
+
+    for lock_id in lock_ids.iter() {
+        let mut lock_save = match get_lock_save(env, lock_id) {
+            Some(lock_save) => lock_save,
+            None => continue,
+        };
+        if lock_save.is_withdrawn || !lock_save.rollover || now < lock_save.maturity_time {
+            continue;
+        }
+        guard::check(env, &lock_save.owner, Op::Manage, 0)?;
+
+        let final_amount = settle_lock_payout(env, &lock_save, now)?;
+        let duration = lock_save.maturity_time - lock_save.start_time;
+        // Campaign bonuses are paid out to Flexi rather than rolled over
+        campaigns::settle_plan(env, &lock_save.owner, PlanKind::Lock, lock_id, true)?;
+
+        lock_save.is_withdrawn = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::LockSave(lock_id), &lock_save);
+
+        // The old principal moves into the new lock, so only the interest is new balance
+        let user_key = DataKey::User(lock_save.owner.clone());
+        if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
+            user_data.total_balance -= lock_save.amount;
+            env.storage().persistent().set(&user_key, &user_data);
+        }
+
+        rates::emit_interest_accrued(
+            env,
+            symbol_short!("lock"),
+            &lock_save.owner,
+            InterestAccrual {
+                plan_id: lock_id,
+                principal: lock_save.amount,
+                rate_bps: lock_save.interest_rate as i128,
+                period_seconds: now.saturating_sub(lock_save.start_time),
+                amount: final_amount - lock_save.amount,
+            },
+        );
+
+        let new_id = insert_lock_save(
+            env,
+            &lock_save.owner,
+            final_amount,
+            duration,
+            lock_save.interest_rate,
+            true,
+            lock_save.compounding,
+        )?;
+        history::record(
+            env,
+            &lock_save.owner,
+            TxKind::Rollover,
+            PlanKind::Lock,
+            new_id,
+            final_amount,
+            0,
+        );
+
+        events::publish(
+            env,
+            events::LockRolledOver {
+                owner: lock_save.owner.clone(),
+                lock_id,
+                new_id,
+            },
+        );
+
+        new_ids.push_back(new_id);
+    }
+
+    Ok(new_ids)
+}
+
+/// Moves every matured lock in `lock_ids` into its owner's Flexi Save balance,
+/// principal plus interest, so owners don't have to withdraw each one.
+///
+/// IDs that are unknown, already withdrawn, not yet matured or set to roll
+/// over are skipped. `keeper` is tipped for the swept locks it does not own.
+/// Returns the IDs of the swept locks.
+pub fn sweep_matured_locks(
+    env: &Env,
+    keeper: Address,
+    lock_ids: Vec<u64>,
+) -> Result<Vec<u64>, SavingsError> {
+    keeper.require_auth();
+    let mut swept = Vec::new(env);
+    let mut tipped_sweeps: u32 = 0;
+
+    for lock_id in lock_ids.iter() {
+        let lock_save = match get_lock_save(env, lock_id) {
+            Some(lock_save) => lock_save,
+            None => continue,
+        };
+        if lock_save.is_withdrawn || lock_save.rollover || !is_unlocked(env, &lock_save) {
+            continue;
+        }
+        guard::check(env, &lock_save.owner, Op::Manage, 0)?;
+
+        let owner = lock_save.owner.clone();
+        let final_amount = sweep_lock_to_flexi(env, lock_save)?;
+
+        events::publish(
+            env,
+            events::LockSwept {
+                owner: owner.clone(),
+                lock_id,
+                amount: final_amount,
+            },
+        );
+        events::publish(
+            env,
+            events::LockSweepExecuted {
+                keeper: keeper.clone(),
+                lock_id,
+                amount: final_amount,
+            },
+        );
+
+        if owner != keeper {
+            tipped_sweeps += 1;
+        }
+        swept.push_back(lock_id);
+    }
+
+    fees::pay_keeper_tip(env, &keeper, tipped_sweeps)?;
+
+    Ok(swept)
+}
+
+/// Reassigns an open lock to another initialized user. Its amount, rate and
+/// maturity are unchanged; the locked principal moves between the two users'
+/// total balances.
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `from` and `to` are the same user
+/// * `UserNotFound` - If `to` is not initialized
+/// * `PlanNotFound` - If the lock does not exist
+/// * `Unauthorized` - If `from` does not own the lock
+/// * `PlanCompleted` - If the lock was already withdrawn
+/// * `MaxPlansExceeded` - If `to` already holds the most locks allowed
+pub fn transfer_lock_save(
+    env: &Env,
+    from: Address,
+    to: Address,
+    lock_id: u64,
+) -> Result<(), SavingsError> {
+    guard::check(env, &from, Op::Manage, 0)?;
+    from.require_auth();
+    custody::require_guardian_consent(env, &from);
+
+    if from == to {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    if !users::user_exists(env, &to) {
+        return Err(SavingsError::UserNotFound);
+    }
+
+    let mut lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
+    if lock_save.owner != from {
+        return Err(SavingsError::Unauthorized);
+    }
+    if lock_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    limits::ensure_plan_capacity(
+        limits::get_plan_limits(env).max_lock_saves,
+        count_open_lock_saves(env, &to),
+    )?;
+
+    let mut from_locks = get_user_lock_saves(env, &from);
+    if let Some(index) = from_locks.first_index_of(lock_id) {
+        from_locks.remove(index);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserLockSaves(from.clone()), &from_locks);
+    add_lock_to_user(env, &to, lock_id);
+
+    lock_save.owner = to.clone();
+    env.storage()
+        .persistent()
+        .set(&DataKey::LockSave(lock_id), &lock_save);
+    campaigns::transfer_plan(env, &from, &to, PlanKind::Lock, lock_id);
+    history::record_transfer(env, &from, &to, PlanKind::Lock, lock_id, lock_save.amount);
+    record_withdrawal_activity(env, &from, lock_save.amount);
+
+    for (user, delta) in [(&from, -lock_save.amount), (&to, lock_save.amount)] {
+        let user_key = DataKey::User(user.clone());
+        if let Some(mut user_data) = env.storage().persistent().get::<DataKey, User>(&user_key) {
+            user_data.total_balance = user_data
+                .total_balance
+                .checked_add(delta)
+                .ok_or(SavingsError::Overflow)?;
+            env.storage().persistent().set(&user_key, &user_data);
+        }
+    }
+
+    events::publish(env, events::LockTransferred { from, to, lock_id });
+
+    Ok(())
+}
+
+/// Whether a lock can be withdrawn: it has matured, or it is a target lock
+/// whose balance has reached its target
 pub fn check_matured_lock(env: &Env, lock_id: u64) -> bool {
-    if let Some(lock_save) = get_lock_save(env, lock_id) {
-        env.ledger().timestamp() >= lock_save.maturity_time
+    get_lock_save(env, lock_id).is_some_and(|lock_save| is_unlocked(env, &lock_save))
+}
+
+pub(crate) fn is_unlocked(env: &Env, lock_save: &LockSave) -> bool {
+    env.ledger().timestamp() >= lock_save.maturity_time
+        || (lock_save.target_amount > 0 && lock_save.amount >= lock_save.target_amount)
+}
+
+/// VIEW FUNCTION - Interest statement of a lock: interest earned so far,
+/// interest and withdrawal fee at maturity, and the yield net of that fee.
+/// Assumes the yield pool covers the interest.
+///
+/// # Errors
+/// * `PlanNotFound` - If the lock does not exist
+/// * `PlanCompleted` - If the lock was already withdrawn
+pub fn get_lock_statement(env: &Env, lock_id: u64) -> Result<InterestStatement, SavingsError> {
+    let lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
+    if lock_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    let accrued_interest =
+        calculate_lock_save_yield(&lock_save, env.ledger().timestamp())? - lock_save.amount;
+    let projected_interest = interest_to_maturity(&lock_save)?;
+    let projected_fee = fees::quote_withdrawal_fee(
+        env,
+        &lock_save.owner,
+        PlanKind::Lock,
+        lock_save.amount + projected_interest,
+    )?
+    .fee;
+    let duration = lock_save.maturity_time.saturating_sub(lock_save.start_time);
+    let effective_apy_bps = if lock_save.amount > 0 && duration > 0 {
+        (projected_interest - projected_fee).saturating_mul(10_000 * SECONDS_PER_YEAR as i128)
+            / (lock_save.amount * duration as i128)
     } else {
-        false
+        0
+    };
+
+    Ok(InterestStatement {
+        plan_kind: PlanKind::Lock,
+        plan_id: lock_id,
+        principal: lock_save.amount,
+        interest_rate: lock_save.interest_rate as i128,
+        accrued_interest,
+        projected_interest,
+        projected_fee,
+        effective_apy_bps,
+    })
+}
+
+/// VIEW FUNCTION - Progress of a target-based lock towards unlocking
+///
+/// # Errors
+/// * `PlanNotFound` - If the lock does not exist
+/// * `InvalidPlanConfig` - If the lock has no target
+pub fn get_target_lock_progress(
+    env: &Env,
+    lock_id: u64,
+) -> Result<TargetLockProgress, SavingsError> {
+    let lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
+    if lock_save.target_amount == 0 {
+        return Err(SavingsError::InvalidPlanConfig);
     }
+    Ok(TargetLockProgress {
+        lock_id,
+        balance: lock_save.amount,
+        target_amount: lock_save.target_amount,
+        remaining: (lock_save.target_amount - lock_save.amount).max(0),
+        long_stop_time: lock_save.maturity_time,
+        unlocked: is_unlocked(env, &lock_save),
+    })
 }
 
 pub fn get_lock_save(env: &Env, lock_id: u64) -> Option<LockSave> {
-    env.storage().persistent().get(&DataKey::LockSave(lock_id))
+    migrations::read_lock_save(env, lock_id)
 }
 
 pub fn get_user_lock_saves(env: &Env, user: &Address) -> Vec<u64> {
@@ -122,20 +721,69 @@ pub fn get_user_lock_saves(env: &Env, user: &Address) -> Vec<u64> {
         .unwrap_or_else(|| Vec::new(env))
 }
 
+/// Number of the user's locks that have not been withdrawn or rolled over
+pub fn count_open_lock_saves(env: &Env, user: &Address) -> u32 {
+    get_user_lock_saves(env, user)
+        .iter()
+        .filter(|id| get_lock_save(env, *id).is_some_and(|l| !l.is_withdrawn))
+        .count() as u32
+}
+
 // --- Internal Helper Functions ---
 
-fn get_next_lock_id(env: &Env) -> u64 {
+/// Stores a new lock for `user` starting now and updates their profile stats
+pub(crate) fn insert_lock_save(
+    env: &Env,
+    user: &Address,
+    amount: i128,
+    duration: u64,
+    interest_rate: u32,
+    rollover: bool,
+    compounding: CompoundFrequency,
+) -> Result<u64, SavingsError> {
+    let lock_id = plan_ids::next_plan_id(env, user, PlanKind::Lock);
+
+    let start_time = env.ledger().timestamp();
+    let maturity_time = start_time
+        .checked_add(duration)
+        .ok_or(SavingsError::Overflow)?;
+
+    let lock_save = LockSave {
+        id: lock_id,
+        owner: user.clone(),
+        amount,
+        interest_rate,
+        start_time,
+        maturity_time,
+        is_withdrawn: false,
+        interest_start: start_time,
+        rollover,
+        compounding,
+        target_amount: 0,
+    };
+
+    // Store the LockSave
     env.storage()
         .persistent()
-        .get(&DataKey::NextLockId)
-        .unwrap_or(1)
-}
+        .set(&DataKey::LockSave(lock_id), &lock_save);
 
-fn increment_next_lock_id(env: &Env) {
-    let current_id = get_next_lock_id(env);
-    env.storage()
+    yield_pool::adjust_outstanding_interest(env, interest_to_maturity(&lock_save)?)?;
+
+    // Update user's lock list
+    add_lock_to_user(env, user, lock_id);
+
+    // Update user's profile stats
+    let user_key = DataKey::User(user.clone());
+    let mut user_data: User = env
+        .storage()
         .persistent()
-        .set(&DataKey::NextLockId, &(current_id + 1));
+        .get(&user_key)
+        .ok_or(SavingsError::UserNotFound)?;
+    user_data.total_balance += amount;
+    user_data.savings_count += 1;
+    env.storage().persistent().set(&user_key, &user_data);
+
+    Ok(lock_id)
 }
 
 fn add_lock_to_user(env: &Env, user: &Address, lock_id: u64) {
@@ -146,10 +794,508 @@ fn add_lock_to_user(env: &Env, user: &Address, lock_id: u64) {
         .set(&DataKey::UserLockSaves(user.clone()), &user_locks);
 }
 
-fn calculate_lock_save_yield(lock_save: &LockSave, current_time: u64) -> i128 {
-    let duration_seconds = current_time.saturating_sub(lock_save.start_time);
+/// Deletes a lock record and drops it from its owner's list
+pub(crate) fn remove_lock_save(env: &Env, lock_save: &LockSave) {
+    let mut user_locks = get_user_lock_saves(env, &lock_save.owner);
+    if let Some(index) = user_locks.first_index_of(lock_save.id) {
+        user_locks.remove(index);
+    }
+    env.storage().persistent().set(
+        &DataKey::UserLockSaves(lock_save.owner.clone()),
+        &user_locks,
+    );
+    env.storage()
+        .persistent()
+        .remove(&DataKey::LockSave(lock_save.id));
+}
+
+/// Interest a lock earns if held exactly to maturity
+fn interest_to_maturity(lock_save: &LockSave) -> Result<i128, SavingsError> {
+    Ok(calculate_lock_save_yield(lock_save, lock_save.maturity_time)? - lock_save.amount)
+}
+
+/// Settles a matured lock: its interest leaves the outstanding total and is
+/// paid from the yield pool as far as the pool allows. Returns the principal
+/// plus the interest actually paid.
+fn settle_lock_payout(env: &Env, lock_save: &LockSave, now: u64) -> Result<i128, SavingsError> {
+    yield_pool::adjust_outstanding_interest(env, -interest_to_maturity(lock_save)?)?;
+    let due = calculate_lock_save_yield(lock_save, now)? - lock_save.amount;
+    let paid = yield_pool::draw_interest(env, &lock_save.owner, due)?;
+    Ok(lock_save.amount + paid)
+}
+
+/// VIEW FUNCTION - What `withdraw_lock_save` would pay out if called now,
+/// with interest limited to what the yield pool can cover
+///
+/// # Errors
+/// * `PlanNotFound` - If the lock does not exist
+/// * `PlanCompleted` - If the lock was already withdrawn
+/// * `TooEarly` - If the lock has not unlocked yet
+pub fn preview_withdraw_lock(env: &Env, lock_id: u64) -> Result<WithdrawalPreview, SavingsError> {
+    let lock_save = get_lock_save(env, lock_id).ok_or(SavingsError::PlanNotFound)?;
+    if lock_save.is_withdrawn {
+        return Err(SavingsError::PlanCompleted);
+    }
+    if !is_unlocked(env, &lock_save) {
+        return Err(SavingsError::TooEarly);
+    }
+
+    let due = calculate_lock_save_yield(&lock_save, env.ledger().timestamp())? - lock_save.amount;
+    let interest = due.clamp(0, yield_pool::get_pool_balance(env).max(0));
+    let breakdown = fees::quote_withdrawal_fee(
+        env,
+        &lock_save.owner,
+        PlanKind::Lock,
+        lock_save.amount + interest,
+    )?;
+    Ok(WithdrawalPreview {
+        gross: breakdown.gross,
+        interest,
+        penalty: 0,
+        fee: breakdown.fee,
+        net: breakdown.net,
+    })
+}
+
+/// Amount a lock created now would pay out at maturity, before fees and
+/// assuming the yield pool can cover its interest
+pub fn preview_maturity_amount(
+    env: &Env,
+    amount: i128,
+    duration: u64,
+    compounding: CompoundFrequency,
+) -> Result<i128, SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if duration == 0 || duration > MAX_LOCK_DURATION {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+    let rate = rates::get_rate_at(env, env.ledger().timestamp());
+    accrue(amount, rate, duration, compounding)
+}
+
+/// Seconds between compounding points, if interest compounds at all
+pub fn compounding_period_seconds(compounding: CompoundFrequency) -> Option<u64> {
+    match compounding {
+        CompoundFrequency::None => None,
+        CompoundFrequency::Monthly => Some(SECONDS_PER_YEAR / 12),
+        CompoundFrequency::Quarterly => Some(SECONDS_PER_YEAR / 4),
+    }
+}
+
+/// Principal plus the interest the lock has accrued by `current_time`
+pub(crate) fn calculate_lock_save_yield(
+    lock_save: &LockSave,
+    current_time: u64,
+) -> Result<i128, SavingsError> {
+    let duration_seconds = current_time.saturating_sub(lock_save.interest_start);
+    accrue(
+        lock_save.amount,
+        lock_save.interest_rate,
+        duration_seconds,
+        lock_save.compounding,
+    )
+}
+
+/// Principal plus interest after `duration_seconds`.
+///
+/// Compounded interest is computed in integers so every node gets the same
+/// result: each full period adds its interest to the balance, rounded down,
+/// and any partial period at the end earns simple interest on the compounded
+/// balance, also rounded down.
+///
+/// # Errors
+/// * `Overflow` - If the balance no longer fits in an `i128`
+fn accrue(
+    amount: i128,
+    rate_bps: u32,
+    duration_seconds: u64,
+    compounding: CompoundFrequency,
+) -> Result<i128, SavingsError> {
+    let Some(period) = compounding_period_seconds(compounding) else {
+        return Ok(simple_accrual(amount, rate_bps, duration_seconds));
+    };
+
+    let periods_per_year = (SECONDS_PER_YEAR / period) as i128;
+    let mut balance = amount;
+    for _ in 0..duration_seconds / period {
+        balance = balance
+            .checked_add(balance.saturating_mul(rate_bps as i128) / (10_000 * periods_per_year))
+            .ok_or(SavingsError::Overflow)?;
+    }
+    let remainder = (duration_seconds % period) as i128;
+    balance
+        .checked_add(
+            balance
+                .saturating_mul(rate_bps as i128)
+                .saturating_mul(remainder)
+                / (10_000 * SECONDS_PER_YEAR as i128),
+        )
+        .ok_or(SavingsError::Overflow)
+}
+
+fn simple_accrual(amount: i128, rate_bps: u32, duration_seconds: u64) -> i128 {
     let duration_years = (duration_seconds as f64) / (365.25 * 24.0 * 3600.0);
-    let rate_decimal = (lock_save.interest_rate as f64) / 10000.0;
+    let rate_decimal = (rate_bps as f64) / 10000.0;
     let multiplier = 1.0 + (rate_decimal * duration_years);
-    (lock_save.amount as f64 * multiplier) as i128
+    (amount as f64 * multiplier) as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_LOCK_DURATION;
+    use crate::storage_types::InterestAccrual;
+    use crate::{CompoundFrequency, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        symbol_short,
+        testutils::{Address as _, Events, Ledger},
+        token, vec, Address, BytesN, Env, IntoVal, Symbol, TryFromVal,
+    };
+
+    const YEAR: u64 = 31_557_600;
+
+    /// Registers the contract with a yield pool holding `pool` tokens
+    fn setup_with_pool(pool: i128) -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        env.mock_all_auths();
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &token,
+        );
+        if pool > 0 {
+            token::StellarAssetClient::new(&env, &token).mint(&admin, &pool);
+            client.fund_yield_pool(&admin, &pool);
+        }
+        client.initialize_user(&user);
+
+        (env, client, user)
+    }
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        setup_with_pool(1_000_000)
+    }
+
+    #[test]
+    fn test_withdraw_lock_save_emits_interest_event() {
+        let (env, client, user) = setup();
+
+        let lock_id = client.create_lock_save(&user, &1_000_000, &YEAR);
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+
+        let final_amount = client.withdraw_lock_save(&user, &lock_id);
+        assert_eq!(final_amount, 1_050_000);
+
+        let interest_topic: Symbol = symbol_short!("interest");
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(0).unwrap())
+                    .map(|t| t == interest_topic)
+                    .unwrap_or(false)
+            })
+            .expect("interest event not emitted");
+
+        assert_eq!(
+            event.1,
+            (interest_topic, symbol_short!("lock"), user.clone()).into_val(&env)
+        );
+        let (_, accrual) = <(u64, InterestAccrual)>::try_from_val(&env, &event.2).unwrap();
+        assert_eq!(
+            accrual,
+            InterestAccrual {
+                plan_id: lock_id,
+                principal: 1_000_000,
+                rate_bps: 500,
+                period_seconds: YEAR,
+                amount: 50_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_process_matured_locks_rolls_over_enabled_locks() {
+        let (env, client, user) = setup();
+
+        let rolling = client.create_lock_save(&user, &1_000_000, &YEAR);
+        let manual = client.create_lock_save(&user, &1_000_000, &YEAR);
+        client.set_lock_rollover(&user, &rolling, &true);
+
+        // Nothing happens before maturity
+        let ids = soroban_sdk::vec![&env, rolling, manual];
+        assert_eq!(client.process_matured_locks(&ids).len(), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        let new_ids = client.process_matured_locks(&ids);
+        assert_eq!(new_ids.len(), 1);
+
+        let new_id = new_ids.get(0).unwrap();
+        let new_lock = client.get_lock_save_detail(&new_id);
+        assert_eq!(new_lock.amount, 1_050_000);
+        assert_eq!(new_lock.start_time, YEAR);
+        assert_eq!(new_lock.maturity_time, 2 * YEAR);
+        assert!(new_lock.rollover);
+
+        assert!(client.get_lock_save_detail(&rolling).is_withdrawn);
+        assert!(!client.get_lock_save_detail(&manual).is_withdrawn);
+        assert_eq!(client.get_user(&user).total_balance, 2_050_000);
+
+        // Already-rolled locks are skipped on a second pass
+        assert_eq!(client.process_matured_locks(&ids).len(), 0);
+    }
+
+    #[test]
+    fn test_deposit_to_lock_save_weights_interest_start() {
+        let (env, client, user) = setup();
+
+        let lock_id = client.create_lock_save(&user, &1_000_000, &YEAR);
+        env.ledger().with_mut(|li| li.timestamp = YEAR / 2);
+        client.deposit_to_lock_save(&user, &lock_id, &1_000_000);
+
+        let lock = client.get_lock_save_detail(&lock_id);
+        assert_eq!(lock.amount, 2_000_000);
+        assert_eq!(lock.interest_start, YEAR / 4);
+        assert_eq!(lock.start_time, 0);
+        assert_eq!(lock.maturity_time, YEAR);
+        assert_eq!(client.get_user(&user).total_balance, 2_000_000);
+
+        // 5% for a full year on the first million plus half a year on the top-up
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        assert_eq!(client.withdraw_lock_save(&user, &lock_id), 2_075_000);
+    }
+
+    #[test]
+    fn test_top_up_keeps_rollover_term() {
+        let (env, client, user) = setup();
+
+        let lock_id = client.create_lock_save(&user, &1_000_000, &YEAR);
+        client.set_lock_rollover(&user, &lock_id, &true);
+        env.ledger().with_mut(|li| li.timestamp = YEAR / 2);
+        client.deposit_to_lock_save(&user, &lock_id, &1_000_000);
+
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        let ids = client.process_matured_locks(&vec![&env, lock_id]);
+        let rolled = client.get_lock_save_detail(&ids.get(0).unwrap());
+        assert_eq!(rolled.maturity_time - rolled.start_time, YEAR);
+        assert_eq!(rolled.interest_start, YEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #52)")]
+    fn test_deposit_to_matured_lock_save_fails() {
+        let (env, client, user) = setup();
+        let lock_id = client.create_lock_save(&user, &1_000, &YEAR);
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        client.deposit_to_lock_save(&user, &lock_id, &500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1)")]
+    fn test_set_lock_rollover_requires_owner() {
+        let (env, client, user) = setup();
+        let lock_id = client.create_lock_save(&user, &1_000, &YEAR);
+        client.set_lock_rollover(&Address::generate(&env), &lock_id, &true);
+    }
+
+    #[test]
+    fn test_sweep_matured_locks_credits_flexi() {
+        let (env, client, user) = setup();
+        let keeper = Address::generate(&env);
+
+        let matured = client.create_lock_save(&user, &1_000_000, &YEAR);
+        let rolling = client.create_lock_save(&user, &1_000_000, &YEAR);
+        let pending = client.create_lock_save(&user, &1_000_000, &(2 * YEAR));
+        client.set_lock_rollover(&user, &rolling, &true);
+
+        let ids = soroban_sdk::vec![&env, matured, rolling, pending, 999];
+        assert_eq!(client.sweep_matured_locks(&keeper, &ids).len(), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        let swept = client.sweep_matured_locks(&keeper, &ids);
+        assert_eq!(swept, soroban_sdk::vec![&env, matured]);
+
+        assert!(client.get_lock_save_detail(&matured).is_withdrawn);
+        assert!(!client.get_lock_save_detail(&rolling).is_withdrawn);
+        assert_eq!(client.get_flexi_balance(&user), 1_050_000);
+        assert_eq!(client.get_user(&user).total_balance, 3_050_000);
+
+        // Swept locks can't be swept or withdrawn again
+        assert_eq!(client.sweep_matured_locks(&keeper, &ids).len(), 0);
+        assert!(client.try_withdraw_lock_save(&user, &matured).is_err());
+    }
+
+    #[test]
+    fn test_transfer_lock_save_moves_ownership_and_balance() {
+        let (env, client, user) = setup();
+        let child = Address::generate(&env);
+        client.initialize_user(&child);
+
+        let lock_id = client.create_lock_save(&user, &1_000, &YEAR);
+        assert!(client
+            .try_transfer_lock_save(&child, &user, &lock_id)
+            .is_err());
+        assert!(client
+            .try_transfer_lock_save(&user, &Address::generate(&env), &lock_id)
+            .is_err());
+
+        client.transfer_lock_save(&user, &child, &lock_id);
+
+        assert_eq!(client.get_lock_save_detail(&lock_id).owner, child);
+        assert_eq!(client.get_user_lock_saves(&user).len(), 0);
+        assert_eq!(
+            client.get_user_lock_saves(&child),
+            soroban_sdk::vec![&env, lock_id]
+        );
+        assert_eq!(client.get_user(&user).total_balance, 0);
+        assert_eq!(client.get_user(&child).total_balance, 1_000);
+
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        assert!(client.try_withdraw_lock_save(&user, &lock_id).is_err());
+        assert!(client.withdraw_lock_save(&child, &lock_id) >= 1_000);
+    }
+
+    #[test]
+    fn test_interest_paid_only_as_far_as_pool_allows() {
+        let (env, client, user) = setup_with_pool(30_000);
+
+        let lock_id = client.create_lock_save(&user, &1_000_000, &YEAR);
+        let status = client.get_yield_pool_status();
+        assert_eq!(status.outstanding_interest, 50_000);
+        assert_eq!(status.shortfall, 20_000);
+
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        assert_eq!(client.withdraw_lock_save(&user, &lock_id), 1_030_000);
+
+        let status = client.get_yield_pool_status();
+        assert_eq!(status.pool_balance, 0);
+        assert_eq!(status.outstanding_interest, 0);
+
+        // An empty pool still returns the principal
+        let second = client.create_lock_save(&user, &1_000_000, &YEAR);
+        env.ledger().with_mut(|li| li.timestamp = 2 * YEAR);
+        assert_eq!(client.withdraw_lock_save(&user, &second), 1_000_000);
+    }
+
+    #[test]
+    fn test_preview_compounded_maturity_rounds_down_each_period() {
+        let (_env, client, _user) = setup_with_pool(0);
+        let quarter = YEAR / 4;
+
+        // 1.25% a quarter: 10_125, 10_251.56 -> 10_251, 10_379.13 -> 10_379,
+        // 10_508.73 -> 10_508
+        assert_eq!(
+            client.preview_maturity_amount(&10_000, &YEAR, &CompoundFrequency::Quarterly),
+            10_508
+        );
+        // Half a quarter after the first: 10_125 plus 63.28 of simple interest
+        assert_eq!(
+            client.preview_maturity_amount(
+                &10_000,
+                &(quarter + quarter / 2),
+                &CompoundFrequency::Quarterly
+            ),
+            10_188
+        );
+        assert_eq!(
+            client.preview_maturity_amount(&1_000_000, &YEAR, &CompoundFrequency::Monthly),
+            1_051_155
+        );
+        assert!(client
+            .try_preview_maturity_amount(&0, &YEAR, &CompoundFrequency::Monthly)
+            .is_err());
+    }
+
+    #[test]
+    fn test_lock_duration_is_capped_and_accrual_checked() {
+        let (_env, client, user) = setup_with_pool(0);
+
+        assert!(client
+            .try_preview_maturity_amount(&10_000, &MAX_LOCK_DURATION, &CompoundFrequency::Monthly)
+            .is_ok());
+        assert_eq!(
+            client
+                .try_preview_maturity_amount(&10_000, &u64::MAX, &CompoundFrequency::Monthly)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidTimestamp)
+        );
+        assert_eq!(
+            client
+                .try_create_lock_save_compounding(
+                    &user,
+                    &10_000,
+                    &(MAX_LOCK_DURATION + 1),
+                    &CompoundFrequency::Monthly
+                )
+                .unwrap_err(),
+            Ok(SavingsError::InvalidTimestamp)
+        );
+        assert_eq!(
+            client
+                .try_preview_maturity_amount(&(i128::MAX - 1), &YEAR, &CompoundFrequency::Monthly)
+                .unwrap_err(),
+            Ok(SavingsError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_compounding_lock_pays_preview_at_maturity() {
+        let (env, client, user) = setup_with_pool(100_000);
+
+        let expected =
+            client.preview_maturity_amount(&1_000_000, &YEAR, &CompoundFrequency::Monthly);
+        let lock_id = client.create_lock_save_compounding(
+            &user,
+            &1_000_000,
+            &YEAR,
+            &CompoundFrequency::Monthly,
+        );
+        assert_eq!(
+            client.get_lock_save_detail(&lock_id).compounding,
+            CompoundFrequency::Monthly
+        );
+        assert_eq!(
+            client.get_yield_pool_status().outstanding_interest,
+            expected - 1_000_000
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        assert_eq!(client.withdraw_lock_save(&user, &lock_id), expected);
+    }
+
+    #[test]
+    fn test_target_lock_unlocks_once_target_is_reached() {
+        let (env, client, user) = setup();
+
+        assert!(client.try_create_target_lock(&user, &500, &500).is_err());
+        let lock_id = client.create_target_lock(&user, &1_000, &400);
+        let progress = client.get_target_lock_progress(&lock_id);
+        assert_eq!((progress.remaining, progress.unlocked), (600, false));
+        assert_eq!(progress.long_stop_time, super::TARGET_LOCK_LONG_STOP);
+        assert!(client.try_withdraw_lock_save(&user, &lock_id).is_err());
+        assert!(client
+            .try_set_lock_rollover(&user, &lock_id, &true)
+            .is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.deposit_to_lock_save(&user, &lock_id, &600);
+        assert!(client.check_matured_lock(&lock_id));
+        assert_eq!(client.get_target_lock_progress(&lock_id).remaining, 0);
+        assert!(client.withdraw_lock_save(&user, &lock_id) >= 1_000);
+
+        let plain = client.create_lock_save(&user, &100, &YEAR);
+        assert!(client.try_get_target_lock_progress(&plain).is_err());
+    }
 }
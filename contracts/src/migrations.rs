@@ -0,0 +1,647 @@
+use soroban_sdk::{panic_with_error, Address, Env, IntoVal, Map, Symbol, TryFromVal, Val};
+
+use crate::autosave;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::flexi;
+use crate::goal;
+use crate::group;
+use crate::lock;
+use crate::storage_types::{
+    CompoundFrequency, ConfigKey, DataKey, GoalSave, GroupJoinRequirements, LockSave, PlanType,
+    SavingsPlan, User,
+};
+use crate::users;
+
+/// Storage layout version written by this code.
+///
+/// Contracts initialized by this code start at this version. Contracts
+/// upgraded from older code keep their stored version (0 if none was
+/// recorded) until `run_migration` brings them up to date.
+///
+/// Version history:
+/// * 0 - AutoSave schedules were not listed in the due index
+/// * 1 - Every live AutoSave schedule is listed in the due index
+/// * 2 - Lock and Goal Save IDs are allocated per user; the global ID
+///   counters are gone. Plans created before keep their IDs.
+/// * 3 - Lock Saves record their compounding frequency. Older locks read as
+///   `CompoundFrequency::None`.
+/// * 4 - Lock Saves record a target balance. Older locks read as having none.
+/// * 5 - Goal Saves record whether they are public. Older goals read as
+///   private.
+/// * 6 - Group join requirements record a join stake. Every stored record
+///   carries it.
+/// * 7 - Lock Saves accrue interest from a separate, amount-weighted
+///   `interest_start` instead of moving `start_time` on top-ups. Older locks
+///   read with it equal to their `start_time`.
+/// * 8 - Keeper tips are flat per execution and capped per ledger. The old
+///   amount-based tip setting is dropped, so no tips are paid until one is
+///   set again.
+pub const CURRENT_STORAGE_VERSION: u32 = 8;
+
+pub fn get_storage_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::StorageVersion))
+        .unwrap_or(0)
+}
+
+pub fn set_storage_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::StorageVersion), &version);
+}
+
+/// Runs up to `batch_size` steps of the migration from the stored version to
+/// the next one and returns the storage version afterwards.
+///
+/// Progress is saved between calls, so a migration too large for one
+/// transaction is finished by calling again. Each call advances at most one
+/// version; once the stored version is current, calls do nothing.
+///
+/// # Errors
+/// * `InvalidAmount` - If `batch_size` is 0
+/// * `DataCorruption` - If the stored version is newer than this code knows
+pub fn run_migration(env: &Env, batch_size: u32) -> Result<u32, SavingsError> {
+    if batch_size == 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let version = get_storage_version(env);
+    if version == CURRENT_STORAGE_VERSION {
+        return Ok(version);
+    }
+
+    let cursor_key = DataKey::Config(ConfigKey::MigrationCursor);
+    let cursor: u64 = env.storage().instance().get(&cursor_key).unwrap_or(0);
+    let next_cursor = match version {
+        0 => migrate_v0_to_v1(env, cursor, batch_size),
+        1 => migrate_v1_to_v2(env),
+        2 => migrate_v2_to_v3(),
+        3 => migrate_v3_to_v4(),
+        4 => migrate_v4_to_v5(),
+        5 => migrate_v5_to_v6(env, cursor, batch_size),
+        6 => migrate_v6_to_v7(),
+        7 => migrate_v7_to_v8(env),
+        _ => return Err(SavingsError::DataCorruption),
+    };
+
+    match next_cursor {
+        Some(next_cursor) => {
+            env.storage().instance().set(&cursor_key, &next_cursor);
+            events::publish(
+                env,
+                events::MigrationProgressed {
+                    version,
+                    next_cursor,
+                },
+            );
+            Ok(version)
+        }
+        None => {
+            env.storage().instance().remove(&cursor_key);
+            set_storage_version(env, version + 1);
+            events::publish(
+                env,
+                events::MigrationCompleted {
+                    version: version + 1,
+                },
+            );
+            Ok(version + 1)
+        }
+    }
+}
+
+/// Lists AutoSave schedules created before the due index in it. The cursor
+/// is the next schedule ID to visit.
+///
+/// Returns the cursor to resume from, or `None` once every schedule has been
+/// visited.
+fn migrate_v0_to_v1(env: &Env, cursor: u64, batch_size: u32) -> Option<u64> {
+    let start = cursor.max(1);
+    let end = autosave::get_next_schedule_id(env).min(start + batch_size as u64);
+    for schedule_id in start..end {
+        autosave::reindex_autosave(env, schedule_id);
+    }
+
+    if end < autosave::get_next_schedule_id(env) {
+        Some(end)
+    } else {
+        None
+    }
+}
+
+/// Removes the global Lock and Goal Save ID counters, which per-user IDs
+/// replace. Existing plans stay under their IDs, which new IDs skip, so no
+/// plan is re-keyed and this finishes in one step.
+fn migrate_v1_to_v2(env: &Env) -> Option<u64> {
+    env.storage().persistent().remove(&DataKey::NextLockId);
+    env.storage().persistent().remove(&DataKey::NextGoalId);
+    None
+}
+
+/// Lock Saves are keyed by per-user IDs that cannot be enumerated, so locks
+/// written before `compounding` existed are not rewritten here. They are
+/// filled in whenever read, see `read_lock_save`.
+fn migrate_v2_to_v3() -> Option<u64> {
+    None
+}
+
+/// Locks written before `target_amount` existed are filled in when read, as
+/// for the v3 step.
+fn migrate_v3_to_v4() -> Option<u64> {
+    None
+}
+
+/// Goal Saves are keyed by per-user IDs as well, so goals written before
+/// `is_public` existed are filled in when read, see `read_goal_save`.
+fn migrate_v4_to_v5() -> Option<u64> {
+    None
+}
+
+/// Rewrites the join requirements of groups created before `join_stake`
+/// existed with no stake. The cursor is the next group ID to visit.
+///
+/// Returns the cursor to resume from, or `None` once every group has been
+/// visited.
+fn migrate_v5_to_v6(env: &Env, cursor: u64, batch_size: u32) -> Option<u64> {
+    let next_group_id = group::get_next_group_id(env);
+    let start = cursor.max(1);
+    let end = next_group_id.min(start + batch_size as u64);
+    for group_id in start..end {
+        if let Some(requirements) = read_join_requirements(env, group_id) {
+            env.storage()
+                .persistent()
+                .set(&DataKey::GroupJoinRequirements(group_id), &requirements);
+        }
+    }
+
+    if end < next_group_id {
+        Some(end)
+    } else {
+        None
+    }
+}
+
+/// Locks written before `interest_start` existed are filled in when read, as
+/// for the v3 step.
+fn migrate_v6_to_v7() -> Option<u64> {
+    None
+}
+
+/// Removes the amount-based keeper tip setting, which the flat, capped one
+/// replaces. Finishes in one step.
+fn migrate_v7_to_v8(env: &Env) -> Option<u64> {
+    env.storage()
+        .instance()
+        .remove(&DataKey::Config(ConfigKey::KeeperTip));
+    None
+}
+
+/// Reads a Lock Save, filling in fields added to the layout since it was
+/// written
+pub(crate) fn read_lock_save(env: &Env, lock_id: u64) -> Option<LockSave> {
+    let mut fields = read_fields(env, &DataKey::LockSave(lock_id))?;
+    fill_field(
+        env,
+        &mut fields,
+        "compounding",
+        CompoundFrequency::None.into_val(env),
+    );
+    fill_field(env, &mut fields, "target_amount", 0i128.into_val(env));
+    if let Some(start_time) = fields.get(Symbol::new(env, "start_time")) {
+        fill_field(env, &mut fields, "interest_start", start_time);
+    }
+    Some(decode_fields(env, fields))
+}
+
+/// Reads a Goal Save, filling in fields added to the layout since it was
+/// written
+pub(crate) fn read_goal_save(env: &Env, goal_id: u64) -> Option<GoalSave> {
+    let mut fields = read_fields(env, &DataKey::GoalSave(goal_id))?;
+    fill_field(env, &mut fields, "is_public", false.into_val(env));
+    Some(decode_fields(env, fields))
+}
+
+/// Reads a group's join requirements, filling in fields added to the layout
+/// since they were written
+pub(crate) fn read_join_requirements(env: &Env, group_id: u64) -> Option<GroupJoinRequirements> {
+    let mut fields = read_fields(env, &DataKey::GroupJoinRequirements(group_id))?;
+    fill_field(env, &mut fields, "join_stake", 0i128.into_val(env));
+    Some(decode_fields(env, fields))
+}
+
+/// Raw fields of a stored struct, whatever layout it was written with
+fn read_fields(env: &Env, key: &DataKey) -> Option<Map<Symbol, Val>> {
+    env.storage().persistent().get(key)
+}
+
+/// Sets field `name` to `value` unless the record already has it
+fn fill_field(env: &Env, fields: &mut Map<Symbol, Val>, name: &str, value: Val) {
+    let name = Symbol::new(env, name);
+    if !fields.contains_key(name.clone()) {
+        fields.set(name, value);
+    }
+}
+
+fn decode_fields<T: TryFromVal<Env, Val>>(env: &Env, fields: Map<Symbol, Val>) -> T {
+    T::try_from_val(env, &fields.to_val())
+        .unwrap_or_else(|_| panic_with_error!(env, SavingsError::DataCorruption))
+}
+
+/// Moves the user's legacy `SavingsPlan` records onto the dedicated plan
+/// structs and deletes them. Returns the number of records imported.
+///
+/// Legacy records are keyed per user, so this runs user by user rather than
+/// through `run_migration`. Records mirroring a group membership are dropped,
+/// since the group already tracks the member's contributions. Calling again
+/// once a user is migrated does nothing.
+///
+/// # Errors
+/// * `UserNotFound` - If the user is not initialized
+pub fn migrate_legacy_plans(env: &Env, user: &Address) -> Result<u32, SavingsError> {
+    let user_data = users::get_user(env, user)?;
+    let storage = env.storage().persistent();
+    for group_id in group::get_user_groups(env, user).iter() {
+        storage.remove(&DataKey::SavingsPlan(user.clone(), group_id));
+    }
+
+    let mut imported = 0;
+    for plan_id in 1..=user_data.savings_count as u64 {
+        let key = DataKey::SavingsPlan(user.clone(), plan_id);
+        let Some(plan) = storage.get::<DataKey, SavingsPlan>(&key) else {
+            continue;
+        };
+        storage.remove(&key);
+        import_legacy_plan(env, user, &plan)?;
+        imported += 1;
+    }
+
+    events::publish(
+        env,
+        events::LegacyPlansMigrated {
+            user: user.clone(),
+            imported,
+        },
+    );
+    Ok(imported)
+}
+
+/// Creates the dedicated plan a legacy `SavingsPlan` describes and returns
+/// its ID, or 0 if the balance went to the user's Flexi Save. The balance
+/// must already count towards the user's `total_balance`, as legacy plan
+/// balances did; it is re-booked to match where it lands.
+///
+/// Group-type legacy plans have no group behind them, so their balance goes
+/// to the Flexi Save. Withdrawn plans import nothing.
+fn import_legacy_plan(env: &Env, user: &Address, plan: &SavingsPlan) -> Result<u64, SavingsError> {
+    if plan.is_withdrawn {
+        return Ok(0);
+    }
+
+    let user_key = DataKey::User(user.clone());
+    let mut user_data: User = users::get_user(env, user)?;
+    user_data.total_balance = user_data
+        .total_balance
+        .checked_sub(plan.balance)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage().persistent().set(&user_key, &user_data);
+
+    match &plan.plan_type {
+        PlanType::Flexi | PlanType::Group(..) => {
+            if plan.balance > 0 {
+                flexi::credit_flexi(env, user, plan.balance)?;
+            }
+            Ok(0)
+        }
+        PlanType::Lock(locked_until) => {
+            if plan.balance <= 0 {
+                return Ok(0);
+            }
+            lock::insert_lock_save(
+                env,
+                user,
+                plan.balance,
+                locked_until.saturating_sub(env.ledger().timestamp()),
+                plan.interest_rate,
+                false,
+                CompoundFrequency::None,
+            )
+        }
+        PlanType::Goal(goal_name, target_amount, _) => goal::insert_goal_save(
+            env,
+            user.clone(),
+            goal_name.clone(),
+            *target_amount,
+            plan.balance,
+            0,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cursor, GroupMetadata, NesteraContract, NesteraContractClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec, Address, BytesN, Env, String,
+    };
+
+    #[test]
+    fn test_v0_migration_indexes_autosaves_across_calls() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        assert_eq!(client.get_storage_version(), CURRENT_STORAGE_VERSION);
+        assert_eq!(client.run_migration(&admin, &10), CURRENT_STORAGE_VERSION);
+
+        client.initialize_user(&user);
+        let first = client.create_autosave(&user, &100, &86_400, &0);
+        let second = client.create_autosave(&user, &100, &86_400, &0);
+        let third = client.create_autosave(&user, &100, &86_400, &0);
+        client.cancel_autosave(&user, &second);
+
+        // Roll storage back to a deployment that predates the due index
+        env.as_contract(&contract_id, || {
+            let buckets_key = DataKey::Config(ConfigKey::AutoSaveDueBuckets);
+            env.storage().persistent().remove(&buckets_key);
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Config(ConfigKey::AutoSaveDueBucket(0)));
+            set_storage_version(&env, 0);
+        });
+        let page = Cursor {
+            offset: 0,
+            limit: 0,
+        };
+        assert_eq!(client.get_due_autosaves(&1, &page).0.len(), 0);
+
+        assert_eq!(client.run_migration(&admin, &2), 0);
+        assert_eq!(client.run_migration(&admin, &2), 1);
+        assert_eq!(
+            client.get_due_autosaves(&1, &page).0,
+            vec![&env, first, third]
+        );
+
+        // Each later step takes one call, after which nothing changes
+        assert_eq!(client.run_migration(&admin, &2), 2);
+        while client.get_storage_version() < CURRENT_STORAGE_VERSION {
+            client.run_migration(&admin, &2);
+        }
+        assert_eq!(client.run_migration(&admin, &2), CURRENT_STORAGE_VERSION);
+        assert_eq!(
+            client.get_due_autosaves(&1, &page).0,
+            vec![&env, first, third]
+        );
+    }
+
+    #[test]
+    fn test_v1_migration_retires_global_plan_counters() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+        let lock_id = client.create_lock_save(&user, &100, &86_400);
+
+        // Roll storage back to a deployment with the global lock counter
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&DataKey::NextLockId, &2u64);
+            set_storage_version(&env, 1);
+        });
+
+        assert_eq!(client.run_migration(&admin, &1), 2);
+        env.as_contract(&contract_id, || {
+            assert!(!env.storage().persistent().has(&DataKey::NextLockId));
+        });
+        assert_eq!(client.get_lock_save_detail(&lock_id).amount, 100);
+        assert_ne!(client.create_lock_save(&user, &100, &86_400), lock_id);
+    }
+
+    #[test]
+    fn test_older_lock_layouts_read_with_defaults() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+        let lock_id = client.create_lock_save(&user, &100, &86_400);
+        let current = client.get_lock_save_detail(&lock_id);
+
+        // Store the lock as version 2 wrote it, before `compounding`,
+        // `target_amount` and `interest_start`
+        env.as_contract(&contract_id, || {
+            let key = DataKey::LockSave(lock_id);
+            let mut fields: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+            fields.remove(Symbol::new(&env, "compounding"));
+            fields.remove(Symbol::new(&env, "target_amount"));
+            fields.remove(Symbol::new(&env, "interest_start"));
+            env.storage().persistent().set(&key, &fields);
+            set_storage_version(&env, 2);
+        });
+
+        assert_eq!(client.get_lock_save_detail(&lock_id), current);
+        assert_eq!(client.run_migration(&admin, &1), 3);
+        assert_eq!(client.run_migration(&admin, &1), 4);
+        env.ledger().with_mut(|li| li.timestamp = 86_400);
+        assert_eq!(client.withdraw_lock_save(&user, &lock_id), 100);
+    }
+
+    #[test]
+    fn test_older_goal_layouts_read_as_private() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+        let goal_name = Symbol::new(&env, "car");
+        let goal_id = client.create_goal_save(&user, &goal_name, &1_000, &100);
+        let current = client.get_goal_save_detail(&goal_id);
+
+        // Store the goal as version 4 wrote it, before `is_public`
+        env.as_contract(&contract_id, || {
+            let key = DataKey::GoalSave(goal_id);
+            let mut fields: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+            fields.remove(Symbol::new(&env, "is_public"));
+            env.storage().persistent().set(&key, &fields);
+            set_storage_version(&env, 4);
+        });
+
+        assert_eq!(client.get_goal_save_detail(&goal_id), current);
+        assert_eq!(client.run_migration(&admin, &1), 5);
+        client.deposit_to_goal_save(&user, &goal_id, &100);
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 200);
+    }
+
+    #[test]
+    fn test_v5_migration_adds_join_stakes_across_calls() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+        let metadata = GroupMetadata {
+            title: String::from_str(&env, "Circle"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        };
+        let mut group_ids = vec![&env];
+        for _ in 0..3 {
+            let group_id =
+                client.create_group_save(&user, &metadata, &5000, &0, &100, &true, &1, &500, &0);
+            client.set_group_join_requirements(&user, &group_id, &10, &0);
+            group_ids.push_back(group_id);
+        }
+
+        // Store the requirements as version 5 wrote them, before `join_stake`
+        env.as_contract(&contract_id, || {
+            for group_id in group_ids.iter() {
+                let key = DataKey::GroupJoinRequirements(group_id);
+                let mut fields: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+                fields.remove(Symbol::new(&env, "join_stake"));
+                env.storage().persistent().set(&key, &fields);
+            }
+            set_storage_version(&env, 5);
+        });
+
+        assert_eq!(client.run_migration(&admin, &2), 5);
+        assert_eq!(client.run_migration(&admin, &2), 6);
+        env.as_contract(&contract_id, || {
+            for group_id in group_ids.iter() {
+                let key = DataKey::GroupJoinRequirements(group_id);
+                let fields: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+                assert!(fields.contains_key(Symbol::new(&env, "join_stake")));
+            }
+        });
+        let requirements = client.get_group_join_requirements(&group_ids.get(0).unwrap());
+        assert_eq!(
+            (requirements.min_reputation, requirements.join_stake),
+            (10, 0)
+        );
+    }
+
+    #[test]
+    fn test_legacy_plans_move_onto_dedicated_structs() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        client.initialize_user(&user);
+
+        // Records as the legacy create_savings_plan left them: balances count
+        // towards total_balance
+        let legacy = |plan_id: u64, plan_type: PlanType, balance: i128| SavingsPlan {
+            plan_id,
+            plan_type,
+            balance,
+            start_time: 0,
+            last_deposit: 0,
+            last_withdraw: 0,
+            interest_rate: 500,
+            is_completed: false,
+            is_withdrawn: false,
+        };
+        let goal_name = soroban_sdk::Symbol::new(&env, "car");
+        env.as_contract(&contract_id, || {
+            let storage = env.storage().persistent();
+            storage.set(
+                &DataKey::SavingsPlan(user.clone(), 1),
+                &legacy(1, PlanType::Flexi, 100),
+            );
+            storage.set(
+                &DataKey::SavingsPlan(user.clone(), 2),
+                &legacy(2, PlanType::Lock(86_400), 200),
+            );
+            storage.set(
+                &DataKey::SavingsPlan(user.clone(), 3),
+                &legacy(3, PlanType::Goal(goal_name.clone(), 1_000, 0), 300),
+            );
+            storage.set(
+                &DataKey::User(user.clone()),
+                &User {
+                    total_balance: 600,
+                    savings_count: 3,
+                },
+            );
+        });
+
+        assert_eq!(client.migrate_legacy_plans(&admin, &user), 3);
+        assert_eq!(client.get_savings_plan(&user, &2), None);
+        assert_eq!(client.get_flexi_balance(&user), 100);
+        let lock_id = client.get_user_lock_saves(&user).get(0).unwrap();
+        let lock_save = client.get_lock_save_detail(&lock_id);
+        assert_eq!((lock_save.amount, lock_save.maturity_time), (200, 86_400));
+        let goal_id = client.get_user_goal_saves(&user).get(0).unwrap();
+        let goal_save = client.get_goal_save_detail(&goal_id);
+        assert_eq!(
+            (goal_save.goal_name, goal_save.current_amount),
+            (goal_name, 300)
+        );
+        // Goal balances leave total_balance, as for any goal
+        assert_eq!(client.get_user(&user).total_balance, 300);
+        assert!(client.check_invariants(&user).ok);
+
+        assert_eq!(client.migrate_legacy_plans(&admin, &user), 0);
+        assert_eq!(
+            client.try_migrate_legacy_plans(&user, &user),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+    }
+}
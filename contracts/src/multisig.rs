@@ -0,0 +1,521 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::campaigns;
+use crate::emergency;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::fees;
+use crate::storage_types::{
+    ConfigKey, DataKey, MultisigAction, MultisigConfig, MultisigKey, MultisigProposal,
+};
+use crate::timelock;
+
+/// Largest signer set that may be configured
+pub const MAX_MULTISIG_SIGNERS: u32 = 20;
+
+/// How long a proposal collects approvals before it lapses (7 days)
+pub const PROPOSAL_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+pub fn get_config(env: &Env) -> Option<MultisigConfig> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::Multisig(MultisigKey::Config)))
+}
+
+/// Fails with `MultisigRequired` once a signer set approves critical actions
+pub fn ensure_single_key_allowed(env: &Env) -> Result<(), SavingsError> {
+    if get_config(env).is_some() {
+        return Err(SavingsError::MultisigRequired);
+    }
+    Ok(())
+}
+
+pub fn get_proposal(env: &Env, proposal_id: u64) -> Option<MultisigProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::Multisig(
+            MultisigKey::Proposal(proposal_id),
+        )))
+}
+
+fn validate_config(config: &MultisigConfig) -> Result<(), SavingsError> {
+    let count = config.signers.len();
+    if count == 0 || count > MAX_MULTISIG_SIGNERS {
+        return Err(SavingsError::InvalidAmount);
+    }
+    for (i, signer) in config.signers.iter().enumerate() {
+        if config.signers.first_index_of(&signer) != Some(i as u32) {
+            return Err(SavingsError::InvalidAmount);
+        }
+    }
+    if config.threshold == 0
+        || config.threshold > count
+        || config.signer_threshold == 0
+        || config.signer_threshold > count
+    {
+        return Err(SavingsError::InvalidAmount);
+    }
+    Ok(())
+}
+
+fn validate_action(action: &MultisigAction) -> Result<(), SavingsError> {
+    match action {
+        MultisigAction::Admin(admin_action) => timelock::validate(admin_action),
+        MultisigAction::EmergencyWithdraw(_, amount) if *amount <= 0 => {
+            Err(SavingsError::InvalidAmount)
+        }
+        MultisigAction::EmergencyWithdraw(_, _) => Ok(()),
+        MultisigAction::SetSigners(config) => validate_config(config),
+        MultisigAction::WithdrawProtocolFees(amount, _) if *amount <= 0 => {
+            Err(SavingsError::InvalidAmount)
+        }
+        MultisigAction::WithdrawProtocolFees(_, _)
+        | MultisigAction::CreateCampaign(..)
+        | MultisigAction::CancelAdminAction(_) => Ok(()),
+    }
+}
+
+/// Hands critical admin actions over to a signer set.
+///
+/// Can only be done once; afterwards the set is changed through a
+/// `SetSigners` proposal. Callers are responsible for authorization.
+///
+/// # Errors
+/// * `MultisigRequired` - If a signer set is already configured
+/// * `InvalidAmount` - If the set is empty, too large, has duplicates or a
+///   threshold outside `1..=signers`
+pub fn init(env: &Env, config: MultisigConfig) -> Result<(), SavingsError> {
+    ensure_single_key_allowed(env)?;
+    validate_config(&config)?;
+    env.storage().instance().set(
+        &DataKey::Config(ConfigKey::Multisig(MultisigKey::Config)),
+        &config,
+    );
+    events::publish(env, events::MultisigConfigured { config });
+    Ok(())
+}
+
+fn require_signer(config: &MultisigConfig, signer: &Address) -> Result<(), SavingsError> {
+    signer.require_auth();
+    if !config.signers.contains(signer) {
+        return Err(SavingsError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Opens a proposal with the proposer's approval; executes it straight away
+/// if that already meets the threshold. Returns the proposal ID.
+///
+/// # Errors
+/// * `ActionNotFound` - If no signer set is configured
+/// * `Unauthorized` - If `signer` is not in the signer set
+/// * `InvalidAmount` - If the action's parameters are invalid
+pub fn propose(env: &Env, signer: Address, action: MultisigAction) -> Result<u64, SavingsError> {
+    let config = get_config(env).ok_or(SavingsError::ActionNotFound)?;
+    require_signer(&config, &signer)?;
+    validate_action(&action)?;
+
+    let id_key = DataKey::Config(ConfigKey::Multisig(MultisigKey::NextProposalId));
+    let proposal_id: u64 = env.storage().instance().get(&id_key).unwrap_or(1);
+    env.storage().instance().set(&id_key, &(proposal_id + 1));
+
+    let now = env.ledger().timestamp();
+    let proposal = MultisigProposal {
+        id: proposal_id,
+        action,
+        proposer: signer.clone(),
+        approvals: Vec::from_array(env, [signer.clone()]),
+        created_at: now,
+        expires_at: now.saturating_add(PROPOSAL_TTL_SECONDS),
+    };
+    events::publish(
+        env,
+        events::MultisigProposed {
+            proposal_id,
+            signer,
+            action: proposal.action.clone(),
+        },
+    );
+    settle(env, &config, proposal)?;
+    Ok(proposal_id)
+}
+
+/// Adds a signer's approval; executes the proposal once it meets the
+/// threshold. Returns whether it was executed.
+///
+/// # Errors
+/// * `ActionNotFound` - If the proposal does not exist or was executed
+/// * `Unauthorized` - If `signer` is not in the signer set
+/// * `AlreadyVoted` - If `signer` already approved it
+/// * `TooLate` - If the proposal has lapsed
+pub fn approve(env: &Env, signer: Address, proposal_id: u64) -> Result<bool, SavingsError> {
+    let config = get_config(env).ok_or(SavingsError::ActionNotFound)?;
+    require_signer(&config, &signer)?;
+    let mut proposal = get_proposal(env, proposal_id).ok_or(SavingsError::ActionNotFound)?;
+    if env.ledger().timestamp() > proposal.expires_at {
+        return Err(SavingsError::TooLate);
+    }
+    if proposal.approvals.contains(&signer) {
+        return Err(SavingsError::AlreadyVoted);
+    }
+    proposal.approvals.push_back(signer.clone());
+    events::publish(
+        env,
+        events::MultisigApproved {
+            proposal_id,
+            signer,
+            approvals: proposal.approvals.len(),
+        },
+    );
+    settle(env, &config, proposal)
+}
+
+/// Stores the proposal, or executes and removes it if the approvals of
+/// current signers meet the threshold the action needs
+fn settle(
+    env: &Env,
+    config: &MultisigConfig,
+    proposal: MultisigProposal,
+) -> Result<bool, SavingsError> {
+    let required = match proposal.action {
+        MultisigAction::SetSigners(_) => config.signer_threshold,
+        _ => config.threshold,
+    };
+    let approved = proposal
+        .approvals
+        .iter()
+        .filter(|approver| config.signers.contains(approver))
+        .count() as u32;
+    let key = DataKey::Config(ConfigKey::Multisig(MultisigKey::Proposal(proposal.id)));
+    if approved < required {
+        env.storage().persistent().set(&key, &proposal);
+        return Ok(false);
+    }
+
+    env.storage().persistent().remove(&key);
+    match &proposal.action {
+        MultisigAction::Admin(action) => {
+            // Approval replaces the single key, not the announcement delay
+            if timelock::get_timelock(env) > 0 {
+                timelock::queue(env, action.clone())?;
+            } else {
+                timelock::apply(env, action)?;
+            }
+        }
+        MultisigAction::EmergencyWithdraw(recovery, amount) => {
+            emergency::emergency_withdraw(env, &proposal.proposer, recovery, *amount)?;
+        }
+        MultisigAction::SetSigners(new_config) => {
+            validate_config(new_config)?;
+            env.storage().instance().set(
+                &DataKey::Config(ConfigKey::Multisig(MultisigKey::Config)),
+                new_config,
+            );
+            events::publish(
+                env,
+                events::MultisigConfigured {
+                    config: new_config.clone(),
+                },
+            );
+        }
+        MultisigAction::WithdrawProtocolFees(amount, to) => {
+            fees::withdraw_protocol_fees(env, *amount, to)?;
+        }
+        MultisigAction::CreateCampaign(plan_kind, start_time, end_time, bonus_bps, budget) => {
+            campaigns::create_campaign(
+                env,
+                *plan_kind,
+                *start_time,
+                *end_time,
+                *bonus_bps,
+                *budget,
+            )?;
+        }
+        MultisigAction::CancelAdminAction(action_id) => timelock::cancel(env, *action_id)?,
+    }
+    events::publish(
+        env,
+        events::MultisigExecuted {
+            proposal_id: proposal.id,
+            action: proposal.action,
+        },
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AdminAction, FeeExemption, KeeperTipConfig, MultisigAction, MultisigConfig,
+        NesteraContract, NesteraContractClient, PlanKind, SavingsError,
+    };
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec, Address, BytesN, Env,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address, [Address; 3]) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        let signers = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        client.init_multisig(
+            &admin,
+            &MultisigConfig {
+                signers: vec![
+                    &env,
+                    signers[0].clone(),
+                    signers[1].clone(),
+                    signers[2].clone(),
+                ],
+                threshold: 2,
+                signer_threshold: 3,
+            },
+        );
+        (env, client, admin, signers)
+    }
+
+    #[test]
+    fn test_single_key_paths_blocked_once_configured() {
+        let (env, client, admin, _) = setup();
+        assert_eq!(
+            client
+                .try_set_early_break_fee_bps(&admin, &100)
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_upgrade(&admin, &BytesN::from_array(&env, &[2u8; 32]))
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_emergency_withdraw(&admin, &Address::generate(&env), &1)
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_queue_admin_action(&admin, &AdminAction::SetEarlyBreakFeeBps(100))
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_withdraw_protocol_fees(&admin, &1, &admin)
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_create_campaign(&admin, &PlanKind::Lock, &0, &100, &100, &1_000)
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_set_fee_exemption(&admin, &FeeExemption::User(admin.clone()), &true)
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_set_keeper_tip(&admin, &KeeperTipConfig::default())
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_set_failed_goal_penalty(&admin, &true)
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_set_strategy_yield_split(&admin, &100)
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client
+                .try_set_token(&admin, &Address::generate(&env))
+                .unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        assert_eq!(
+            client.try_cancel_admin_action(&admin, &1).unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+        let config = client.get_multisig_config().unwrap();
+        assert_eq!(client.get_protocol_params().multisig, config);
+        assert_eq!(
+            client.try_init_multisig(&admin, &config).unwrap_err(),
+            Ok(SavingsError::MultisigRequired)
+        );
+    }
+
+    #[test]
+    fn test_signers_run_actions_closed_to_single_keys() {
+        let (env, client, _, signers) = setup();
+        let tip = KeeperTipConfig {
+            flat: 5,
+            max_per_ledger: 50,
+        };
+        let action = MultisigAction::Admin(AdminAction::SetKeeperTip(tip.clone()));
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        client.approve_multisig_action(&signers[1], &proposal_id);
+        assert_eq!(client.get_keeper_tip(), tip);
+
+        let user = Address::generate(&env);
+        let action = MultisigAction::Admin(AdminAction::SetFeeExemption(
+            FeeExemption::User(user.clone()),
+            true,
+        ));
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        client.approve_multisig_action(&signers[2], &proposal_id);
+        assert!(client.is_fee_exempt(&user, &PlanKind::Flexi));
+
+        // Queued actions are cancelled by the signers too
+        let timelock = MultisigAction::Admin(AdminAction::SetTimelock(3_600));
+        let proposal_id = client.propose_multisig_action(&signers[0], &timelock);
+        client.approve_multisig_action(&signers[1], &proposal_id);
+        let action = MultisigAction::Admin(AdminAction::SetFailedGoalPenalty(true));
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        client.approve_multisig_action(&signers[1], &proposal_id);
+        let action_id = 1;
+        assert!(client.get_pending_admin_action(&action_id).is_some());
+        let action = MultisigAction::CancelAdminAction(action_id);
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        client.approve_multisig_action(&signers[1], &proposal_id);
+        assert!(client.get_pending_admin_action(&action_id).is_none());
+    }
+
+    #[test]
+    fn test_fee_change_executes_at_threshold() {
+        let (env, client, _, signers) = setup();
+        let action = MultisigAction::Admin(AdminAction::SetEarlyBreakFeeBps(250));
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        assert_eq!(client.get_early_break_fee_bps(), 0);
+        assert_eq!(
+            client
+                .try_approve_multisig_action(&signers[0], &proposal_id)
+                .unwrap_err(),
+            Ok(SavingsError::AlreadyVoted)
+        );
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client
+                .try_approve_multisig_action(&stranger, &proposal_id)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+
+        assert!(client.approve_multisig_action(&signers[1], &proposal_id));
+        assert_eq!(client.get_early_break_fee_bps(), 250);
+        assert_eq!(client.get_multisig_proposal(&proposal_id), None);
+    }
+
+    #[test]
+    fn test_signer_set_changes_need_their_own_threshold() {
+        let (env, client, _, signers) = setup();
+        let newcomer = Address::generate(&env);
+        let action = MultisigAction::SetSigners(MultisigConfig {
+            signers: vec![&env, signers[0].clone(), newcomer.clone()],
+            threshold: 1,
+            signer_threshold: 2,
+        });
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        assert!(!client.approve_multisig_action(&signers[1], &proposal_id));
+        assert!(client.approve_multisig_action(&signers[2], &proposal_id));
+
+        let config = client.get_multisig_config().unwrap();
+        assert_eq!(config.signers, vec![&env, signers[0].clone(), newcomer]);
+        // Removed signers can no longer propose; a 1-of-n action runs at once
+        assert_eq!(
+            client
+                .try_propose_multisig_action(
+                    &signers[1],
+                    &MultisigAction::Admin(AdminAction::SetTimelock(60))
+                )
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+        client.propose_multisig_action(
+            &signers[0],
+            &MultisigAction::Admin(AdminAction::SetTimelock(60)),
+        );
+        assert_eq!(client.get_admin_timelock(), 60);
+    }
+
+    #[test]
+    fn test_approved_action_still_waits_for_timelock() {
+        let (env, client, _, signers) = setup();
+        let timelock = MultisigAction::Admin(AdminAction::SetTimelock(3_600));
+        let proposal_id = client.propose_multisig_action(&signers[0], &timelock);
+        client.approve_multisig_action(&signers[1], &proposal_id);
+
+        let recipient = Address::generate(&env);
+        let action = MultisigAction::Admin(AdminAction::SetFeeRecipient(recipient.clone()));
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        client.approve_multisig_action(&signers[2], &proposal_id);
+        assert_ne!(client.get_fee_recipient(), Some(recipient.clone()));
+
+        // The timelock queue assigns its own IDs, starting at 1
+        env.ledger().with_mut(|li| li.timestamp += 3_600);
+        client.execute_admin_action(&1);
+        assert_eq!(client.get_fee_recipient(), Some(recipient));
+    }
+
+    #[test]
+    fn test_lapsed_or_invalid_proposals_are_rejected() {
+        let (env, client, _, signers) = setup();
+        assert_eq!(
+            client
+                .try_propose_multisig_action(
+                    &signers[0],
+                    &MultisigAction::EmergencyWithdraw(Address::generate(&env), 0)
+                )
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+        let duplicate = MultisigAction::SetSigners(MultisigConfig {
+            signers: vec![&env, signers[0].clone(), signers[0].clone()],
+            threshold: 1,
+            signer_threshold: 1,
+        });
+        assert_eq!(
+            client
+                .try_propose_multisig_action(&signers[0], &duplicate)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+
+        let action = MultisigAction::Admin(AdminAction::SetEarlyBreakFeeBps(100));
+        let proposal_id = client.propose_multisig_action(&signers[0], &action);
+        env.ledger()
+            .with_mut(|li| li.timestamp += super::PROPOSAL_TTL_SECONDS + 1);
+        assert_eq!(
+            client
+                .try_approve_multisig_action(&signers[1], &proposal_id)
+                .unwrap_err(),
+            Ok(SavingsError::TooLate)
+        );
+        assert_eq!(client.get_early_break_fee_bps(), 0);
+    }
+}
@@ -0,0 +1,225 @@
+use soroban_sdk::{symbol_short, vec, Address, Env, IntoVal};
+
+use crate::errors::SavingsError;
+use crate::goal;
+use crate::storage_types::{ConfigKey, DataKey, GoalFiatProgress, UserKey};
+use crate::strategy;
+
+/// Token base units one oracle price is quoted for (one whole 7-decimal token)
+pub const PRICE_SCALE: i128 = 10_000_000;
+
+/// Calls `price(asset) -> i128` on the oracle, which returns the value of
+/// `PRICE_SCALE` base units of `asset` in its reference unit (e.g. USD cents)
+fn oracle_price(env: &Env, oracle: &Address, asset: &Address) -> i128 {
+    env.invoke_contract(
+        oracle,
+        &symbol_short!("price"),
+        vec![env, asset.into_val(env)],
+    )
+}
+
+/// Returns the configured price oracle
+pub fn get_oracle(env: &Env) -> Result<Address, SavingsError> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::Oracle))
+        .ok_or(SavingsError::MissingParameter)
+}
+
+pub fn set_oracle(env: &Env, oracle: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::Oracle), oracle);
+}
+
+/// Fetches the current price of the deposit token.
+///
+/// # Errors
+/// * `MissingParameter` - If no token or oracle is configured
+/// * `UnsupportedAsset` - If the oracle quotes a non-positive price
+pub fn get_price(env: &Env) -> Result<i128, SavingsError> {
+    let token = strategy::get_token(env)?;
+    let price = oracle_price(env, &get_oracle(env)?, &token);
+    if price <= 0 {
+        return Err(SavingsError::UnsupportedAsset);
+    }
+    Ok(price)
+}
+
+/// Converts token base units to reference units, rounding down
+pub fn to_fiat(amount: i128, price: i128) -> Result<i128, SavingsError> {
+    Ok(amount.checked_mul(price).ok_or(SavingsError::Overflow)? / PRICE_SCALE)
+}
+
+/// Converts reference units to token base units, rounding up so a goal is
+/// never marked complete short of its fiat target
+pub fn to_tokens(fiat: i128, price: i128) -> Result<i128, SavingsError> {
+    let scaled = fiat
+        .checked_mul(PRICE_SCALE)
+        .ok_or(SavingsError::Overflow)?;
+    Ok((scaled + price - 1) / price)
+}
+
+/// Returns a goal's target in reference units, if it was set in fiat
+pub fn get_fiat_target(env: &Env, goal_id: u64) -> Option<i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::GoalFiatTarget(goal_id)))
+}
+
+pub fn set_fiat_target(env: &Env, goal_id: u64, target_fiat: i128) {
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::GoalFiatTarget(goal_id)),
+        &target_fiat,
+    );
+}
+
+pub fn remove_fiat_target(env: &Env, goal_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::UserState(UserKey::GoalFiatTarget(goal_id)));
+}
+
+/// Reports a goal's progress in both token and reference units at the
+/// current oracle price. A fiat goal's token target follows the price.
+pub fn get_goal_progress_fiat(env: &Env, goal_id: u64) -> Result<GoalFiatProgress, SavingsError> {
+    let goal_save = goal::get_goal_save(env, goal_id).ok_or(SavingsError::PlanNotFound)?;
+    let price = get_price(env)?;
+    let (target_amount, target_fiat) = match get_fiat_target(env, goal_id) {
+        Some(target_fiat) => (to_tokens(target_fiat, price)?, target_fiat),
+        None => (
+            goal_save.target_amount,
+            to_fiat(goal_save.target_amount, price)?,
+        ),
+    };
+
+    Ok(GoalFiatProgress {
+        current_amount: goal_save.current_amount,
+        target_amount,
+        current_fiat: to_fiat(goal_save.current_amount, price)?,
+        target_fiat,
+        price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        contract, contractimpl, symbol_short, testutils::Address as _, Address, BytesN, Env,
+    };
+
+    /// Oracle quoting whatever price the test last set, for any asset
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn set_price(env: Env, price: i128) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("price"), &price);
+        }
+
+        pub fn price(env: Env, _asset: Address) -> i128 {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("price"))
+                .unwrap_or(0)
+        }
+    }
+
+    fn setup() -> (
+        Env,
+        NesteraContractClient<'static>,
+        Address,
+        MockOracleClient<'static>,
+    ) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let oracle_id = env.register(MockOracle, ());
+        let oracle = MockOracleClient::new(&env, &oracle_id);
+        client.set_oracle(&admin, &oracle_id);
+
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        (env, client, user, oracle)
+    }
+
+    #[test]
+    fn test_fiat_goal_target_follows_price() {
+        let (_env, client, user, oracle) = setup();
+        // 1 token (10^7 units) = 50 cents
+        oracle.set_price(&50);
+
+        // $10 target
+        let goal_id =
+            client.create_goal_save_fiat(&user, &symbol_short!("trip"), &1_000, &100_000_000, &0);
+        let progress = client.get_goal_progress_fiat(&goal_id);
+        assert_eq!(progress.target_fiat, 1_000);
+        assert_eq!(progress.target_amount, 200_000_000);
+        assert_eq!(progress.current_amount, 100_000_000);
+        assert_eq!(progress.current_fiat, 500);
+        assert!(!client.get_goal_save_detail(&goal_id).is_completed);
+
+        // The token doubles in price: the savings now cover the fiat target
+        oracle.set_price(&100);
+        let progress = client.get_goal_progress_fiat(&goal_id);
+        assert_eq!(progress.target_amount, 100_000_000);
+        assert_eq!(progress.current_fiat, 1_000);
+
+        client.deposit_to_goal_save(&user, &goal_id, &1);
+        let goal = client.get_goal_save_detail(&goal_id);
+        assert_eq!(goal.target_amount, 100_000_000);
+        assert!(goal.is_completed);
+    }
+
+    #[test]
+    fn test_token_goal_reports_fiat_value() {
+        let (_env, client, user, oracle) = setup();
+        oracle.set_price(&200);
+
+        let goal_id =
+            client.create_goal_save(&user, &symbol_short!("car"), &50_000_000, &10_000_000);
+        let progress = client.get_goal_progress_fiat(&goal_id);
+        assert_eq!(progress.target_amount, 50_000_000);
+        assert_eq!(progress.target_fiat, 1_000);
+        assert_eq!(progress.current_fiat, 200);
+        assert_eq!(progress.price, 200);
+    }
+
+    #[test]
+    fn test_fiat_goal_requires_a_price() {
+        let (_env, client, user, _oracle) = setup();
+        assert_eq!(
+            client
+                .try_create_goal_save_fiat(&user, &symbol_short!("trip"), &1_000, &0, &0)
+                .unwrap_err(),
+            Ok(SavingsError::UnsupportedAsset)
+        );
+    }
+
+    #[test]
+    fn test_set_oracle_requires_admin() {
+        let (env, client, _user, oracle) = setup();
+        assert_eq!(
+            client
+                .try_set_oracle(&Address::generate(&env), &oracle.address)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+}
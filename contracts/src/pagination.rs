@@ -0,0 +1,60 @@
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val, Vec};
+
+use crate::storage_types::{ChunkedList, Cursor, MAX_PAGE_LIMIT};
+
+/// Returns the effective page size for a cursor
+///
+/// A limit of 0 requests the default page size; anything above
+/// `MAX_PAGE_LIMIT` is capped so a single view can't exhaust the budget.
+pub fn effective_limit(cursor: &Cursor) -> u32 {
+    if cursor.limit == 0 || cursor.limit > MAX_PAGE_LIMIT {
+        MAX_PAGE_LIMIT
+    } else {
+        cursor.limit
+    }
+}
+
+/// Returns the cursor for the page after `cursor`, or `None` if it was the last
+pub fn next_cursor(cursor: &Cursor, total: u32) -> Option<Cursor> {
+    let limit = effective_limit(cursor);
+    let next_offset = cursor.offset.saturating_add(limit);
+    if next_offset < total {
+        Some(Cursor {
+            offset: next_offset,
+            limit,
+        })
+    } else {
+        None
+    }
+}
+
+/// Slices one page out of an in-memory vector
+pub fn paginate<T>(env: &Env, items: &Vec<T>, cursor: &Cursor) -> (Vec<T>, Option<Cursor>)
+where
+    T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let total = items.len();
+    let start = cursor.offset.min(total);
+    let end = start.saturating_add(effective_limit(cursor)).min(total);
+
+    let mut page = Vec::new(env);
+    for i in start..end {
+        if let Some(item) = items.get(i) {
+            page.push_back(item);
+        }
+    }
+    (page, next_cursor(cursor, total))
+}
+
+/// Reads one page out of a chunked list
+pub fn paginate_chunked<T>(
+    env: &Env,
+    list: &ChunkedList,
+    cursor: &Cursor,
+) -> (Vec<T>, Option<Cursor>)
+where
+    T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let page = list.range(env, cursor.offset, effective_limit(cursor));
+    (page, next_cursor(cursor, list.len(env)))
+}
@@ -0,0 +1,148 @@
+use soroban_sdk::{Env, Vec};
+
+use crate::compliance;
+use crate::deprecation;
+use crate::emergency;
+use crate::fees;
+use crate::goal;
+use crate::governance;
+use crate::group;
+use crate::limits;
+use crate::multisig;
+use crate::oracle;
+use crate::rates;
+use crate::storage_types::{DataKey, LockRateEntry, MultisigConfig, PlanKind, ProtocolParams};
+use crate::strategy;
+use crate::streaks;
+use crate::timelock;
+use crate::users;
+
+/// Current rate of every lock tier, recovered from the lock rate history
+fn lock_rate_table(env: &Env) -> Vec<LockRateEntry> {
+    let mut table: Vec<LockRateEntry> = Vec::new(env);
+    for change in rates::get_rate_history(env, PlanKind::Lock).iter() {
+        if table
+            .iter()
+            .any(|e| e.duration_days == change.duration_days)
+        {
+            continue;
+        }
+        if let Ok(rate) = rates::get_lock_rate(env, change.duration_days) {
+            table.push_back(LockRateEntry {
+                duration_days: change.duration_days,
+                rate,
+            });
+        }
+    }
+    table
+}
+
+/// Collects every tunable into one struct so tooling can diff live config
+/// against the intended deployment in a single call
+pub fn get_protocol_params(env: &Env) -> ProtocolParams {
+    ProtocolParams {
+        paused: env
+            .storage()
+            .persistent()
+            .get(&DataKey::Paused)
+            .unwrap_or(false),
+        legacy_entrypoints_enabled: deprecation::legacy_entrypoints_enabled(env),
+        admin_timelock: timelock::get_timelock(env),
+        early_break_fee_bps: env
+            .storage()
+            .instance()
+            .get(&DataKey::EarlyBreakFeeBps)
+            .unwrap_or(0),
+        goal_break_grace: goal::get_goal_break_grace(env),
+        failed_goal_penalty: goal::get_failed_goal_penalty(env),
+        fee_recipient: env.storage().instance().get(&DataKey::FeeRecipient),
+        referral_reward_bps: users::get_referral_reward_bps(env),
+        withdraw_limit: limits::get_withdraw_limit(env),
+        flexi_rate: rates::get_flexi_rate(env),
+        goal_rate: rates::get_goal_rate(env),
+        group_rate: rates::get_group_rate(env),
+        lock_rates: lock_rate_table(env),
+        lock_rate_bps: rates::get_rate_at(env, env.ledger().timestamp()),
+        lock_rate_schedule: rates::get_lock_rate_schedule(env),
+        token: strategy::get_token(env).ok(),
+        strategy: strategy::get_strategy(env).ok(),
+        target_liquidity_bps: strategy::get_target_liquidity_bps(env),
+        strategy_yield_treasury_bps: strategy::get_yield_treasury_bps(env),
+        group_duration_bounds: group::get_duration_bounds(env),
+        dispute_threshold: group::get_dispute_threshold(env),
+        group_quorum_bps: governance::get_quorum_bps(env),
+        streak_period: streaks::get_streak_period(env),
+        plan_limits: limits::get_plan_limits(env),
+        minimum_deposit: limits::get_minimum_deposit(env),
+        dust_threshold: limits::get_dust_threshold(env),
+        withdrawal_fee: fees::get_withdrawal_fee(env),
+        fee_tiers: fees::get_fee_tiers(env),
+        oracle: oracle::get_oracle(env).ok(),
+        keeper_tip: fees::get_keeper_tip(env),
+        withdrawal_only_mode: emergency::is_withdrawal_only_mode(env),
+        compliance: compliance::get_compliance_config(env),
+        multisig: multisig::get_config(env).unwrap_or(MultisigConfig {
+            signers: Vec::new(env),
+            threshold: 0,
+            signer_threshold: 0,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, StreakPeriod};
+    use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+
+    #[test]
+    fn test_protocol_params_reflect_admin_changes() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let initial_treasury = Address::generate(&env);
+        let token = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &initial_treasury,
+            &0,
+            &token,
+        );
+
+        let defaults = client.get_protocol_params();
+        assert!(!defaults.paused);
+        assert_eq!(defaults.fee_recipient, Some(initial_treasury));
+        assert_eq!(defaults.token, Some(token));
+        assert_eq!(defaults.lock_rates.len(), 0);
+        assert_eq!(defaults.lock_rate_bps, 500);
+        assert_eq!(defaults.streak_period, StreakPeriod::Weekly);
+        assert!(!defaults.failed_goal_penalty);
+
+        let treasury = Address::generate(&env);
+        client.set_fee_recipient(&admin, &treasury);
+        client.set_early_break_fee_bps(&admin, &250);
+        client.set_withdraw_limit(&admin, &5_000);
+        client.set_flexi_rate(&admin, &400);
+        client.set_strategy_yield_split(&admin, &2_000);
+        client.set_failed_goal_penalty(&admin, &true);
+        client.set_lock_rate(&admin, &30, &800);
+        client.set_lock_rate(&admin, &90, &1_000);
+        client.set_lock_rate(&admin, &30, &850);
+        client.pause(&admin);
+
+        let params = client.get_protocol_params();
+        assert!(params.paused);
+        assert_eq!(params.fee_recipient, Some(treasury));
+        assert_eq!(params.early_break_fee_bps, 250);
+        assert_eq!(params.withdraw_limit, 5_000);
+        assert_eq!(params.flexi_rate, 400);
+        assert_eq!(params.strategy_yield_treasury_bps, 2_000);
+        assert!(params.failed_goal_penalty);
+        assert_eq!(params.lock_rates.len(), 2);
+        let first = params.lock_rates.get(0).unwrap();
+        assert_eq!((first.duration_days, first.rate), (30, 850));
+        assert_eq!(params.lock_rates.get(1).unwrap().rate, 1_000);
+    }
+}
@@ -0,0 +1,106 @@
+use soroban_sdk::{xdr::ToXdr, Address, Env};
+
+use crate::errors::SavingsError;
+use crate::storage_types::{DataKey, PlanKind, UserKey};
+
+/// Allocates the ID of a new Lock or Goal Save owned by `user`.
+///
+/// IDs hash the owner, the plan kind and a sequence number kept per user, so
+/// allocating one touches no shared counter and reveals nothing about how
+/// many plans other users hold. A client can work out the ID a plan will get
+/// beforehand with `peek_plan_id`. Sequence numbers whose ID is 0 or already
+/// taken, such as by a plan from before per-user IDs, are skipped.
+pub fn next_plan_id(env: &Env, user: &Address, kind: PlanKind) -> u64 {
+    let (id, seq) = find_free_id(env, user, kind);
+    env.storage()
+        .persistent()
+        .set(&seq_key(user, kind), &(seq + 1));
+    id
+}
+
+/// VIEW FUNCTION - ID the next Lock or Goal Save of `kind` created by `user`
+/// will get
+///
+/// # Errors
+/// * `InvalidPlanConfig` - If `kind` is not `Lock` or `Goal`
+pub fn peek_plan_id(env: &Env, user: &Address, kind: PlanKind) -> Result<u64, SavingsError> {
+    if !matches!(kind, PlanKind::Lock | PlanKind::Goal) {
+        return Err(SavingsError::InvalidPlanConfig);
+    }
+    Ok(find_free_id(env, user, kind).0)
+}
+
+/// First free ID from the user's current sequence number on, with the
+/// sequence number it was derived from
+fn find_free_id(env: &Env, user: &Address, kind: PlanKind) -> (u64, u64) {
+    let mut seq: u64 = env
+        .storage()
+        .persistent()
+        .get(&seq_key(user, kind))
+        .unwrap_or(0);
+    loop {
+        let id = derive_id(env, user, kind, seq);
+        if id != 0 && !is_taken(env, kind, id) {
+            return (id, seq);
+        }
+        seq += 1;
+    }
+}
+
+fn derive_id(env: &Env, user: &Address, kind: PlanKind, seq: u64) -> u64 {
+    let digest = env
+        .crypto()
+        .sha256(&(user.clone(), kind, seq).to_xdr(env))
+        .to_array();
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(id)
+}
+
+fn is_taken(env: &Env, kind: PlanKind, id: u64) -> bool {
+    // Only Lock and Goal Saves are allocated IDs here
+    let key = match kind {
+        PlanKind::Lock => DataKey::LockSave(id),
+        _ => DataKey::GoalSave(id),
+    };
+    env.storage().persistent().has(&key)
+}
+
+fn seq_key(user: &Address, kind: PlanKind) -> DataKey {
+    DataKey::UserState(UserKey::PlanSeq(user.clone(), kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::PlanKind;
+    use crate::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+
+    #[test]
+    fn test_plan_ids_are_predictable_and_per_user() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&alice);
+        client.initialize_user(&bob);
+
+        let expected = client.get_next_plan_id(&alice, &PlanKind::Lock);
+        // Another user's plans do not move alice's next ID
+        client.create_lock_save(&bob, &100, &86_400);
+        assert_eq!(client.get_next_plan_id(&alice, &PlanKind::Lock), expected);
+
+        let lock_id = client.create_lock_save(&alice, &100, &86_400);
+        assert_eq!(lock_id, expected);
+        assert_ne!(client.get_next_plan_id(&alice, &PlanKind::Lock), lock_id);
+
+        let goal_id = client.create_goal_save(&alice, &Symbol::new(&env, "car"), &500, &0);
+        assert_ne!(goal_id, lock_id);
+        assert_eq!(client.get_goal_save_detail(&goal_id).owner, alice);
+        assert!(client
+            .try_get_next_plan_id(&alice, &PlanKind::Flexi)
+            .is_err());
+    }
+}
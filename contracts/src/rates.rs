@@ -1,6 +1,10 @@
-use crate::storage_types::DataKey;
+use crate::events;
+use crate::lock::LOCK_RATE_BPS;
+use crate::storage_types::{
+    ConfigKey, DataKey, InterestAccrual, PlanKind, RateChange, ScheduledRate,
+};
 use crate::SavingsError;
-use soroban_sdk::Env;
+use soroban_sdk::{Address, Env, Symbol, Vec};
 
 // --- Admin Setters ---
 
@@ -8,7 +12,9 @@ pub fn set_flexi_rate(env: &Env, rate: i128) -> Result<(), SavingsError> {
     if rate < 0 {
         return Err(SavingsError::InvalidInterestRate);
     }
+    let old_rate = get_flexi_rate(env);
     env.storage().instance().set(&DataKey::FlexiRate, &rate);
+    record_rate_change(env, PlanKind::Flexi, 0, old_rate, rate);
     Ok(())
 }
 
@@ -16,7 +22,9 @@ pub fn set_goal_rate(env: &Env, rate: i128) -> Result<(), SavingsError> {
     if rate < 0 {
         return Err(SavingsError::InvalidInterestRate);
     }
+    let old_rate = get_goal_rate(env);
     env.storage().instance().set(&DataKey::GoalRate, &rate);
+    record_rate_change(env, PlanKind::Goal, 0, old_rate, rate);
     Ok(())
 }
 
@@ -24,7 +32,9 @@ pub fn set_group_rate(env: &Env, rate: i128) -> Result<(), SavingsError> {
     if rate < 0 {
         return Err(SavingsError::InvalidInterestRate);
     }
+    let old_rate = get_group_rate(env);
     env.storage().instance().set(&DataKey::GroupRate, &rate);
+    record_rate_change(env, PlanKind::Group, 0, old_rate, rate);
     Ok(())
 }
 
@@ -32,12 +42,129 @@ pub fn set_lock_rate(env: &Env, duration_days: u64, rate: i128) -> Result<(), Sa
     if rate < 0 {
         return Err(SavingsError::InvalidInterestRate);
     }
+    let old_rate = get_lock_rate(env, duration_days).unwrap_or(0);
     env.storage()
         .instance()
         .set(&DataKey::LockRate(duration_days), &rate);
+    record_rate_change(env, PlanKind::Lock, duration_days, old_rate, rate);
     Ok(())
 }
 
+// --- Scheduled Lock Rates ---
+
+/// Schedules the rate new Lock Saves get from `effective_at` on. Open locks
+/// keep the rate they were created with.
+///
+/// Scheduling a second change for the same time replaces the first. Changes
+/// already in effect cannot be rewritten.
+///
+/// # Errors
+/// * `InvalidInterestRate` - If `rate_bps` exceeds 10,000 (100%)
+/// * `InvalidTimestamp` - If `effective_at` is in the past
+pub fn schedule_rate_change(
+    env: &Env,
+    rate_bps: u32,
+    effective_at: u64,
+) -> Result<(), SavingsError> {
+    if rate_bps > 10_000 {
+        return Err(SavingsError::InvalidInterestRate);
+    }
+    if effective_at < env.ledger().timestamp() {
+        return Err(SavingsError::InvalidTimestamp);
+    }
+
+    let mut schedule = get_lock_rate_schedule(env);
+    let scheduled = ScheduledRate {
+        rate_bps,
+        effective_at,
+    };
+    let index = entries_in_effect_at(&schedule, effective_at);
+    if index > 0 && schedule.get_unchecked(index - 1).effective_at == effective_at {
+        schedule.set(index - 1, scheduled.clone());
+    } else {
+        schedule.insert(index, scheduled.clone());
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::LockRateSchedule), &schedule);
+
+    events::publish(
+        env,
+        events::RateScheduled {
+            plan_kind: PlanKind::Lock,
+            scheduled,
+        },
+    );
+    Ok(())
+}
+
+/// Rate, in basis points, a Lock Save created at `timestamp` is fixed at
+pub fn get_rate_at(env: &Env, timestamp: u64) -> u32 {
+    let schedule = get_lock_rate_schedule(env);
+    match entries_in_effect_at(&schedule, timestamp) {
+        0 => LOCK_RATE_BPS,
+        count => schedule.get_unchecked(count - 1).rate_bps,
+    }
+}
+
+/// Number of schedule entries in effect at `timestamp`, found by binary
+/// search since the schedule is kept sorted by `effective_at`
+fn entries_in_effect_at(schedule: &Vec<ScheduledRate>, timestamp: u64) -> u32 {
+    let (mut low, mut high) = (0, schedule.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if schedule.get_unchecked(mid).effective_at <= timestamp {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Returns every scheduled Lock Save rate, past and pending, in effect order
+pub fn get_lock_rate_schedule(env: &Env) -> Vec<ScheduledRate> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::LockRateSchedule))
+        .unwrap_or(Vec::new(env))
+}
+
+// --- Rate History ---
+
+/// Appends a change to the plan kind's rate history and emits `rate_chg`.
+///
+/// The history is what settles "which rate applied when I deposited": look up
+/// the last entry whose `effective_from` is at or before the deposit time.
+fn record_rate_change(
+    env: &Env,
+    plan_kind: PlanKind,
+    duration_days: u64,
+    old_rate: i128,
+    new_rate: i128,
+) {
+    let change = RateChange {
+        duration_days,
+        old_rate,
+        new_rate,
+        effective_from: env.ledger().timestamp(),
+    };
+    let key = DataKey::RateHistory(plan_kind);
+    let mut history = get_rate_history(env, plan_kind);
+    history.push_back(change.clone());
+    env.storage().persistent().set(&key, &history);
+
+    events::publish(env, events::RateChanged { plan_kind, change });
+}
+
+/// Returns every recorded rate change for a plan kind, oldest first
+pub fn get_rate_history(env: &Env, plan_kind: PlanKind) -> Vec<RateChange> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RateHistory(plan_kind))
+        .unwrap_or(Vec::new(env))
+}
+
 // --- Getters ---
 
 pub fn get_flexi_rate(env: &Env) -> i128 {
@@ -68,6 +195,29 @@ pub fn get_lock_rate(env: &Env, duration_days: u64) -> Result<i128, SavingsError
         .ok_or(SavingsError::PlanNotFound)
 }
 
+// --- Interest Events ---
+
+/// Emits an `interest` event for an interest credit.
+///
+/// Every path that credits interest must call this so yield analytics and
+/// tax reports can be rebuilt from events alone. Topics are
+/// `("interest", plan_kind, user)`; the payload is an `InterestAccrual`.
+pub fn emit_interest_accrued(
+    env: &Env,
+    plan_kind: Symbol,
+    user: &Address,
+    accrual: InterestAccrual,
+) {
+    events::publish(
+        env,
+        events::InterestAccrued {
+            plan_kind,
+            user: user.clone(),
+            accrual,
+        },
+    );
+}
+
 // --- Interest Calculation Helpers ---
 
 pub fn calculate_flexi_interest(balance: i128, rate: i128, duration_seconds: u64) -> i128 {
@@ -1,5 +1,9 @@
-use crate::{NesteraContract, NesteraContractClient, SavingsError};
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+use crate::{NesteraContract, NesteraContractClient, PlanKind, SavingsError, WithdrawalFeeConfig};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env,
+};
 
 fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let env = Env::default();
@@ -9,7 +13,13 @@ fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
 
     env.mock_all_auths();
-    client.initialize(&admin, &admin_pk);
+    client.initialize(
+        &admin,
+        &admin_pk,
+        &Address::generate(&env),
+        &0,
+        &Address::generate(&env),
+    );
 
     (env, client, admin)
 }
@@ -36,30 +46,30 @@ fn test_admin_can_set_rates() {
     env.mock_all_auths();
 
     // Set Flexi Rate to 500 (5%)
-    assert!(client.try_set_flexi_rate(&500).is_ok());
+    assert!(client.try_set_flexi_rate(&admin, &500).is_ok());
     assert_eq!(client.get_flexi_rate(), 500);
 
     // Set Goal Rate
-    assert!(client.try_set_goal_rate(&600).is_ok());
+    assert!(client.try_set_goal_rate(&admin, &600).is_ok());
     assert_eq!(client.get_goal_rate(), 600);
 
     // Set Group Rate
-    assert!(client.try_set_group_rate(&700).is_ok());
+    assert!(client.try_set_group_rate(&admin, &700).is_ok());
     assert_eq!(client.get_group_rate(), 700);
 
     // Set Lock Rate for 30 days
-    assert!(client.try_set_lock_rate(&30, &800).is_ok());
+    assert!(client.try_set_lock_rate(&admin, &30, &800).is_ok());
     assert_eq!(client.get_lock_rate(&30), 800);
 
     // Verify independent lock rates
-    assert!(client.try_set_lock_rate(&60, &900).is_ok());
+    assert!(client.try_set_lock_rate(&admin, &60, &900).is_ok());
     assert_eq!(client.get_lock_rate(&60), 900);
     assert_eq!(client.get_lock_rate(&30), 800); // 30 days unchanged
 }
 
 #[test]
 fn test_non_admin_cannot_set_rates() {
-    let (env, client, _admin) = setup();
+    let (env, client, admin) = setup();
     let user = Address::generate(&env);
 
     // Clear the "mock all" from setup so we can test failures
@@ -71,19 +81,19 @@ fn test_non_admin_cannot_set_rates() {
     // `admin.require_auth()` inside the contract should fail.
 
     // Try set flexi rate
-    let res = client.try_set_flexi_rate(&500);
+    let res = client.try_set_flexi_rate(&admin, &500);
     assert!(res.is_err());
 
     // Try set goal rate
-    let res = client.try_set_goal_rate(&500);
+    let res = client.try_set_goal_rate(&admin, &500);
     assert!(res.is_err());
 
     // Try set group rate
-    let res = client.try_set_group_rate(&500);
+    let res = client.try_set_group_rate(&admin, &500);
     assert!(res.is_err());
 
     // Try set lock rate
-    let res = client.try_set_lock_rate(&30, &500);
+    let res = client.try_set_lock_rate(&admin, &30, &500);
     assert!(res.is_err());
 }
 
@@ -114,10 +124,177 @@ fn test_calculate_lock_interest_logic() {
 
 #[test]
 fn test_invalid_rates() {
-    let (env, client, _admin) = setup();
+    let (env, client, admin) = setup();
     env.mock_all_auths();
 
     // Try set negative rate
-    let res = client.try_set_flexi_rate(&-100);
+    let res = client.try_set_flexi_rate(&admin, &-100);
     assert_eq!(res.unwrap_err(), Ok(SavingsError::InvalidInterestRate));
 }
+
+#[test]
+fn test_rate_changes_are_logged_with_effective_time() {
+    let (env, client, admin) = setup();
+    assert_eq!(client.get_rate_history(&PlanKind::Flexi).len(), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.set_flexi_rate(&admin, &500);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.set_flexi_rate(&admin, &450);
+
+    let history = client.get_rate_history(&PlanKind::Flexi);
+    assert_eq!(history.len(), 2);
+    let first = history.get(0).unwrap();
+    assert_eq!(
+        (first.old_rate, first.new_rate, first.effective_from),
+        (0, 500, 1_000)
+    );
+    let second = history.get(1).unwrap();
+    assert_eq!(
+        (second.old_rate, second.new_rate, second.effective_from),
+        (500, 450, 2_000)
+    );
+
+    // Other plan kinds keep separate logs
+    assert_eq!(client.get_rate_history(&PlanKind::Goal).len(), 0);
+}
+
+#[test]
+fn test_lock_rate_history_records_duration_tier() {
+    let (_env, client, admin) = setup();
+    client.set_lock_rate(&admin, &30, &800);
+    client.set_lock_rate(&admin, &90, &1_200);
+    client.set_lock_rate(&admin, &30, &700);
+
+    let history = client.get_rate_history(&PlanKind::Lock);
+    assert_eq!(history.len(), 3);
+    let last = history.get(2).unwrap();
+    assert_eq!(last.duration_days, 30);
+    assert_eq!((last.old_rate, last.new_rate), (800, 700));
+}
+
+#[test]
+fn test_rejected_rate_change_is_not_logged() {
+    let (_env, client, admin) = setup();
+    assert!(client.try_set_goal_rate(&admin, &-1).is_err());
+    assert_eq!(client.get_rate_history(&PlanKind::Goal).len(), 0);
+}
+
+#[test]
+fn test_scheduled_lock_rate_applies_to_new_locks_only() {
+    let (env, client, admin) = setup();
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    env.ledger().with_mut(|li| li.timestamp = 500);
+
+    assert_eq!(
+        client.try_schedule_rate_change(&admin, &800, &499),
+        Err(Ok(SavingsError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_schedule_rate_change(&admin, &10_001, &1_000),
+        Err(Ok(SavingsError::InvalidInterestRate))
+    );
+    assert!(client
+        .try_schedule_rate_change(&Address::generate(&env), &800, &1_000)
+        .is_err());
+
+    client.schedule_rate_change(&admin, &300, &5_000);
+    client.schedule_rate_change(&admin, &900, &1_000);
+    // Rescheduling the same time replaces the pending change
+    client.schedule_rate_change(&admin, &800, &1_000);
+    assert_eq!(client.get_lock_rate_schedule().len(), 2);
+    assert_eq!(client.get_rate_at(&999), 500);
+    assert_eq!(client.get_rate_at(&1_000), 800);
+    assert_eq!(client.get_rate_at(&6_000), 300);
+
+    let early = client.create_lock_save(&user, &1_000, &86_400);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let late = client.create_lock_save(&user, &1_000, &86_400);
+    assert_eq!(client.get_lock_save_detail(&early).interest_rate, 500);
+    assert_eq!(client.get_lock_save_detail(&late).interest_rate, 800);
+}
+
+#[test]
+fn test_lock_and_flexi_statements() {
+    let (env, client, admin) = setup();
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 0 });
+    client.set_flexi_rate(&admin, &500);
+
+    // 365.25 days, the year lock rates are quoted over
+    let lock_year = 31_557_600;
+    let lock_id = client.create_lock_save(&user, &1_000_000, &lock_year);
+    client.deposit_flexi(&user, &1_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp = lock_year / 2);
+    let statement = client.get_lock_statement(&lock_id);
+    assert_eq!(statement.plan_kind, PlanKind::Lock);
+    assert_eq!(statement.principal, 1_000_000);
+    // Simple lock interest is rounded down
+    assert_eq!(statement.accrued_interest, 24_999);
+    assert_eq!(statement.projected_interest, 50_000);
+    assert_eq!(statement.projected_fee, 10_500);
+    assert_eq!(statement.effective_apy_bps, 395);
+
+    let flexi_year = 365 * 24 * 60 * 60;
+    env.ledger().with_mut(|li| li.timestamp = flexi_year / 2);
+    let statement = client.get_flexi_statement(&user);
+    assert_eq!(statement.plan_id, 0);
+    assert_eq!(statement.accrued_interest, 25_000);
+    assert_eq!(statement.projected_interest, 50_000);
+    assert_eq!(statement.projected_fee, 10_500);
+    assert_eq!(statement.effective_apy_bps, 395);
+
+    env.ledger().with_mut(|li| li.timestamp = lock_year);
+    client.withdraw_lock_save(&user, &lock_id);
+    assert_eq!(
+        client.try_get_lock_statement(&lock_id),
+        Err(Ok(SavingsError::PlanCompleted))
+    );
+}
+
+#[test]
+fn test_withdrawal_previews_match_payouts() {
+    let (env, client, admin) = setup();
+    let user = Address::generate(&env);
+    client.initialize_user(&user);
+    client.set_withdrawal_fee(&admin, &WithdrawalFeeConfig { bps: 100, flat: 0 });
+    client.set_early_break_fee_bps(&admin, &500);
+
+    client.deposit_flexi(&user, &10_000);
+    let preview = client.preview_withdraw_flexi(&user, &4_000);
+    assert_eq!(
+        (preview.gross, preview.fee, preview.net),
+        (4_000, 40, 3_960)
+    );
+    assert_eq!(
+        client.try_preview_withdraw_flexi(&user, &20_000),
+        Err(Ok(SavingsError::InsufficientBalance))
+    );
+    assert_eq!(client.get_flexi_balance(&user), 10_000);
+
+    let goal_id = client.create_goal_save(&user, &symbol_short!("car"), &50_000, &2_000);
+    let preview = client.preview_break_goal(&goal_id);
+    assert_eq!(
+        (preview.gross, preview.penalty, preview.net),
+        (2_000, 100, 1_900)
+    );
+    assert_eq!(client.break_goal_save(&user, &goal_id), 1_900);
+    assert_eq!(
+        client.try_preview_break_goal(&goal_id),
+        Err(Ok(SavingsError::PlanCompleted))
+    );
+
+    let lock_id = client.create_lock_save(&user, &1_000, &100);
+    assert_eq!(
+        client.try_preview_withdraw_lock(&lock_id),
+        Err(Ok(SavingsError::TooEarly))
+    );
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    // The yield pool is empty, so no interest can be paid
+    let preview = client.preview_withdraw_lock(&lock_id);
+    assert_eq!((preview.interest, preview.fee, preview.net), (0, 10, 990));
+    assert!(!client.get_lock_save_detail(&lock_id).is_withdrawn);
+}
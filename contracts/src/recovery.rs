@@ -0,0 +1,386 @@
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
+
+use crate::buckets;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::storage_types::{BalanceCheckpoint, BalanceProof, ConfigKey, DataKey, User, UserKey};
+use crate::ttl;
+use crate::users;
+
+/// Checkpoints kept for recovery; older ones are dropped first
+pub const MAX_BALANCE_CHECKPOINTS: u32 = 32;
+
+/// Most users a single checkpoint may cover
+pub const MAX_CHECKPOINT_USERS: u32 = 100;
+
+/// Prefixes keeping leaf and node hashes apart, so a node can't be passed off
+/// as a leaf
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Records a Merkle root over the current total and Flexi balances of
+/// `users`, in the order given, and returns the checkpoint's ID.
+///
+/// The root lives in instance storage, which stays live with the contract,
+/// so it outlasts user entries archived for expiring. Only the latest
+/// `MAX_BALANCE_CHECKPOINTS` checkpoints are kept.
+///
+/// # Errors
+/// * `InvalidAmount` - If `users` is empty or longer than
+///   `MAX_CHECKPOINT_USERS`
+/// * `UserNotFound` - If any of the users is not initialized
+pub fn checkpoint_balances(env: &Env, users: Vec<Address>) -> Result<u32, SavingsError> {
+    if users.is_empty() || users.len() > MAX_CHECKPOINT_USERS {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let mut leaves = Vec::new(env);
+    for user in users.iter() {
+        let user_data = users::get_user(env, &user)?;
+        let flexi_balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::FlexiBalance(user.clone()))
+            .unwrap_or(0);
+        leaves.push_back(leaf_hash(
+            env,
+            &user,
+            user_data.total_balance,
+            flexi_balance,
+        ));
+    }
+
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::NextCheckpointId))
+        .unwrap_or(1);
+    let checkpoint = BalanceCheckpoint {
+        id,
+        root: merkle_root(env, leaves),
+        leaf_count: users.len(),
+        taken_at: env.ledger().timestamp(),
+    };
+
+    let mut checkpoints = get_balance_checkpoints(env);
+    if checkpoints.len() >= MAX_BALANCE_CHECKPOINTS {
+        checkpoints.pop_front();
+    }
+    checkpoints.push_back(checkpoint.clone());
+    env.storage().instance().set(
+        &DataKey::Config(ConfigKey::BalanceCheckpoints),
+        &checkpoints,
+    );
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::NextCheckpointId), &(id + 1));
+    for user in users.iter() {
+        let key = DataKey::UserState(UserKey::LatestCheckpoint(user));
+        env.storage().persistent().set(&key, &id);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ttl::TTL_EXTEND_TO, ttl::TTL_EXTEND_TO);
+    }
+
+    events::publish(
+        env,
+        events::BalancesCheckpointed {
+            checkpoint_id: id,
+            root: checkpoint.root,
+            leaf_count: checkpoint.leaf_count,
+        },
+    );
+    Ok(id)
+}
+
+/// Recreates the account of a user whose entries were archived, with the
+/// total and Flexi balances proven against a retained checkpoint.
+///
+/// Only the account and its Flexi balance are restored; plans must be
+/// restored from the ledger archive. The balances stay counted in the Flexi
+/// liabilities, which are not archived, so those are left untouched.
+///
+/// Only the latest checkpoint covering the user is accepted, and only once:
+/// restoring consumes it, so neither an older balance nor the same proof can
+/// be replayed.
+///
+/// # Errors
+/// * `UserAlreadyExists` - If the user still has a live account
+/// * `AccountClosed` - If the user closed their account
+/// * `InvalidAmount` - If the balances are negative or the Flexi balance
+///   exceeds the total
+/// * `InvalidProof` - If the checkpoint is not the user's latest, was already
+///   used, is not retained, or the proof does not match its root
+pub fn restore_user(env: &Env, user: Address, proof: BalanceProof) -> Result<(), SavingsError> {
+    if users::user_exists(env, &user) {
+        return Err(SavingsError::UserAlreadyExists);
+    }
+    if users::is_closed(env, &user) {
+        return Err(SavingsError::AccountClosed);
+    }
+    if proof.flexi_balance < 0 || proof.total_balance < proof.flexi_balance {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let latest_key = DataKey::UserState(UserKey::LatestCheckpoint(user.clone()));
+    let latest: Option<u32> = env.storage().persistent().get(&latest_key);
+    if latest != Some(proof.checkpoint_id) {
+        return Err(SavingsError::InvalidProof);
+    }
+
+    let checkpoint = get_balance_checkpoints(env)
+        .iter()
+        .find(|c| c.id == proof.checkpoint_id)
+        .ok_or(SavingsError::InvalidProof)?;
+    let leaf = leaf_hash(env, &user, proof.total_balance, proof.flexi_balance);
+    if !verify_proof(env, &checkpoint, leaf, proof.leaf_index, &proof.siblings) {
+        return Err(SavingsError::InvalidProof);
+    }
+    env.storage().persistent().remove(&latest_key);
+
+    env.storage().persistent().set(
+        &DataKey::User(user.clone()),
+        &User {
+            total_balance: proof.total_balance,
+            savings_count: 0,
+        },
+    );
+    env.storage()
+        .persistent()
+        .set(&DataKey::FlexiBalance(user.clone()), &proof.flexi_balance);
+    buckets::fit_to_balance(env, &user, proof.flexi_balance);
+    env.storage().persistent().set(
+        &DataKey::UserCreatedAt(user.clone()),
+        &env.ledger().timestamp(),
+    );
+
+    events::publish(
+        env,
+        events::UserRestored {
+            user,
+            checkpoint_id: proof.checkpoint_id,
+            total_balance: proof.total_balance,
+            flexi_balance: proof.flexi_balance,
+        },
+    );
+    Ok(())
+}
+
+/// Retained checkpoints, oldest first
+pub fn get_balance_checkpoints(env: &Env) -> Vec<BalanceCheckpoint> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::BalanceCheckpoints))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Hash committing to one user's checkpointed balances
+fn leaf_hash(env: &Env, user: &Address, total_balance: i128, flexi_balance: i128) -> BytesN<32> {
+    let mut bytes = Bytes::from_array(env, &[LEAF_PREFIX]);
+    bytes.append(&(user.clone(), total_balance, flexi_balance).to_xdr(env));
+    env.crypto().sha256(&bytes).to_bytes()
+}
+
+fn node_hash(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut bytes = Bytes::from_array(env, &[NODE_PREFIX]);
+    bytes.append(&Bytes::from(left));
+    bytes.append(&Bytes::from(right));
+    env.crypto().sha256(&bytes).to_bytes()
+}
+
+/// Root of the tree over `leaves`; a level with an odd count pairs its last
+/// node with itself
+fn merkle_root(env: &Env, mut level: Vec<BytesN<32>>) -> BytesN<32> {
+    while level.len() > 1 {
+        let mut next = Vec::new(env);
+        let mut i = 0;
+        while i < level.len() {
+            let left = level.get_unchecked(i);
+            let right = level.get(i + 1).unwrap_or_else(|| left.clone());
+            next.push_back(node_hash(env, &left, &right));
+            i += 2;
+        }
+        level = next;
+    }
+    level.get_unchecked(0)
+}
+
+/// Checks that `leaf` sits at `leaf_index` of the checkpointed tree. The
+/// path must have exactly one sibling per level of that tree.
+fn verify_proof(
+    env: &Env,
+    checkpoint: &BalanceCheckpoint,
+    leaf: BytesN<32>,
+    leaf_index: u32,
+    siblings: &Vec<BytesN<32>>,
+) -> bool {
+    if leaf_index >= checkpoint.leaf_count {
+        return false;
+    }
+    let mut depth = 0;
+    let mut width = checkpoint.leaf_count;
+    while width > 1 {
+        width = width.div_ceil(2);
+        depth += 1;
+    }
+    if siblings.len() != depth {
+        return false;
+    }
+
+    let mut hash = leaf;
+    let mut index = leaf_index;
+    for sibling in siblings.iter() {
+        hash = if index.is_multiple_of(2) {
+            node_hash(env, &hash, &sibling)
+        } else {
+            node_hash(env, &sibling, &hash)
+        };
+        index /= 2;
+    }
+    hash == checkpoint.root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::{testutils::Address as _, vec, BytesN};
+
+    #[test]
+    fn test_restore_user_from_checkpoint_proof() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let users = vec![
+            &env,
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        for (i, user) in users.iter().enumerate() {
+            client.initialize_user(&user);
+            client.deposit_flexi(&user, &(100 * (i as i128 + 1)));
+        }
+        let checkpoint_id = client.checkpoint_balances(&admin, &users);
+        let dormant = users.get(2).unwrap();
+
+        // Lose the dormant user's entries as if they had been archived
+        let leaves: Vec<BytesN<32>> = env.as_contract(&contract_id, || {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::User(dormant.clone()));
+            env.storage()
+                .persistent()
+                .remove(&DataKey::FlexiBalance(dormant.clone()));
+            let mut leaves = Vec::new(&env);
+            for (i, user) in users.iter().enumerate() {
+                let balance = 100 * (i as i128 + 1);
+                leaves.push_back(leaf_hash(&env, &user, balance, balance));
+            }
+            leaves
+        });
+        let left = env.as_contract(&contract_id, || {
+            node_hash(&env, &leaves.get(0).unwrap(), &leaves.get(1).unwrap())
+        });
+        let proof = |balance: i128| BalanceProof {
+            checkpoint_id,
+            leaf_index: 2,
+            total_balance: balance,
+            flexi_balance: balance,
+            siblings: vec![&env, leaves.get(2).unwrap(), left.clone()],
+        };
+
+        assert_eq!(
+            client.try_restore_user(&admin, &dormant, &proof(3_000)),
+            Err(Ok(SavingsError::InvalidProof))
+        );
+        assert_eq!(
+            client.try_restore_user(&admin, &users.get(0).unwrap(), &proof(300)),
+            Err(Ok(SavingsError::UserAlreadyExists))
+        );
+
+        client.restore_user(&admin, &dormant, &proof(300));
+        assert_eq!(client.get_user(&dormant).total_balance, 300);
+        assert_eq!(client.get_flexi_balance(&dormant), 300);
+        assert_eq!(
+            client.try_restore_user(&admin, &dormant, &proof(300)),
+            Err(Ok(SavingsError::UserAlreadyExists))
+        );
+
+        // Once used, the proof cannot restore the account again
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::User(dormant.clone()));
+        });
+        assert_eq!(
+            client.try_restore_user(&admin, &dormant, &proof(300)),
+            Err(Ok(SavingsError::InvalidProof))
+        );
+    }
+
+    #[test]
+    fn test_restore_user_needs_latest_checkpoint_of_open_account() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &500);
+        let stale_id = client.checkpoint_balances(&admin, &vec![&env, user.clone()]);
+        client.withdraw_flexi(&user, &500);
+        client.checkpoint_balances(&admin, &vec![&env, user.clone()]);
+
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::User(user.clone()));
+        });
+        // A single leaf is its own root
+        let stale = BalanceProof {
+            checkpoint_id: stale_id,
+            leaf_index: 0,
+            total_balance: 500,
+            flexi_balance: 500,
+            siblings: Vec::new(&env),
+        };
+        assert_eq!(
+            client.try_restore_user(&admin, &user, &stale),
+            Err(Ok(SavingsError::InvalidProof))
+        );
+
+        let closer = Address::generate(&env);
+        client.initialize_user(&closer);
+        client.checkpoint_balances(&admin, &vec![&env, closer.clone()]);
+        client.close_account(&closer);
+        let closed = BalanceProof {
+            checkpoint_id: stale_id + 2,
+            total_balance: 0,
+            flexi_balance: 0,
+            ..stale
+        };
+        assert_eq!(
+            client.try_restore_user(&admin, &closer, &closed),
+            Err(Ok(SavingsError::AccountClosed))
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use soroban_sdk::{token, Address, Env};
+
+use crate::errors::SavingsError;
+use crate::storage_types::{ConfigKey, DataKey};
+
+/// Fails if an external call made by this contract is in progress
+pub fn ensure_not_entered(env: &Env) -> Result<(), SavingsError> {
+    if env
+        .storage()
+        .instance()
+        .has(&DataKey::Config(ConfigKey::ReentrancyLock))
+    {
+        return Err(SavingsError::Reentrancy);
+    }
+    Ok(())
+}
+
+/// Runs `call`, which invokes another contract, with the reentrancy flag
+/// held in instance storage; `guard::check` rejects any entrypoint reached
+/// while it is set.
+///
+/// Callers finish their checks and storage updates first and make the
+/// external call last, so a callback never sees half-applied state.
+///
+/// # Errors
+/// * `Reentrancy` - If another external call is already in progress
+pub fn external<T>(env: &Env, call: impl FnOnce() -> T) -> Result<T, SavingsError> {
+    ensure_not_entered(env)?;
+    let key = DataKey::Config(ConfigKey::ReentrancyLock);
+    env.storage().instance().set(&key, &true);
+    let result = call();
+    env.storage().instance().remove(&key);
+    Ok(result)
+}
+
+/// Transfers `amount` of `token` from `from` to `to` with the reentrancy flag
+/// held
+pub fn transfer(
+    env: &Env,
+    token: &Address,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    external(env, || {
+        token::Client::new(env, token).transfer(from, to, &amount)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guard::{self, Op};
+    use crate::NesteraContract;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_entrypoints_rejected_during_external_call() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let user = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(external(&env, || 7), Ok(7));
+            assert_eq!(guard::check(&env, &user, Op::Deposit, 100), Ok(()));
+
+            let nested = external(&env, || {
+                (
+                    guard::check(&env, &user, Op::Withdraw, 100),
+                    guard::check_protocol(&env),
+                    external(&env, || ()),
+                )
+            });
+            assert_eq!(
+                nested,
+                Ok((
+                    Err(SavingsError::Reentrancy),
+                    Err(SavingsError::Reentrancy),
+                    Err(SavingsError::Reentrancy),
+                ))
+            );
+
+            // The flag is released once the call returns
+            assert_eq!(ensure_not_entered(&env), Ok(()));
+        });
+    }
+}
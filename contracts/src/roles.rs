@@ -0,0 +1,135 @@
+use soroban_sdk::{Address, Env};
+
+use crate::errors::SavingsError;
+use crate::storage_types::{DataKey, Role};
+
+pub(crate) fn is_admin(env: &Env, addr: &Address) -> bool {
+    let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+    admin.as_ref() == Some(addr)
+}
+
+/// Returns whether `addr` holds `role`, either directly or as the admin
+pub fn has_role(env: &Env, addr: &Address, role: Role) -> bool {
+    is_admin(env, addr)
+        || env
+            .storage()
+            .persistent()
+            .get(&DataKey::Role(role, addr.clone()))
+            .unwrap_or(false)
+}
+
+/// Verifies `caller` signed the call and holds `role`
+pub fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), SavingsError> {
+    caller.require_auth();
+    if !has_role(env, caller, role) {
+        return Err(SavingsError::Unauthorized);
+    }
+    Ok(())
+}
+
+pub fn grant_role(env: &Env, addr: &Address, role: Role) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Role(role, addr.clone()), &true);
+}
+
+pub fn revoke_role(env: &Env, addr: &Address, role: Role) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Role(role, addr.clone()));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, Role, SavingsError};
+    use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        (env, client, admin)
+    }
+
+    #[test]
+    fn test_admin_holds_every_role() {
+        let (_env, client, admin) = setup();
+        assert!(client.has_role(&admin, &Role::Pauser));
+        assert!(client.has_role(&admin, &Role::Upgrader));
+    }
+
+    #[test]
+    fn test_pauser_can_pause_but_not_set_fees() {
+        let (env, client, admin) = setup();
+        let bot = Address::generate(&env);
+        client.grant_role(&admin, &bot, &Role::Pauser);
+
+        client.pause(&bot);
+        assert!(client.is_paused());
+        client.unpause(&bot);
+
+        assert_eq!(
+            client.try_set_early_break_fee_bps(&bot, &100).unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_revoked_role_loses_access() {
+        let (env, client, admin) = setup();
+        let manager = Address::generate(&env);
+        client.grant_role(&admin, &manager, &Role::RateManager);
+        client.set_flexi_rate(&manager, &300);
+        assert_eq!(client.get_flexi_rate(), 300);
+
+        client.revoke_role(&admin, &manager, &Role::RateManager);
+        assert!(!client.has_role(&manager, &Role::RateManager));
+        assert_eq!(
+            client.try_set_flexi_rate(&manager, &400).unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_only_admin_can_grant_roles() {
+        let (env, client, admin) = setup();
+        let manager = Address::generate(&env);
+        client.grant_role(&admin, &manager, &Role::FeeManager);
+
+        // Holding a role does not allow granting it to others
+        let other = Address::generate(&env);
+        assert_eq!(
+            client
+                .try_grant_role(&manager, &other, &Role::FeeManager)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_treasurer_configures_strategy() {
+        let (env, client, admin) = setup();
+        let treasurer = Address::generate(&env);
+        let strategy = Address::generate(&env);
+
+        assert_eq!(
+            client.try_set_strategy(&treasurer, &strategy).unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+        client.grant_role(&admin, &treasurer, &Role::Treasurer);
+        client.set_target_liquidity_bps(&treasurer, &2_500);
+        assert_eq!(client.get_liquidity_status().target_liquidity_bps, 2_500);
+    }
+}
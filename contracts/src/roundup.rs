@@ -0,0 +1,133 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::flexi;
+use crate::guard::{self, Op};
+use crate::storage_types::RoundupPreview;
+
+/// Most purchases a single round-up call may carry
+pub const MAX_ROUNDUP_PURCHASES: u32 = 50;
+
+/// Spare change of each purchase: what it takes to reach the next multiple
+/// of `round_to`, or nothing for a purchase already on one
+fn compute_roundups(
+    env: &Env,
+    purchase_amounts: &Vec<i128>,
+    round_to: i128,
+) -> Result<(Vec<i128>, i128), SavingsError> {
+    if round_to <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    if purchase_amounts.len() > MAX_ROUNDUP_PURCHASES {
+        return Err(SavingsError::AmountExceedsLimit);
+    }
+
+    let mut roundups = Vec::new(env);
+    let mut total: i128 = 0;
+    for purchase in purchase_amounts.iter() {
+        if purchase <= 0 {
+            return Err(SavingsError::InvalidAmount);
+        }
+        let roundup = (round_to - purchase % round_to) % round_to;
+        total = total.checked_add(roundup).ok_or(SavingsError::Overflow)?;
+        roundups.push_back(roundup);
+    }
+    Ok((roundups, total))
+}
+
+/// VIEW FUNCTION - Round-ups `deposit_roundups` would deposit for the given
+/// purchases, without depositing anything
+///
+/// # Errors
+/// * `InvalidAmount` - If `round_to` or a purchase is not positive
+/// * `AmountExceedsLimit` - If there are more than `MAX_ROUNDUP_PURCHASES`
+///   purchases
+/// * `UserNotFound` - If the user is not initialized
+pub fn preview_roundup(
+    env: &Env,
+    user: Address,
+    purchase_amounts: Vec<i128>,
+    round_to: i128,
+) -> Result<RoundupPreview, SavingsError> {
+    let (roundups, total) = compute_roundups(env, &purchase_amounts, round_to)?;
+    let flexi_balance = flexi::get_flexi_balance(env, user)?;
+    Ok(RoundupPreview {
+        roundups,
+        total,
+        flexi_balance_after: flexi_balance
+            .checked_add(total)
+            .ok_or(SavingsError::Overflow)?,
+    })
+}
+
+/// Deposits the round-up spare change of the given purchases into the
+/// user's Flexi Save as a single deposit. Returns the amount deposited.
+///
+/// # Errors
+/// * `InvalidAmount` - If `round_to` or a purchase is not positive, or no
+///   purchase leaves any spare change
+/// * `AmountExceedsLimit` - If there are more than `MAX_ROUNDUP_PURCHASES`
+///   purchases
+pub fn deposit_roundups(
+    env: &Env,
+    user: Address,
+    purchase_amounts: Vec<i128>,
+    round_to: i128,
+) -> Result<i128, SavingsError> {
+    let (_, total) = compute_roundups(env, &purchase_amounts, round_to)?;
+    if total == 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    guard::check(env, &user, Op::Deposit, total)?;
+    user.require_auth();
+
+    flexi::apply_flexi_deposit(env, &user, total)?;
+
+    events::publish(
+        env,
+        events::RoundUpsDeposited {
+            user,
+            count: purchase_amounts.len(),
+            total,
+        },
+    );
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+    #[test]
+    fn test_roundups_preview_and_deposit() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &1_000);
+
+        let purchases = vec![&env, 345, 1_200, 99];
+        let preview = client.preview_roundup(&user, &purchases, &100);
+        assert_eq!(preview.roundups, vec![&env, 55, 0, 1]);
+        assert_eq!(preview.total, 56);
+        assert_eq!(preview.flexi_balance_after, 1_056);
+        // The preview moves nothing
+        assert_eq!(client.get_flexi_balance(&user), 1_000);
+
+        assert_eq!(client.deposit_roundups(&user, &purchases, &100), 56);
+        assert_eq!(client.get_flexi_balance(&user), 1_056);
+
+        assert_eq!(
+            client.try_deposit_roundups(&user, &vec![&env, 500], &100),
+            Err(Ok(SavingsError::InvalidAmount))
+        );
+        assert_eq!(
+            client.try_preview_roundup(&user, &purchases, &0),
+            Err(Ok(SavingsError::InvalidAmount))
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use soroban_sdk::{symbol_short, xdr::ToXdr, Bytes, BytesN, Env, Symbol};
+
+use crate::storage_types::SignedEnvelope;
+
+/// Tag for admin-signed `mint` payloads
+pub const MINT: Symbol = symbol_short!("mint");
+/// Tag for admin-signed sponsored Flexi deposits
+pub const DEPOSIT_FLEXI: Symbol = symbol_short!("sp_dep");
+/// Tag for admin-signed sponsored Goal Save creation
+pub const CREATE_GOAL: Symbol = symbol_short!("sp_goal");
+/// Tag for user-signed AutoSave consents
+pub const AUTOSAVE_CONSENT: Symbol = symbol_short!("as_consnt");
+
+/// Wraps an XDR-encoded payload in an envelope bound to this contract and network
+pub fn envelope(env: &Env, function: Symbol, nonce: u64, payload: Bytes) -> SignedEnvelope {
+    SignedEnvelope {
+        contract: env.current_contract_address(),
+        network_id: env.ledger().network_id(),
+        function,
+        nonce,
+        payload,
+    }
+}
+
+/// Returns the exact bytes an off-chain signer must sign for a payload
+pub fn message(env: &Env, function: Symbol, nonce: u64, payload: Bytes) -> Bytes {
+    envelope(env, function, nonce, payload).to_xdr(env)
+}
+
+/// Verifies an Ed25519 signature over the payload's envelope.
+///
+/// Panics (and rolls back the call) if the signature does not match, including
+/// when it was produced for another contract, network or entrypoint.
+pub fn verify(
+    env: &Env,
+    public_key: &BytesN<32>,
+    function: Symbol,
+    nonce: u64,
+    payload: Bytes,
+    signature: &BytesN<64>,
+) {
+    let message = message(env, function, nonce, payload);
+    env.crypto().ed25519_verify(public_key, &message, signature);
+}
@@ -0,0 +1,270 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::guard::{self, Op};
+use crate::rates;
+use crate::storage_types::{DataKey, PlanKind, SimulatedPlan, SimulationProjection, UserKey};
+
+/// Most simulations a user may keep at once
+pub const MAX_SIMULATIONS: u32 = 5;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+pub fn get_simulations(env: &Env, user: &Address) -> Vec<SimulatedPlan> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::Simulations(user.clone())))
+        .unwrap_or(Vec::new(env))
+}
+
+fn save_simulations(env: &Env, user: &Address, simulations: &Vec<SimulatedPlan>) {
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::Simulations(user.clone())),
+        simulations,
+    );
+}
+
+fn find(simulations: &Vec<SimulatedPlan>, sim_id: u64) -> Result<u32, SavingsError> {
+    simulations
+        .iter()
+        .position(|s| s.id == sim_id)
+        .map(|i| i as u32)
+        .ok_or(SavingsError::PlanNotFound)
+}
+
+/// Interest a simulation has accrued by `at`, using the same simple annual
+/// interest as real plans and stopping at maturity
+fn accrued_interest(plan: &SimulatedPlan, at: u64) -> i128 {
+    let end = at.min(plan.matures_at);
+    let elapsed = end.saturating_sub(plan.start_time);
+    rates::calculate_flexi_interest(plan.principal, plan.rate, elapsed)
+}
+
+/// Opens a simulated plan. Lock simulations use the rate a Lock Save created
+/// now would be fixed at and mature after `duration_days`; other kinds use
+/// their default rate and mature after `duration_days` if it is non-zero.
+pub fn create_simulation(
+    env: &Env,
+    user: Address,
+    plan_kind: PlanKind,
+    amount: i128,
+    duration_days: u64,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let rate = match plan_kind {
+        PlanKind::Flexi => rates::get_flexi_rate(env),
+        PlanKind::Goal => rates::get_goal_rate(env),
+        PlanKind::Group => rates::get_group_rate(env),
+        PlanKind::Lock => {
+            if duration_days == 0 {
+                return Err(SavingsError::InvalidPlanConfig);
+            }
+            rates::get_rate_at(env, env.ledger().timestamp()) as i128
+        }
+    };
+
+    let mut simulations = get_simulations(env, &user);
+    if simulations.len() >= MAX_SIMULATIONS {
+        return Err(SavingsError::MaxPlansExceeded);
+    }
+
+    let now = env.ledger().timestamp();
+    let matures_at = if duration_days == 0 {
+        u64::MAX
+    } else {
+        now.saturating_add(duration_days.saturating_mul(SECONDS_PER_DAY))
+    };
+    let id = simulations.last().map(|s| s.id + 1).unwrap_or(1);
+    let plan = SimulatedPlan {
+        id,
+        plan_kind,
+        principal: amount,
+        rate,
+        start_time: now,
+        matures_at,
+        is_simulation: true,
+    };
+    simulations.push_back(plan.clone());
+    save_simulations(env, &user, &simulations);
+
+    events::publish(env, events::SimulationCreated { user, plan });
+    Ok(id)
+}
+
+/// Adds a simulated deposit. Interest accrued so far is folded into the
+/// principal first, so each deposit earns from its own start.
+pub fn simulate_deposit(
+    env: &Env,
+    user: Address,
+    sim_id: u64,
+    amount: i128,
+) -> Result<SimulatedPlan, SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let mut simulations = get_simulations(env, &user);
+    let index = find(&simulations, sim_id)?;
+    let mut plan = simulations.get(index).unwrap();
+    let now = env.ledger().timestamp();
+    if now >= plan.matures_at {
+        return Err(SavingsError::PlanCompleted);
+    }
+
+    plan.principal = plan
+        .principal
+        .checked_add(accrued_interest(&plan, now))
+        .and_then(|p| p.checked_add(amount))
+        .ok_or(SavingsError::Overflow)?;
+    plan.start_time = now;
+    simulations.set(index, plan.clone());
+    save_simulations(env, &user, &simulations);
+
+    events::publish(env, events::SimulationDeposited { user, amount });
+    Ok(plan)
+}
+
+/// VIEW FUNCTION - Value of a simulation at `at` (capped at maturity)
+pub fn project_simulation(
+    env: &Env,
+    user: &Address,
+    sim_id: u64,
+    at: u64,
+) -> Result<SimulationProjection, SavingsError> {
+    let simulations = get_simulations(env, user);
+    let plan = simulations.get(find(&simulations, sim_id)?).unwrap();
+    let interest = accrued_interest(&plan, at);
+    Ok(SimulationProjection {
+        principal: plan.principal,
+        interest,
+        total: plan.principal + interest,
+    })
+}
+
+pub fn delete_simulation(env: &Env, user: Address, sim_id: u64) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let mut simulations = get_simulations(env, &user);
+    let index = find(&simulations, sim_id)?;
+    simulations.remove(index);
+    save_simulations(env, &user, &simulations);
+
+    events::publish(env, events::SimulationDeleted { user, sim_id });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, PlanKind, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env,
+    };
+
+    const YEAR: u64 = 365 * 24 * 60 * 60;
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        (env, client, admin)
+    }
+
+    #[test]
+    fn test_simulation_projects_interest_without_touching_balances() {
+        let (env, client, admin) = setup();
+        client.set_flexi_rate(&admin, &500);
+        // Simulations don't require an initialized account
+        let visitor = Address::generate(&env);
+
+        let sim_id = client.create_simulation(&visitor, &PlanKind::Flexi, &10_000, &0);
+        let plan = client.get_simulations(&visitor).get(0).unwrap();
+        assert!(plan.is_simulation);
+        assert_eq!(plan.rate, 500);
+
+        let projection = client.project_simulation(&visitor, &sim_id, &YEAR);
+        assert_eq!(projection.interest, 500);
+        assert_eq!(projection.total, 10_500);
+
+        assert!(!client.user_exists(&visitor));
+        assert_eq!(client.get_liquidity_status().flexi_liabilities, 0);
+    }
+
+    #[test]
+    fn test_lock_simulation_stops_at_maturity() {
+        let (env, client, admin) = setup();
+        let visitor = Address::generate(&env);
+        assert_eq!(
+            client
+                .try_create_simulation(&visitor, &PlanKind::Lock, &1_000, &0)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidPlanConfig)
+        );
+
+        // Simulated locks get the rate a lock created now would be fixed at
+        client.schedule_rate_change(&admin, &1_000, &0);
+        let sim_id = client.create_simulation(&visitor, &PlanKind::Lock, &1_000, &365);
+        assert_eq!(client.get_simulations(&visitor).get(0).unwrap().rate, 1_000);
+        let at_maturity = client.project_simulation(&visitor, &sim_id, &YEAR);
+        let later = client.project_simulation(&visitor, &sim_id, &(3 * YEAR));
+        assert_eq!(at_maturity.interest, 100);
+        assert_eq!(later, at_maturity);
+    }
+
+    #[test]
+    fn test_simulated_deposit_folds_in_accrued_interest() {
+        let (env, client, admin) = setup();
+        client.set_goal_rate(&admin, &1_000);
+        let visitor = Address::generate(&env);
+        let sim_id = client.create_simulation(&visitor, &PlanKind::Goal, &1_000, &0);
+
+        env.ledger().with_mut(|li| li.timestamp = YEAR);
+        let plan = client.simulate_deposit(&visitor, &sim_id, &500);
+        assert_eq!(plan.principal, 1_600);
+        assert_eq!(plan.start_time, YEAR);
+
+        client.delete_simulation(&visitor, &sim_id);
+        assert_eq!(client.get_simulations(&visitor).len(), 0);
+        assert_eq!(
+            client
+                .try_project_simulation(&visitor, &sim_id, &YEAR)
+                .unwrap_err(),
+            Ok(SavingsError::PlanNotFound)
+        );
+    }
+
+    #[test]
+    fn test_simulation_cap() {
+        let (env, client, _admin) = setup();
+        let visitor = Address::generate(&env);
+        for _ in 0..super::MAX_SIMULATIONS {
+            client.create_simulation(&visitor, &PlanKind::Flexi, &100, &0);
+        }
+        assert_eq!(
+            client
+                .try_create_simulation(&visitor, &PlanKind::Flexi, &100, &0)
+                .unwrap_err(),
+            Ok(SavingsError::MaxPlansExceeded)
+        );
+    }
+}
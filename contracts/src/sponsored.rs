@@ -0,0 +1,147 @@
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::flexi;
+use crate::goal;
+use crate::guard::{self, Op};
+use crate::signing;
+use crate::storage_types::{DataKey, DepositPayload, GoalSavePayload, UserKey};
+
+/// Returns the nonce the user's next sponsored payload must carry
+pub fn get_nonce(env: &Env, user: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserState(UserKey::SponsoredNonce(user.clone())))
+        .unwrap_or(0)
+}
+
+/// Replay-protection fields every sponsored payload carries
+struct PayloadValidity {
+    timestamp: u64,
+    expiry_duration: u64,
+    nonce: u64,
+}
+
+/// Checks an admin-signed payload and consumes the user's nonce.
+///
+/// # Errors
+/// * `InvalidSignature` - If no admin key is set or the nonce is not the next one
+/// * `TooLate` - If the payload has expired
+fn verify(
+    env: &Env,
+    user: &Address,
+    function: Symbol,
+    payload_bytes: Bytes,
+    signature: &BytesN<64>,
+    validity: PayloadValidity,
+) -> Result<(), SavingsError> {
+    let PayloadValidity {
+        timestamp,
+        expiry_duration,
+        nonce,
+    } = validity;
+    let admin_public_key: BytesN<32> = env
+        .storage()
+        .instance()
+        .get(&DataKey::AdminPublicKey)
+        .ok_or(SavingsError::InvalidSignature)?;
+    if env.ledger().timestamp() > timestamp.saturating_add(expiry_duration) {
+        return Err(SavingsError::TooLate);
+    }
+    let next_nonce = get_nonce(env, user);
+    if nonce != next_nonce {
+        return Err(SavingsError::InvalidSignature);
+    }
+
+    // Panics (and rolls back the call) if the signature does not match
+    signing::verify(
+        env,
+        &admin_public_key,
+        function,
+        nonce,
+        payload_bytes,
+        signature,
+    );
+
+    env.storage().persistent().set(
+        &DataKey::UserState(UserKey::SponsoredNonce(user.clone())),
+        &(next_nonce + 1),
+    );
+    Ok(())
+}
+
+/// Deposits into a user's Flexi Save from an admin-signed payload
+///
+/// Lets the backend sponsor the transaction: the user's authorization is
+/// replaced by the admin's Ed25519 signature over the payload's
+/// `SignedEnvelope` (see `signing`).
+pub fn deposit_flexi(
+    env: &Env,
+    payload: DepositPayload,
+    signature: BytesN<64>,
+) -> Result<(), SavingsError> {
+    guard::check(env, &payload.user, Op::Deposit, payload.amount)?;
+    verify(
+        env,
+        &payload.user,
+        signing::DEPOSIT_FLEXI,
+        payload.clone().to_xdr(env),
+        &signature,
+        PayloadValidity {
+            timestamp: payload.timestamp,
+            expiry_duration: payload.expiry_duration,
+            nonce: payload.nonce,
+        },
+    )?;
+    flexi::apply_flexi_deposit(env, &payload.user, payload.amount)?;
+    events::publish(
+        env,
+        events::SponsoredDeposit {
+            user: payload.user,
+            amount: payload.amount,
+            nonce: payload.nonce,
+        },
+    );
+    Ok(())
+}
+
+/// Creates a Goal Save for a user from an admin-signed payload
+///
+/// See `deposit_flexi` for how the payload is authorized.
+pub fn create_goal_save(
+    env: &Env,
+    payload: GoalSavePayload,
+    signature: BytesN<64>,
+) -> Result<u64, SavingsError> {
+    guard::check(env, &payload.user, Op::CreatePlan, payload.initial_deposit)?;
+    verify(
+        env,
+        &payload.user,
+        signing::CREATE_GOAL,
+        payload.clone().to_xdr(env),
+        &signature,
+        PayloadValidity {
+            timestamp: payload.timestamp,
+            expiry_duration: payload.expiry_duration,
+            nonce: payload.nonce,
+        },
+    )?;
+    let goal_id = goal::insert_goal_save(
+        env,
+        payload.user.clone(),
+        payload.goal_name,
+        payload.target_amount,
+        payload.initial_deposit,
+        0,
+    )?;
+    events::publish(
+        env,
+        events::SponsoredGoalCreated {
+            user: payload.user,
+            goal_id,
+            nonce: payload.nonce,
+        },
+    );
+    Ok(goal_id)
+}
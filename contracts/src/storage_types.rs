@@ -1,4 +1,7 @@
-use soroban_sdk::{contracterror, contracttype, Address, String, Symbol};
+use soroban_sdk::{
+    contracterror, contracttype, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, TryFromVal,
+    Val, Vec,
+};
 
 /// Represents the different types of savings plans available in Nestera
 #[contracttype]
@@ -10,6 +13,16 @@ pub enum PlanType {
     Group(u64, bool, u32, i128),
 }
 
+/// Identifies which kind of plan an ID refers to
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlanKind {
+    Flexi,
+    Lock,
+    Goal,
+    Group,
+}
+
 /// Represents an individual savings plan for a user
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -61,6 +74,58 @@ pub struct GroupSave {
     pub start_time: u64,
     pub end_time: u64,
     pub is_completed: bool,
+    /// Length of a contribution round in seconds (0 = no round schedule)
+    pub contribution_frequency: u64,
+    /// Most members the group accepts, creator included (0 = no cap)
+    pub max_members: u32,
+}
+
+/// Interest a group has earned on its `current_amount` and not yet paid out
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupInterest {
+    /// Interest earned up to `last_accrual`
+    pub accrued: i128,
+    pub last_accrual: u64,
+}
+
+/// Descriptive fields of a new group, bundled to keep `create_group_save`
+/// within the contract function parameter limit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupMetadata {
+    /// Title/name of the group savings plan
+    pub title: String,
+    /// Description of the group savings goal
+    pub description: String,
+    /// Category of the group savings (e.g., "education", "emergency")
+    pub category: String,
+}
+
+/// Lifecycle state of a Group Save
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupStatus {
+    /// Accepting members and contributions
+    Active,
+    /// Reached its target
+    Completed,
+    /// Ended short of its target, or cancelled by an upheld dispute; members
+    /// may reclaim their contributions
+    Failed,
+}
+
+/// Minimum reputation and tenure a user needs to join a public group, and
+/// the stake they must put in on joining
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupJoinRequirements {
+    /// Minimum reputation score (0 = no requirement)
+    pub min_reputation: u32,
+    /// Minimum account age in seconds (0 = no requirement)
+    pub min_account_age: u64,
+    /// Contributed on joining without an invite (0 = no stake)
+    pub join_stake: i128,
 }
 
 /// Represents a Lock Save plan with fixed duration and maturity
@@ -74,6 +139,27 @@ pub struct LockSave {
     pub start_time: u64,
     pub maturity_time: u64,
     pub is_withdrawn: bool,
+    /// Amount-weighted start of the current balance, which top-ups move
+    /// forward. Only interest accrues from it; the term runs from
+    /// `start_time`.
+    pub interest_start: u64,
+    /// Roll principal plus interest into a new lock of the same duration at maturity
+    pub rollover: bool,
+    /// How often accrued interest is added to the principal
+    pub compounding: CompoundFrequency,
+    /// Balance that unlocks the lock before `maturity_time` (0 = unlocks
+    /// only at maturity)
+    pub target_amount: i128,
+}
+
+/// How often a Lock Save's interest is compounded into its principal
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompoundFrequency {
+    /// Simple interest on the principal
+    None,
+    Monthly,
+    Quarterly,
 }
 
 /// Custom error types for the savings contract
@@ -100,8 +186,874 @@ pub struct GoalSave {
     pub current_amount: i128,
     pub interest_rate: u32,
     pub start_time: u64,
+    /// Unix timestamp after which deposits are rejected (0 = no deadline)
+    pub deadline: u64,
     pub is_completed: bool,
     pub is_withdrawn: bool,
+    /// Whether any initialized user may contribute towards the goal
+    pub is_public: bool,
+}
+
+/// Total a user other than the goal's owners contributed to a public goal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalContribution {
+    pub contributor: Address,
+    pub amount: i128,
+}
+
+/// Whose authorization withdrawing from a co-owned goal takes
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GoalWithdrawPolicy {
+    /// The owner and every co-owner must sign
+    AllOwners,
+    /// Any one owner may withdraw alone
+    AnyOwner,
+}
+
+/// A goal's progress valued at the current oracle price
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalFiatProgress {
+    pub current_amount: i128,
+    /// Target in token base units; follows the price for fiat goals
+    pub target_amount: i128,
+    /// `current_amount` in the oracle's reference unit
+    pub current_fiat: i128,
+    pub target_fiat: i128,
+    /// Reference units per `PRICE_SCALE` token base units
+    pub price: i128,
+}
+
+/// A progress threshold (percent of target) reached by a Goal Save
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalMilestone {
+    /// Threshold crossed, as a percentage of the target amount
+    pub percent: u32,
+    /// Ledger timestamp of the deposit that crossed the threshold
+    pub reached_at: u64,
+}
+
+/// Period over which a user must deposit at least once to keep a streak alive
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StreakPeriod {
+    Weekly,
+    Monthly,
+}
+
+/// Per-user record of consecutive deposit periods
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserStreak {
+    /// Number of consecutive periods ending at `last_deposit_period`
+    pub current_streak: u32,
+    /// Longest streak the user has ever reached
+    pub longest_streak: u32,
+    /// Index of the last period with a deposit (timestamp / period length)
+    pub last_deposit_period: u64,
+}
+
+/// Achievement badges awarded automatically by savings activity
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Badge {
+    /// Made a first deposit of any kind
+    FirstDeposit = 1,
+    /// Completed a Goal Save
+    GoalCompleted = 2,
+    /// Deposited at least 10,000 in total
+    TenKSaved = 3,
+    /// Kept a deposit streak alive for twelve weeks
+    TwelveWeekStreak = 4,
+    /// Created a Group Save
+    GroupFounder = 5,
+    /// Met every period of a savings challenge
+    ChallengeCompleted = 6,
+}
+
+/// A badge held by a user and when it was awarded
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BadgeAward {
+    pub badge: Badge,
+    pub awarded_at: u64,
+}
+
+/// Merkle root over the balances of a batch of users at one point in time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceCheckpoint {
+    pub id: u32,
+    pub root: BytesN<32>,
+    /// Number of users the root covers
+    pub leaf_count: u32,
+    pub taken_at: u64,
+}
+
+/// A user's checkpointed balances and the Merkle path proving them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceProof {
+    pub checkpoint_id: u32,
+    /// Position of the user in the checkpointed batch
+    pub leaf_index: u32,
+    pub total_balance: i128,
+    pub flexi_balance: i128,
+    /// Sibling hashes from the leaf up to the root
+    pub siblings: Vec<BytesN<32>>,
+}
+
+/// A savings challenge users can enroll in, such as the 52-week challenge
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChallengeTemplate {
+    pub id: u64,
+    pub creator: Address,
+    pub name: Symbol,
+    pub period_seconds: u64,
+    /// Amount to deposit in each period, in order; one entry per period
+    pub amounts: Vec<i128>,
+    /// Paid from the yield pool on completion; only admin challenges carry one
+    pub bonus: i128,
+}
+
+/// Treasury-funded promotion paying a bonus on deposits made into plans of
+/// one kind while it runs
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Campaign {
+    pub id: u64,
+    pub plan_kind: PlanKind,
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Bonus earned on each qualifying deposit, in basis points of it
+    pub bonus_bps: u32,
+    /// Amount taken from the treasury to pay bonuses from
+    pub budget: i128,
+    /// Bonuses earned and not yet paid out or forfeited
+    pub accrued: i128,
+    /// Bonuses credited to users
+    pub paid: i128,
+    /// Sum of the deposits that earned a bonus
+    pub qualifying_deposits: i128,
+    /// Set once the unused budget went back to the treasury
+    pub closed: bool,
+}
+
+/// Plan holding a bonus from a campaign that has not been settled yet
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignHolder {
+    pub user: Address,
+    pub plan_kind: PlanKind,
+    pub plan_id: u64,
+}
+
+/// Bonus a user has earned from a campaign on one plan and not been paid yet
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignAccrual {
+    pub campaign_id: u64,
+    pub amount: i128,
+}
+
+/// What a withdrawal or break would pay out if made now, as returned by the
+/// `preview_*` views
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalPreview {
+    /// Amount leaving the plan, interest included
+    pub gross: i128,
+    /// Interest included in `gross`
+    pub interest: i128,
+    /// Early-break penalty deducted
+    pub penalty: i128,
+    /// Withdrawal fee deducted
+    pub fee: i128,
+    /// Amount the user would receive
+    pub net: i128,
+}
+
+/// Interest a plan has earned and is set to earn, as returned by
+/// `get_lock_statement` and `get_flexi_statement`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestStatement {
+    pub plan_kind: PlanKind,
+    /// ID of the plan; 0 for Flexi Save
+    pub plan_id: u64,
+    pub principal: i128,
+    /// Annual rate in basis points
+    pub interest_rate: i128,
+    /// Interest earned up to now
+    pub accrued_interest: i128,
+    /// Interest earned by maturity, or over the next year for Flexi Save
+    pub projected_interest: i128,
+    /// Withdrawal fee on the principal plus projected interest
+    pub projected_fee: i128,
+    /// Annual yield in basis points once the projected fee is deducted
+    pub effective_apy_bps: i128,
+}
+
+/// Result of checking one user's stored balances against each other
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvariantReport {
+    pub user: Address,
+    /// `User.total_balance` as stored
+    pub recorded_total: i128,
+    /// Flexi balance plus open Lock Save principal plus live legacy plan
+    /// balances, which `total_balance` should equal
+    pub computed_total: i128,
+    /// IDs of the user's plans flagged withdrawn that still hold a balance
+    pub withdrawn_with_balance: Vec<u64>,
+    /// IDs of the user's groups whose `current_amount` differs from the sum
+    /// of their members' contributions
+    pub group_mismatches: Vec<u64>,
+    /// Sum of the user's Flexi bucket balances, which may not exceed their
+    /// Flexi balance
+    pub bucketed_total: i128,
+    /// True if every check passed
+    pub ok: bool,
+}
+
+/// Spare change a list of purchases would round up to
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundupPreview {
+    /// Round-up of each purchase, in the order given
+    pub roundups: Vec<i128>,
+    pub total: i128,
+    /// The user's Flexi balance once the total is deposited
+    pub flexi_balance_after: i128,
+}
+
+/// An admin-curated starting point for a Goal Save
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalTemplate {
+    pub id: u64,
+    pub name: Symbol,
+    pub suggested_target: i128,
+    /// Seconds from creation to the goal's deadline (0 = no deadline)
+    pub suggested_duration: u64,
+    pub category: Symbol,
+}
+
+/// Values that replace a template's suggestions when creating a goal from it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalTemplateOverrides {
+    pub goal_name: Option<Symbol>,
+    pub target_amount: Option<i128>,
+    pub duration: Option<u64>,
+    pub initial_deposit: i128,
+}
+
+/// A user's progress through a challenge, counted from their enrollment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChallengeEnrollment {
+    pub challenge_id: u64,
+    pub started_at: u64,
+    /// Index of the period `period_deposited` belongs to
+    pub period: u32,
+    /// Deposits net of withdrawals in the current period
+    pub period_deposited: i128,
+    /// Past periods whose amount was reached
+    pub periods_met: u32,
+    /// Deposits net of withdrawals since enrolling
+    pub total_deposited: i128,
+    pub claimed: bool,
+}
+
+/// One row of a challenge leaderboard
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChallengeStanding {
+    pub user: Address,
+    pub periods_met: u32,
+    pub total_deposited: i128,
+}
+
+/// Kind of balance movement recorded in a user's transaction history
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TxKind {
+    Deposit,
+    /// Payout of a plan that ran its course
+    Withdraw,
+    /// Early exit from a plan, possibly with a penalty
+    Break,
+    /// Contribution returned from a failed or cancelled group
+    Refund,
+    /// Matured lock carried into a new lock, recorded against the new lock
+    Rollover,
+    /// Flexi Save balance moved into one of the user's own plans; the plan's
+    /// side is recorded as a deposit
+    Convert,
+    /// Plan handed over to another user
+    TransferOut,
+    /// Plan received from another user
+    TransferIn,
+    /// Reward, bonus or mint credited to the user's Flexi Save
+    Credit,
+}
+
+/// One entry of a user's on-chain transaction history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TxRecord {
+    /// Position in the user's history, starting at 1
+    pub seq: u64,
+    pub kind: TxKind,
+    pub plan_kind: PlanKind,
+    /// ID of the plan moved in or out of; 0 for Flexi Save
+    pub plan_id: u64,
+    /// Gross amount, before any fee
+    pub amount: i128,
+    /// Fee or penalty charged on the movement
+    pub fee: i128,
+    pub timestamp: u64,
+}
+
+/// Ranking kept by a protocol-wide leaderboard
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LeaderboardKind {
+    /// Ranks by cumulative deposits across all plan types
+    TotalSaved,
+    /// Ranks by longest savings streak ever reached
+    LongestStreak,
+}
+
+/// One row of a protocol-wide leaderboard
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardEntry {
+    pub user: Address,
+    /// Cumulative deposits or streak length, depending on the leaderboard
+    pub score: i128,
+}
+
+/// A group the user belongs to and how much they have put into it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupMembership {
+    pub group: GroupSave,
+    pub contribution: i128,
+}
+
+/// Everything stored for one user, gathered for backup and migration tools
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserStateExport {
+    pub user: User,
+    pub flexi_balance: i128,
+    pub lock_saves: Vec<LockSave>,
+    pub goal_saves: Vec<GoalSave>,
+    pub groups: Vec<GroupMembership>,
+    pub autosaves: Vec<AutoSave>,
+    pub streak: UserStreak,
+    pub badges: Vec<BadgeAward>,
+    pub referrer: Option<Address>,
+    /// Guardian of a custodial account, if the user has one
+    pub guardian: Option<Address>,
+    /// When a custodial account is released (0 if the user has no guardian)
+    pub release_time: u64,
+    /// Ledger timestamp the snapshot was taken at
+    pub exported_at: u64,
+}
+
+/// How an admin settled a group dispute
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisputeOutcome {
+    /// The flags were unfounded; the group continues as before
+    Dismissed,
+    /// The flags were justified; members may leave with a refund even after completion
+    Upheld,
+}
+
+/// Dispute state of a group
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupDispute {
+    /// Members who flagged the group since the last resolution
+    pub flaggers: Vec<Address>,
+    /// Reason code given by each flagger, in the same order
+    pub reason_codes: Vec<u32>,
+    /// Whether distributions are currently frozen
+    pub frozen: bool,
+    /// Set once a dispute has been upheld; members may then leave with a refund
+    pub refunds_enabled: bool,
+}
+
+/// Guardian controls over a custodial (minor's) account
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Custody {
+    pub guardian: Address,
+    /// Withdrawals before this timestamp need the guardian's signature
+    pub release_time: u64,
+}
+
+/// Running referral totals for a referrer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ReferralStats {
+    /// Number of users registered with this referrer
+    pub referral_count: u32,
+    /// Rewards accrued but not yet claimed
+    pub pending_rewards: i128,
+    /// Rewards paid out so far
+    pub total_claimed: i128,
+}
+
+/// Whether the yield pool can cover the interest promised to open plans
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldPoolStatus {
+    pub pool_balance: i128,
+    /// Interest open locks will have earned by maturity
+    pub outstanding_interest: i128,
+    /// Outstanding interest the pool can't currently cover
+    pub shortfall: i128,
+}
+
+/// Yield realized from the strategy in one harvest and how it was split
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StrategyHarvest {
+    /// Strategy balance above the amount supplied to it
+    pub realized: i128,
+    /// Portion moved into the yield pool for savers
+    pub user_share: i128,
+    /// Portion credited to the fee recipient
+    pub treasury_share: i128,
+}
+
+/// Snapshot of how flexi liabilities are split between the contract and the strategy
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidityStatus {
+    /// Sum of every user's Flexi Save balance
+    pub flexi_liabilities: i128,
+    /// Amount currently supplied to the yield strategy
+    pub invested: i128,
+    /// Portion of the liabilities not supplied to the strategy
+    pub liquid: i128,
+    /// Configured share of liabilities to keep liquid, in basis points
+    pub target_liquidity_bps: u32,
+    /// Current share of liabilities kept liquid, in basis points
+    pub current_liquidity_bps: u32,
+}
+
+/// Zero-value plan that mirrors a real plan's interest without moving funds.
+///
+/// Simulations never touch balances, liabilities, streaks or badges.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimulatedPlan {
+    pub id: u64,
+    pub plan_kind: PlanKind,
+    pub principal: i128,
+    /// Annual rate in basis points, taken from the live rate for the plan kind
+    pub rate: i128,
+    /// Start of the current accrual period
+    pub start_time: u64,
+    /// Timestamp interest stops accruing (u64::MAX for open-ended plans)
+    pub matures_at: u64,
+    /// Always true; lets frontends tell demo plans from real ones
+    pub is_simulation: bool,
+}
+
+/// Projected value of a simulated plan at a point in time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimulationProjection {
+    pub principal: i128,
+    pub interest: i128,
+    pub total: i128,
+}
+
+/// Current rate of one lock duration tier
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockRateEntry {
+    pub duration_days: u64,
+    pub rate: i128,
+}
+
+/// What remains of a withdrawn plan once its full record is archived
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArchivedPlanSummary {
+    pub plan_kind: PlanKind,
+    pub plan_id: u64,
+    /// Principal of a lock, or the amount saved towards a goal
+    pub amount: i128,
+    pub start_time: u64,
+    /// Maturity of a lock, or deadline of a goal (0 = no deadline)
+    pub end_time: u64,
+    pub archived_at: u64,
+}
+
+/// Fee charged on Flexi, Lock and Goal withdrawals: `bps` of the amount plus
+/// a `flat` amount, never more than the amount withdrawn. A FeeTier schedule,
+/// once set, takes the place of `bps`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WithdrawalFeeConfig {
+    pub bps: u32,
+    pub flat: i128,
+}
+
+/// Step of the tiered withdrawal fee: withdrawals of at least `min_amount`
+/// are charged `bps` instead of the flat config's `bps`, up to the next tier
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTier {
+    pub min_amount: i128,
+    pub bps: u32,
+}
+
+/// Most tiers a withdrawal fee schedule may have
+pub const MAX_FEE_TIERS: u32 = 10;
+
+/// Tip paid from the treasury to whoever runs keeper batches: `flat` per
+/// execution on another user's plan, with at most `max_per_ledger` paid out
+/// across all batches in one ledger
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct KeeperTipConfig {
+    pub flat: i128,
+    pub max_per_ledger: i128,
+}
+
+/// Who a regulated deployment accepts large deposits from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ComplianceMode {
+    /// Every user may deposit any amount
+    Off,
+    /// Users must be on the admin-managed allowlist
+    Allowlist,
+    /// Users must be on the allowlist or attested by this contract
+    Attestation(Address),
+}
+
+/// Compliance gate applied to every deposit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComplianceConfig {
+    pub mode: ComplianceMode,
+    /// Deposits up to this amount are accepted from anyone
+    pub threshold: i128,
+}
+
+/// A user or plan kind whose withdrawals are not charged the withdrawal fee
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeeExemption {
+    /// Every withdrawal by this address
+    User(Address),
+    /// Every withdrawal out of a plan of this kind
+    Kind(PlanKind),
+}
+
+/// How a withdrawal was split between the user and the treasury
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeBreakdown {
+    pub gross: i128,
+    pub fee: i128,
+    pub net: i128,
+}
+
+/// Per-user caps on open plans of each kind; 0 means no cap
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlanLimits {
+    /// Most un-withdrawn Lock Saves a user may hold
+    pub max_lock_saves: u32,
+    /// Most un-withdrawn Goal Saves a user may hold
+    pub max_goal_saves: u32,
+    /// Most groups a user may create or join
+    pub max_group_saves: u32,
+    /// Most active AutoSave schedules a user may hold
+    pub max_autosaves: u32,
+}
+
+/// Snapshot of every admin-tunable protocol setting
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolParams {
+    pub paused: bool,
+    pub legacy_entrypoints_enabled: bool,
+    /// Delay applied to critical admin actions, in seconds
+    pub admin_timelock: u64,
+    pub early_break_fee_bps: u32,
+    /// Seconds after creation during which breaking a goal is free
+    pub goal_break_grace: u64,
+    /// Whether goals that miss their deadline pay the early-break fee
+    pub failed_goal_penalty: bool,
+    pub fee_recipient: Option<Address>,
+    pub referral_reward_bps: u32,
+    /// Per-user withdrawal cap per window (0 = no cap)
+    pub withdraw_limit: i128,
+    pub flexi_rate: i128,
+    pub goal_rate: i128,
+    pub group_rate: i128,
+    /// Every lock duration tier that has been configured, in the order first set
+    pub lock_rates: Vec<LockRateEntry>,
+    /// Rate new Lock Saves are created at now
+    pub lock_rate_bps: u32,
+    /// Past and pending Lock Save rate changes, in effect order
+    pub lock_rate_schedule: Vec<ScheduledRate>,
+    pub token: Option<Address>,
+    pub strategy: Option<Address>,
+    pub target_liquidity_bps: u32,
+    /// Share of realized strategy yield, in basis points, paid to the treasury
+    pub strategy_yield_treasury_bps: u32,
+    pub group_duration_bounds: GroupDurationBounds,
+    pub dispute_threshold: u32,
+    /// Share of members, in basis points, that must approve a group proposal
+    pub group_quorum_bps: u32,
+    pub streak_period: StreakPeriod,
+    pub plan_limits: PlanLimits,
+    /// Smallest amount a single deposit may add (0 = no minimum), in
+    /// reference units
+    pub minimum_deposit: i128,
+    /// Plan balances below this count as dust (0 = none do)
+    pub dust_threshold: i128,
+    pub withdrawal_fee: WithdrawalFeeConfig,
+    /// Tiered withdrawal fee, lowest tier first; empty when `withdrawal_fee.bps` applies
+    pub fee_tiers: Vec<FeeTier>,
+    pub oracle: Option<Address>,
+    pub keeper_tip: KeeperTipConfig,
+    /// Whether users may exit through `emergency_user_withdraw`
+    pub withdrawal_only_mode: bool,
+    pub compliance: ComplianceConfig,
+    /// Signer set critical admin actions need (no signers = not configured)
+    pub multisig: MultisigConfig,
+}
+
+/// Critical admin change that can be delayed by the timelock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Raise (or change) the early-break fee, in basis points
+    SetEarlyBreakFeeBps(u32),
+    /// Redirect protocol fees to a new treasury address
+    SetFeeRecipient(Address),
+    /// Point idle funds at a different yield strategy
+    SetStrategy(Address),
+    /// Change the token fees, tips and emergency withdrawals are paid in
+    SetToken(Address),
+    /// Replace the contract code
+    Upgrade(BytesN<32>),
+    /// Change the timelock delay itself
+    SetTimelock(u64),
+    /// Raise (or change) the fee charged on withdrawals
+    SetWithdrawalFee(WithdrawalFeeConfig),
+    /// Replace the tiered withdrawal fee schedule
+    SetFeeTiers(Vec<FeeTier>),
+    /// Replace the tip paid to callers of keeper batches
+    SetKeeperTip(KeeperTipConfig),
+    /// Turn the penalty on failed goals on or off
+    SetFailedGoalPenalty(bool),
+    /// Grant (true) or revoke (false) a withdrawal fee exemption
+    SetFeeExemption(FeeExemption, bool),
+    /// Change the treasury's share of strategy yield, in basis points
+    SetStrategyYieldSplit(u32),
+    /// Change the fee-free window after a goal's creation, in seconds
+    SetGoalBreakGrace(u64),
+}
+
+/// Admin action announced on-chain and waiting for its execution time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdminAction {
+    pub action: AdminAction,
+    /// Earliest ledger timestamp the action may be executed at
+    pub eta: u64,
+}
+
+/// Token registered with the contract and the decimals its amounts use
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetConfig {
+    pub token: Address,
+    pub decimals: u32,
+}
+
+/// Signer set that approves critical admin actions, m-of-n
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultisigConfig {
+    pub signers: Vec<Address>,
+    /// Approvals needed for upgrades, fee changes and emergency withdrawals
+    pub threshold: u32,
+    /// Approvals needed to replace the signer set itself
+    pub signer_threshold: u32,
+}
+
+/// Operation a multisig proposal performs once enough signers approve
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MultisigAction {
+    /// A critical admin action; still queued if a timelock is configured
+    Admin(AdminAction),
+    /// Moves protocol-owned funds to a recovery address (amount)
+    EmergencyWithdraw(Address, i128),
+    /// Replaces the signer set and its thresholds
+    SetSigners(MultisigConfig),
+    /// Pays accrued protocol fees out to an address (amount, to)
+    WithdrawProtocolFees(i128, Address),
+    /// Opens a bonus campaign (plan kind, start, end, bonus bps, budget)
+    CreateCampaign(PlanKind, u64, u64, u32, i128),
+    /// Drops an admin action waiting in the timelock queue
+    CancelAdminAction(u64),
+}
+
+/// Multisig proposal collecting signer approvals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultisigProposal {
+    pub id: u64,
+    pub action: MultisigAction,
+    pub proposer: Address,
+    /// Signers who have approved, the proposer first
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+    /// Approvals are accepted until this timestamp
+    pub expires_at: u64,
+}
+
+/// Group parameter a proposal would change
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GroupChange {
+    TargetAmount(i128),
+    EndTime(u64),
+    ContributionAmount(i128),
+}
+
+/// Creator-proposed change to a group, decided by member vote
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupProposal {
+    pub id: u64,
+    pub group_id: u64,
+    pub change: GroupChange,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    /// Members who have voted, for and against
+    pub voters: Vec<Address>,
+    /// Votes are accepted until this timestamp
+    pub deadline: u64,
+    pub executed: bool,
+}
+
+/// Message posted on-chain by a group's creator
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupAnnouncement {
+    pub message: String,
+    pub timestamp: u64,
+}
+
+/// Payment status of a group's current contribution round
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupRoundStatus {
+    /// Zero-based index of the round
+    pub round: u32,
+    pub total_rounds: u32,
+    pub round_start: u64,
+    /// Timestamp the round's payments are due by
+    pub due_time: u64,
+    /// Amount each member owes for the round
+    pub required_amount: i128,
+    pub paid: Vec<Address>,
+    pub unpaid: Vec<Address>,
+}
+
+/// A group contribution a member still owes in the open round
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DueGroupContribution {
+    pub group_id: u64,
+    /// Zero-based index of the open round
+    pub round: u32,
+    /// Timestamp the round's payments are due by
+    pub due_time: u64,
+    /// Part of the round's required amount not yet paid
+    pub amount_owed: i128,
+}
+
+/// Limits on the window a group may be created with
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupDurationBounds {
+    /// Shortest allowed `end_time - start_time`, in seconds
+    pub min_duration: u64,
+    /// Furthest `end_time` may lie after the creation time, in seconds
+    pub max_horizon: u64,
+}
+
+/// Withdrawals a user has made in their current limit window
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawWindow {
+    /// Timestamp the window opened; it closes `WITHDRAW_WINDOW_SECONDS` later
+    pub start: u64,
+    /// Total withdrawn since `start`
+    pub withdrawn: i128,
+}
+
+/// One entry of the on-chain rate history kept per plan kind
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateChange {
+    /// Lock duration tier the rate applies to; 0 for other plan kinds
+    pub duration_days: u64,
+    /// Rate in force before the change, in basis points
+    pub old_rate: i128,
+    /// Rate in force from `effective_from`, in basis points
+    pub new_rate: i128,
+    /// Ledger timestamp the new rate took effect
+    pub effective_from: u64,
+}
+
+/// A Lock Save rate taking effect at a given time; new locks are fixed at
+/// the rate in effect when they are created
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledRate {
+    pub rate_bps: u32,
+    pub effective_at: u64,
+}
+
+/// Payload of the `interest` event emitted whenever interest is credited
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestAccrual {
+    /// ID of the plan the interest was credited to
+    pub plan_id: u64,
+    /// Principal the interest was computed on
+    pub principal: i128,
+    /// Annual rate applied, in basis points
+    pub rate_bps: i128,
+    /// Length of the accrual period in seconds
+    pub period_seconds: u64,
+    /// Interest credited for the period
+    pub amount: i128,
 }
 
 /// Represents an automated recurring deposit schedule for Flexi Save
@@ -113,7 +1065,135 @@ pub struct AutoSave {
     pub amount: i128,
     pub interval_seconds: u64,
     pub next_execution_time: u64,
+    /// False once the schedule is cancelled; cancelled schedules never run again
     pub is_active: bool,
+    /// Set while the owner has paused the schedule; it can be resumed
+    pub is_paused: bool,
+    /// Where the funds of each execution come from
+    pub source: AutoSaveSource,
+    /// How executions missed by a late run are handled
+    pub catch_up: AutoSaveCatchUp,
+    /// How each execution is allocated; empty means the whole amount goes to
+    /// Flexi
+    pub split: Vec<AutoSaveLeg>,
+    /// Amount each leg received on the most recent execution
+    pub last_results: Vec<AutoSaveLegResult>,
+}
+
+/// Where an AutoSave schedule draws its funds from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AutoSaveSource {
+    /// A fresh deposit on every execution
+    ExternalToken,
+    /// The user's Flexi Save balance
+    FlexiBalance,
+}
+
+/// What a late AutoSave run does about the executions it missed.
+///
+/// Either way the next execution is realigned to the schedule's interval grid,
+/// so a late run never shifts later executions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AutoSaveCatchUp {
+    /// Deposit a single interval's amount and drop the missed ones
+    SkipMissed,
+    /// Deposit the amount of every missed interval, up to the given number
+    ExecuteAll(u32),
+}
+
+/// Plan an AutoSave leg deposits into
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AutoSaveTarget {
+    Flexi,
+    Goal(u64),
+    Group(u64),
+    Lock(u64),
+}
+
+/// Share of each AutoSave execution sent to one target
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoSaveLeg {
+    pub target: AutoSaveTarget,
+    /// Percent of the schedule amount; the legs of a schedule sum to 100
+    pub percent: u32,
+}
+
+/// One deposit of a `batch_deposit` call
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositOp {
+    pub target: AutoSaveTarget,
+    pub amount: i128,
+}
+
+/// Amount deposited into one leg by an AutoSave execution
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoSaveLegResult {
+    pub target: AutoSaveTarget,
+    pub amount: i128,
+}
+
+/// Autosave terms a user signs off-chain so a partner app can set up the
+/// schedule on their behalf
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoSaveConsent {
+    pub user: Address,
+    pub amount: i128,
+    pub interval_seconds: u64,
+    pub start_time: u64,
+    /// Ledger timestamp after which the consent can no longer be submitted
+    pub expiry: u64,
+    /// Must match the user's next consent nonce; prevents replays
+    pub nonce: u64,
+}
+
+/// Named share of a user's Flexi balance; purely organizational
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlexiBucket {
+    pub name: Symbol,
+    pub balance: i128,
+}
+
+/// Amount a spender may still deposit into a user's Flexi Save
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositAllowance {
+    pub remaining: i128,
+    /// Ledger timestamp after which the allowance can no longer be used
+    pub expiry: u64,
+}
+
+/// A user's registered consent signing key and replay counter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserConsent {
+    pub public_key: BytesN<32>,
+    pub next_nonce: u64,
+}
+
+/// Scoped permissions an admin can delegate to other addresses.
+///
+/// The contract admin implicitly holds every role.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// May pause and unpause the contract
+    Pauser,
+    /// May change fee rates and the fee recipient
+    FeeManager,
+    /// May configure the deposit token, yield strategy and liquidity target
+    Treasurer,
+    /// May upgrade the contract code
+    Upgrader,
+    /// May change interest rates
+    RateManager,
 }
 
 /// Storage keys for the contract's persistent data
@@ -127,9 +1207,9 @@ pub enum DataKey {
     Paused,
     /// Minimum allowed deposit amount
     MinimumDeposit,
-    /// Fee applied on withdrawals
+    /// WithdrawalFeeConfig applied on withdrawals
     WithdrawalFee,
-    /// Protocol fee configuration
+    /// FeeTier schedule replacing the WithdrawalFeeConfig `bps`, by amount
     PlatformFee,
     /// Early break fee (basis points) for goal saves
     EarlyBreakFeeBps,
@@ -150,13 +1230,15 @@ pub enum DataKey {
     LockSave(u64),
     /// Maps user to a list of their LockSave IDs
     UserLockSaves(Address),
-    /// Stores the next auto-incrementing LockSave ID
+    /// Next LockSave ID under the retired global counter; removed by the
+    /// v2 storage migration
     NextLockId,
     /// Maps goal plan ID to GoalSave struct
     GoalSave(u64),
     /// Maps user to a list of their GoalSave IDs
     UserGoalSaves(Address),
-    /// Stores the next auto-incrementing GoalSave ID
+    /// Next GoalSave ID under the retired global counter; removed by the
+    /// v2 storage migration
     NextGoalId,
     /// Maps (group_id, user) to their contribution amount
     GroupMemberContribution(u64, Address),
@@ -174,6 +1256,409 @@ pub enum DataKey {
     GroupRate,
     /// Maps duration (days) to interest rate
     LockRate(u64),
+    /// Maps user to the ledger timestamp their account was initialized at
+    UserCreatedAt(Address),
+    /// Maps user to their admin-assigned reputation score
+    UserReputation(Address),
+    /// Maps group ID to its GroupJoinRequirements
+    GroupJoinRequirements(u64),
+    /// Stores the number of records in a chunked list (its head pointer)
+    ChunkedListHead(ChunkedListId),
+    /// Maps (list, chunk index) to a fixed-size chunk of records
+    ChunkedListChunk(ChunkedListId, u32),
+    /// Maps goal ID to the list of GoalMilestones it has reached
+    GoalMilestones(u64),
+    /// Maps user to their UserStreak record
+    UserStreak(Address),
+    /// Maps user to the list of BadgeAwards they have earned
+    UserBadges(Address),
+    /// Maps user to the cumulative amount they have deposited
+    UserTotalDeposited(Address),
+    /// Maps user to the address that referred them
+    Referrer(Address),
+    /// Maps referrer to their ReferralStats
+    ReferralStats(Address),
+    /// Maps (plan kind, plan ID) to an attached content hash
+    AttachmentHash(PlanKind, u64),
+    /// Per-group dispute and round tracking, see GroupKey
+    Group(GroupKey),
+    /// Maps user to their UserConsent signing key and nonce
+    UserConsent(Address),
+    /// Marks that an address holds a delegated admin role
+    Role(Role, Address),
+    /// Global protocol settings and counters, see ConfigKey
+    Config(ConfigKey),
+    /// Append-only log of default rate changes for a plan kind
+    RateHistory(PlanKind),
+    /// Per-user counters added after the original user records, see UserKey
+    UserState(UserKey),
+    /// Admin action waiting out the timelock
+    PendingAdminAction(u64),
+}
+
+/// Keys for per-user counters added after the original user records.
+///
+/// Nested under `DataKey::UserState` for the same reason as `ConfigKey`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UserKey {
+    /// Amount a user has withdrawn in their current limit window
+    WithdrawWindow(Address),
+    /// Cumulative protocol fees a user has paid
+    LifetimeFees(Address),
+    /// Maps user to their zero-value SimulatedPlans
+    Simulations(Address),
+    /// Timestamp of the last mint payload a user redeemed
+    LastMintTimestamp(Address),
+    /// Next nonce a user's admin-signed sponsored payload must carry
+    SponsoredNonce(Address),
+    /// Maps goal ID to its target in the oracle's reference unit, for goals
+    /// set in fiat
+    GoalFiatTarget(u64),
+    /// Maps goal ID to the users sharing it with its owner
+    GoalCoOwners(u64),
+    /// Maps goal ID to the GoalWithdrawPolicy of a co-owned goal
+    GoalWithdrawPolicy(u64),
+    /// Maps goal ID to the GoalContributions of a public goal, in the order
+    /// contributors first gave
+    GoalContributors(u64),
+    /// Maps a custodial account to its Custody
+    Custody(Address),
+    /// Maps a guardian to the custodial accounts they control
+    GuardianWards(Address),
+    /// Set for users an admin has added to the compliance allowlist
+    Allowlisted(Address),
+    /// Maps (user, challenge ID) to the user's ChallengeEnrollment
+    ChallengeEnrollment(Address, u64),
+    /// Maps user to the challenges they are enrolled in and have not claimed
+    Challenges(Address),
+    /// Maps user to the challenges they have claimed, oldest first
+    ClaimedChallenges(Address),
+    /// Set for users who asked to be left off the leaderboards
+    LeaderboardOptOut(Address),
+    /// Maps user to their most recent TxRecords, oldest first
+    TxHistory(Address),
+    /// Set in temporary storage for each idempotency key a user consumed
+    IdempotencyKey(Address, BytesN<32>),
+    /// Next sequence number hashed into the ID of a user's plan of a kind
+    PlanSeq(Address, PlanKind),
+    /// Set to the closing time for users who closed their account
+    Closed(Address),
+    /// Maps (user, plan kind, plan ID) to the CampaignAccruals earned on it
+    CampaignBonus(Address, PlanKind, u64),
+    /// Maps (user, spender) to the DepositAllowance the user granted
+    DepositAllowance(Address, Address),
+    /// Maps user to the spenders holding an allowance from them
+    AllowanceSpenders(Address),
+    /// Maps user to the latest balance checkpoint covering them, until a
+    /// restore consumes it
+    LatestCheckpoint(Address),
+    /// Maps user to their FlexiBuckets, in creation order
+    FlexiBuckets(Address),
+}
+
+/// Temporary-storage key remembering the ledger a persistent entry's TTL
+/// was last extended at
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlRecordKey(pub DataKey);
+
+/// Keys for per-group state added after the original group records.
+///
+/// Nested under `DataKey::Group` for the same reason as `ConfigKey`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GroupKey {
+    /// Maps group ID to its GroupDispute
+    Dispute(u64),
+    /// Maps (group ID, member) to the timestamp the member joined
+    MemberJoinedAt(u64, Address),
+    /// Maps (group ID, round, member) to the amount paid towards that round
+    RoundPaid(u64, u32, Address),
+    /// Number of rounds of a group already closed by the keeper
+    ClosedRounds(u64),
+    /// Maps group ID to its most recent GroupAnnouncements, oldest first
+    Announcements(u64),
+    /// Maps proposal ID to a GroupProposal
+    Proposal(u64),
+    /// Maps group ID to the IDs of its proposals, oldest first
+    Proposals(u64),
+    /// Set once refunds of a failed or cancelled group have started
+    Failed(u64),
+    /// Maps group ID to the co-admins appointed by its creator
+    Admins(u64),
+    /// Set for a user invited to join a group, until they join
+    Invited(u64, Address),
+    /// Maps group ID to the GroupInterest it has earned
+    Interest(u64),
+    /// Maps a public group's ID to every category bucket it was indexed in
+    IndexedCategories(u64),
+}
+
+/// Keys for global protocol settings and counters.
+///
+/// Nested under `DataKey::Config` so `DataKey` stays within the variant limit
+/// of a contract type.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigKey {
+    /// Period length used for savings streaks
+    StreakPeriod,
+    /// Address of the token contract backing deposits
+    Token,
+    /// Address of the external yield strategy idle funds are swept into
+    Strategy,
+    /// Share of flexi liabilities, in basis points, kept liquid in the contract
+    TargetLiquidityBps,
+    /// Sum of every user's Flexi Save balance
+    FlexiLiabilities,
+    /// Amount currently supplied to the yield strategy
+    StrategyInvested,
+    /// Share of a referee's deposits, in basis points, accrued to their referrer
+    ReferralRewardBps,
+    /// Set once an admin switches off deprecated entrypoints
+    LegacyEntrypointsDisabled,
+    /// Number of distinct member flags that freezes a group
+    DisputeThreshold,
+    /// Most a single user may withdraw per window; absent or 0 means no cap
+    WithdrawLimit,
+    /// Allowed length and horizon of a group's start/end window
+    GroupDurationBounds,
+    /// Delay, in seconds, between queueing and executing a critical admin action
+    AdminTimelock,
+    /// Next ID handed out to a queued admin action
+    NextAdminActionId,
+    /// Share of members, in basis points, that must approve a group proposal
+    GroupQuorumBps,
+    /// Next ID handed out to a group proposal
+    NextGroupProposalId,
+    /// Per-user caps on open plans
+    PlanLimits,
+    /// Fees held for every fee recipient, net of claims and withdrawals
+    ProtocolFeeTotal,
+    /// Balance of the pool interest payouts are drawn from
+    YieldPool,
+    /// Interest open locks will have earned by maturity and not yet paid out
+    OutstandingInterest,
+    /// Share of realized strategy yield, in basis points, kept by the treasury
+    StrategyYieldTreasuryBps,
+    /// Address of the price oracle fiat-denominated goals are valued with
+    Oracle,
+    /// Whether goals finalized short of their target forfeit the early-break fee
+    FailedGoalPenalty,
+    /// KeeperTipConfig paid to callers of keeper batches
+    KeeperTip,
+    /// Sorted numbers of the non-empty buckets of the due-autosave index
+    AutoSaveDueBuckets,
+    /// Maps an index bucket to the IDs of the runnable schedules due in it
+    AutoSaveDueBucket(u64),
+    /// Set while users may exit through `emergency_user_withdraw`
+    WithdrawalOnlyMode,
+    /// ComplianceConfig gating deposits in regulated deployments
+    Compliance,
+    /// Storage layout version the contract's data has been migrated to
+    StorageVersion,
+    /// Progress of the migration to the next storage version
+    MigrationCursor,
+    /// Most recent BalanceCheckpoints, oldest first
+    BalanceCheckpoints,
+    /// Next ID handed out to a BalanceCheckpoint
+    NextCheckpointId,
+    /// Seconds after creation during which breaking a goal is free
+    GoalBreakGrace,
+    /// Maps challenge ID to its ChallengeTemplate
+    Challenge(u64),
+    /// Next ID handed out to a ChallengeTemplate
+    NextChallengeId,
+    /// Maps challenge ID to every user who enrolled, in enrollment order
+    ChallengeParticipants(u64),
+    /// Maps a LeaderboardKind to its entries, best first
+    Leaderboard(LeaderboardKind),
+    /// Every ScheduledRate for new Lock Saves, ordered by `effective_at`
+    LockRateSchedule,
+    /// Catalog of GoalTemplates, in creation order
+    GoalTemplates,
+    /// Next ID handed out to a GoalTemplate
+    NextGoalTemplateId,
+    /// Set in instance storage while an external call is in progress
+    ReentrancyLock,
+    /// Sequence number the next emitted event carries
+    EventSeq,
+    /// Every FeeExemption in force, in the order they were granted
+    FeeExemptions,
+    /// Set while a FeeExemption is in force
+    FeeExempt(FeeExemption),
+    /// Maps campaign ID to its Campaign
+    Campaign(u64),
+    /// Next ID handed out to a Campaign
+    NextCampaignId,
+    /// IDs of every Campaign, in creation order
+    Campaigns,
+    /// IDs of the campaigns that have not ended, in creation order
+    LiveCampaigns,
+    /// Protocol settings keyed by ParamKey
+    Param(ParamKey),
+    /// Signer set and proposals keyed by MultisigKey
+    Multisig(MultisigKey),
+}
+
+/// Keys of the multisig signer set and its proposals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MultisigKey {
+    /// The MultisigConfig approving critical admin actions, once set
+    Config,
+    /// Maps proposal ID to a MultisigProposal awaiting approvals
+    Proposal(u64),
+    /// Next ID handed out to a MultisigProposal
+    NextProposalId,
+}
+
+/// Keys for protocol settings added after `ConfigKey` filled up.
+///
+/// Nested under `ConfigKey::Param` for the same reason as `ConfigKey`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParamKey {
+    /// Plan balances below this are swept into Flexi by `consolidate_dust`
+    DustThreshold,
+    /// Maps token to the AssetConfig recorded when it was registered
+    AssetConfig(Address),
+    /// Ledger sequence and total keeper tips paid in that ledger
+    KeeperTipsPaid,
+    /// Maps campaign ID to the CampaignHolders of its unsettled bonuses
+    CampaignHolders(u64),
+}
+
+/// Maximum number of items returned by a single page of any paginated view
+pub const MAX_PAGE_LIMIT: u32 = 50;
+
+/// Opaque paging position shared by every paginated view
+///
+/// Views return the cursor for the next page alongside the items, or `None`
+/// once the last page has been read.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cursor {
+    /// Index of the first item of the page
+    pub offset: u32,
+    /// Maximum number of items in the page (0 = default, capped at MAX_PAGE_LIMIT)
+    pub limit: u32,
+}
+
+/// Maximum number of records stored under a single chunk key
+pub const CHUNK_SIZE: u32 = 100;
+
+/// Identifies an append-only history list stored in fixed-size chunks
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChunkedListId {
+    /// Contribution history of a group
+    GroupContributions(u64),
+    /// IDs of every public group, in creation order
+    PublicGroups,
+    /// IDs of the public groups in a category, in creation order
+    PublicGroupsByCategory(String),
+    /// Summaries of a user's archived plans, in archival order
+    ArchivedPlans(Address),
+}
+
+/// Append-only list split across `CHUNK_SIZE`-record storage entries.
+///
+/// Large per-user or per-group histories stored as a single growing `Vec`
+/// eventually exceed ledger entry limits and make every append rewrite the
+/// whole history. A chunked list only ever rewrites the last chunk, and the
+/// head entry records the total number of records.
+pub struct ChunkedList {
+    id: ChunkedListId,
+}
+
+impl ChunkedList {
+    pub fn new(id: ChunkedListId) -> Self {
+        Self { id }
+    }
+
+    /// Returns the number of records in the list
+    pub fn len(&self, env: &Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ChunkedListHead(self.id.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Appends a record, starting a new chunk when the last one is full
+    pub fn push<T>(&self, env: &Env, item: T)
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+    {
+        let len = self.len(env);
+        let chunk_key = DataKey::ChunkedListChunk(self.id.clone(), len / CHUNK_SIZE);
+        let mut chunk: Vec<T> = env
+            .storage()
+            .persistent()
+            .get(&chunk_key)
+            .unwrap_or(Vec::new(env));
+        chunk.push_back(item);
+        env.storage().persistent().set(&chunk_key, &chunk);
+        env.storage()
+            .persistent()
+            .set(&DataKey::ChunkedListHead(self.id.clone()), &(len + 1));
+    }
+
+    /// Returns up to `limit` records starting at `offset`, oldest first
+    pub fn range<T>(&self, env: &Env, offset: u32, limit: u32) -> Vec<T>
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+    {
+        let mut records = Vec::new(env);
+        let end = self.len(env).min(offset.saturating_add(limit));
+        let mut index = offset;
+        while index < end {
+            let chunk_index = index / CHUNK_SIZE;
+            let chunk: Vec<T> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::ChunkedListChunk(self.id.clone(), chunk_index))
+                .unwrap_or(Vec::new(env));
+            let chunk_end = end.min((chunk_index + 1) * CHUNK_SIZE);
+            while index < chunk_end {
+                if let Some(record) = chunk.get(index % CHUNK_SIZE) {
+                    records.push_back(record);
+                }
+                index += 1;
+            }
+        }
+        records
+    }
+}
+
+/// A single contribution recorded in a group's contribution history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupContribution {
+    pub contributor: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Domain-separated wrapper around every off-chain signed payload.
+///
+/// Signers sign the XDR encoding of this envelope rather than the bare
+/// payload, which binds a signature to one contract deployment, one network
+/// and one entrypoint so it can't be replayed anywhere else.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SignedEnvelope {
+    /// Address of the contract the payload is meant for
+    pub contract: Address,
+    /// Network passphrase hash of the network the payload is meant for
+    pub network_id: BytesN<32>,
+    /// Tag of the entrypoint that may consume the payload
+    pub function: Symbol,
+    /// Replay counter the entrypoint checks (a timestamp for `mint`)
+    pub nonce: u64,
+    /// XDR encoding of the payload itself
+    pub payload: Bytes,
 }
 
 /// Payload structure that the admin signs off-chain
@@ -191,7 +1676,69 @@ pub struct MintPayload {
     pub expiry_duration: u64,
 }
 
+/// Admin-signed Flexi deposit the backend submits on a user's behalf
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DepositPayload {
+    pub user: Address,
+    pub amount: i128,
+    /// Unix timestamp when the signature was created
+    pub timestamp: u64,
+    /// Signature is valid until timestamp + expiry_duration
+    pub expiry_duration: u64,
+    /// Must match the user's next sponsored nonce; prevents replays
+    pub nonce: u64,
+}
+
+/// Admin-signed Goal Save creation the backend submits on a user's behalf
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct GoalSavePayload {
+    pub user: Address,
+    pub goal_name: Symbol,
+    pub target_amount: i128,
+    pub initial_deposit: i128,
+    /// Unix timestamp when the signature was created
+    pub timestamp: u64,
+    /// Signature is valid until timestamp + expiry_duration
+    pub expiry_duration: u64,
+    /// Must match the user's next sponsored nonce; prevents replays
+    pub nonce: u64,
+}
+
 // View-specific structures (used by views.rs module)
+
+/// Balances of one user, as returned by `get_balances_bulk`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct UserBalances {
+    pub user: Address,
+    /// False if the address was never initialized; balances are then 0
+    pub exists: bool,
+    pub flexi_balance: i128,
+    pub total_balance: i128,
+}
+
+/// Identifies any plan by its kind and ID. The ID of a Flexi Save is
+/// ignored, since each user has exactly one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PlanRef {
+    pub kind: PlanKind,
+    pub id: u64,
+}
+
+/// A plan of any kind, as returned by `get_plan`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PlanDetail {
+    /// The user's Flexi balance
+    Flexi(i128),
+    Lock(LockSave),
+    Goal(GoalSave),
+    Group(GroupSave),
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct LockSaveView {
@@ -203,6 +1750,20 @@ pub struct LockSaveView {
     pub is_withdrawn: bool,
 }
 
+/// How far a target-based Lock Save is from unlocking
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TargetLockProgress {
+    pub lock_id: u64,
+    pub balance: i128,
+    pub target_amount: i128,
+    /// Amount still to deposit before the target is reached
+    pub remaining: i128,
+    /// Time the lock unlocks even if the target is never reached
+    pub long_stop_time: u64,
+    pub unlocked: bool,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct GoalSaveView {
@@ -212,7 +1773,6 @@ pub struct GoalSaveView {
     pub start_time: u64,
     pub interest_rate: u32,
     pub is_completed: bool,
-    pub contribution_type: u32,
     pub goal_name: Symbol,
 }
 
@@ -220,12 +1780,18 @@ pub struct GoalSaveView {
 #[contracttype]
 pub struct GroupSaveView {
     pub plan_id: u64,
+    /// The member's own contributions to the group
     pub balance: i128,
     pub target_amount: i128,
     pub start_time: u64,
-    pub interest_rate: u32,
     pub is_completed: bool,
     pub is_public: bool,
     pub contribution_type: u32,
     pub group_id: u64,
+    /// Places left before the group is full (u32::MAX if it has no cap)
+    pub remaining_slots: u32,
+    pub status: GroupStatus,
+    /// The member's pro-rata share of interest the group earned and has not
+    /// paid out yet
+    pub accrued_interest: i128,
 }
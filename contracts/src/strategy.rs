@@ -0,0 +1,548 @@
+use soroban_sdk::{symbol_short, token, vec, Address, Env, IntoVal};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::fees;
+use crate::guard;
+use crate::reentrancy;
+use crate::storage_types::{ConfigKey, DataKey, LiquidityStatus, StrategyHarvest};
+use crate::yield_pool;
+
+/// Basis-point denominator for liquidity ratios
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Liquidity target used until an admin configures one: keep everything liquid
+pub const DEFAULT_TARGET_LIQUIDITY_BPS: u32 = 10_000;
+
+/// Calls `supply(from, amount)` on the external yield strategy. The tokens
+/// must already have been transferred to it.
+fn strategy_supply(env: &Env, strategy: &Address, from: &Address, amount: i128) {
+    env.invoke_contract::<()>(
+        strategy,
+        &symbol_short!("supply"),
+        vec![env, from.into_val(env), amount.into_val(env)],
+    )
+}
+
+/// Calls `withdraw(to, amount)` on the external yield strategy, which must
+/// transfer `amount` back to `to` before returning
+fn strategy_withdraw(env: &Env, strategy: &Address, to: &Address, amount: i128) {
+    env.invoke_contract::<()>(
+        strategy,
+        &symbol_short!("withdraw"),
+        vec![env, to.into_val(env), amount.into_val(env)],
+    )
+}
+
+/// Calls `balance(owner) -> i128` on the external yield strategy
+fn strategy_balance(env: &Env, strategy: &Address, owner: &Address) -> i128 {
+    env.invoke_contract(
+        strategy,
+        &symbol_short!("balance"),
+        vec![env, owner.into_val(env)],
+    )
+}
+
+/// Returns the configured deposit token
+pub fn get_token(env: &Env) -> Result<Address, SavingsError> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::Token))
+        .ok_or(SavingsError::MissingParameter)
+}
+
+/// Returns the configured yield strategy
+pub fn get_strategy(env: &Env) -> Result<Address, SavingsError> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::Strategy))
+        .ok_or(SavingsError::MissingParameter)
+}
+
+pub fn get_target_liquidity_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::TargetLiquidityBps))
+        .unwrap_or(DEFAULT_TARGET_LIQUIDITY_BPS)
+}
+
+pub fn set_target_liquidity_bps(env: &Env, bps: u32) -> Result<(), SavingsError> {
+    if bps as i128 > BPS_DENOMINATOR {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::TargetLiquidityBps), &bps);
+    Ok(())
+}
+
+pub fn get_flexi_liabilities(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::FlexiLiabilities))
+        .unwrap_or(0)
+}
+
+/// Adjusts the running total of Flexi Save balances by `delta`
+pub fn adjust_flexi_liabilities(env: &Env, delta: i128) -> Result<(), SavingsError> {
+    let total = get_flexi_liabilities(env)
+        .checked_add(delta)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::FlexiLiabilities), &total);
+    Ok(())
+}
+
+pub fn get_invested(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::StrategyInvested))
+        .unwrap_or(0)
+}
+
+fn set_invested(env: &Env, invested: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::StrategyInvested), &invested);
+}
+
+/// Sends `amount` of the token to the strategy and has it supply the funds.
+/// Callers update `invested` first.
+fn supply(
+    env: &Env,
+    token: &Address,
+    strategy_address: &Address,
+    amount: i128,
+) -> Result<(), SavingsError> {
+    let this = env.current_contract_address();
+    reentrancy::transfer(env, token, &this, strategy_address, amount)?;
+    reentrancy::external(env, || {
+        strategy_supply(env, strategy_address, &this, amount)
+    })
+}
+
+pub fn get_yield_treasury_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::StrategyYieldTreasuryBps))
+        .unwrap_or(0)
+}
+
+pub fn set_yield_treasury_bps(env: &Env, bps: u32) -> Result<(), SavingsError> {
+    if bps as i128 > BPS_DENOMINATOR {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::StrategyYieldTreasuryBps), &bps);
+    Ok(())
+}
+
+pub fn get_liquidity_status(env: &Env) -> LiquidityStatus {
+    let flexi_liabilities = get_flexi_liabilities(env);
+    let invested = get_invested(env);
+    let liquid = flexi_liabilities - invested;
+    let current_liquidity_bps = if flexi_liabilities <= 0 {
+        DEFAULT_TARGET_LIQUIDITY_BPS
+    } else {
+        (liquid.max(0) * BPS_DENOMINATOR / flexi_liabilities) as u32
+    };
+
+    LiquidityStatus {
+        flexi_liabilities,
+        invested,
+        liquid,
+        target_liquidity_bps: get_target_liquidity_bps(env),
+        current_liquidity_bps,
+    }
+}
+
+/// Moves funds between the contract and the strategy so that the configured
+/// share of flexi liabilities stays liquid. Supplies are capped by the
+/// contract's token balance.
+pub fn rebalance(env: &Env) -> Result<LiquidityStatus, SavingsError> {
+    guard::check_protocol(env)?;
+
+    let token_address = get_token(env)?;
+    let strategy_address = get_strategy(env)?;
+
+    let liabilities = get_flexi_liabilities(env).max(0);
+    let target_liquid = liabilities
+        .checked_mul(get_target_liquidity_bps(env) as i128)
+        .ok_or(SavingsError::Overflow)?
+        / BPS_DENOMINATOR;
+    let target_invested = liabilities - target_liquid;
+    let mut invested = get_invested(env);
+
+    let this = env.current_contract_address();
+
+    if target_invested > invested {
+        let held = token::Client::new(env, &token_address).balance(&this);
+        let amount = (target_invested - invested).min(held);
+        if amount > 0 {
+            invested += amount;
+            set_invested(env, invested);
+            supply(env, &token_address, &strategy_address, amount)?;
+            events::publish(env, events::StrategySupplied { amount });
+        }
+    } else if target_invested < invested {
+        let amount = invested - target_invested;
+        invested -= amount;
+        set_invested(env, invested);
+        reentrancy::external(env, || {
+            strategy_withdraw(env, &strategy_address, &this, amount)
+        })?;
+        events::publish(env, events::StrategyWithdrawn { amount });
+    }
+
+    Ok(get_liquidity_status(env))
+}
+
+/// Supplies `amount` to the strategy when positive, or pulls `-amount` back
+/// when negative, regardless of the liquidity target.
+///
+/// Callers are responsible for authorization.
+///
+/// # Errors
+/// * `InvalidAmount` - If `amount` is zero
+/// * `InsufficientBalance` - If the contract holds less than a supply, or
+///   less is invested than a withdrawal
+/// * `MissingParameter` - If no token or strategy is configured
+pub fn move_funds(env: &Env, amount: i128) -> Result<LiquidityStatus, SavingsError> {
+    guard::check_protocol(env)?;
+    if amount == 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+
+    let token_address = get_token(env)?;
+    let strategy_address = get_strategy(env)?;
+    let this = env.current_contract_address();
+    let invested = get_invested(env);
+
+    if amount > 0 {
+        if token::Client::new(env, &token_address).balance(&this) < amount {
+            return Err(SavingsError::InsufficientBalance);
+        }
+        set_invested(
+            env,
+            invested.checked_add(amount).ok_or(SavingsError::Overflow)?,
+        );
+        supply(env, &token_address, &strategy_address, amount)?;
+        events::publish(env, events::StrategySupplied { amount });
+    } else {
+        let amount = amount.checked_neg().ok_or(SavingsError::Overflow)?;
+        if invested < amount {
+            return Err(SavingsError::InsufficientBalance);
+        }
+        set_invested(env, invested - amount);
+        reentrancy::external(env, || {
+            strategy_withdraw(env, &strategy_address, &this, amount)
+        })?;
+        events::publish(env, events::StrategyWithdrawn { amount });
+    }
+
+    Ok(get_liquidity_status(env))
+}
+
+/// Pulls the strategy's earnings above the invested principal back into the
+/// contract and splits them: the treasury's configured share goes to the fee
+/// recipient and the rest funds the yield pool savers' interest is paid from.
+/// Everything goes to the pool if no fee recipient is set.
+pub fn harvest(env: &Env) -> Result<StrategyHarvest, SavingsError> {
+    guard::check_protocol(env)?;
+
+    let strategy_address = get_strategy(env)?;
+    let this = env.current_contract_address();
+
+    let realized = reentrancy::external(env, || strategy_balance(env, &strategy_address, &this))?
+        - get_invested(env);
+    if realized <= 0 {
+        return Ok(StrategyHarvest {
+            realized: 0,
+            user_share: 0,
+            treasury_share: 0,
+        });
+    }
+
+    let mut treasury_share = realized
+        .checked_mul(get_yield_treasury_bps(env) as i128)
+        .ok_or(SavingsError::Overflow)?
+        / BPS_DENOMINATOR;
+    if treasury_share > 0 && fees::credit_treasury(env, treasury_share)?.is_none() {
+        treasury_share = 0;
+    }
+    let user_share = realized - treasury_share;
+    if user_share > 0 {
+        yield_pool::credit_pool(env, user_share)?;
+    }
+    reentrancy::external(env, || {
+        strategy_withdraw(env, &strategy_address, &this, realized)
+    })?;
+
+    events::publish(
+        env,
+        events::StrategyHarvested {
+            realized,
+            user_share,
+            treasury_share,
+        },
+    );
+    Ok(StrategyHarvest {
+        realized,
+        user_share,
+        treasury_share,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        contract, contractimpl, symbol_short, testutils::Address as _, token, Address, BytesN, Env,
+    };
+
+    /// Minimal strategy that records supplied amounts and returns them on demand
+    #[contract]
+    pub struct MockStrategy;
+
+    #[contractimpl]
+    impl MockStrategy {
+        pub fn __constructor(env: Env, token: Address) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("token"), &token);
+        }
+
+        pub fn supply(env: Env, from: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), from.clone());
+            env.storage().persistent().set(&from, &(balance + amount));
+        }
+
+        pub fn withdraw(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance - amount));
+            let token: Address = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("token"))
+                .unwrap();
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &to,
+                &amount,
+            );
+        }
+
+        pub fn balance(env: Env, owner: Address) -> i128 {
+            env.storage().persistent().get(&owner).unwrap_or(0)
+        }
+
+        /// Test hook crediting `owner` with earnings the strategy already holds
+        pub fn accrue(env: Env, owner: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), owner.clone());
+            env.storage().persistent().set(&owner, &(balance + amount));
+        }
+    }
+
+    fn setup() -> (
+        Env,
+        NesteraContractClient<'static>,
+        Address,
+        Address,
+        Address,
+    ) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let strategy = env.register(MockStrategy, (token.clone(),));
+
+        (env, client, admin, token, strategy)
+    }
+
+    #[test]
+    fn test_rebalance_supplies_and_withdraws_to_target() {
+        let (env, client, admin, token, strategy) = setup();
+        client.set_token(&admin, &token);
+        client.set_strategy(&admin, &strategy);
+        client.set_target_liquidity_bps(&admin, &2_000);
+
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &1_000);
+        token::StellarAssetClient::new(&env, &token).mint(&client.address, &1_000);
+
+        let status = client.rebalance();
+        assert_eq!(status.flexi_liabilities, 1_000);
+        assert_eq!(status.invested, 800);
+        assert_eq!(status.liquid, 200);
+        assert_eq!(status.current_liquidity_bps, 2_000);
+
+        let token_client = token::Client::new(&env, &token);
+        assert_eq!(token_client.balance(&strategy), 800);
+        assert_eq!(token_client.balance(&client.address), 200);
+
+        client.withdraw_flexi(&user, &500);
+        let status = client.rebalance();
+        assert_eq!(status.invested, 400);
+        assert_eq!(status.liquid, 100);
+        assert_eq!(token_client.balance(&strategy), 400);
+        assert_eq!(token_client.balance(&client.address), 600);
+    }
+
+    #[test]
+    fn test_rebalance_caps_supply_at_contract_balance() {
+        let (env, client, admin, token, strategy) = setup();
+        client.set_token(&admin, &token);
+        client.set_strategy(&admin, &strategy);
+        client.set_target_liquidity_bps(&admin, &0);
+
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &1_000);
+        token::StellarAssetClient::new(&env, &token).mint(&client.address, &300);
+
+        let status = client.rebalance();
+        assert_eq!(status.invested, 300);
+        assert_eq!(status.current_liquidity_bps, 7_000);
+    }
+
+    #[test]
+    fn test_move_funds_pushes_and_pulls_exact_amounts() {
+        let (env, client, admin, token, strategy) = setup();
+        client.set_token(&admin, &token);
+        client.set_strategy(&admin, &strategy);
+        token::StellarAssetClient::new(&env, &token).mint(&client.address, &1_000);
+
+        let status = client.move_strategy_funds(&admin, &600);
+        assert_eq!(status.invested, 600);
+        let status = client.move_strategy_funds(&admin, &-250);
+        assert_eq!(status.invested, 350);
+
+        let token_client = token::Client::new(&env, &token);
+        assert_eq!(token_client.balance(&strategy), 350);
+        assert_eq!(token_client.balance(&client.address), 650);
+
+        assert_eq!(
+            client.try_move_strategy_funds(&admin, &-351).unwrap_err(),
+            Ok(SavingsError::InsufficientBalance)
+        );
+        assert_eq!(
+            client.try_move_strategy_funds(&admin, &651).unwrap_err(),
+            Ok(SavingsError::InsufficientBalance)
+        );
+        assert_eq!(
+            client
+                .try_move_strategy_funds(&Address::generate(&env), &1)
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_harvest_splits_yield_between_pool_and_treasury() {
+        let (env, client, admin, token, strategy) = setup();
+        client.set_token(&admin, &token);
+        client.set_strategy(&admin, &strategy);
+        client.set_strategy_yield_split(&admin, &2_000);
+        let sac = token::StellarAssetClient::new(&env, &token);
+        sac.mint(&client.address, &1_000);
+        client.move_strategy_funds(&admin, &1_000);
+
+        // Strategy earns 100 on the principal
+        sac.mint(&strategy, &100);
+        MockStrategyClient::new(&env, &strategy).accrue(&client.address, &100);
+
+        let pool_before = client.get_yield_pool_status().pool_balance;
+        let fees_before = client.get_total_protocol_fee_balance();
+        let harvest = client.harvest_strategy_yield();
+        assert_eq!(harvest.realized, 100);
+        assert_eq!(harvest.treasury_share, 20);
+        assert_eq!(harvest.user_share, 80);
+        assert_eq!(
+            client.get_yield_pool_status().pool_balance,
+            pool_before + 80
+        );
+        assert_eq!(client.get_total_protocol_fee_balance(), fees_before + 20);
+
+        let token_client = token::Client::new(&env, &token);
+        assert_eq!(token_client.balance(&strategy), 1_000);
+        assert_eq!(token_client.balance(&client.address), 100);
+        assert_eq!(client.get_liquidity_status().invested, 1_000);
+
+        // Nothing left to harvest
+        assert_eq!(client.harvest_strategy_yield().realized, 0);
+    }
+
+    #[test]
+    fn test_set_strategy_yield_split_rejects_over_100_percent() {
+        let (_env, client, admin, _token, _strategy) = setup();
+        assert_eq!(client.get_strategy_yield_split(), 0);
+        assert_eq!(
+            client
+                .try_set_strategy_yield_split(&admin, &10_001)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+    }
+
+    #[test]
+    fn test_default_status_is_fully_liquid() {
+        let (_env, client, _admin, _token, _strategy) = setup();
+        let status = client.get_liquidity_status();
+        assert_eq!(status.target_liquidity_bps, 10_000);
+        assert_eq!(status.current_liquidity_bps, 10_000);
+        assert_eq!(status.invested, 0);
+    }
+
+    #[test]
+    fn test_rebalance_requires_configuration() {
+        let (_env, client, admin, token, _strategy) = setup();
+        assert_eq!(
+            client.try_rebalance().unwrap_err(),
+            Ok(SavingsError::MissingParameter)
+        );
+
+        client.set_token(&admin, &token);
+        assert_eq!(
+            client.try_rebalance().unwrap_err(),
+            Ok(SavingsError::MissingParameter)
+        );
+    }
+
+    #[test]
+    fn test_set_target_liquidity_bps_rejects_over_100_percent() {
+        let (_env, client, admin, _token, _strategy) = setup();
+        assert_eq!(
+            client
+                .try_set_target_liquidity_bps(&admin, &10_001)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+    }
+
+    #[test]
+    fn test_strategy_setters_require_admin() {
+        let (env, client, _admin, _token, strategy) = setup();
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_set_strategy(&stranger, &strategy).unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+}
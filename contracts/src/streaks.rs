@@ -0,0 +1,156 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{ConfigKey, DataKey, StreakPeriod, UserStreak};
+
+const WEEK_SECONDS: u64 = 7 * 24 * 60 * 60;
+const MONTH_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Returns the configured streak period (defaults to weekly)
+pub fn get_streak_period(env: &Env) -> StreakPeriod {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::StreakPeriod))
+        .unwrap_or(StreakPeriod::Weekly)
+}
+
+/// Sets the streak period used for all users
+///
+/// Changing the period re-buckets time, so existing streaks continue only if
+/// the next deposit falls in the period right after the last recorded one.
+pub fn set_streak_period(env: &Env, period: StreakPeriod) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::StreakPeriod), &period);
+}
+
+/// Returns the length of a streak period in seconds
+pub fn period_seconds(period: &StreakPeriod) -> u64 {
+    match period {
+        StreakPeriod::Weekly => WEEK_SECONDS,
+        StreakPeriod::Monthly => MONTH_SECONDS,
+    }
+}
+
+/// Returns a user's streak record (all zeros if they never deposited)
+pub fn get_user_streak(env: &Env, user: &Address) -> UserStreak {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserStreak(user.clone()))
+        .unwrap_or(UserStreak {
+            current_streak: 0,
+            longest_streak: 0,
+            last_deposit_period: 0,
+        })
+}
+
+/// Records a deposit of any type for streak tracking
+///
+/// A deposit in the period right after the last recorded one extends the
+/// streak, a deposit in the same period is a no-op, and any gap resets the
+/// current streak to one.
+pub fn record_deposit(env: &Env, user: &Address) -> UserStreak {
+    let period = env.ledger().timestamp() / period_seconds(&get_streak_period(env));
+    let mut streak = get_user_streak(env, user);
+
+    if streak.current_streak > 0 && streak.last_deposit_period == period {
+        return streak;
+    }
+
+    if streak.current_streak > 0 && streak.last_deposit_period + 1 == period {
+        streak.current_streak += 1;
+    } else {
+        streak.current_streak = 1;
+    }
+    if streak.current_streak > streak.longest_streak {
+        streak.longest_streak = streak.current_streak;
+    }
+    streak.last_deposit_period = period;
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserStreak(user.clone()), &streak);
+
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::StreakPeriod;
+    use crate::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, Symbol,
+    };
+
+    const WEEK: u64 = 7 * 24 * 60 * 60;
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+
+        (env, client, user)
+    }
+
+    #[test]
+    fn test_consecutive_weeks_extend_streak() {
+        let (env, client, user) = setup();
+
+        client.deposit_flexi(&user, &100);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        client.create_goal_save(&user, &Symbol::new(&env, "trip"), &1000, &50);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        let goal_id = client.get_user_goal_saves(&user).get(0).unwrap();
+        client.deposit_to_goal_save(&user, &goal_id, &50);
+        // Second deposit in the same week does not count twice
+        client.deposit_flexi(&user, &10);
+
+        let streak = client.get_user_streak(&user);
+        assert_eq!(streak.current_streak, 3);
+        assert_eq!(streak.longest_streak, 3);
+        assert_eq!(streak.last_deposit_period, 2);
+    }
+
+    #[test]
+    fn test_gap_resets_current_streak() {
+        let (env, client, user) = setup();
+
+        client.deposit_flexi(&user, &100);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        client.deposit_flexi(&user, &100);
+        env.ledger().with_mut(|li| li.timestamp += 3 * WEEK);
+        client.deposit_flexi(&user, &100);
+
+        let streak = client.get_user_streak(&user);
+        assert_eq!(streak.current_streak, 1);
+        assert_eq!(streak.longest_streak, 2);
+    }
+
+    #[test]
+    fn test_monthly_period_groups_weekly_deposits() {
+        let (env, client, user) = setup();
+        client.set_streak_period(&StreakPeriod::Monthly);
+
+        client.deposit_flexi(&user, &100);
+        env.ledger().with_mut(|li| li.timestamp += WEEK);
+        client.deposit_flexi(&user, &100);
+
+        let streak = client.get_user_streak(&user);
+        assert_eq!(streak.current_streak, 1);
+        assert_eq!(client.get_streak_period(), StreakPeriod::Monthly);
+    }
+}
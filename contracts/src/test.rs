@@ -1,7 +1,13 @@
 #![cfg(test)]
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, String,
+};
 
-use crate::{NesteraContract, NesteraContractClient, SavingsError};
+use crate::{
+    Cursor, DisputeOutcome, DueGroupContribution, GroupMetadata, GroupStatus, NesteraContract,
+    NesteraContractClient, PlanDetail, PlanKind, PlanRef, SavingsError,
+};
 
 fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let env = Env::default();
@@ -11,7 +17,13 @@ fn setup() -> (Env, NesteraContractClient<'static>, Address) {
     let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
 
     env.mock_all_auths();
-    client.initialize(&admin, &admin_pk);
+    client.initialize(
+        &admin,
+        &admin_pk,
+        &Address::generate(&env),
+        &0,
+        &Address::generate(&env),
+    );
 
     (env, client, admin)
 }
@@ -33,15 +45,18 @@ fn test_successful_break_group_save() {
     // Create a group
     let group_id = client.create_group_save(
         &creator,
-        &String::from_str(&env, "Test Group"),
-        &String::from_str(&env, "Test Description"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Test Group"),
+            description: String::from_str(&env, "Test Description"),
+            category: String::from_str(&env, "savings"),
+        },
         &10000, // target_amount
         &0,     // contribution_type
         &100,   // contribution_amount
         &true,  // is_public
         &1,     // start_time
         &1000,  // end_time
+        &0,     // max_members
     );
 
     // Member1 joins
@@ -76,15 +91,18 @@ fn test_break_group_updates_member_count() {
 
     let group_id = client.create_group_save(
         &creator,
-        &String::from_str(&env, "Test Group"),
-        &String::from_str(&env, "Description"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Test Group"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        },
         &5000,
         &0,
         &100,
         &true,
         &1,
         &500,
+        &0,
     );
 
     // Two members join (making it 3 total with creator)
@@ -108,29 +126,35 @@ fn test_break_group_updates_user_groups_list() {
     // Create first group
     let group1_id = client.create_group_save(
         &user,
-        &String::from_str(&env, "Group 1"),
-        &String::from_str(&env, "Description 1"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Group 1"),
+            description: String::from_str(&env, "Description 1"),
+            category: String::from_str(&env, "savings"),
+        },
         &5000,
         &0,
         &100,
         &true,
         &1,
         &500,
+        &0,
     );
 
     // Create second group
     let group2_id = client.create_group_save(
         &user,
-        &String::from_str(&env, "Group 2"),
-        &String::from_str(&env, "Description 2"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Group 2"),
+            description: String::from_str(&env, "Description 2"),
+            category: String::from_str(&env, "savings"),
+        },
         &3000,
         &0,
         &50,
         &true,
         &1,
         &400,
+        &0,
     );
 
     // User leaves group1
@@ -153,15 +177,18 @@ fn test_break_group_refunds_contribution() {
 
     let group_id = client.create_group_save(
         &creator,
-        &String::from_str(&env, "Test Group"),
-        &String::from_str(&env, "Description"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Test Group"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        },
         &5000,
         &0,
         &100,
         &true,
         &1,
         &500,
+        &0,
     );
 
     client.join_group_save(&member, &group_id);
@@ -187,15 +214,18 @@ fn test_break_group_with_zero_contribution() {
 
     let group_id = client.create_group_save(
         &creator,
-        &String::from_str(&env, "Test Group"),
-        &String::from_str(&env, "Description"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Test Group"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        },
         &5000,
         &0,
         &100,
         &true,
         &1,
         &500,
+        &0,
     );
 
     client.join_group_save(&member, &group_id);
@@ -218,15 +248,18 @@ fn test_error_user_not_member() {
 
     let group_id = client.create_group_save(
         &creator,
-        &String::from_str(&env, "Test Group"),
-        &String::from_str(&env, "Description"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Test Group"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        },
         &5000,
         &0,
         &100,
         &true,
         &1,
         &500,
+        &0,
     );
 
     // Non-member tries to leave
@@ -244,15 +277,18 @@ fn test_error_group_completed() {
 
     let group_id = client.create_group_save(
         &creator,
-        &String::from_str(&env, "Test Group"),
-        &String::from_str(&env, "Description"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Test Group"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        },
         &100, // Low target to easily complete
         &0,
         &10,
         &true,
         &1,
         &500,
+        &0,
     );
 
     // Complete the group by contributing the full amount
@@ -287,18 +323,1289 @@ fn test_error_user_not_found() {
 
     let group_id = client.create_group_save(
         &creator,
-        &String::from_str(&env, "Test Group"),
-        &String::from_str(&env, "Description"),
-        &String::from_str(&env, "savings"),
+        &GroupMetadata {
+            title: String::from_str(&env, "Test Group"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        },
         &5000,
         &0,
         &100,
         &true,
         &1,
         &500,
+        &0,
     );
 
     // Non-initialized user tries to leave
     let result = client.try_break_group_save(&non_existent_user, &group_id);
     assert_eq!(result.unwrap_err(), Ok(SavingsError::UserNotFound));
 }
+
+fn create_public_group(env: &Env, client: &NesteraContractClient, creator: &Address) -> u64 {
+    client.create_group_save(
+        creator,
+        &GroupMetadata {
+            title: String::from_str(env, "Open Circle"),
+            description: String::from_str(env, "Description"),
+            category: String::from_str(env, "savings"),
+        },
+        &5000,
+        &0,
+        &100,
+        &true,
+        &1,
+        &500,
+        &0,
+    )
+}
+
+#[test]
+fn test_join_requires_minimum_reputation() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+
+    let group_id = create_public_group(&env, &client, &creator);
+    client.set_group_join_requirements(&creator, &group_id, &50, &0);
+
+    let result = client.try_join_group_save(&member, &group_id);
+    assert_eq!(result.unwrap_err(), Ok(SavingsError::ReputationTooLow));
+
+    client.set_user_reputation(&member, &50);
+    client.join_group_save(&member, &group_id);
+}
+
+#[test]
+fn test_join_requires_minimum_account_age() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+
+    let group_id = create_public_group(&env, &client, &creator);
+    client.set_group_join_requirements(&creator, &group_id, &0, &86_400);
+
+    let result = client.try_join_group_save(&member, &group_id);
+    assert_eq!(result.unwrap_err(), Ok(SavingsError::AccountTooNew));
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    client.join_group_save(&member, &group_id);
+}
+
+#[test]
+fn test_only_creator_sets_join_requirements() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize_user(&creator);
+
+    let group_id = create_public_group(&env, &client, &creator);
+    let result = client.try_set_group_join_requirements(&other, &group_id, &10, &0);
+    assert_eq!(result.unwrap_err(), Ok(SavingsError::Unauthorized));
+}
+
+#[test]
+fn test_join_stake_contributed_on_uninvited_join() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    let guest = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    client.initialize_user(&guest);
+
+    // Flexible group with a 100 minimum contribution
+    let group_id = create_group_with_contribution(&env, &client, &creator, 1, 100);
+    assert_eq!(
+        client.try_set_group_join_stake(&creator, &group_id, &50),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_group_join_stake(&member, &group_id, &150),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    client.set_group_join_requirements(&creator, &group_id, &0, &0);
+    client.set_group_join_stake(&creator, &group_id, &150);
+    // Updating the other requirements keeps the stake
+    client.set_group_join_requirements(&creator, &group_id, &0, &0);
+    assert_eq!(
+        client.get_group_join_requirements(&group_id).join_stake,
+        150
+    );
+
+    // The stake comes out of the joiner's Flexi Save
+    client.deposit_flexi(&member, &100);
+    assert_eq!(
+        client.try_join_group_save(&member, &group_id),
+        Err(Ok(SavingsError::InsufficientBalance))
+    );
+    client.deposit_flexi(&member, &100);
+    client.join_group_save(&member, &group_id);
+    assert_eq!(env.auths()[0].0, member);
+    let contribution = env.as_contract(&client.address, || {
+        crate::group::get_member_contribution(&env, group_id, &member)
+    });
+    assert_eq!(contribution, 150);
+    assert_eq!(client.get_flexi_balance(&member), 50);
+
+    // Invited users join without staking
+    client.invite_to_group(&creator, &group_id, &guest);
+    client.join_group_save(&guest, &group_id);
+    let contribution = env.as_contract(&client.address, || {
+        crate::group::get_member_contribution(&env, group_id, &guest)
+    });
+    assert_eq!(contribution, 0);
+}
+
+#[test]
+fn test_leaving_group_requires_auth_and_refunds_stake() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+
+    let group_id = create_group_with_contribution(&env, &client, &creator, 1, 100);
+    client.set_group_join_stake(&creator, &group_id, &150);
+    client.deposit_flexi(&member, &200);
+    client.join_group_save(&member, &group_id);
+    assert_eq!(client.get_flexi_balance(&member), 50);
+
+    env.mock_auths(&[]);
+    assert!(client.try_break_group_save(&member, &group_id).is_err());
+    assert!(client
+        .try_contribute_to_group_save(&member, &group_id, &100)
+        .is_err());
+
+    env.mock_all_auths();
+    client.break_group_save(&member, &group_id);
+    assert_eq!(env.auths()[0].0, member);
+    assert_eq!(client.get_flexi_balance(&member), 200);
+    assert_eq!(client.get_user(&member).total_balance, 200);
+}
+
+#[test]
+fn test_contribution_history_spans_chunks() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize_user(&creator);
+
+    let group_id = client.create_group_save(
+        &creator,
+        &GroupMetadata {
+            title: String::from_str(&env, "Big Pot"),
+            description: String::from_str(&env, "Description"),
+            category: String::from_str(&env, "savings"),
+        },
+        &1_000_000,
+        &1,
+        &1,
+        &true,
+        &1,
+        &500,
+        &0,
+    );
+
+    for amount in 1..=105i128 {
+        client.contribute_to_group_save(&creator, &group_id, &amount);
+    }
+
+    let (page, next) = client.get_group_contribution_history(
+        &group_id,
+        &Cursor {
+            offset: 98,
+            limit: 5,
+        },
+    );
+    assert_eq!(page.len(), 5);
+    assert_eq!(page.get(0).unwrap().amount, 99);
+    assert_eq!(page.get(2).unwrap().amount, 101);
+    assert_eq!(page.get(4).unwrap().amount, 103);
+    assert_eq!(
+        next,
+        Some(Cursor {
+            offset: 103,
+            limit: 5
+        })
+    );
+
+    let (tail, next) = client.get_group_contribution_history(&group_id, &next.unwrap());
+    assert_eq!(tail.len(), 2);
+    assert_eq!(tail.get(1).unwrap().contributor, creator);
+    assert_eq!(next, None);
+}
+
+#[test]
+fn test_pages_are_capped_and_chained() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let members = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+
+    env.mock_all_auths();
+    client.initialize_user(&creator);
+    let group_id = create_public_group(&env, &client, &creator);
+    for member in members.iter() {
+        client.initialize_user(member);
+        client.join_group_save(member, &group_id);
+    }
+
+    let (first, next) = client.get_group_members_page(
+        &group_id,
+        &Cursor {
+            offset: 0,
+            limit: 3,
+        },
+    );
+    assert_eq!(first.len(), 3);
+    assert_eq!(first.get(0).unwrap(), creator);
+
+    let (second, next) = client.get_group_members_page(&group_id, &next.unwrap());
+    assert_eq!(second.len(), 1);
+    assert_eq!(second.get(0).unwrap(), members[2]);
+    assert_eq!(next, None);
+
+    // A zero or oversized limit falls back to the maximum page size
+    let (all, next) = client.get_group_members_page(
+        &group_id,
+        &Cursor {
+            offset: 0,
+            limit: 0,
+        },
+    );
+    assert_eq!(all.len(), 4);
+    assert_eq!(next, None);
+}
+
+fn setup_disputed_group(
+    env: &Env,
+    client: &NesteraContractClient,
+) -> (u64, Address, Address, Address) {
+    let creator = Address::generate(env);
+    let member1 = Address::generate(env);
+    let member2 = Address::generate(env);
+
+    env.mock_all_auths();
+    client.initialize_user(&creator);
+    client.initialize_user(&member1);
+    client.initialize_user(&member2);
+
+    let group_id = create_public_group(env, client, &creator);
+    client.join_group_save(&member1, &group_id);
+    client.join_group_save(&member2, &group_id);
+    client.contribute_to_group_save(&member1, &group_id, &200);
+
+    (group_id, creator, member1, member2)
+}
+
+#[test]
+fn test_dispute_freezes_group_at_threshold() {
+    let (env, client, _admin) = setup();
+    let (group_id, _creator, member1, member2) = setup_disputed_group(&env, &client);
+
+    client.flag_group_dispute(&member1, &group_id, &1);
+    assert!(!client.get_group_dispute(&group_id).frozen);
+    client.break_group_save(&member2, &group_id);
+
+    let (group_id, _creator, member1, member2) = setup_disputed_group(&env, &client);
+    client.flag_group_dispute(&member1, &group_id, &1);
+    client.flag_group_dispute(&member2, &group_id, &3);
+
+    let dispute = client.get_group_dispute(&group_id);
+    assert!(dispute.frozen);
+    assert_eq!(dispute.flaggers.len(), 2);
+    assert_eq!(dispute.reason_codes.get(1).unwrap(), 3);
+
+    let result = client.try_break_group_save(&member1, &group_id);
+    assert_eq!(result, Err(Ok(SavingsError::GroupFrozen)));
+}
+
+#[test]
+fn test_dispute_flags_must_be_distinct_members() {
+    let (env, client, _admin) = setup();
+    let (group_id, _creator, member1, _member2) = setup_disputed_group(&env, &client);
+    let outsider = Address::generate(&env);
+
+    client.flag_group_dispute(&member1, &group_id, &1);
+    assert_eq!(
+        client.try_flag_group_dispute(&member1, &group_id, &2),
+        Err(Ok(SavingsError::AlreadyFlagged))
+    );
+    assert_eq!(
+        client.try_flag_group_dispute(&outsider, &group_id, &1),
+        Err(Ok(SavingsError::NotGroupMember))
+    );
+}
+
+#[test]
+fn test_resolve_dispute_dismissed_unfreezes() {
+    let (env, client, admin) = setup();
+    let (group_id, _creator, member1, member2) = setup_disputed_group(&env, &client);
+
+    client.flag_group_dispute(&member1, &group_id, &1);
+    client.flag_group_dispute(&member2, &group_id, &1);
+    client.resolve_dispute(&admin, &group_id, &DisputeOutcome::Dismissed);
+
+    let dispute = client.get_group_dispute(&group_id);
+    assert!(!dispute.frozen);
+    assert_eq!(dispute.flaggers.len(), 0);
+    assert!(!dispute.refunds_enabled);
+
+    client.break_group_save(&member1, &group_id);
+    let group = env.as_contract(&client.address, || {
+        crate::group::get_group_save(&env, group_id).unwrap()
+    });
+    assert_eq!(group.member_count, 2);
+}
+
+#[test]
+fn test_upheld_dispute_allows_refunds_from_completed_group() {
+    let (env, client, admin) = setup();
+    let (group_id, _creator, member1, member2) = setup_disputed_group(&env, &client);
+    client.contribute_to_group_save(&member2, &group_id, &4800);
+    assert_eq!(
+        client.try_break_group_save(&member1, &group_id),
+        Err(Ok(SavingsError::PlanCompleted))
+    );
+
+    client.flag_group_dispute(&member1, &group_id, &7);
+    client.flag_group_dispute(&member2, &group_id, &7);
+    client.resolve_dispute(&admin, &group_id, &DisputeOutcome::Upheld);
+
+    client.break_group_save(&member1, &group_id);
+    let group = env.as_contract(&client.address, || {
+        crate::group::get_group_save(&env, group_id).unwrap()
+    });
+    assert_eq!(group.current_amount, 4800);
+}
+
+#[test]
+fn test_resolve_dispute_requires_admin() {
+    let (env, client, _admin) = setup();
+    let (group_id, creator, _member1, _member2) = setup_disputed_group(&env, &client);
+
+    assert_eq!(
+        client.try_resolve_dispute(&creator, &group_id, &DisputeOutcome::Dismissed),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+}
+
+fn create_group_with_window(
+    env: &Env,
+    client: &NesteraContractClient,
+    creator: &Address,
+    start_time: u64,
+    end_time: u64,
+) -> Result<u64, SavingsError> {
+    client
+        .try_create_group_save(
+            creator,
+            &GroupMetadata {
+                title: String::from_str(env, "Trip"),
+                description: String::from_str(env, "Summer trip"),
+                category: String::from_str(env, "travel"),
+            },
+            &5000,
+            &0,
+            &100,
+            &true,
+            &start_time,
+            &end_time,
+            &0,
+        )
+        .map(|r| r.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+#[test]
+fn test_group_duration_bounds_default_to_two_year_horizon() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+
+    let bounds = client.get_group_duration_bounds();
+    assert_eq!(bounds.min_duration, 0);
+    assert_eq!(bounds.max_horizon, crate::group::DEFAULT_MAX_GROUP_HORIZON);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let horizon = 1_000 + bounds.max_horizon;
+    assert!(create_group_with_window(&env, &client, &creator, 1_000, horizon).is_ok());
+    assert_eq!(
+        create_group_with_window(&env, &client, &creator, 1_000, horizon + 1),
+        Err(SavingsError::InvalidTimestamp)
+    );
+}
+
+#[test]
+fn test_group_duration_bounds_enforce_minimum() {
+    let (env, client, admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+
+    let week = 7 * 24 * 60 * 60;
+    client.set_group_duration_bounds(&admin, &week, &(52 * week));
+    assert_eq!(
+        create_group_with_window(&env, &client, &creator, 0, week - 1),
+        Err(SavingsError::InvalidTimestamp)
+    );
+    assert!(create_group_with_window(&env, &client, &creator, 0, week).is_ok());
+    assert_eq!(
+        create_group_with_window(&env, &client, &creator, 0, 53 * week),
+        Err(SavingsError::InvalidTimestamp)
+    );
+}
+
+#[test]
+fn test_set_group_duration_bounds_validation() {
+    let (env, client, admin) = setup();
+    assert_eq!(
+        client.try_set_group_duration_bounds(&admin, &10, &5),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+    assert_eq!(
+        client.try_set_group_duration_bounds(&admin, &0, &0),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_set_group_duration_bounds(&stranger, &0, &10),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+}
+
+fn create_group_with_contribution(
+    env: &Env,
+    client: &NesteraContractClient,
+    creator: &Address,
+    contribution_type: u32,
+    contribution_amount: i128,
+) -> u64 {
+    client.create_group_save(
+        creator,
+        &GroupMetadata {
+            title: String::from_str(env, "Club"),
+            description: String::from_str(env, "Savings club"),
+            category: String::from_str(env, "community"),
+        },
+        &10_000,
+        &contribution_type,
+        &contribution_amount,
+        &true,
+        &0,
+        &1_000,
+        &0,
+    )
+}
+
+#[test]
+fn test_fixed_group_requires_multiples_of_contribution() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+    let group_id = create_group_with_contribution(&env, &client, &creator, 0, 500);
+
+    assert_eq!(
+        client.try_contribute_to_group_save(&creator, &group_id, &750),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+    client.contribute_to_group_save(&creator, &group_id, &500);
+    client.contribute_to_group_save(&creator, &group_id, &1_000);
+}
+
+#[test]
+fn test_flexible_group_enforces_minimum() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+    let group_id = create_group_with_contribution(&env, &client, &creator, 1, 300);
+
+    assert_eq!(
+        client.try_contribute_to_group_save(&creator, &group_id, &299),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+    client.contribute_to_group_save(&creator, &group_id, &301);
+}
+
+#[test]
+fn test_percentage_group_contributes_share_of_target() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+    // 2.5% of a 10_000 target
+    let group_id = create_group_with_contribution(&env, &client, &creator, 2, 250);
+
+    assert_eq!(
+        client.try_contribute_to_group_save(&creator, &group_id, &300),
+        Err(Ok(SavingsError::InvalidAmount))
+    );
+    client.contribute_to_group_save(&creator, &group_id, &250);
+    client.contribute_to_group_save(&creator, &group_id, &500);
+
+    let result = client.try_create_group_save(
+        &creator,
+        &GroupMetadata {
+            title: String::from_str(&env, "Club"),
+            description: String::from_str(&env, "Savings club"),
+            category: String::from_str(&env, "community"),
+        },
+        &10_000,
+        &2,
+        &10_001,
+        &true,
+        &0,
+        &1_000,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(SavingsError::InvalidGroupConfig)));
+}
+
+#[test]
+fn test_refund_group_members_pages_through_failed_group() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+    let group_id = create_group_with_contribution(&env, &client, &creator, 1, 100);
+
+    let mut members = soroban_sdk::Vec::new(&env);
+    members.push_back(creator.clone());
+    for _ in 0..3 {
+        let member = Address::generate(&env);
+        client.initialize_user(&member);
+        client.join_group_save(&member, &group_id);
+        members.push_back(member);
+    }
+    for (i, member) in members.iter().enumerate() {
+        if i != 2 {
+            client.contribute_to_group_save(&member, &group_id, &(100 * (i as i128 + 1)));
+        }
+    }
+
+    // Still running: nothing to refund yet
+    assert_eq!(
+        client.try_refund_group_members(&group_id, &0, &10),
+        Err(Ok(SavingsError::TooEarly))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(client.refund_group_members(&group_id, &0, &2), 2);
+    // Member 2 never contributed, so the second page refunds only member 3
+    assert_eq!(client.refund_group_members(&group_id, &2, &2), 1);
+    // Re-running a page is a no-op
+    assert_eq!(client.refund_group_members(&group_id, &0, &2), 0);
+
+    assert_eq!(client.get_flexi_balance(&members.get(0).unwrap()), 100);
+    assert_eq!(client.get_flexi_balance(&members.get(1).unwrap()), 200);
+    assert_eq!(client.get_flexi_balance(&members.get(3).unwrap()), 400);
+    let group = env.as_contract(&client.address, || {
+        crate::group::get_group_save(&env, group_id).unwrap()
+    });
+    assert_eq!(group.current_amount, 0);
+}
+
+#[test]
+fn test_member_reclaims_contribution_from_failed_group() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    let group_id = create_group_with_contribution(&env, &client, &creator, 1, 100);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&creator, &group_id, &300);
+    client.contribute_to_group_save(&member, &group_id, &200);
+
+    assert_eq!(client.get_group_status(&group_id), GroupStatus::Active);
+    assert_eq!(
+        client.try_refund_group_contribution(&member, &group_id),
+        Err(Ok(SavingsError::TooEarly))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(client.get_group_status(&group_id), GroupStatus::Failed);
+    assert_eq!(
+        client.try_contribute_to_group_save(&member, &group_id, &100),
+        Err(Ok(SavingsError::PlanCompleted))
+    );
+
+    assert_eq!(client.refund_group_contribution(&member, &group_id), 200);
+    assert_eq!(client.get_flexi_balance(&member), 200);
+    assert_eq!(
+        client.try_refund_group_contribution(&member, &group_id),
+        Err(Ok(SavingsError::InsufficientBalance))
+    );
+
+    // The keeper batch picks up whoever has not reclaimed yet
+    assert_eq!(client.refund_group_members(&group_id, &0, &10), 1);
+    assert_eq!(client.get_flexi_balance(&creator), 300);
+    assert_eq!(client.get_group_status(&group_id), GroupStatus::Failed);
+}
+
+#[test]
+fn test_refund_group_members_after_upheld_dispute() {
+    let (env, client, admin) = setup();
+    let (group_id, _creator, member1, member2) = setup_disputed_group(&env, &client);
+    client.flag_group_dispute(&member1, &group_id, &1);
+    client.flag_group_dispute(&member2, &group_id, &1);
+    assert_eq!(
+        client.try_refund_group_members(&group_id, &0, &10),
+        Err(Ok(SavingsError::GroupFrozen))
+    );
+
+    client.resolve_dispute(&admin, &group_id, &DisputeOutcome::Upheld);
+    assert_eq!(client.refund_group_members(&group_id, &0, &10), 1);
+    assert_eq!(client.get_flexi_balance(&member1), 200);
+}
+
+#[test]
+fn test_group_rounds_track_payments_and_misses() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    // Fixed 100 per round, window 0..1_000 split into 4 rounds of 250s
+    let group_id = create_group_with_contribution(&env, &client, &creator, 0, 100);
+    client.join_group_save(&member, &group_id);
+    client.set_group_schedule(&creator, &group_id, &250);
+
+    client.contribute_to_group_save(&creator, &group_id, &100);
+    let status = client.get_group_round_status(&group_id);
+    assert_eq!((status.round, status.total_rounds), (0, 4));
+    assert_eq!((status.round_start, status.due_time), (0, 250));
+    assert_eq!(status.required_amount, 100);
+    assert_eq!(status.paid.len(), 1);
+    assert_eq!(status.unpaid.get(0).unwrap(), member);
+
+    env.ledger().with_mut(|li| li.timestamp = 300);
+    client.contribute_to_group_save(&creator, &group_id, &100);
+    client.contribute_to_group_save(&member, &group_id, &200);
+    assert_eq!(client.get_group_round_status(&group_id).unpaid.len(), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    let missed = client.get_member_missed_rounds(&group_id, &member);
+    assert_eq!(missed.len(), 1);
+    assert_eq!(missed.get(0).unwrap(), 0);
+    assert_eq!(
+        client.get_member_missed_rounds(&group_id, &creator).len(),
+        0
+    );
+
+    assert_eq!(client.close_group_rounds(&group_id), 2);
+    assert_eq!(client.close_group_rounds(&group_id), 0);
+}
+
+#[test]
+fn test_group_schedule_rules() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&other);
+    let group_id = create_group_with_contribution(&env, &client, &creator, 0, 100);
+
+    assert_eq!(
+        client.try_get_group_round_status(&group_id),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+    assert_eq!(
+        client.try_set_group_schedule(&other, &group_id, &100),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_group_schedule(&creator, &group_id, &1_001),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+
+    client.contribute_to_group_save(&creator, &group_id, &100);
+    assert_eq!(
+        client.try_set_group_schedule(&creator, &group_id, &100),
+        Err(Ok(SavingsError::PlanLocked))
+    );
+}
+
+#[test]
+fn test_group_announcements_keep_latest_messages() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    let group_id = create_group_with_contribution(&env, &client, &creator, 0, 100);
+    assert_eq!(client.get_group_announcements(&group_id).len(), 0);
+
+    let max = crate::group::MAX_GROUP_ANNOUNCEMENTS;
+    for i in 0..max + 2 {
+        env.ledger().with_mut(|li| li.timestamp = i as u64);
+        client.post_group_announcement(
+            &creator,
+            &group_id,
+            &String::from_str(&env, "Round due Friday"),
+        );
+    }
+    let announcements = client.get_group_announcements(&group_id);
+    assert_eq!(announcements.len(), max);
+    assert_eq!(announcements.get(0).unwrap().timestamp, 2);
+    assert_eq!(
+        announcements.get(max - 1).unwrap().timestamp,
+        max as u64 + 1
+    );
+
+    assert_eq!(
+        client.try_post_group_announcement(&member, &group_id, &String::from_str(&env, "hi")),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_post_group_announcement(&creator, &group_id, &String::from_str(&env, "")),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+}
+
+fn create_capped_group(
+    env: &Env,
+    client: &NesteraContractClient,
+    creator: &Address,
+    max_members: u32,
+) -> u64 {
+    client.create_group_save(
+        creator,
+        &GroupMetadata {
+            title: String::from_str(env, "Circle"),
+            description: String::from_str(env, "Small circle"),
+            category: String::from_str(env, "savings"),
+        },
+        &5000,
+        &1,
+        &100,
+        &true,
+        &100,
+        &1_000,
+        &max_members,
+    )
+}
+
+#[test]
+fn test_join_rejected_when_group_full() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    let late = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    client.initialize_user(&late);
+
+    let group_id = create_capped_group(&env, &client, &creator, 2);
+    client.join_group_save(&member, &group_id);
+    assert_eq!(
+        client.try_join_group_save(&late, &group_id),
+        Err(Ok(SavingsError::GroupFull))
+    );
+
+    client.set_group_max_members(&creator, &group_id, &4);
+    assert_eq!(
+        client
+            .get_group_save_view(&member, &group_id)
+            .remaining_slots,
+        2
+    );
+    client.join_group_save(&late, &group_id);
+    assert_eq!(
+        client.get_group_save_view(&late, &group_id).remaining_slots,
+        1
+    );
+}
+
+#[test]
+fn test_max_members_can_only_be_raised_before_start() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+    let group_id = create_capped_group(&env, &client, &creator, 5);
+
+    assert_eq!(
+        client.try_set_group_max_members(&creator, &group_id, &4),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+    client.set_group_max_members(&creator, &group_id, &0);
+    // An uncapped group can't be capped afterwards
+    assert_eq!(
+        client.try_set_group_max_members(&creator, &group_id, &10),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+
+    let capped_id = create_capped_group(&env, &client, &creator, 5);
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    assert_eq!(
+        client.try_set_group_max_members(&creator, &capped_id, &6),
+        Err(Ok(SavingsError::TooLate))
+    );
+}
+
+#[test]
+fn test_public_groups_are_listed_by_category() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    client.initialize_user(&creator);
+
+    let first = create_public_group(&env, &client, &creator);
+    let private = client.create_group_save(
+        &creator,
+        &GroupMetadata {
+            title: String::from_str(&env, "Family"),
+            description: String::from_str(&env, "Invite only"),
+            category: String::from_str(&env, "savings"),
+        },
+        &5000,
+        &0,
+        &100,
+        &false,
+        &1,
+        &500,
+        &0,
+    );
+    let education = client.create_group_save(
+        &creator,
+        &GroupMetadata {
+            title: String::from_str(&env, "Tuition"),
+            description: String::from_str(&env, "School fees"),
+            category: String::from_str(&env, "education"),
+        },
+        &5000,
+        &0,
+        &100,
+        &true,
+        &1,
+        &500,
+        &0,
+    );
+    let second = create_public_group(&env, &client, &creator);
+
+    let (page, next) = client.list_public_groups(&Cursor {
+        offset: 0,
+        limit: 2,
+    });
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().id, first);
+    assert_eq!(page.get(1).unwrap().id, education);
+    let (page, next) = client.list_public_groups(&next.unwrap());
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().id, second);
+    assert_eq!(next, None);
+    assert!(page.iter().all(|g| g.id != private));
+
+    let (savings, _) = client.list_public_groups_by_category(
+        &String::from_str(&env, "savings"),
+        &Cursor {
+            offset: 0,
+            limit: 0,
+        },
+    );
+    assert_eq!(savings.len(), 2);
+    assert_eq!(savings.get(1).unwrap().id, second);
+    let (empty, next) = client.list_public_groups_by_category(
+        &String::from_str(&env, "housing"),
+        &Cursor {
+            offset: 0,
+            limit: 0,
+        },
+    );
+    assert_eq!(empty.len(), 0);
+    assert_eq!(next, None);
+}
+
+#[test]
+fn test_get_balances_bulk_reports_each_address() {
+    let (env, client, _admin) = setup();
+    let saver = Address::generate(&env);
+    let idle = Address::generate(&env);
+    let unknown = Address::generate(&env);
+    client.initialize_user(&saver);
+    client.initialize_user(&idle);
+    client.deposit_flexi(&saver, &700);
+    client.create_lock_save(&saver, &300, &86_400);
+
+    let mut users = soroban_sdk::vec![&env, saver.clone(), idle.clone(), unknown.clone()];
+    let balances = client.get_balances_bulk(&users);
+    assert_eq!(balances.len(), 3);
+
+    let first = balances.get(0).unwrap();
+    assert_eq!(first.user, saver);
+    assert!(first.exists);
+    assert_eq!((first.flexi_balance, first.total_balance), (700, 1_000));
+
+    let second = balances.get(1).unwrap();
+    assert!(second.exists);
+    assert_eq!((second.flexi_balance, second.total_balance), (0, 0));
+
+    let third = balances.get(2).unwrap();
+    assert_eq!(third.user, unknown);
+    assert!(!third.exists);
+
+    for _ in 0..crate::storage_types::MAX_PAGE_LIMIT {
+        users.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        client.try_get_balances_bulk(&users),
+        Err(Ok(SavingsError::AmountExceedsLimit))
+    );
+}
+
+#[test]
+fn test_creator_updates_group_before_start() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    let group_id = create_public_group(&env, &client, &creator);
+    client.join_group_save(&member, &group_id);
+
+    let housing = GroupMetadata {
+        title: String::from_str(&env, "Deposit Fund"),
+        description: String::from_str(&env, "Saving for a flat"),
+        category: String::from_str(&env, "housing"),
+    };
+    assert_eq!(
+        client.try_update_group_save(&member, &group_id, &housing, &8_000),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    client.update_group_save(&creator, &group_id, &housing, &8_000);
+
+    let group = env.as_contract(&client.address, || {
+        crate::group::get_group_save(&env, group_id).unwrap()
+    });
+    assert_eq!(group.title, String::from_str(&env, "Deposit Fund"));
+    assert_eq!(group.target_amount, 8_000);
+    let view = env.as_contract(&client.address, || {
+        crate::views::get_group_save(&env, member.clone(), group_id).unwrap()
+    });
+    assert_eq!(view.target_amount, 8_000);
+
+    let page = Cursor {
+        offset: 0,
+        limit: 0,
+    };
+    let (savings, _) =
+        client.list_public_groups_by_category(&String::from_str(&env, "savings"), &page);
+    assert_eq!(savings.len(), 0);
+    let (moved, _) =
+        client.list_public_groups_by_category(&String::from_str(&env, "housing"), &page);
+    assert_eq!(moved.get(0).unwrap().id, group_id);
+
+    // Moving back lists the group once in its first category
+    let savings_again = GroupMetadata {
+        category: String::from_str(&env, "savings"),
+        ..housing.clone()
+    };
+    client.update_group_save(&creator, &group_id, &savings_again, &8_000);
+    let (savings, _) =
+        client.list_public_groups_by_category(&String::from_str(&env, "savings"), &page);
+    assert_eq!(savings.len(), 1);
+    client.update_group_save(&creator, &group_id, &housing, &8_000);
+    let (moved, _) =
+        client.list_public_groups_by_category(&String::from_str(&env, "housing"), &page);
+    assert_eq!(moved.len(), 1);
+
+    env.ledger().with_mut(|li| li.timestamp = 1);
+    assert_eq!(
+        client.try_update_group_save(&creator, &group_id, &housing, &9_000),
+        Err(Ok(SavingsError::TooLate))
+    );
+}
+
+#[test]
+fn test_creator_cancels_group_without_contributions() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    let funded = create_public_group(&env, &client, &creator);
+    let empty = create_public_group(&env, &client, &creator);
+    client.join_group_save(&member, &funded);
+    client.join_group_save(&member, &empty);
+    client.contribute_to_group_save(&member, &funded, &100);
+
+    assert_eq!(
+        client.try_cancel_group_save(&creator, &funded),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+    assert_eq!(
+        client.try_cancel_group_save(&member, &empty),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+
+    client.cancel_group_save(&creator, &empty);
+    assert_eq!(
+        client.try_get_group_status(&empty),
+        Err(Ok(SavingsError::PlanNotFound))
+    );
+    let (groups, _) = client.list_public_groups(&Cursor {
+        offset: 0,
+        limit: 0,
+    });
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups.get(0).unwrap().id, funded);
+    let member_groups = env.as_contract(&client.address, || {
+        crate::group::get_user_groups(&env, &member)
+    });
+    assert_eq!(member_groups, soroban_sdk::vec![&env, funded]);
+}
+
+#[test]
+fn test_group_admins_invite_remove_and_announce() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let member = Address::generate(&env);
+    let guest = Address::generate(&env);
+    for user in [&creator, &manager, &member, &guest] {
+        client.initialize_user(user);
+    }
+    let group_id = client.create_group_save(
+        &creator,
+        &GroupMetadata {
+            title: String::from_str(&env, "Family"),
+            description: String::from_str(&env, "Private circle"),
+            category: String::from_str(&env, "savings"),
+        },
+        &5_000,
+        &1,
+        &100,
+        &false,
+        &0,
+        &1_000,
+        &0,
+    );
+
+    // Private: only invited users get in
+    assert_eq!(
+        client.try_join_group_save(&manager, &group_id),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+    client.invite_to_group(&creator, &group_id, &manager);
+    client.join_group_save(&manager, &group_id);
+
+    let message = String::from_str(&env, "Welcome");
+    assert_eq!(
+        client.try_post_group_announcement(&manager, &group_id, &message),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_group_admin(&manager, &group_id, &manager),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_group_admin(&creator, &group_id, &guest),
+        Err(Ok(SavingsError::NotGroupMember))
+    );
+    client.add_group_admin(&creator, &group_id, &manager);
+    assert_eq!(
+        client.get_group_admins(&group_id),
+        soroban_sdk::vec![&env, manager.clone()]
+    );
+
+    client.post_group_announcement(&manager, &group_id, &message);
+    client.invite_to_group(&manager, &group_id, &member);
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&member, &group_id, &300);
+
+    assert_eq!(
+        client.try_remove_group_member(&manager, &group_id, &creator),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+    assert_eq!(
+        client.remove_group_member(&manager, &group_id, &member),
+        300
+    );
+    assert_eq!(client.get_flexi_balance(&member), 300);
+    assert_eq!(
+        client.try_remove_group_member(&manager, &group_id, &member),
+        Err(Ok(SavingsError::NotGroupMember))
+    );
+    // The used-up invitation does not let them back in
+    assert_eq!(
+        client.try_join_group_save(&member, &group_id),
+        Err(Ok(SavingsError::InvalidGroupConfig))
+    );
+
+    client.remove_group_admin(&creator, &group_id, &manager);
+    assert_eq!(
+        client.try_invite_to_group(&manager, &group_id, &guest),
+        Err(Ok(SavingsError::Unauthorized))
+    );
+
+    // Once the group reaches its target nobody can be removed
+    client.contribute_to_group_save(&creator, &group_id, &5_000);
+    assert_eq!(
+        client.try_remove_group_member(&creator, &group_id, &manager),
+        Err(Ok(SavingsError::PlanCompleted))
+    );
+}
+
+#[test]
+fn test_groups_with_due_contributions() {
+    let (env, client, _admin) = setup();
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    // Fixed 50 per round
+    let group_id = create_group_with_contribution(&env, &client, &creator, 0, 50);
+    let unscheduled = create_group_with_contribution(&env, &client, &creator, 0, 50);
+    client.join_group_save(&member, &group_id);
+    client.join_group_save(&member, &unscheduled);
+    client.set_group_schedule(&creator, &group_id, &250);
+
+    // Round 0 is due at 250, outside a 100s window
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    assert_eq!(client.get_due_group_contributions(&member, &100).len(), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    let due = client.get_due_group_contributions(&member, &100);
+    assert_eq!(
+        due,
+        soroban_sdk::vec![
+            &env,
+            DueGroupContribution {
+                group_id,
+                round: 0,
+                due_time: 250,
+                amount_owed: 50,
+            }
+        ]
+    );
+
+    client.contribute_to_group_save(&member, &group_id, &50);
+    assert_eq!(client.get_due_group_contributions(&member, &100).len(), 0);
+}
+
+#[test]
+fn test_get_plan_covers_every_kind() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.initialize_user(&user);
+    client.initialize_user(&stranger);
+
+    client.deposit_flexi(&user, &300);
+    let lock_id = client.create_lock_save(&user, &200, &86_400);
+    let goal_id =
+        client.create_goal_save(&user, &soroban_sdk::Symbol::new(&env, "bike"), &1_000, &50);
+    let group_id = create_group_with_contribution(&env, &client, &user, 0, 50);
+
+    let plan = |kind, id| PlanRef { kind, id };
+    assert_eq!(
+        client.get_plan(&user, &plan(PlanKind::Flexi, 0)),
+        PlanDetail::Flexi(300)
+    );
+    assert_eq!(
+        client.get_plan(&user, &plan(PlanKind::Lock, lock_id)),
+        PlanDetail::Lock(client.get_lock_save_detail(&lock_id))
+    );
+    assert_eq!(
+        client.get_plan(&user, &plan(PlanKind::Goal, goal_id)),
+        PlanDetail::Goal(client.get_goal_save_detail(&goal_id))
+    );
+    match client.get_plan(&user, &plan(PlanKind::Group, group_id)) {
+        PlanDetail::Group(group) => assert_eq!(group.id, group_id),
+        other => panic!("unexpected plan {:?}", other),
+    }
+
+    // Other users' plans are not visible through it
+    assert_eq!(
+        client.try_get_plan(&stranger, &plan(PlanKind::Lock, lock_id)),
+        Err(Ok(SavingsError::PlanNotFound))
+    );
+    assert_eq!(
+        client.try_get_plan(&stranger, &plan(PlanKind::Group, group_id)),
+        Err(Ok(SavingsError::PlanNotFound))
+    );
+}
+
+#[test]
+fn test_group_interest_shared_pro_rata_on_refund() {
+    let env = Env::default();
+    let contract_id = env.register(NesteraContract, ());
+    let client = NesteraContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    env.mock_all_auths();
+    let token = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    client.initialize(
+        &admin,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Address::generate(&env),
+        &0,
+        &token,
+    );
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&admin, &35_000);
+    client.fund_yield_pool(&admin, &35_000);
+    client.set_group_rate(&admin, &1_000);
+
+    let year = 365 * 24 * 60 * 60;
+    let creator = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.initialize_user(&creator);
+    client.initialize_user(&member);
+    let group_id = client.create_group_save(
+        &creator,
+        &GroupMetadata {
+            title: String::from_str(&env, "Club"),
+            description: String::from_str(&env, "Savings club"),
+            category: String::from_str(&env, "community"),
+        },
+        &1_000_000,
+        &1,
+        &100,
+        &true,
+        &0,
+        &year,
+        &0,
+    );
+    client.join_group_save(&member, &group_id);
+    client.contribute_to_group_save(&creator, &group_id, &300_000);
+    client.contribute_to_group_save(&member, &group_id, &100_000);
+
+    // 10% a year on 400_000, split 3:1
+    env.ledger().with_mut(|li| li.timestamp = year / 2);
+    let accrued = |user: &Address| {
+        env.as_contract(&client.address, || {
+            crate::views::get_group_save(&env, user.clone(), group_id)
+                .unwrap()
+                .accrued_interest
+        })
+    };
+    assert_eq!(accrued(&creator), 15_000);
+    assert_eq!(accrued(&member), 5_000);
+
+    // The group missed its target; refunds pay interest as far as the pool
+    // covers it
+    env.ledger().with_mut(|li| li.timestamp = year);
+    assert_eq!(
+        client.refund_group_contribution(&creator, &group_id),
+        300_000
+    );
+    assert_eq!(client.get_flexi_balance(&creator), 330_000);
+    // The member's share stays reserved until it is paid
+    assert_eq!(client.get_yield_pool_status().outstanding_interest, 10_000);
+    assert_eq!(client.refund_group_members(&group_id, &0, &10), 1);
+    assert_eq!(client.get_flexi_balance(&member), 105_000);
+    assert_eq!(client.get_yield_pool_status().pool_balance, 0);
+    assert_eq!(client.get_yield_pool_status().outstanding_interest, 0);
+}
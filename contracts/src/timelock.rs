@@ -0,0 +1,305 @@
+use soroban_sdk::Env;
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::fees;
+use crate::goal;
+use crate::storage_types::{AdminAction, ConfigKey, DataKey, PendingAdminAction};
+use crate::strategy;
+
+/// Longest delay the timelock may be configured with (30 days)
+pub const MAX_TIMELOCK_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Returns the configured delay; 0 means critical actions apply immediately
+pub fn get_timelock(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::AdminTimelock))
+        .unwrap_or(0)
+}
+
+/// Fails with `TimelockRequired` if critical actions must be queued
+pub fn ensure_direct_allowed(env: &Env) -> Result<(), SavingsError> {
+    if get_timelock(env) > 0 {
+        return Err(SavingsError::TimelockRequired);
+    }
+    Ok(())
+}
+
+/// Checks an action's parameters without applying it
+pub fn validate(action: &AdminAction) -> Result<(), SavingsError> {
+    match action {
+        AdminAction::SetEarlyBreakFeeBps(bps) if *bps > 10_000 => Err(SavingsError::InvalidAmount),
+        AdminAction::SetTimelock(seconds) if *seconds > MAX_TIMELOCK_SECONDS => {
+            Err(SavingsError::InvalidAmount)
+        }
+        AdminAction::SetWithdrawalFee(fee) if fee.bps > 10_000 || fee.flat < 0 => {
+            Err(SavingsError::InvalidAmount)
+        }
+        AdminAction::SetFeeTiers(tiers) => fees::validate_fee_tiers(tiers),
+        AdminAction::SetKeeperTip(tip) if tip.flat < 0 || tip.max_per_ledger < 0 => {
+            Err(SavingsError::InvalidAmount)
+        }
+        AdminAction::SetStrategyYieldSplit(bps) if *bps > 10_000 => {
+            Err(SavingsError::InvalidAmount)
+        }
+        AdminAction::SetGoalBreakGrace(seconds) if *seconds > goal::MAX_GOAL_BREAK_GRACE => {
+            Err(SavingsError::InvalidTimestamp)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Performs the state change behind an admin action.
+///
+/// Callers are responsible for authorization and the timelock.
+pub fn apply(env: &Env, action: &AdminAction) -> Result<(), SavingsError> {
+    validate(action)?;
+    match action {
+        AdminAction::SetEarlyBreakFeeBps(bps) => env
+            .storage()
+            .instance()
+            .set(&DataKey::EarlyBreakFeeBps, bps),
+        AdminAction::SetFeeRecipient(recipient) => env
+            .storage()
+            .instance()
+            .set(&DataKey::FeeRecipient, recipient),
+        AdminAction::SetStrategy(strategy) => env
+            .storage()
+            .instance()
+            .set(&DataKey::Config(ConfigKey::Strategy), strategy),
+        AdminAction::SetToken(token) => env
+            .storage()
+            .instance()
+            .set(&DataKey::Config(ConfigKey::Token), token),
+        AdminAction::Upgrade(wasm_hash) => env
+            .deployer()
+            .update_current_contract_wasm(wasm_hash.clone()),
+        AdminAction::SetTimelock(seconds) => env
+            .storage()
+            .instance()
+            .set(&DataKey::Config(ConfigKey::AdminTimelock), seconds),
+        AdminAction::SetWithdrawalFee(fee) => {
+            env.storage().instance().set(&DataKey::WithdrawalFee, fee)
+        }
+        AdminAction::SetFeeTiers(tiers) => {
+            env.storage().instance().set(&DataKey::PlatformFee, tiers)
+        }
+        AdminAction::SetKeeperTip(tip) => fees::set_keeper_tip(env, tip)?,
+        AdminAction::SetFailedGoalPenalty(enabled) => goal::set_failed_goal_penalty(env, *enabled),
+        AdminAction::SetFeeExemption(exemption, exempt) => {
+            fees::set_fee_exemption(env, exemption, *exempt);
+        }
+        AdminAction::SetStrategyYieldSplit(bps) => strategy::set_yield_treasury_bps(env, *bps)?,
+        AdminAction::SetGoalBreakGrace(seconds) => goal::set_goal_break_grace(env, *seconds)?,
+    }
+    Ok(())
+}
+
+pub fn get_pending(env: &Env, action_id: u64) -> Option<PendingAdminAction> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingAdminAction(action_id))
+}
+
+/// Announces an action that becomes executable once the timelock elapses
+pub fn queue(env: &Env, action: AdminAction) -> Result<u64, SavingsError> {
+    validate(&action)?;
+
+    let id_key = DataKey::Config(ConfigKey::NextAdminActionId);
+    let action_id: u64 = env.storage().instance().get(&id_key).unwrap_or(1);
+    env.storage().instance().set(&id_key, &(action_id + 1));
+
+    let eta = env
+        .ledger()
+        .timestamp()
+        .checked_add(get_timelock(env))
+        .ok_or(SavingsError::Overflow)?;
+    let pending = PendingAdminAction { action, eta };
+    env.storage()
+        .persistent()
+        .set(&DataKey::PendingAdminAction(action_id), &pending);
+
+    events::publish(env, events::AdminActionQueued { action_id, pending });
+    Ok(action_id)
+}
+
+/// Applies a queued action whose eta has passed and removes it from the queue
+pub fn execute(env: &Env, action_id: u64) -> Result<AdminAction, SavingsError> {
+    let pending = get_pending(env, action_id).ok_or(SavingsError::ActionNotFound)?;
+    if env.ledger().timestamp() < pending.eta {
+        return Err(SavingsError::TooEarly);
+    }
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingAdminAction(action_id));
+    apply(env, &pending.action)?;
+
+    events::publish(
+        env,
+        events::AdminActionExecuted {
+            action_id,
+            action: pending.action.clone(),
+        },
+    );
+    Ok(pending.action)
+}
+
+pub fn cancel(env: &Env, action_id: u64) -> Result<(), SavingsError> {
+    let key = DataKey::PendingAdminAction(action_id);
+    if !env.storage().persistent().has(&key) {
+        return Err(SavingsError::ActionNotFound);
+    }
+    env.storage().persistent().remove(&key);
+
+    events::publish(env, events::AdminActionCancelled { action_id });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AdminAction, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+        (env, client, admin)
+    }
+
+    #[test]
+    fn test_direct_changes_allowed_without_timelock() {
+        let (env, client, admin) = setup();
+        let treasury = Address::generate(&env);
+        assert_eq!(client.get_admin_timelock(), 0);
+        client.set_fee_recipient(&admin, &treasury);
+        assert_eq!(client.get_fee_recipient(), Some(treasury));
+    }
+
+    #[test]
+    fn test_timelock_forces_queue_and_delay() {
+        let (env, client, admin) = setup();
+        client.set_admin_timelock(&admin, &3_600);
+        assert_eq!(client.get_admin_timelock(), 3_600);
+
+        let treasury = Address::generate(&env);
+        assert_eq!(
+            client.try_set_fee_recipient(&admin, &treasury).unwrap_err(),
+            Ok(SavingsError::TimelockRequired)
+        );
+        // Lowering a fee stays instant; raising it must be announced
+        client.set_early_break_fee_bps(&admin, &0);
+        assert_eq!(
+            client
+                .try_set_early_break_fee_bps(&admin, &500)
+                .unwrap_err(),
+            Ok(SavingsError::TimelockRequired)
+        );
+
+        let action_id =
+            client.queue_admin_action(&admin, &AdminAction::SetFeeRecipient(treasury.clone()));
+        let pending = client.get_pending_admin_action(&action_id).unwrap();
+        assert_eq!(pending.eta, env.ledger().timestamp() + 3_600);
+        assert_eq!(
+            client.try_execute_admin_action(&action_id).unwrap_err(),
+            Ok(SavingsError::TooEarly)
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += 3_600);
+        client.execute_admin_action(&action_id);
+        assert_eq!(client.get_fee_recipient(), Some(treasury));
+        assert_eq!(client.get_pending_admin_action(&action_id), None);
+        assert_eq!(
+            client.try_execute_admin_action(&action_id).unwrap_err(),
+            Ok(SavingsError::ActionNotFound)
+        );
+    }
+
+    #[test]
+    fn test_cancelled_action_cannot_execute() {
+        let (env, client, admin) = setup();
+        client.set_admin_timelock(&admin, &60);
+        let action_id = client.queue_admin_action(&admin, &AdminAction::SetEarlyBreakFeeBps(900));
+        client.cancel_admin_action(&admin, &action_id);
+
+        env.ledger().with_mut(|li| li.timestamp += 60);
+        assert_eq!(
+            client.try_execute_admin_action(&action_id).unwrap_err(),
+            Ok(SavingsError::ActionNotFound)
+        );
+        assert_eq!(client.get_early_break_fee_bps(), 0);
+    }
+
+    #[test]
+    fn test_timelock_change_is_itself_timelocked() {
+        let (env, client, admin) = setup();
+        client.set_admin_timelock(&admin, &60);
+        assert_eq!(
+            client.try_set_admin_timelock(&admin, &0).unwrap_err(),
+            Ok(SavingsError::TimelockRequired)
+        );
+
+        let action_id = client.queue_admin_action(&admin, &AdminAction::SetTimelock(0));
+        env.ledger().with_mut(|li| li.timestamp += 60);
+        client.execute_admin_action(&action_id);
+        assert_eq!(client.get_admin_timelock(), 0);
+    }
+
+    #[test]
+    fn test_goal_break_grace_can_be_shortened_through_queue() {
+        let (env, client, admin) = setup();
+        client.set_goal_break_grace(&admin, &7_200);
+        client.set_admin_timelock(&admin, &60);
+
+        // Lengthening the fee-free window stays instant; shortening it must be announced
+        client.set_goal_break_grace(&admin, &10_800);
+        assert_eq!(
+            client.try_set_goal_break_grace(&admin, &3_600).unwrap_err(),
+            Ok(SavingsError::TimelockRequired)
+        );
+
+        let action_id = client.queue_admin_action(&admin, &AdminAction::SetGoalBreakGrace(3_600));
+        env.ledger().with_mut(|li| li.timestamp += 60);
+        client.execute_admin_action(&action_id);
+        assert_eq!(client.get_goal_break_grace(), 3_600);
+    }
+
+    #[test]
+    fn test_queue_validates_and_requires_admin() {
+        let (env, client, admin) = setup();
+        assert_eq!(
+            client
+                .try_queue_admin_action(&admin, &AdminAction::SetEarlyBreakFeeBps(10_001))
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+        assert_eq!(
+            client
+                .try_queue_admin_action(&admin, &AdminAction::SetGoalBreakGrace(31 * 24 * 60 * 60))
+                .unwrap_err(),
+            Ok(SavingsError::InvalidTimestamp)
+        );
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client
+                .try_queue_admin_action(&stranger, &AdminAction::SetTimelock(10))
+                .unwrap_err(),
+            Ok(SavingsError::Unauthorized)
+        );
+    }
+}
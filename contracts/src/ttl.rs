@@ -0,0 +1,244 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::allowances;
+use crate::autosave;
+use crate::challenges;
+use crate::errors::SavingsError;
+use crate::events;
+use crate::goal;
+use crate::group;
+use crate::lock;
+use crate::storage_types::{DataKey, GroupKey, PlanKind, TtlRecordKey, UserKey, MAX_PAGE_LIMIT};
+
+/// Ledgers in a day at roughly five seconds per ledger
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Ledgers an extended entry is kept alive for
+pub const TTL_EXTEND_TO: u32 = 180 * DAY_IN_LEDGERS;
+
+/// Extends every entry stored for `user`: their account records, plan lists
+/// and the plans and schedules in them. Returns the number of entries
+/// extended.
+///
+/// Extending only costs the caller fees, so anyone may call this.
+pub fn extend_user_storage(env: &Env, user: &Address) -> u32 {
+    let mut extended = 0;
+    for key in user_keys(env, user).iter() {
+        if extend(env, &key) {
+            extended += 1;
+        }
+    }
+
+    events::publish(
+        env,
+        events::UserStorageExtended {
+            user: user.clone(),
+            extended,
+        },
+    );
+    extended
+}
+
+/// Extends a page of entries, plus the contract instance, on a keeper's
+/// behalf. Keys with no entry are skipped. Returns the number of entries
+/// extended.
+///
+/// # Errors
+/// * `AmountExceedsLimit` - If more than `MAX_PAGE_LIMIT` keys are given
+pub fn bulk_extend(env: &Env, keeper: Address, keys: Vec<DataKey>) -> Result<u32, SavingsError> {
+    keeper.require_auth();
+
+    if keys.len() > MAX_PAGE_LIMIT {
+        return Err(SavingsError::AmountExceedsLimit);
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_EXTEND_TO, TTL_EXTEND_TO);
+    let mut extended = 0;
+    for key in keys.iter() {
+        if extend(env, &key) {
+            extended += 1;
+        }
+    }
+
+    events::publish(env, events::StorageBulkExtended { keeper, extended });
+    Ok(extended)
+}
+
+/// Ledgers `key` is guaranteed to live for, counted from its last extension
+/// through this contract.
+///
+/// The ledger does not expose TTLs to contracts, so this is a lower bound.
+/// Returns `None` if the entry does not exist or no extension is on record.
+pub fn get_entry_ttl(env: &Env, key: &DataKey) -> Option<u32> {
+    if !env.storage().persistent().has(key) {
+        return None;
+    }
+    let extended_at: u32 = env.storage().temporary().get(&TtlRecordKey(key.clone()))?;
+    let live_until = extended_at.saturating_add(TTL_EXTEND_TO);
+    Some(live_until.saturating_sub(env.ledger().sequence()))
+}
+
+/// Extends a persistent entry to `TTL_EXTEND_TO` and records when, if it
+/// exists. Returns whether it did.
+fn extend(env: &Env, key: &DataKey) -> bool {
+    if !env.storage().persistent().has(key) {
+        return false;
+    }
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_EXTEND_TO, TTL_EXTEND_TO);
+
+    let record_key = TtlRecordKey(key.clone());
+    env.storage()
+        .temporary()
+        .set(&record_key, &env.ledger().sequence());
+    env.storage()
+        .temporary()
+        .extend_ttl(&record_key, TTL_EXTEND_TO, TTL_EXTEND_TO);
+    true
+}
+
+/// Every key that may hold an entry belonging to `user`
+fn user_keys(env: &Env, user: &Address) -> Vec<DataKey> {
+    let mut keys = Vec::from_array(
+        env,
+        [
+            DataKey::User(user.clone()),
+            DataKey::FlexiBalance(user.clone()),
+            DataKey::TotalBalance(user.clone()),
+            DataKey::UserCreatedAt(user.clone()),
+            DataKey::UserReputation(user.clone()),
+            DataKey::UserStreak(user.clone()),
+            DataKey::UserBadges(user.clone()),
+            DataKey::UserTotalDeposited(user.clone()),
+            DataKey::Referrer(user.clone()),
+            DataKey::ReferralStats(user.clone()),
+            DataKey::UserConsent(user.clone()),
+            DataKey::UserLockSaves(user.clone()),
+            DataKey::UserGoalSaves(user.clone()),
+            DataKey::UserGroupSaves(user.clone()),
+            DataKey::UserAutoSaves(user.clone()),
+            DataKey::UserState(UserKey::WithdrawWindow(user.clone())),
+            DataKey::UserState(UserKey::LifetimeFees(user.clone())),
+            DataKey::UserState(UserKey::Simulations(user.clone())),
+            DataKey::UserState(UserKey::LastMintTimestamp(user.clone())),
+            DataKey::UserState(UserKey::SponsoredNonce(user.clone())),
+            DataKey::UserState(UserKey::Custody(user.clone())),
+            DataKey::UserState(UserKey::GuardianWards(user.clone())),
+            DataKey::UserState(UserKey::Allowlisted(user.clone())),
+            DataKey::UserState(UserKey::TxHistory(user.clone())),
+            DataKey::UserState(UserKey::FlexiBuckets(user.clone())),
+            DataKey::UserState(UserKey::PlanSeq(user.clone(), PlanKind::Lock)),
+            DataKey::UserState(UserKey::PlanSeq(user.clone(), PlanKind::Goal)),
+            DataKey::UserState(UserKey::Challenges(user.clone())),
+            DataKey::UserState(UserKey::ClaimedChallenges(user.clone())),
+            DataKey::UserState(UserKey::LeaderboardOptOut(user.clone())),
+            DataKey::UserState(UserKey::Closed(user.clone())),
+            DataKey::UserState(UserKey::CampaignBonus(user.clone(), PlanKind::Flexi, 0)),
+            DataKey::UserState(UserKey::AllowanceSpenders(user.clone())),
+            DataKey::UserState(UserKey::LatestCheckpoint(user.clone())),
+        ],
+    );
+
+    for spender in allowances::get_spenders(env, user).iter() {
+        keys.push_back(DataKey::UserState(UserKey::DepositAllowance(
+            user.clone(),
+            spender,
+        )));
+    }
+
+    for challenge_id in challenges::get_enrolled_challenges(env, user).iter() {
+        keys.push_back(DataKey::UserState(UserKey::ChallengeEnrollment(
+            user.clone(),
+            challenge_id,
+        )));
+    }
+
+    for lock_id in lock::get_user_lock_saves(env, user).iter() {
+        keys.push_back(DataKey::LockSave(lock_id));
+        keys.push_back(DataKey::UserState(UserKey::CampaignBonus(
+            user.clone(),
+            PlanKind::Lock,
+            lock_id,
+        )));
+    }
+    for goal_id in goal::get_user_goal_saves(env, user).iter() {
+        keys.push_back(DataKey::GoalSave(goal_id));
+        keys.push_back(DataKey::GoalMilestones(goal_id));
+        keys.push_back(DataKey::UserState(UserKey::GoalFiatTarget(goal_id)));
+        keys.push_back(DataKey::UserState(UserKey::GoalCoOwners(goal_id)));
+        keys.push_back(DataKey::UserState(UserKey::GoalWithdrawPolicy(goal_id)));
+        keys.push_back(DataKey::UserState(UserKey::GoalContributors(goal_id)));
+        keys.push_back(DataKey::UserState(UserKey::CampaignBonus(
+            user.clone(),
+            PlanKind::Goal,
+            goal_id,
+        )));
+    }
+    for group_id in group::get_user_groups(env, user).iter() {
+        keys.push_back(DataKey::GroupMemberContribution(group_id, user.clone()));
+        keys.push_back(DataKey::Group(GroupKey::Interest(group_id)));
+        keys.push_back(DataKey::Group(GroupKey::IndexedCategories(group_id)));
+    }
+    for schedule_id in autosave::get_user_autosaves(env, user).iter() {
+        keys.push_back(DataKey::AutoSave(schedule_id));
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::testutils::{storage::Persistent as _, Address as _, Ledger};
+
+    #[test]
+    fn test_extend_user_storage_refreshes_plans() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        env.mock_all_auths();
+
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &500);
+        let lock_id = client.create_lock_save(&user, &1_000, &86_400);
+        let lock_key = DataKey::LockSave(lock_id);
+        assert_eq!(client.get_entry_ttl(&lock_key), None);
+
+        assert!(client.extend_user_storage(&user) >= 4);
+        env.as_contract(&contract_id, || {
+            assert!(env.storage().persistent().get_ttl(&lock_key) >= TTL_EXTEND_TO);
+        });
+        assert_eq!(client.get_entry_ttl(&lock_key), Some(TTL_EXTEND_TO));
+
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        assert_eq!(client.get_entry_ttl(&lock_key), Some(TTL_EXTEND_TO - 100));
+    }
+
+    #[test]
+    fn test_bulk_extend_skips_missing_and_caps_page() {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let user = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        env.mock_all_auths();
+
+        client.initialize_user(&user);
+        let keys = Vec::from_array(&env, [DataKey::User(user.clone()), DataKey::LockSave(99)]);
+        assert_eq!(client.bulk_extend(&keeper, &keys), 1);
+        assert_eq!(client.get_entry_ttl(&DataKey::LockSave(99)), None);
+
+        let mut page = Vec::new(&env);
+        for _ in 0..=MAX_PAGE_LIMIT {
+            page.push_back(DataKey::User(user.clone()));
+        }
+        assert_eq!(
+            client.try_bulk_extend(&keeper, &page),
+            Err(Ok(SavingsError::AmountExceedsLimit))
+        );
+    }
+}
@@ -1,8 +1,21 @@
 use soroban_sdk::{Address, Env};
 
-use crate::ensure_not_paused;
+use crate::allowances;
+use crate::autosave;
+use crate::campaigns;
+use crate::challenges;
+use crate::custody;
 use crate::errors::SavingsError;
-use crate::storage_types::{DataKey, User};
+use crate::events;
+use crate::fees;
+use crate::flexi;
+use crate::goal;
+use crate::group;
+use crate::guard::{self, Op};
+use crate::history;
+use crate::leaderboard;
+use crate::lock;
+use crate::storage_types::{ConfigKey, DataKey, PlanKind, ReferralStats, TxKind, User, UserKey};
 
 /// Check if a user exists in storage
 ///
@@ -48,21 +61,576 @@ pub fn get_user(env: &Env, user: &Address) -> Result<User, SavingsError> {
 /// # Authorization
 /// Requires authorization from the user being initialized
 pub fn initialize_user(env: &Env, user: Address) -> Result<(), SavingsError> {
-    ensure_not_paused(env)?;
+    guard::check(env, &user, Op::Manage, 0)?;
     // Require authorization from the user being initialized
     user.require_auth();
 
+    insert_user(env, user)
+}
+
+/// Creates the record of a new user with zero balances.
+///
+/// Callers are responsible for the guard check and authorization.
+pub(crate) fn insert_user(env: &Env, user: Address) -> Result<(), SavingsError> {
     // Check if user already exists
     if user_exists(env, &user) {
         return Err(SavingsError::UserAlreadyExists);
     }
+    if is_closed(env, &user) {
+        return Err(SavingsError::AccountClosed);
+    }
 
     // Create new user with default values
     let new_user = User::new();
 
     // Store user data
-    let key = DataKey::User(user);
+    let key = DataKey::User(user.clone());
     env.storage().persistent().set(&key, &new_user);
 
+    // Remember when the account was opened for tenure checks
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserCreatedAt(user), &env.ledger().timestamp());
+
+    Ok(())
+}
+
+/// Get how long a user's account has existed, in seconds
+///
+/// Accounts created before creation times were recorded report an age of 0.
+pub fn get_account_age(env: &Env, user: &Address) -> u64 {
+    let created_at: Option<u64> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::UserCreatedAt(user.clone()));
+    match created_at {
+        Some(created_at) => env.ledger().timestamp().saturating_sub(created_at),
+        None => 0,
+    }
+}
+
+/// Get a user's reputation score (defaults to 0)
+pub fn get_reputation(env: &Env, user: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserReputation(user.clone()))
+        .unwrap_or(0)
+}
+
+/// Set a user's reputation score
+///
+/// # Errors
+/// * `UserNotFound` - If the user has not been initialized
+pub fn set_reputation(env: &Env, user: &Address, score: u32) -> Result<(), SavingsError> {
+    if !user_exists(env, user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserReputation(user.clone()), &score);
+    Ok(())
+}
+
+/// Initialize a new user and record who referred them
+///
+/// # Errors
+/// * `InvalidReferrer` - If the user names themselves or an uninitialized referrer
+/// * `UserAlreadyExists` - If the user has already been initialized
+pub fn register_with_referrer(
+    env: &Env,
+    user: Address,
+    referrer: Address,
+) -> Result<(), SavingsError> {
+    if user == referrer || !user_exists(env, &referrer) {
+        return Err(SavingsError::InvalidReferrer);
+    }
+
+    initialize_user(env, user.clone())?;
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Referrer(user.clone()), &referrer);
+
+    let mut stats = get_referral_stats(env, &referrer);
+    stats.referral_count += 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::ReferralStats(referrer.clone()), &stats);
+
+    events::publish(env, events::ReferralRegistered { referrer, user });
+
     Ok(())
 }
+
+/// Get the address that referred a user, if any
+pub fn get_referrer(env: &Env, user: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Referrer(user.clone()))
+}
+
+/// Get a referrer's running totals (zeroed if they have never referred anyone)
+pub fn get_referral_stats(env: &Env, referrer: &Address) -> ReferralStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ReferralStats(referrer.clone()))
+        .unwrap_or_default()
+}
+
+/// Get the referral reward rate in basis points (defaults to 0)
+pub fn get_referral_reward_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Config(ConfigKey::ReferralRewardBps))
+        .unwrap_or(0)
+}
+
+/// Set the referral reward rate in basis points
+///
+/// # Errors
+/// * `InvalidAmount` - If `bps` exceeds 10,000
+pub fn set_referral_reward_bps(env: &Env, bps: u32) -> Result<(), SavingsError> {
+    if bps > 10_000 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::Config(ConfigKey::ReferralRewardBps), &bps);
+    Ok(())
+}
+
+/// Accrue the referrer's share of a deposit made by `user`
+///
+/// Does nothing if the user was not referred or no reward rate is set.
+pub fn accrue_referral_reward(env: &Env, user: &Address, amount: i128) {
+    let referrer = match get_referrer(env, user) {
+        Some(referrer) => referrer,
+        None => return,
+    };
+    let bps = get_referral_reward_bps(env);
+    if bps == 0 || amount <= 0 {
+        return;
+    }
+
+    let reward = amount.saturating_mul(bps as i128) / 10_000;
+    if reward == 0 {
+        return;
+    }
+
+    let mut stats = get_referral_stats(env, &referrer);
+    stats.pending_rewards = stats.pending_rewards.saturating_add(reward);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ReferralStats(referrer), &stats);
+}
+
+/// Pay out a referrer's pending rewards into their Flexi Save balance
+///
+/// Rewards are funded from the protocol fee balance; if it cannot cover the
+/// full amount, the remainder stays pending.
+///
+/// # Returns
+/// The amount paid out
+///
+/// # Errors
+/// * `InsufficientBalance` - If nothing is pending or the fee balance is empty
+/// * `NotAllowlisted` - If the payout fails the compliance gate for deposits
+pub fn claim_referral_rewards(env: &Env, referrer: Address) -> Result<i128, SavingsError> {
+    let mut stats = get_referral_stats(env, &referrer);
+    if stats.pending_rewards <= 0 {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    let fee_recipient: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::FeeRecipient)
+        .ok_or(SavingsError::InsufficientBalance)?;
+    let fee_balance = fees::get_fee_balance(env, &fee_recipient);
+
+    let payout = stats.pending_rewards.min(fee_balance);
+    if payout <= 0 {
+        return Err(SavingsError::InsufficientBalance);
+    }
+
+    // The payout lands in Flexi Save, so it passes the same checks as a deposit
+    guard::check(env, &referrer, Op::Deposit, payout)?;
+    referrer.require_auth();
+
+    fees::debit_treasury(env, &fee_recipient, payout)?;
+
+    stats.pending_rewards -= payout;
+    stats.total_claimed = stats
+        .total_claimed
+        .checked_add(payout)
+        .ok_or(SavingsError::Overflow)?;
+    env.storage()
+        .persistent()
+        .set(&DataKey::ReferralStats(referrer.clone()), &stats);
+
+    flexi::credit_flexi(env, &referrer, payout)?;
+    history::record(
+        env,
+        &referrer,
+        TxKind::Credit,
+        PlanKind::Flexi,
+        0,
+        payout,
+        0,
+    );
+
+    events::publish(env, events::ReferralRewardsClaimed { referrer, payout });
+
+    Ok(payout)
+}
+
+/// Whether the user closed their account and has not reopened it
+pub fn is_closed(env: &Env, user: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::UserState(UserKey::Closed(user.clone())))
+}
+
+/// Closes an emptied account and deletes the data kept about the user: their
+/// profile, balances, plan and group indexes, streak, badges, AutoSaves,
+/// referral links, history, challenge enrollments, leaderboard entries,
+/// custody and co-ownership records and deposit allowances. Unpaid campaign
+/// bonuses are forfeited. Withdrawn plans themselves are kept, as are nonces
+/// and plan ID sequences so old payloads cannot be replayed.
+///
+/// The address is marked closed, so initializing it again takes an explicit
+/// `reopen_account`. A custodial account needs its guardian's signature until
+/// it is released.
+///
+/// # Errors
+/// * `UserNotFound` - If the user is not initialized
+/// * `AccountNotEmpty` - If any balance is non-zero, a Lock or Goal Save is
+///   still open, the user is in a group, has referral rewards pending or is
+///   the guardian of a custodial account
+pub fn close_account(env: &Env, user: Address) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    let user_data = get_user(env, &user)?;
+    if user_data.total_balance != 0
+        || flexi::get_flexi_balance(env, user.clone())? != 0
+        || lock::count_open_lock_saves(env, &user) > 0
+        || goal::count_open_goal_saves(env, &user) > 0
+        || !group::get_user_groups(env, &user).is_empty()
+        || get_referral_stats(env, &user).pending_rewards > 0
+        || !custody::get_guardian_wards(env, &user).is_empty()
+    {
+        return Err(SavingsError::AccountNotEmpty);
+    }
+    custody::require_guardian_consent(env, &user);
+
+    autosave::remove_user_autosaves(env, &user);
+    campaigns::forfeit_plan(env, &user, PlanKind::Flexi, 0)?;
+    for lock_id in lock::get_user_lock_saves(env, &user).iter() {
+        campaigns::forfeit_plan(env, &user, PlanKind::Lock, lock_id)?;
+    }
+    for goal_id in goal::get_user_goal_saves(env, &user).iter() {
+        campaigns::forfeit_plan(env, &user, PlanKind::Goal, goal_id)?;
+    }
+    goal::remove_co_ownerships(env, &user);
+    custody::remove_custody(env, &user);
+    challenges::remove_user(env, &user);
+    leaderboard::remove_user(env, &user);
+    allowances::remove_all(env, &user);
+    let storage = env.storage().persistent();
+    for key in [
+        DataKey::User(user.clone()),
+        DataKey::FlexiBalance(user.clone()),
+        DataKey::TotalBalance(user.clone()),
+        DataKey::UserGroupSaves(user.clone()),
+        DataKey::UserLockSaves(user.clone()),
+        DataKey::UserGoalSaves(user.clone()),
+        DataKey::UserCreatedAt(user.clone()),
+        DataKey::UserReputation(user.clone()),
+        DataKey::UserStreak(user.clone()),
+        DataKey::UserBadges(user.clone()),
+        DataKey::UserTotalDeposited(user.clone()),
+        DataKey::Referrer(user.clone()),
+        DataKey::ReferralStats(user.clone()),
+        DataKey::UserConsent(user.clone()),
+        DataKey::UserState(UserKey::WithdrawWindow(user.clone())),
+        DataKey::UserState(UserKey::LifetimeFees(user.clone())),
+        DataKey::UserState(UserKey::Simulations(user.clone())),
+        DataKey::UserState(UserKey::LeaderboardOptOut(user.clone())),
+        DataKey::UserState(UserKey::TxHistory(user.clone())),
+        DataKey::UserState(UserKey::FlexiBuckets(user.clone())),
+        DataKey::UserState(UserKey::LatestCheckpoint(user.clone())),
+    ] {
+        storage.remove(&key);
+    }
+    storage.set(
+        &DataKey::UserState(UserKey::Closed(user.clone())),
+        &env.ledger().timestamp(),
+    );
+
+    events::publish(env, events::AccountClosed { user });
+    Ok(())
+}
+
+/// Reinitializes a closed account with zero balances
+///
+/// # Errors
+/// * `UserNotFound` - If the account is not closed
+pub fn reopen_account(env: &Env, user: Address) -> Result<(), SavingsError> {
+    guard::check(env, &user, Op::Manage, 0)?;
+    user.require_auth();
+
+    if !is_closed(env, &user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::UserState(UserKey::Closed(user.clone())));
+    insert_user(env, user.clone())?;
+
+    events::publish(env, events::AccountReopened { user });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage_types::{Cursor, DataKey, LeaderboardKind, PlanKind, UserKey};
+    use crate::{ComplianceMode, NesteraContract, NesteraContractClient, SavingsError};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec, Address, BytesN, Env, Symbol,
+    };
+
+    fn setup() -> (Env, NesteraContractClient<'static>, Address, Address) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &[1u8; 32]);
+
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let referrer = Address::generate(&env);
+        client.initialize_user(&referrer);
+
+        (env, client, admin, referrer)
+    }
+
+    #[test]
+    fn test_register_with_referrer_records_relationship() {
+        let (env, client, _admin, referrer) = setup();
+        let user = Address::generate(&env);
+
+        client.register_with_referrer(&user, &referrer);
+
+        assert!(client.user_exists(&user));
+        assert_eq!(client.get_referrer(&user), Some(referrer.clone()));
+        assert_eq!(client.get_referral_stats(&referrer).referral_count, 1);
+    }
+
+    #[test]
+    fn test_register_with_invalid_referrer_fails() {
+        let (env, client, _admin, _referrer) = setup();
+        let user = Address::generate(&env);
+        let stranger = Address::generate(&env);
+
+        assert_eq!(
+            client.try_register_with_referrer(&user, &user).unwrap_err(),
+            Ok(SavingsError::InvalidReferrer)
+        );
+        assert_eq!(
+            client
+                .try_register_with_referrer(&user, &stranger)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidReferrer)
+        );
+        assert!(!client.user_exists(&user));
+    }
+
+    #[test]
+    fn test_referral_rewards_accrue_and_are_paid_from_fees() {
+        let (env, client, admin, referrer) = setup();
+        let treasury = Address::generate(&env);
+        client.set_fee_recipient(&admin, &treasury);
+        client.set_early_break_fee_bps(&admin, &1_000);
+        client.set_referral_reward_bps(&admin, &500);
+
+        let user = Address::generate(&env);
+        client.register_with_referrer(&user, &referrer);
+        client.deposit_flexi(&user, &1_000);
+        assert_eq!(client.get_referral_stats(&referrer).pending_rewards, 50);
+
+        // No fees collected yet, so nothing can be paid out
+        assert_eq!(
+            client.try_claim_referral_rewards(&referrer).unwrap_err(),
+            Ok(SavingsError::InsufficientBalance)
+        );
+
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "trip"), &5_000, &1_000);
+        client.break_goal_save(&user, &goal_id);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 100);
+        assert_eq!(client.get_referral_stats(&referrer).pending_rewards, 100);
+
+        assert_eq!(client.claim_referral_rewards(&referrer), 100);
+        let stats = client.get_referral_stats(&referrer);
+        assert_eq!(stats.pending_rewards, 0);
+        assert_eq!(stats.total_claimed, 100);
+        assert_eq!(client.get_flexi_balance(&referrer), 100);
+        assert_eq!(client.get_protocol_fee_balance(&treasury), 0);
+    }
+
+    #[test]
+    fn test_claim_is_capped_by_fee_balance() {
+        let (env, client, admin, referrer) = setup();
+        let treasury = Address::generate(&env);
+        client.set_fee_recipient(&admin, &treasury);
+        client.set_early_break_fee_bps(&admin, &100);
+        client.set_referral_reward_bps(&admin, &1_000);
+
+        let user = Address::generate(&env);
+        client.register_with_referrer(&user, &referrer);
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "trip"), &5_000, &1_000);
+        client.break_goal_save(&user, &goal_id);
+
+        // Payouts pass the compliance gate deposits do
+        client.set_compliance_mode(&admin, &ComplianceMode::Allowlist, &0);
+        assert_eq!(
+            client.try_claim_referral_rewards(&referrer).unwrap_err(),
+            Ok(SavingsError::NotAllowlisted)
+        );
+        client.add_to_allowlist(&admin, &referrer);
+
+        assert_eq!(client.claim_referral_rewards(&referrer), 10);
+        assert_eq!(client.get_referral_stats(&referrer).pending_rewards, 90);
+    }
+
+    #[test]
+    fn test_set_referral_reward_bps_rejects_over_100_percent() {
+        let (_env, client, admin, _referrer) = setup();
+        assert_eq!(
+            client
+                .try_set_referral_reward_bps(&admin, &10_001)
+                .unwrap_err(),
+            Ok(SavingsError::InvalidAmount)
+        );
+    }
+
+    #[test]
+    fn test_close_account_requires_empty_account_and_blocks_reinit() {
+        let (env, client, _admin, _referrer) = setup();
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        client.deposit_flexi(&user, &100);
+        let lock_id = client.create_lock_save(&user, &50, &100);
+        let schedule_id = client.create_autosave(&user, &10, &86_400, &0);
+
+        assert_eq!(
+            client.try_close_account(&user),
+            Err(Ok(SavingsError::AccountNotEmpty))
+        );
+        client.withdraw_flexi(&user, &100);
+        assert_eq!(
+            client.try_close_account(&user),
+            Err(Ok(SavingsError::AccountNotEmpty))
+        );
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.withdraw_lock_save(&user, &lock_id);
+
+        client.close_account(&user);
+        assert_eq!(
+            client.try_get_user(&user),
+            Err(Ok(SavingsError::UserNotFound))
+        );
+        assert_eq!(client.get_user_lock_saves(&user).len(), 0);
+        assert_eq!(client.get_autosave(&schedule_id), None);
+        assert_eq!(
+            client.try_initialize_user(&user),
+            Err(Ok(SavingsError::AccountClosed))
+        );
+
+        client.reopen_account(&user);
+        assert_eq!(client.get_user(&user).total_balance, 0);
+        assert_eq!(
+            client.try_reopen_account(&user),
+            Err(Ok(SavingsError::UserNotFound))
+        );
+    }
+
+    #[test]
+    fn test_close_account_leaves_no_user_storage() {
+        let (env, client, _admin, _referrer) = setup();
+        let guardian = Address::generate(&env);
+        let minor = Address::generate(&env);
+        let friend = Address::generate(&env);
+        let spender = Address::generate(&env);
+        client.initialize_user(&guardian);
+        client.initialize_user(&friend);
+        client.initialize_custodial_user(&guardian, &minor, &1_000_000);
+
+        let challenge_id = client.create_challenge(
+            &friend,
+            &Symbol::new(&env, "weekly"),
+            &100,
+            &vec![&env, 10],
+            &0,
+        );
+        client.enroll_in_challenge(&minor, &challenge_id);
+        client.grant_allowance(&minor, &spender, &500, &1_000);
+        client.deposit_flexi(&minor, &100);
+        let goal_id = client.create_goal_save(&friend, &Symbol::new(&env, "trip"), &100, &0);
+        client.add_goal_co_owner(&friend, &goal_id, &minor);
+        client.break_goal_save(&friend, &goal_id);
+        client.withdraw_flexi(&minor, &100);
+
+        assert_eq!(
+            client.try_close_account(&guardian),
+            Err(Ok(SavingsError::AccountNotEmpty))
+        );
+        client.close_account(&minor);
+        assert!(env.auths().iter().any(|(signer, _)| *signer == guardian));
+
+        assert_eq!(client.get_guardian_wards(&guardian).len(), 0);
+        assert_eq!(client.get_goal_co_owners(&goal_id).len(), 0);
+        assert_eq!(
+            client.get_leaderboard(&LeaderboardKind::TotalSaved).len(),
+            0
+        );
+        let (standings, _) = client.get_challenge_leaderboard(
+            &challenge_id,
+            &Cursor {
+                offset: 0,
+                limit: 0,
+            },
+        );
+        assert_eq!(standings.len(), 0);
+
+        // Only the closed marker is left to keep alive
+        assert_eq!(client.extend_user_storage(&minor), 1);
+        env.as_contract(&client.address, || {
+            let storage = env.storage().persistent();
+            for key in [
+                DataKey::UserState(UserKey::Custody(minor.clone())),
+                DataKey::UserState(UserKey::GuardianWards(guardian.clone())),
+                DataKey::UserState(UserKey::ChallengeEnrollment(minor.clone(), challenge_id)),
+                DataKey::UserState(UserKey::Challenges(minor.clone())),
+                DataKey::UserState(UserKey::ClaimedChallenges(minor.clone())),
+                DataKey::UserState(UserKey::DepositAllowance(minor.clone(), spender.clone())),
+                DataKey::UserState(UserKey::AllowanceSpenders(minor.clone())),
+                DataKey::UserState(UserKey::CampaignBonus(minor.clone(), PlanKind::Flexi, 0)),
+                DataKey::UserState(UserKey::GoalCoOwners(goal_id)),
+                DataKey::UserState(UserKey::TxHistory(minor.clone())),
+                DataKey::UserGoalSaves(minor.clone()),
+                DataKey::User(minor.clone()),
+            ] {
+                assert!(!storage.has(&key));
+            }
+        });
+    }
+}
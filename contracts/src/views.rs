@@ -1,60 +1,90 @@
 use crate::errors::SavingsError;
 use crate::storage_types::{
-    DataKey, GoalSaveView, GroupSaveView, LockSaveView, PlanType, SavingsPlan, User,
+    DataKey, GoalSave, GoalSaveView, GroupSave, GroupSaveView, LockSave, LockSaveView, PlanDetail,
+    PlanKind, PlanRef, User, UserBalances, MAX_PAGE_LIMIT,
 };
 use soroban_sdk::{Address, Env, Vec};
 
 // ===========================================================================
-// Helper Functions to Convert SavingsPlan to Specific Types
+// Helper Functions to Convert Plans to View Types
 // ===========================================================================
 
-fn to_lock_save(plan: &SavingsPlan) -> Option<LockSaveView> {
-    match plan.plan_type {
-        PlanType::Lock(locked_until) => Some(LockSaveView {
-            plan_id: plan.plan_id,
-            balance: plan.balance,
-            start_time: plan.start_time,
-            locked_until,
-            interest_rate: plan.interest_rate,
-            is_withdrawn: plan.is_withdrawn,
-        }),
-        _ => None,
+fn to_lock_save(lock_save: &LockSave) -> LockSaveView {
+    LockSaveView {
+        plan_id: lock_save.id,
+        balance: lock_save.amount,
+        start_time: lock_save.start_time,
+        locked_until: lock_save.maturity_time,
+        interest_rate: lock_save.interest_rate,
+        is_withdrawn: lock_save.is_withdrawn,
     }
 }
 
-fn to_goal_save(plan: &SavingsPlan) -> Option<GoalSaveView> {
-    match &plan.plan_type {
-        PlanType::Goal(goal_name, target_amount, contribution_type) => Some(GoalSaveView {
-            plan_id: plan.plan_id,
-            balance: plan.balance,
-            target_amount: *target_amount,
-            start_time: plan.start_time,
-            interest_rate: plan.interest_rate,
-            is_completed: plan.is_completed,
-            contribution_type: *contribution_type,
-            goal_name: goal_name.clone(),
-        }),
-        _ => None,
+fn to_goal_save(goal_save: &GoalSave) -> GoalSaveView {
+    GoalSaveView {
+        plan_id: goal_save.id,
+        balance: goal_save.current_amount,
+        target_amount: goal_save.target_amount,
+        start_time: goal_save.start_time,
+        interest_rate: goal_save.interest_rate,
+        is_completed: goal_save.is_completed,
+        goal_name: goal_save.goal_name.clone(),
     }
 }
 
-fn to_group_save(plan: &SavingsPlan) -> Option<GroupSaveView> {
-    match plan.plan_type {
-        PlanType::Group(group_id, is_public, contribution_type, target_amount) => {
-            Some(GroupSaveView {
-                plan_id: plan.plan_id,
-                balance: plan.balance,
-                target_amount,
-                start_time: plan.start_time,
-                interest_rate: plan.interest_rate,
-                is_completed: plan.is_completed,
-                is_public,
-                contribution_type,
-                group_id,
-            })
+fn to_group_save(env: &Env, group: &GroupSave, user: &Address) -> GroupSaveView {
+    GroupSaveView {
+        plan_id: group.id,
+        balance: crate::group::get_member_contribution(env, group.id, user),
+        target_amount: group.target_amount,
+        start_time: group.start_time,
+        is_completed: group.is_completed,
+        is_public: group.is_public,
+        contribution_type: group.contribution_type,
+        group_id: group.id,
+        remaining_slots: crate::group::remaining_slots(group),
+        status: crate::group::get_group_status(env, group),
+        accrued_interest: crate::group::get_member_interest(env, group, user),
+    }
+}
+
+fn user_lock_saves(env: &Env, user: &Address) -> Result<Vec<LockSave>, SavingsError> {
+    if !crate::users::user_exists(env, user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    let mut lock_saves = Vec::new(env);
+    for lock_id in crate::lock::get_user_lock_saves(env, user).iter() {
+        if let Some(lock_save) = crate::lock::get_lock_save(env, lock_id) {
+            lock_saves.push_back(lock_save);
+        }
+    }
+    Ok(lock_saves)
+}
+
+fn user_goal_saves(env: &Env, user: &Address) -> Result<Vec<GoalSave>, SavingsError> {
+    if !crate::users::user_exists(env, user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    let mut goal_saves = Vec::new(env);
+    for goal_id in crate::goal::get_user_goal_saves(env, user).iter() {
+        if let Some(goal_save) = crate::goal::get_goal_save(env, goal_id) {
+            goal_saves.push_back(goal_save);
+        }
+    }
+    Ok(goal_saves)
+}
+
+fn user_group_saves(env: &Env, user: &Address) -> Result<Vec<GroupSave>, SavingsError> {
+    if !crate::users::user_exists(env, user) {
+        return Err(SavingsError::UserNotFound);
+    }
+    let mut groups = Vec::new(env);
+    for group_id in crate::group::get_user_groups(env, user).iter() {
+        if let Some(group) = crate::group::get_group_save(env, group_id) {
+            groups.push_back(group);
         }
-        _ => None,
     }
+    Ok(groups)
 }
 
 // ===========================================================================
@@ -65,25 +95,10 @@ pub fn get_user_ongoing_lock_saves(
     env: &Env,
     user: Address,
 ) -> Result<Vec<LockSaveView>, SavingsError> {
-    if !crate::users::user_exists(env, &user) {
-        return Err(SavingsError::UserNotFound);
-    }
-
-    let user_data: User = crate::users::get_user(env, &user)?;
     let mut ongoing_plans = Vec::new(env);
-
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
-
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(lock_save) = to_lock_save(&plan) {
-                // Ongoing means not withdrawn (and potentially check if locked_until > now,
-                // but "ongoing" usually implies active/fresh. Let's assume active = not withdrawn)
-                if !lock_save.is_withdrawn {
-                    ongoing_plans.push_back(lock_save);
-                }
-            }
+    for lock_save in user_lock_saves(env, &user)?.iter() {
+        if !lock_save.is_withdrawn {
+            ongoing_plans.push_back(to_lock_save(&lock_save));
         }
     }
     Ok(ongoing_plans)
@@ -93,39 +108,20 @@ pub fn get_user_matured_lock_saves(
     env: &Env,
     user: Address,
 ) -> Result<Vec<LockSaveView>, SavingsError> {
-    if !crate::users::user_exists(env, &user) {
-        return Err(SavingsError::UserNotFound);
-    }
-
-    let user_data: User = crate::users::get_user(env, &user)?;
     let mut matured_plans = Vec::new(env);
-    let current_time = env.ledger().timestamp();
-
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
-
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(lock_save) = to_lock_save(&plan) {
-                // Matured means lock time has passed
-                if current_time >= lock_save.locked_until && !lock_save.is_withdrawn {
-                    matured_plans.push_back(lock_save);
-                }
-            }
+    for lock_save in user_lock_saves(env, &user)?.iter() {
+        if !lock_save.is_withdrawn && crate::lock::is_unlocked(env, &lock_save) {
+            matured_plans.push_back(to_lock_save(&lock_save));
         }
     }
     Ok(matured_plans)
 }
 
 pub fn get_lock_save(env: &Env, user: Address, lock_id: u64) -> Result<LockSaveView, SavingsError> {
-    let key = DataKey::SavingsPlan(user, lock_id);
-    let plan = env
-        .storage()
-        .persistent()
-        .get::<DataKey, SavingsPlan>(&key)
-        .ok_or(SavingsError::PlanNotFound)?;
-
-    to_lock_save(&plan).ok_or(SavingsError::PlanNotFound) // Or some mismatched type error? PlanNotFound seems safe enough
+    crate::lock::get_lock_save(env, lock_id)
+        .filter(|lock_save| lock_save.owner == user)
+        .map(|lock_save| to_lock_save(&lock_save))
+        .ok_or(SavingsError::PlanNotFound)
 }
 
 // ===========================================================================
@@ -136,23 +132,10 @@ pub fn get_user_live_goal_saves(
     env: &Env,
     user: Address,
 ) -> Result<Vec<GoalSaveView>, SavingsError> {
-    if !crate::users::user_exists(env, &user) {
-        return Err(SavingsError::UserNotFound);
-    }
-
-    let user_data: User = crate::users::get_user(env, &user)?;
     let mut live_plans = Vec::new(env);
-
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
-
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(goal_save) = to_goal_save(&plan) {
-                if !goal_save.is_completed {
-                    live_plans.push_back(goal_save);
-                }
-            }
+    for goal_save in user_goal_saves(env, &user)?.iter() {
+        if !goal_save.is_completed {
+            live_plans.push_back(to_goal_save(&goal_save));
         }
     }
     Ok(live_plans)
@@ -162,37 +145,20 @@ pub fn get_user_completed_goal_saves(
     env: &Env,
     user: Address,
 ) -> Result<Vec<GoalSaveView>, SavingsError> {
-    if !crate::users::user_exists(env, &user) {
-        return Err(SavingsError::UserNotFound);
-    }
-
-    let user_data: User = crate::users::get_user(env, &user)?;
     let mut completed_plans = Vec::new(env);
-
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
-
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(goal_save) = to_goal_save(&plan) {
-                if goal_save.is_completed {
-                    completed_plans.push_back(goal_save);
-                }
-            }
+    for goal_save in user_goal_saves(env, &user)?.iter() {
+        if goal_save.is_completed {
+            completed_plans.push_back(to_goal_save(&goal_save));
         }
     }
     Ok(completed_plans)
 }
 
 pub fn get_goal_save(env: &Env, user: Address, goal_id: u64) -> Result<GoalSaveView, SavingsError> {
-    let key = DataKey::SavingsPlan(user, goal_id);
-    let plan = env
-        .storage()
-        .persistent()
-        .get::<DataKey, SavingsPlan>(&key)
-        .ok_or(SavingsError::PlanNotFound)?;
-
-    to_goal_save(&plan).ok_or(SavingsError::PlanNotFound)
+    crate::goal::get_goal_save(env, goal_id)
+        .filter(|goal_save| goal_save.owner == user)
+        .map(|goal_save| to_goal_save(&goal_save))
+        .ok_or(SavingsError::PlanNotFound)
 }
 
 // ===========================================================================
@@ -203,23 +169,10 @@ pub fn get_user_live_group_saves(
     env: &Env,
     user: Address,
 ) -> Result<Vec<GroupSaveView>, SavingsError> {
-    if !crate::users::user_exists(env, &user) {
-        return Err(SavingsError::UserNotFound);
-    }
-
-    let user_data: User = crate::users::get_user(env, &user)?;
     let mut live_plans = Vec::new(env);
-
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
-
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(group_save) = to_group_save(&plan) {
-                if !group_save.is_completed {
-                    live_plans.push_back(group_save);
-                }
-            }
+    for group in user_group_saves(env, &user)?.iter() {
+        if !group.is_completed {
+            live_plans.push_back(to_group_save(env, &group, &user));
         }
     }
     Ok(live_plans)
@@ -229,23 +182,10 @@ pub fn get_user_completed_group_saves(
     env: &Env,
     user: Address,
 ) -> Result<Vec<GroupSaveView>, SavingsError> {
-    if !crate::users::user_exists(env, &user) {
-        return Err(SavingsError::UserNotFound);
-    }
-
-    let user_data: User = crate::users::get_user(env, &user)?;
     let mut completed_plans = Vec::new(env);
-
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
-
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let Some(group_save) = to_group_save(&plan) {
-                if group_save.is_completed {
-                    completed_plans.push_back(group_save);
-                }
-            }
+    for group in user_group_saves(env, &user)?.iter() {
+        if group.is_completed {
+            completed_plans.push_back(to_group_save(env, &group, &user));
         }
     }
     Ok(completed_plans)
@@ -256,14 +196,11 @@ pub fn get_group_save(
     user: Address,
     group_id: u64,
 ) -> Result<GroupSaveView, SavingsError> {
-    let key = DataKey::SavingsPlan(user, group_id);
-    let plan = env
-        .storage()
-        .persistent()
-        .get::<DataKey, SavingsPlan>(&key)
-        .ok_or(SavingsError::PlanNotFound)?;
-
-    to_group_save(&plan).ok_or(SavingsError::PlanNotFound)
+    if !is_group_member(env, group_id, user.clone())? {
+        return Err(SavingsError::PlanNotFound);
+    }
+    let group = crate::group::get_group_save(env, group_id).ok_or(SavingsError::PlanNotFound)?;
+    Ok(to_group_save(env, &group, &user))
 }
 
 // ===========================================================================
@@ -274,23 +211,7 @@ pub fn is_group_member(env: &Env, group_id: u64, user: Address) -> Result<bool,
     if !crate::users::user_exists(env, &user) {
         return Ok(false);
     }
-
-    let user_data: User = crate::users::get_user(env, &user)?;
-
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
-
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let PlanType::Group(valid_group_id, _, _, _) = plan.plan_type {
-                if valid_group_id == group_id {
-                    return Ok(true);
-                }
-            }
-        }
-    }
-
-    Ok(false)
+    Ok(crate::group::get_group_members(env, group_id).contains(&user))
 }
 
 pub fn get_group_member_contribution(
@@ -301,21 +222,77 @@ pub fn get_group_member_contribution(
     if !crate::users::user_exists(env, &user) {
         return Err(SavingsError::UserNotFound);
     }
+    if !crate::group::get_group_members(env, group_id).contains(&user) {
+        return Err(SavingsError::PlanNotFound);
+    }
+    Ok(crate::group::get_member_contribution(env, group_id, &user))
+}
 
-    let user_data: User = crate::users::get_user(env, &user)?;
+// ===========================================================================
+// Cross-Plan Views
+// ===========================================================================
 
-    for i in 1..=user_data.savings_count {
-        let plan_id = i as u64;
-        let key = DataKey::SavingsPlan(user.clone(), plan_id);
+/// Any one of the user's plans, looked up by kind and ID. A goal is the
+/// user's if they own or co-own it, and a group if they are a member.
+///
+/// # Errors
+/// * `UserNotFound` - If a Flexi Save is requested for an unknown user
+/// * `PlanNotFound` - If the plan does not exist or is not the user's
+pub fn get_plan(env: &Env, user: Address, plan_ref: PlanRef) -> Result<PlanDetail, SavingsError> {
+    match plan_ref.kind {
+        PlanKind::Flexi => Ok(PlanDetail::Flexi(crate::flexi::get_flexi_balance(
+            env, user,
+        )?)),
+        PlanKind::Lock => crate::lock::get_lock_save(env, plan_ref.id)
+            .filter(|lock_save| lock_save.owner == user)
+            .map(PlanDetail::Lock)
+            .ok_or(SavingsError::PlanNotFound),
+        PlanKind::Goal => crate::goal::get_goal_save(env, plan_ref.id)
+            .filter(|goal_save| {
+                goal_save.owner == user
+                    || crate::goal::get_goal_co_owners(env, plan_ref.id).contains(&user)
+            })
+            .map(PlanDetail::Goal)
+            .ok_or(SavingsError::PlanNotFound),
+        PlanKind::Group => crate::group::get_group_save(env, plan_ref.id)
+            .filter(|_| crate::group::get_group_members(env, plan_ref.id).contains(&user))
+            .map(PlanDetail::Group)
+            .ok_or(SavingsError::PlanNotFound),
+    }
+}
 
-        if let Some(plan) = env.storage().persistent().get::<DataKey, SavingsPlan>(&key) {
-            if let PlanType::Group(valid_group_id, _, _, _) = plan.plan_type {
-                if valid_group_id == group_id {
-                    return Ok(plan.balance);
-                }
-            }
-        }
+// ===========================================================================
+// Bulk Views
+// ===========================================================================
+
+/// Flexi and total balance of each address, in input order, so the backend
+/// can reconcile many users in one call. Unknown addresses are reported with
+/// `exists: false` rather than failing the whole call.
+///
+/// # Errors
+/// * `AmountExceedsLimit` - If more than `MAX_PAGE_LIMIT` addresses are given
+pub fn get_balances_bulk(
+    env: &Env,
+    users: Vec<Address>,
+) -> Result<Vec<UserBalances>, SavingsError> {
+    if users.len() > MAX_PAGE_LIMIT {
+        return Err(SavingsError::AmountExceedsLimit);
     }
 
-    Err(SavingsError::PlanNotFound)
+    let mut balances = Vec::new(env);
+    for user in users.iter() {
+        let user_data: Option<User> = env.storage().persistent().get(&DataKey::User(user.clone()));
+        let flexi_balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::FlexiBalance(user.clone()))
+            .unwrap_or(0);
+        balances.push_back(UserBalances {
+            exists: user_data.is_some(),
+            total_balance: user_data.map_or(0, |data| data.total_balance),
+            flexi_balance,
+            user,
+        });
+    }
+    Ok(balances)
 }
@@ -0,0 +1,125 @@
+use soroban_sdk::{Address, Env};
+
+use crate::errors::SavingsError;
+use crate::events;
+use crate::fees;
+use crate::reentrancy;
+use crate::storage_types::{ConfigKey, DataKey, YieldPoolStatus};
+use crate::strategy;
+
+/// Funds set aside to pay interest; every interest payout draws from here
+pub fn get_pool_balance(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::YieldPool))
+        .unwrap_or(0)
+}
+
+fn set_pool_balance(env: &Env, balance: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Config(ConfigKey::YieldPool), &balance);
+}
+
+/// Interest open plans will have earned by maturity and not yet paid out
+pub fn get_outstanding_interest(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Config(ConfigKey::OutstandingInterest))
+        .unwrap_or(0)
+}
+
+/// Adds `delta` to the interest owed to open plans.
+///
+/// Every path that opens, resizes or pays out an interest-bearing plan must
+/// keep this in step.
+pub fn adjust_outstanding_interest(env: &Env, delta: i128) -> Result<(), SavingsError> {
+    let outstanding = get_outstanding_interest(env)
+        .checked_add(delta)
+        .ok_or(SavingsError::Overflow)?
+        .max(0);
+    env.storage().persistent().set(
+        &DataKey::Config(ConfigKey::OutstandingInterest),
+        &outstanding,
+    );
+    Ok(())
+}
+
+/// Moves `amount` of the deposit token from `from` into the pool
+pub fn fund(env: &Env, from: &Address, amount: i128) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let token = strategy::get_token(env)?;
+    credit_pool(env, amount)?;
+    reentrancy::transfer(env, &token, from, &env.current_contract_address(), amount)
+}
+
+/// Moves `amount` of the current fee recipient's accumulated fees into the
+/// pool. The fees are already held by the contract, so no tokens move.
+pub fn fund_from_fees(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    if amount <= 0 {
+        return Err(SavingsError::InvalidAmount);
+    }
+    let recipient: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::FeeRecipient)
+        .ok_or(SavingsError::InsufficientBalance)?;
+    fees::debit_treasury(env, &recipient, amount)?;
+    credit_pool(env, amount)
+}
+
+/// Adds `amount` already held by the contract to the pool
+pub fn credit_pool(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    let balance = get_pool_balance(env)
+        .checked_add(amount)
+        .ok_or(SavingsError::Overflow)?;
+    set_pool_balance(env, balance);
+    events::publish(env, events::YieldPoolFunded { amount });
+    Ok(())
+}
+
+/// Takes `amount` out of the pool, failing with `InsufficientBalance` if it
+/// holds less
+pub fn debit_pool(env: &Env, amount: i128) -> Result<(), SavingsError> {
+    let balance = get_pool_balance(env);
+    if amount > balance {
+        return Err(SavingsError::InsufficientBalance);
+    }
+    set_pool_balance(env, balance - amount);
+    Ok(())
+}
+
+/// Pays as much of `due` interest as the pool holds and returns the amount
+/// paid. A shortfall is reported in a `yld_short` event rather than failing,
+/// so principal can always be withdrawn.
+pub fn draw_interest(env: &Env, user: &Address, due: i128) -> Result<i128, SavingsError> {
+    if due <= 0 {
+        return Ok(0);
+    }
+    let balance = get_pool_balance(env);
+    let paid = due.min(balance);
+    set_pool_balance(env, balance - paid);
+    if paid < due {
+        events::publish(
+            env,
+            events::YieldShortfall {
+                user: user.clone(),
+                due,
+                paid,
+            },
+        );
+    }
+    Ok(paid)
+}
+
+pub fn get_status(env: &Env) -> YieldPoolStatus {
+    let pool_balance = get_pool_balance(env);
+    let outstanding_interest = get_outstanding_interest(env);
+    YieldPoolStatus {
+        pool_balance,
+        outstanding_interest,
+        shortfall: (outstanding_interest - pool_balance).max(0),
+    }
+}
@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod autosave_tests {
-    use soroban_sdk::{testutils::Address as _, Address, Env};
-    use Nestera::{NesteraContract, NesteraContractClient};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec, Address, BytesN, Env, Symbol,
+    };
+    use Nestera::{
+        AutoSaveCatchUp, AutoSaveConsent, AutoSaveLeg, AutoSaveSource, AutoSaveTarget, Cursor,
+        NesteraContract, NesteraContractClient, SavingsError,
+    };
 
     fn setup_test_contract() -> (Env, NesteraContractClient<'static>, Address) {
         let env = Env::default();
@@ -171,4 +177,454 @@ mod autosave_tests {
         }));
         assert!(result.is_err()); // Should panic with InvalidPlanConfig
     }
+
+    #[test]
+    fn test_split_autosave_allocates_each_leg() {
+        let (env, client, user) = setup_test_contract();
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "house"), &10_000, &100);
+
+        let split = vec![
+            &env,
+            AutoSaveLeg {
+                target: AutoSaveTarget::Flexi,
+                percent: 33,
+            },
+            AutoSaveLeg {
+                target: AutoSaveTarget::Goal(goal_id),
+                percent: 67,
+            },
+        ];
+        let schedule_id = client.create_split_autosave(
+            &user,
+            &1001,
+            &86400,
+            &env.ledger().timestamp(),
+            &AutoSaveSource::ExternalToken,
+            &split,
+        );
+        client.execute_autosave(&schedule_id);
+
+        assert_eq!(client.get_flexi_balance(&user), 330);
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 771);
+
+        let results = client.get_autosave(&schedule_id).unwrap().last_results;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results.get(0).unwrap().amount, 330);
+        assert_eq!(results.get(1).unwrap().amount, 671);
+    }
+
+    #[test]
+    fn test_split_autosave_rejects_percents_not_summing_to_100() {
+        let (env, client, user) = setup_test_contract();
+
+        let split = vec![
+            &env,
+            AutoSaveLeg {
+                target: AutoSaveTarget::Flexi,
+                percent: 50,
+            },
+            AutoSaveLeg {
+                target: AutoSaveTarget::Goal(1),
+                percent: 30,
+            },
+        ];
+        let result = client.try_create_split_autosave(
+            &user,
+            &1000,
+            &86400,
+            &env.ledger().timestamp(),
+            &AutoSaveSource::ExternalToken,
+            &split,
+        );
+        assert_eq!(result, Err(Ok(SavingsError::InvalidPlanConfig)));
+    }
+
+    #[test]
+    fn test_split_autosave_failing_leg_reverts_execution() {
+        let (env, client, user) = setup_test_contract();
+
+        let split = vec![
+            &env,
+            AutoSaveLeg {
+                target: AutoSaveTarget::Flexi,
+                percent: 50,
+            },
+            AutoSaveLeg {
+                target: AutoSaveTarget::Goal(99),
+                percent: 50,
+            },
+        ];
+        let schedule_id = client.create_split_autosave(
+            &user,
+            &1000,
+            &86400,
+            &env.ledger().timestamp(),
+            &AutoSaveSource::ExternalToken,
+            &split,
+        );
+
+        let result = client.try_execute_autosave(&schedule_id);
+        assert_eq!(result, Err(Ok(SavingsError::PlanNotFound)));
+        assert_eq!(client.get_flexi_balance(&user), 0);
+    }
+
+    #[test]
+    fn test_flexi_funded_autosave_moves_balance_into_goal() {
+        let (env, client, user) = setup_test_contract();
+        client.deposit_flexi(&user, &1500);
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "bike"), &10_000, &0);
+
+        let split = vec![
+            &env,
+            AutoSaveLeg {
+                target: AutoSaveTarget::Goal(goal_id),
+                percent: 100,
+            },
+        ];
+        let schedule_id = client.create_split_autosave(
+            &user,
+            &1000,
+            &86400,
+            &env.ledger().timestamp(),
+            &AutoSaveSource::FlexiBalance,
+            &split,
+        );
+        client.execute_autosave(&schedule_id);
+
+        assert_eq!(client.get_flexi_balance(&user), 500);
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 1000);
+    }
+
+    #[test]
+    fn test_autosave_batch_skips_underfunded_flexi_source() {
+        let (env, client, user) = setup_test_contract();
+        client.deposit_flexi(&user, &500);
+        let goal_id = client.create_goal_save(&user, &Symbol::new(&env, "bike"), &10_000, &0);
+
+        let split = vec![
+            &env,
+            AutoSaveLeg {
+                target: AutoSaveTarget::Goal(goal_id),
+                percent: 100,
+            },
+        ];
+        let now = env.ledger().timestamp();
+        let funded = client.create_autosave(&user, &200, &86400, &now);
+        let underfunded = client.create_split_autosave(
+            &user,
+            &1000,
+            &86400,
+            &now,
+            &AutoSaveSource::FlexiBalance,
+            &split,
+        );
+
+        let results = client.execute_autosave_batch(
+            &Address::generate(&env),
+            &vec![&env, funded, underfunded, 99],
+        );
+        assert_eq!(results, vec![&env, true, false, false]);
+        assert_eq!(client.get_flexi_balance(&user), 700);
+        assert_eq!(client.get_goal_save_detail(&goal_id).current_amount, 0);
+        assert_eq!(
+            client
+                .get_autosave(&underfunded)
+                .unwrap()
+                .next_execution_time,
+            now
+        );
+    }
+
+    #[test]
+    fn test_flexi_funded_autosave_rejects_flexi_leg() {
+        let (env, client, user) = setup_test_contract();
+
+        let split = vec![
+            &env,
+            AutoSaveLeg {
+                target: AutoSaveTarget::Flexi,
+                percent: 100,
+            },
+        ];
+        let result = client.try_create_split_autosave(
+            &user,
+            &1000,
+            &86400,
+            &env.ledger().timestamp(),
+            &AutoSaveSource::FlexiBalance,
+            &split,
+        );
+        assert_eq!(result, Err(Ok(SavingsError::InvalidPlanConfig)));
+    }
+
+    #[test]
+    fn test_paused_autosave_skips_until_resumed() {
+        let (env, client, user) = setup_test_contract();
+        let schedule_id = client.create_autosave(&user, &1000, &100, &0);
+
+        client.pause_autosave(&user, &schedule_id);
+        let schedule = client.get_autosave(&schedule_id).unwrap();
+        assert!(schedule.is_active && schedule.is_paused);
+        assert_eq!(
+            client.try_execute_autosave(&schedule_id),
+            Err(Ok(SavingsError::InvalidPlanConfig))
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = 250);
+        client.resume_autosave(&user, &schedule_id);
+        assert_eq!(
+            client
+                .get_autosave(&schedule_id)
+                .unwrap()
+                .next_execution_time,
+            300
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = 300);
+        client.execute_autosave(&schedule_id);
+        assert_eq!(client.get_flexi_balance(&user), 1000);
+    }
+
+    #[test]
+    fn test_cancelled_autosave_cannot_be_resumed() {
+        let (env, client, user) = setup_test_contract();
+        let schedule_id = client.create_autosave(&user, &1000, &100, &env.ledger().timestamp());
+
+        client.pause_autosave(&user, &schedule_id);
+        client.cancel_autosave(&user, &schedule_id);
+        assert_eq!(
+            client.try_resume_autosave(&user, &schedule_id),
+            Err(Ok(SavingsError::InvalidPlanConfig))
+        );
+    }
+
+    #[test]
+    fn test_update_autosave_changes_amount_and_interval() {
+        let (env, client, user) = setup_test_contract();
+        let schedule_id = client.create_autosave(&user, &1000, &100, &0);
+
+        let other_user = Address::generate(&env);
+        assert_eq!(
+            client.try_update_autosave(&other_user, &schedule_id, &500, &50),
+            Err(Ok(SavingsError::Unauthorized))
+        );
+
+        client.update_autosave(&user, &schedule_id, &500, &50);
+        client.execute_autosave(&schedule_id);
+
+        let schedule = client.get_autosave(&schedule_id).unwrap();
+        assert_eq!(schedule.amount, 500);
+        assert_eq!(schedule.next_execution_time, 50);
+        assert_eq!(client.get_flexi_balance(&user), 500);
+    }
+
+    #[test]
+    fn test_late_execution_skips_missed_and_realigns() {
+        let (env, client, user) = setup_test_contract();
+        let schedule_id = client.create_autosave(&user, &1000, &100, &100);
+
+        env.ledger().with_mut(|li| li.timestamp = 350);
+        client.execute_autosave(&schedule_id);
+
+        assert_eq!(client.get_flexi_balance(&user), 1000);
+        assert_eq!(
+            client
+                .get_autosave(&schedule_id)
+                .unwrap()
+                .next_execution_time,
+            400
+        );
+    }
+
+    #[test]
+    fn test_execute_all_catch_up_deposits_missed_intervals_up_to_limit() {
+        let (env, client, user) = setup_test_contract();
+        let schedule_id = client.create_autosave(&user, &1000, &100, &100);
+        client.set_autosave_catch_up(&user, &schedule_id, &AutoSaveCatchUp::ExecuteAll(2));
+
+        env.ledger().with_mut(|li| li.timestamp = 350);
+        client.execute_autosave(&schedule_id);
+        assert_eq!(client.get_flexi_balance(&user), 2000);
+        assert_eq!(
+            client
+                .get_autosave(&schedule_id)
+                .unwrap()
+                .next_execution_time,
+            400
+        );
+
+        client.set_autosave_catch_up(&user, &schedule_id, &AutoSaveCatchUp::ExecuteAll(5));
+        env.ledger().with_mut(|li| li.timestamp = 610);
+        client.execute_autosave(&schedule_id);
+        assert_eq!(client.get_flexi_balance(&user), 5000);
+        assert_eq!(
+            client
+                .get_autosave(&schedule_id)
+                .unwrap()
+                .next_execution_time,
+            700
+        );
+    }
+
+    #[test]
+    fn test_catch_up_limit_must_be_in_range() {
+        let (env, client, user) = setup_test_contract();
+        let schedule_id = client.create_autosave(&user, &1000, &100, &env.ledger().timestamp());
+
+        assert_eq!(
+            client.try_set_autosave_catch_up(&user, &schedule_id, &AutoSaveCatchUp::ExecuteAll(0)),
+            Err(Ok(SavingsError::InvalidPlanConfig))
+        );
+    }
+
+    #[test]
+    fn test_get_due_autosaves_tracks_schedule_changes() {
+        let (env, client, user) = setup_test_contract();
+        let page = Cursor {
+            offset: 0,
+            limit: 0,
+        };
+
+        let hourly = client.create_autosave(&user, &1000, &3_600, &100);
+        let later = client.create_autosave(&user, &1000, &3_600, &5_000);
+        let paused = client.create_autosave(&user, &1000, &3_600, &200);
+        client.pause_autosave(&user, &paused);
+
+        let (due, next) = client.get_due_autosaves(&1_000, &page);
+        assert_eq!(due, vec![&env, hourly]);
+        assert_eq!(next, None);
+
+        env.ledger().with_mut(|li| li.timestamp = 150);
+        client.execute_autosave(&hourly);
+        assert_eq!(client.get_due_autosaves(&1_000, &page).0.len(), 0);
+        // Both now fall in the second hour bucket, in the order they moved there
+        assert_eq!(
+            client.get_due_autosaves(&10_000, &page).0,
+            vec![&env, later, hourly]
+        );
+
+        client.cancel_autosave(&user, &later);
+        assert_eq!(
+            client.get_due_autosaves(&10_000, &page).0,
+            vec![&env, hourly]
+        );
+
+        let (first, next) = client.get_due_autosaves(
+            &10_000,
+            &Cursor {
+                offset: 0,
+                limit: 1,
+            },
+        );
+        assert_eq!(first.len(), 1);
+        assert_eq!(next, None);
+
+        // Pages run across buckets, earliest first
+        let early = client.create_autosave(&user, &1000, &3_600, &200);
+        let (first, next) = client.get_due_autosaves(
+            &10_000,
+            &Cursor {
+                offset: 0,
+                limit: 1,
+            },
+        );
+        assert_eq!(first, vec![&env, early]);
+        let next = next.unwrap();
+        assert_eq!(next.offset, 1);
+        let (second, next) = client.get_due_autosaves(&10_000, &next);
+        assert_eq!(second, vec![&env, hourly]);
+        assert_eq!(next, None);
+    }
+
+    fn sign_consent(
+        client: &NesteraContractClient,
+        signing_key: &ed25519_dalek::SigningKey,
+        consent: &AutoSaveConsent,
+    ) -> BytesN<64> {
+        use ed25519_dalek::Signer;
+
+        let message: Vec<u8> = client.consent_signing_message(consent).iter().collect();
+        BytesN::from_array(&client.env, &signing_key.sign(&message).to_bytes())
+    }
+
+    fn consent_for(user: &Address, nonce: u64) -> AutoSaveConsent {
+        AutoSaveConsent {
+            user: user.clone(),
+            amount: 500,
+            interval_seconds: 86400,
+            start_time: 0,
+            expiry: 1000,
+            nonce,
+        }
+    }
+
+    fn register_consent_key(
+        env: &Env,
+        client: &NesteraContractClient,
+        user: &Address,
+    ) -> ed25519_dalek::SigningKey {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = BytesN::from_array(env, &signing_key.verifying_key().to_bytes());
+        client.set_consent_key(user, &public_key);
+        signing_key
+    }
+
+    #[test]
+    fn test_create_autosave_with_consent() {
+        let (env, client, user) = setup_test_contract();
+        let partner = Address::generate(&env);
+        let signing_key = register_consent_key(&env, &client, &user);
+
+        let consent = consent_for(&user, 0);
+        let signature = sign_consent(&client, &signing_key, &consent);
+        let schedule_id = client.create_autosave_with_consent(&partner, &consent, &signature);
+
+        let schedule = client.get_autosave(&schedule_id).unwrap();
+        assert_eq!(schedule.user, user);
+        assert_eq!(schedule.amount, 500);
+        assert_eq!(client.get_consent(&user).unwrap().next_nonce, 1);
+    }
+
+    #[test]
+    fn test_consent_cannot_be_replayed() {
+        let (env, client, user) = setup_test_contract();
+        let partner = Address::generate(&env);
+        let signing_key = register_consent_key(&env, &client, &user);
+
+        let consent = consent_for(&user, 0);
+        let signature = sign_consent(&client, &signing_key, &consent);
+        client.create_autosave_with_consent(&partner, &consent, &signature);
+
+        let result = client.try_create_autosave_with_consent(&partner, &consent, &signature);
+        assert_eq!(result, Err(Ok(SavingsError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_expired_consent_is_rejected() {
+        let (env, client, user) = setup_test_contract();
+        let partner = Address::generate(&env);
+        let signing_key = register_consent_key(&env, &client, &user);
+
+        let consent = consent_for(&user, 0);
+        let signature = sign_consent(&client, &signing_key, &consent);
+        env.ledger().with_mut(|li| li.timestamp = 1001);
+
+        let result = client.try_create_autosave_with_consent(&partner, &consent, &signature);
+        assert_eq!(result, Err(Ok(SavingsError::TooLate)));
+    }
+
+    #[test]
+    fn test_consent_signed_by_other_key_is_rejected() {
+        let (env, client, user) = setup_test_contract();
+        let partner = Address::generate(&env);
+        register_consent_key(&env, &client, &user);
+
+        let consent = consent_for(&user, 0);
+        let other_key = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+        let signature = sign_consent(&client, &other_key, &consent);
+
+        let result = client.try_create_autosave_with_consent(&partner, &consent, &signature);
+        assert!(result.is_err());
+        assert_eq!(client.get_user_autosaves(&user).len(), 0);
+    }
 }
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod mint_tests {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env,
+    };
+    use Nestera::{MintPayload, NesteraContract, NesteraContractClient, SavingsError};
+
+    fn setup_test_contract() -> (
+        Env,
+        NesteraContractClient<'static>,
+        Address,
+        ed25519_dalek::SigningKey,
+    ) {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[3u8; 32]);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+
+        (env, client, user, signing_key)
+    }
+
+    fn sign_payload(
+        client: &NesteraContractClient,
+        signing_key: &ed25519_dalek::SigningKey,
+        payload: &MintPayload,
+    ) -> BytesN<64> {
+        use ed25519_dalek::Signer;
+
+        let message: Vec<u8> = client.mint_signing_message(payload).iter().collect();
+        BytesN::from_array(&client.env, &signing_key.sign(&message).to_bytes())
+    }
+
+    fn payload_for(user: &Address, amount: i128, timestamp: u64) -> MintPayload {
+        MintPayload {
+            user: user.clone(),
+            amount,
+            timestamp,
+            expiry_duration: 1000,
+        }
+    }
+
+    #[test]
+    fn test_mint_credits_flexi_balance() {
+        let (_, client, user, signing_key) = setup_test_contract();
+
+        let payload = payload_for(&user, 750, 0);
+        let signature = sign_payload(&client, &signing_key, &payload);
+        assert_eq!(client.mint(&payload, &signature), 750);
+
+        assert_eq!(client.get_flexi_balance(&user), 750);
+        assert_eq!(client.get_user(&user).total_balance, 750);
+    }
+
+    #[test]
+    fn test_mint_payload_cannot_be_replayed() {
+        let (env, client, user, signing_key) = setup_test_contract();
+
+        let payload = payload_for(&user, 500, 10);
+        let signature = sign_payload(&client, &signing_key, &payload);
+        env.ledger().with_mut(|li| li.timestamp = 20);
+        client.mint(&payload, &signature);
+        assert!(client.try_mint(&payload, &signature).is_err());
+
+        let next = payload_for(&user, 200, 11);
+        let next_signature = sign_payload(&client, &signing_key, &next);
+        client.mint(&next, &next_signature);
+        assert_eq!(client.get_flexi_balance(&user), 700);
+    }
+
+    #[test]
+    fn test_mint_rejects_unsigned_or_invalid_payloads() {
+        let (_, client, user, signing_key) = setup_test_contract();
+
+        let payload = payload_for(&user, 500, 0);
+        let other_key = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+        let forged = sign_payload(&client, &other_key, &payload);
+        assert!(client.try_mint(&payload, &forged).is_err());
+
+        let zero = payload_for(&user, 0, 0);
+        let signature = sign_payload(&client, &signing_key, &zero);
+        assert_eq!(
+            client.try_mint(&zero, &signature),
+            Err(Ok(SavingsError::InvalidAmount.into()))
+        );
+        assert_eq!(client.get_flexi_balance(&user), 0);
+    }
+}
@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod sponsored_tests {
+    use soroban_sdk::{
+        symbol_short,
+        testutils::{Address as _, Ledger},
+        xdr::ToXdr,
+        Address, BytesN, Env,
+    };
+    use Nestera::{
+        DepositPayload, GoalSavePayload, NesteraContract, NesteraContractClient, SavingsError,
+    };
+
+    fn setup_test_contract() -> (
+        Env,
+        NesteraContractClient<'static>,
+        Address,
+        ed25519_dalek::SigningKey,
+    ) {
+        let env = Env::default();
+        let contract_id = env.register(NesteraContract, ());
+        let client = NesteraContractClient::new(&env, &contract_id);
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[5u8; 32]);
+        let admin = Address::generate(&env);
+        let admin_pk = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+        env.mock_all_auths();
+        client.initialize(
+            &admin,
+            &admin_pk,
+            &Address::generate(&env),
+            &0,
+            &Address::generate(&env),
+        );
+
+        let user = Address::generate(&env);
+        client.initialize_user(&user);
+        // Sponsored calls must succeed without any user authorization
+        env.set_auths(&[]);
+
+        (env, client, user, signing_key)
+    }
+
+    fn sign(
+        env: &Env,
+        signing_key: &ed25519_dalek::SigningKey,
+        message: soroban_sdk::Bytes,
+    ) -> BytesN<64> {
+        use ed25519_dalek::Signer;
+
+        let message: Vec<u8> = message.iter().collect();
+        BytesN::from_array(env, &signing_key.sign(&message).to_bytes())
+    }
+
+    fn deposit_for(user: &Address, amount: i128, nonce: u64) -> DepositPayload {
+        DepositPayload {
+            user: user.clone(),
+            amount,
+            timestamp: 0,
+            expiry_duration: 1000,
+            nonce,
+        }
+    }
+
+    #[test]
+    fn test_sponsored_deposit_credits_flexi() {
+        let (env, client, user, signing_key) = setup_test_contract();
+
+        let payload = deposit_for(&user, 400, 0);
+        let signature = sign(&env, &signing_key, client.deposit_signing_message(&payload));
+        client.deposit_flexi_signed(&payload, &signature);
+
+        assert_eq!(client.get_flexi_balance(&user), 400);
+        assert_eq!(client.get_sponsored_nonce(&user), 1);
+    }
+
+    #[test]
+    fn test_sponsored_deposit_cannot_be_replayed() {
+        let (env, client, user, signing_key) = setup_test_contract();
+
+        let payload = deposit_for(&user, 400, 0);
+        let signature = sign(&env, &signing_key, client.deposit_signing_message(&payload));
+        client.deposit_flexi_signed(&payload, &signature);
+
+        assert_eq!(
+            client.try_deposit_flexi_signed(&payload, &signature),
+            Err(Ok(SavingsError::InvalidSignature))
+        );
+        assert_eq!(client.get_flexi_balance(&user), 400);
+    }
+
+    #[test]
+    fn test_expired_or_forged_sponsored_deposit_is_rejected() {
+        let (env, client, user, signing_key) = setup_test_contract();
+
+        let payload = deposit_for(&user, 400, 0);
+        let other_key = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+        let forged = sign(&env, &other_key, client.deposit_signing_message(&payload));
+        assert!(client.try_deposit_flexi_signed(&payload, &forged).is_err());
+
+        let signature = sign(&env, &signing_key, client.deposit_signing_message(&payload));
+        env.ledger().with_mut(|li| li.timestamp = 1001);
+        assert_eq!(
+            client.try_deposit_flexi_signed(&payload, &signature),
+            Err(Ok(SavingsError::TooLate))
+        );
+        assert_eq!(client.get_flexi_balance(&user), 0);
+        assert_eq!(client.get_sponsored_nonce(&user), 0);
+    }
+
+    #[test]
+    fn test_sponsored_goal_save_shares_nonce_with_deposits() {
+        let (env, client, user, signing_key) = setup_test_contract();
+
+        let deposit = deposit_for(&user, 100, 0);
+        let signature = sign(&env, &signing_key, client.deposit_signing_message(&deposit));
+        client.deposit_flexi_signed(&deposit, &signature);
+
+        let payload = GoalSavePayload {
+            user: user.clone(),
+            goal_name: symbol_short!("laptop"),
+            target_amount: 5_000,
+            initial_deposit: 1_000,
+            timestamp: 0,
+            expiry_duration: 1000,
+            nonce: 1,
+        };
+        let signature = sign(&env, &signing_key, client.goal_signing_message(&payload));
+        let goal_id = client.create_goal_save_signed(&payload, &signature);
+
+        let goal = client.get_goal_save_detail(&goal_id);
+        assert_eq!(goal.owner, user);
+        assert_eq!(goal.current_amount, 1_000);
+        assert_eq!(client.get_sponsored_nonce(&user), 2);
+    }
+
+    #[test]
+    fn test_signature_is_bound_to_function_and_contract() {
+        let (env, client, user, signing_key) = setup_test_contract();
+
+        // A signature over the bare payload XDR is no longer accepted
+        let payload = deposit_for(&user, 400, 0);
+        let raw = sign(&env, &signing_key, payload.clone().to_xdr(&env));
+        assert!(client.try_deposit_flexi_signed(&payload, &raw).is_err());
+
+        // Nor is one produced for another entrypoint with the same nonce
+        let envelope = client.get_signing_envelope(
+            &symbol_short!("sp_goal"),
+            &0,
+            &payload.clone().to_xdr(&env),
+        );
+        let wrong_function = sign(&env, &signing_key, envelope.to_xdr(&env));
+        assert!(client
+            .try_deposit_flexi_signed(&payload, &wrong_function)
+            .is_err());
+
+        // Nor one produced for another deployment of the contract
+        let other = NesteraContractClient::new(&env, &env.register(NesteraContract, ()));
+        let wrong_contract = sign(&env, &signing_key, other.deposit_signing_message(&payload));
+        assert!(client
+            .try_deposit_flexi_signed(&payload, &wrong_contract)
+            .is_err());
+        assert_eq!(client.get_sponsored_nonce(&user), 0);
+
+        let envelope = client.get_signing_envelope(
+            &symbol_short!("sp_dep"),
+            &0,
+            &payload.clone().to_xdr(&env),
+        );
+        assert_eq!(envelope.contract, client.address);
+        assert_eq!(envelope.network_id, env.ledger().network_id());
+        let signature = sign(&env, &signing_key, envelope.to_xdr(&env));
+        client.deposit_flexi_signed(&payload, &signature);
+        assert_eq!(client.get_flexi_balance(&user), 400);
+    }
+}